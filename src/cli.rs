@@ -0,0 +1,413 @@
+/*!
+ * Command-Line Views for Anchora Backend
+ *
+ * Read-only reports over the stored project data, used by the
+ * non-server modes of the binary.
+ */
+
+use crate::communication::{BasicResponse, ExportTasksParams};
+use crate::handler::TaskManagerHandler;
+use crate::report::ReportFormat;
+use crate::statistics::{StatisticsFilter, StatisticsManager, TaskStatistics};
+use crate::storage::StorageManager;
+use crate::task_manager::{ProjectData, SectionOrder, TaskStatus};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Widest title shown in the task table before it is truncated
+pub const MAX_TITLE_WIDTH: usize = 48;
+
+/// Unfinished tasks untouched for this long count as stale in `--mode stats`
+pub const STALE_TASK_DAYS: i64 = 30;
+
+/// Characters in a section's completion bar
+const COMPLETION_BAR_WIDTH: usize = 20;
+
+/// Order of the rows printed by `--mode list`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListSort {
+    /// By section, then task id
+    #[default]
+    Section,
+    /// By status in workflow order, then section and task id
+    Status,
+    /// Most recently updated first
+    Updated,
+    /// By title, case-insensitively
+    Title,
+}
+
+impl std::str::FromStr for ListSort {
+    type Err = anyhow::Error;
+
+    fn from_str(sort: &str) -> anyhow::Result<Self> {
+        match sort.to_lowercase().as_str() {
+            "section" => Ok(ListSort::Section),
+            "status" => Ok(ListSort::Status),
+            "updated" => Ok(ListSort::Updated),
+            "title" => Ok(ListSort::Title),
+            _ => Err(anyhow::anyhow!(
+                "Invalid sort: {} (expected section, status, updated or title)",
+                sort
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub section: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub sort: ListSort,
+}
+
+/// One task as printed by `--mode list`
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRow {
+    pub section: String,
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub title: String,
+    pub updated: DateTime<Utc>,
+}
+
+/// Arguments of `--mode export`
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub out: PathBuf,
+    pub format: String,
+    pub sections: Option<Vec<String>>,
+    pub statuses: Option<Vec<TaskStatus>>,
+    pub include_notes: bool,
+    /// Replace `out` when it already exists
+    pub force: bool,
+}
+
+/// Export through the same code path as the `export_tasks` method, refusing
+/// to replace an existing file unless forced
+pub async fn export(
+    handler: &TaskManagerHandler,
+    options: ExportOptions,
+) -> anyhow::Result<BasicResponse> {
+    options.format.parse::<ReportFormat>()?;
+    if options.out.exists() && !options.force {
+        return Err(anyhow::anyhow!(
+            "{} already exists (pass --force to overwrite it)",
+            options.out.display()
+        ));
+    }
+    handler
+        .export_tasks(ExportTasksParams {
+            path: options.out.to_string_lossy().to_string(),
+            format: Some(options.format),
+            sections: options.sections,
+            status: options.statuses,
+            include_notes: Some(options.include_notes),
+        })
+        .await
+}
+
+/// Load `.anchora/tasks.json`, failing when it is missing or unreadable
+/// instead of falling back to an empty project like the server does
+pub async fn load_stored_project(workspace_path: &Path) -> anyhow::Result<ProjectData> {
+    StorageManager::new(workspace_path)
+        .load_existing_project_data()
+        .await
+}
+
+/// Tasks matching the filters, in the requested order
+pub fn list_tasks(project_data: &ProjectData, options: &ListOptions) -> Vec<TaskRow> {
+    let mut rows: Vec<TaskRow> = project_data
+        .sections
+        .iter()
+        .filter(|(section, _)| options.section.as_ref().is_none_or(|s| s == *section))
+        .flat_map(|(section, tasks)| {
+            tasks.iter().map(move |(task_id, task)| TaskRow {
+                section: section.clone(),
+                task_id: task_id.clone(),
+                status: task.status.clone(),
+                title: task.title.clone(),
+                updated: task.updated,
+            })
+        })
+        .filter(|row| options.status.as_ref().is_none_or(|s| *s == row.status))
+        .collect();
+
+    let by_key = |a: &TaskRow, b: &TaskRow| (&a.section, &a.task_id).cmp(&(&b.section, &b.task_id));
+    match options.sort {
+        ListSort::Section => rows.sort_by(by_key),
+        ListSort::Status => rows.sort_by(|a, b| {
+            status_rank(&a.status)
+                .cmp(&status_rank(&b.status))
+                .then_with(|| by_key(a, b))
+        }),
+        ListSort::Updated => {
+            rows.sort_by(|a, b| b.updated.cmp(&a.updated).then_with(|| by_key(a, b)))
+        }
+        ListSort::Title => rows.sort_by(|a, b| {
+            a.title
+                .to_lowercase()
+                .cmp(&b.title.to_lowercase())
+                .then_with(|| by_key(a, b))
+        }),
+    }
+    rows
+}
+
+fn status_rank(status: &TaskStatus) -> u8 {
+    match status {
+        TaskStatus::InProgress => 0,
+        TaskStatus::Blocked => 1,
+        TaskStatus::Todo => 2,
+        TaskStatus::Done => 3,
+    }
+}
+
+/// Plain-text table of the rows, aligned by display width
+pub fn render_task_table(rows: &[TaskRow], now: DateTime<Utc>) -> String {
+    if rows.is_empty() {
+        return "No tasks found\n".to_string();
+    }
+    let header = ["SECTION", "ID", "STATUS", "TITLE", "UPDATED"].map(String::from);
+    let cells: Vec<[String; 5]> = std::iter::once(header)
+        .chain(rows.iter().map(|row| {
+            [
+                row.section.clone(),
+                row.task_id.clone(),
+                row.status.as_str().to_string(),
+                truncate_to_width(&row.title, MAX_TITLE_WIDTH),
+                relative_time(row.updated, now),
+            ]
+        }))
+        .collect();
+
+    let mut widths = [0usize; 5];
+    for line in &cells {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+
+    let mut output = String::new();
+    for line in &cells {
+        let mut text = String::new();
+        for (column, cell) in line.iter().enumerate() {
+            if column > 0 {
+                text.push_str("  ");
+            }
+            text.push_str(cell);
+            let padding = widths[column] - display_width(cell);
+            text.extend(std::iter::repeat_n(' ', padding));
+        }
+        output.push_str(text.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// Statistics over the stored tasks, optionally limited to one section, with sections
+/// in the given order
+pub fn project_statistics(
+    project_data: &ProjectData,
+    section: Option<&str>,
+    order: SectionOrder,
+) -> anyhow::Result<TaskStatistics> {
+    let manager = StatisticsManager::new(None);
+    let filter = StatisticsFilter {
+        sections: section.map(|s| vec![s.to_string()]),
+        ..Default::default()
+    };
+    manager.get_filtered_statistics(project_data, &filter, order)
+}
+
+/// Unfinished tasks not updated in the last `STALE_TASK_DAYS` days
+pub fn stale_task_count(
+    project_data: &ProjectData,
+    section: Option<&str>,
+    now: DateTime<Utc>,
+) -> usize {
+    let cutoff = now - Duration::days(STALE_TASK_DAYS);
+    project_data
+        .sections
+        .iter()
+        .filter(|(name, _)| section.is_none_or(|s| s == *name))
+        .flat_map(|(_, tasks)| tasks.values())
+        .filter(|task| task.status != TaskStatus::Done && task.updated < cutoff)
+        .count()
+}
+
+/// Plain-text summary of the statistics: status totals, a completion bar per
+/// section, the busiest files and the counts that need attention
+pub fn render_statistics(stats: &TaskStatistics, stale_tasks: usize) -> String {
+    let overview = &stats.overview;
+    let todo = overview.total_tasks
+        - overview.completed_tasks
+        - overview.in_progress_tasks
+        - overview.blocked_tasks;
+    let mut output = String::new();
+    if let Some(name) = &stats.project_name {
+        output.push_str(&format!("Project: {}\n", name));
+    }
+    output.push_str(&format!(
+        "Tasks: {} total, {} done, {} in progress, {} blocked, {} todo ({:.1}% complete)\n",
+        overview.total_tasks,
+        overview.completed_tasks,
+        overview.in_progress_tasks,
+        overview.blocked_tasks,
+        todo,
+        overview.completion_rate
+    ));
+
+    if !overview.sections.is_empty() {
+        output.push_str("\nSections\n");
+        let name_width = overview
+            .sections
+            .iter()
+            .map(|s| display_width(&s.name))
+            .max()
+            .unwrap_or(0);
+        for summary in &overview.sections {
+            let done = stats.sections.get(&summary.name).map_or(0, |s| s.done);
+            let filled = ((summary.completion_percentage / 100.0) * COMPLETION_BAR_WIDTH as f32)
+                .round() as usize;
+            let filled = filled.min(COMPLETION_BAR_WIDTH);
+            output.push_str(&format!(
+                "  {}{}  [{}{}] {:>5.1}%  {}/{}\n",
+                summary.name,
+                " ".repeat(name_width - display_width(&summary.name)),
+                "#".repeat(filled),
+                "-".repeat(COMPLETION_BAR_WIDTH - filled),
+                summary.completion_percentage,
+                done,
+                summary.total_tasks
+            ));
+        }
+    }
+
+    let active_files: Vec<(&str, &[String])> = overview
+        .sections
+        .iter()
+        .filter_map(|summary| stats.sections.get(&summary.name))
+        .filter(|section| !section.most_active_files.is_empty())
+        .map(|section| (section.name.as_str(), section.most_active_files.as_slice()))
+        .collect();
+    if !active_files.is_empty() {
+        output.push_str("\nMost active files\n");
+        for (section, files) in active_files {
+            output.push_str(&format!("  {}: {}\n", section, files.join(", ")));
+        }
+    }
+
+    let overdue = overview.milestones.iter().filter(|m| m.overdue).count();
+    output.push_str("\nAttention\n");
+    output.push_str(&format!("  Overdue milestones: {}\n", overdue));
+    output.push_str(&format!(
+        "  Stale tasks (no update in {} days): {}\n",
+        STALE_TASK_DAYS, stale_tasks
+    ));
+    output
+}
+
+/// Coarse "time ago" label, such as `5m ago` or `3d ago`
+pub fn relative_time(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - then).num_seconds();
+    match seconds {
+        ..60 => "just now".to_string(),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        86_400..2_592_000 => format!("{}d ago", seconds / 86_400),
+        2_592_000..31_536_000 => format!("{}mo ago", seconds / 2_592_000),
+        _ => format!("{}y ago", seconds / 31_536_000),
+    }
+}
+
+/// Columns a character takes in a terminal: 0 for combining marks and
+/// zero-width characters, 2 for East Asian wide characters and emoji
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x00..=0x1F | 0x7F..=0x9F => 0,
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Cut `text` to at most `max_width` columns, marking the cut with an ellipsis
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let next = width + char_width(c);
+        if next + 1 > max_width {
+            break;
+        }
+        truncated.push(c);
+        width = next;
+    }
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_table_aligns_wide_characters() {
+        let now = Utc::now();
+        let rows = vec![
+            TaskRow {
+                section: "ui".to_string(),
+                task_id: "t1".to_string(),
+                status: TaskStatus::Todo,
+                title: "日本語のタイトル".to_string(),
+                updated: now - Duration::hours(3),
+            },
+            TaskRow {
+                section: "backend".to_string(),
+                task_id: "t2".to_string(),
+                status: TaskStatus::InProgress,
+                title: "Plain".to_string(),
+                updated: now - Duration::days(2),
+            },
+        ];
+
+        let table = render_task_table(&rows, now);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        let updated_column = |line: &str| display_width(&line[..line.rfind("  ").unwrap()]);
+        assert_eq!(updated_column(lines[1]), updated_column(lines[2]));
+        assert!(lines[1].ends_with("3h ago"));
+        assert!(lines[2].ends_with("2d ago"));
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("abcdefghij", 5), "abcd…");
+        assert_eq!(truncate_to_width("日本語です", 6), "日本…");
+        assert_eq!(display_width("e\u{301}"), 1);
+    }
+}
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
 use tokio::sync::mpsc;
 const JSONRPC_VERSION: &str = "2.0";
+/// Version of the request and response shapes, bumped on breaking changes
+pub const PROTOCOL_SCHEMA_VERSION: u32 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcRequest {
@@ -74,6 +78,14 @@ impl JsonRpcError {
         }
     }
 
+    pub fn not_initialized() -> Self {
+        Self {
+            code: -32002,
+            message: "Server not initialized: call initialize first".to_string(),
+            data: None,
+        }
+    }
+
     pub fn custom(code: i32, message: String, data: Option<Value>) -> Self {
         Self {
             code,
@@ -83,10 +95,38 @@ impl JsonRpcError {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InitializeParams {
+    pub client_version: String,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InitializeResult {
+    pub server_version: String,
+    pub schema_version: u32,
+    pub supported_methods: Vec<String>,
+}
+
+/// Names the method to document
+#[derive(Debug, Deserialize)]
+pub struct DescribeMethodParams {
+    pub name: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ScanProjectParams {
     pub workspace_path: String,
     pub file_patterns: Option<Vec<String>>,
+    #[serde(default)]
+    pub recognize_keyword_comments: Option<bool>,
+    /// Report labels that break validation rules in `diagnostics`
+    #[serde(default)]
+    pub validate_labels: Option<bool>,
+    /// Overrides `scan.reconcile_status` for this scan
+    #[serde(default)]
+    pub reconcile_status: Option<crate::file_parser::StatusReconciliation>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -95,12 +135,145 @@ pub struct ScanProjectResult {
     pub tasks_found: u32,
     pub tasks_removed: u32,
     pub errors: Vec<String>,
+    /// Errors left out of `errors` because of the configured cap
+    #[serde(default)]
+    pub errors_omitted: u32,
+    #[serde(default)]
+    pub diagnostics: Vec<crate::file_parser::LabelDiagnostic>,
+    /// Edited labels whose text was not applied over a manual edit
+    #[serde(default)]
+    pub description_conflicts: Vec<crate::file_parser::DescriptionConflict>,
+    /// Notes converted because their generated link turned up in the scanned code
+    #[serde(default)]
+    pub converted_notes: Vec<String>,
+    /// Lines past `scan.max_line_length` that were not parsed for labels
+    #[serde(default)]
+    pub skipped_lines: Vec<crate::file_parser::SkippedLine>,
+    /// Labels whose status marker disagreed with the stored status; under
+    /// `code_wins` this includes status edits made in the code
+    #[serde(default)]
+    pub status_divergences: Vec<crate::file_parser::StatusDivergence>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BenchmarkScanParams {
+    /// Defaults to the workspace the server was started with
+    pub workspace_path: Option<String>,
+    pub file_patterns: Option<Vec<String>>,
+    #[serde(default)]
+    pub recognize_keyword_comments: Option<bool>,
+    /// Number of slowest files to list, 10 by default
+    pub slowest_limit: Option<usize>,
+}
+
+/// Timing of a scan that was run without saving anything; times are in milliseconds
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchmarkScanReport {
+    pub files_scanned: u32,
+    pub tasks_found: u32,
+    pub total_ms: f64,
+    pub enumeration_ms: f64,
+    pub parse_ms: f64,
+    pub update_ms: f64,
+    pub by_extension: Vec<ExtensionTiming>,
+    pub slowest_files: Vec<FileTiming>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtensionTiming {
+    pub extension: String,
+    pub files: u32,
+    pub tasks_found: u32,
+    pub parse_ms: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileTiming {
+    pub file: String,
+    pub bytes: u64,
+    pub parse_ms: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScanFilesParams {
+    /// Files relative to the workspace root, or absolute paths inside it
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub recognize_keyword_comments: Option<bool>,
+    /// Overrides `scan.reconcile_status` for this scan
+    #[serde(default)]
+    pub reconcile_status: Option<crate::file_parser::StatusReconciliation>,
+}
+
+/// Tasks touched by a `scan_files` call, as `section:task_id`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanFilesResult {
+    pub files_scanned: u32,
+    pub tasks_added: Vec<String>,
+    pub tasks_updated: Vec<String>,
+    pub tasks_removed: Vec<String>,
+    pub errors: Vec<String>,
+    #[serde(default)]
+    pub description_conflicts: Vec<crate::file_parser::DescriptionConflict>,
+    #[serde(default)]
+    pub converted_notes: Vec<String>,
+    #[serde(default)]
+    pub skipped_lines: Vec<crate::file_parser::SkippedLine>,
+    #[serde(default)]
+    pub status_divergences: Vec<crate::file_parser::StatusDivergence>,
+}
+
+/// Unsaved buffer text to parse for labels
+#[derive(Debug, Deserialize)]
+pub struct PreviewFileLabelsParams {
+    pub content: String,
+    /// File the buffer belongs to, picking the comment syntax by its extension
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Labels found in a previewed buffer, stored data untouched
+#[derive(Debug, Serialize)]
+pub struct PreviewFileLabelsResult {
+    pub labels: Vec<PreviewedLabel>,
+    pub near_misses: Vec<crate::file_parser::LabelNearMiss>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewedLabel {
+    pub line: u32,
+    #[serde(flatten)]
+    pub label: crate::file_parser::ParsedTaskLabel,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct GetTasksParams {
     pub section: Option<String>,
-    pub status: Option<String>,
+    pub status: Option<StatusFilter>,
+    /// Archived sections are left out unless this is set
+    #[serde(default)]
+    pub include_archived: Option<bool>,
+    /// `"manual"` adds an `order` map listing each section's task IDs by rank
+    #[serde(default)]
+    pub sort_by: Option<String>,
+}
+
+/// Either a single status or a list of statuses to match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StatusFilter {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl StatusFilter {
+    pub fn values(&self) -> Vec<&str> {
+        match self {
+            StatusFilter::Single(status) => vec![status.as_str()],
+            StatusFilter::Multiple(statuses) => statuses.iter().map(String::as_str).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -116,12 +289,138 @@ pub struct CreateTaskParams {
     pub task_id: String,
     pub title: String,
     pub description: Option<String>,
+    /// Replace an existing task with the same id instead of failing
+    #[serde(default)]
+    pub overwrite: Option<bool>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// Create the task as a subtask of this one, given as `section.task_id`
+    #[serde(default)]
+    pub parent: Option<crate::TaskKey>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateTaskFromTemplateParams {
+    #[serde(alias = "template")]
+    pub template_id: String,
+    /// Section for the task, taking precedence over the template's
+    #[serde(default)]
+    pub section: Option<String>,
+    pub task_id: String,
+    /// Values for the `{placeholder}`s in the template's title and description
+    #[serde(default)]
+    pub substitutions: std::collections::HashMap<String, String>,
+    /// Values taking precedence over the template's defaults
+    #[serde(default)]
+    pub overrides: crate::TaskTemplate,
+}
+
+/// Stores a template in the project config, replacing one with the same ID
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SaveTaskTemplateParams {
+    pub template_id: String,
+    pub template: crate::TaskTemplate,
+}
+
+/// Assigns a task; an absent or blank assignee unassigns it
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTaskAssigneeParams {
+    pub section: String,
+    pub task_id: String,
+    pub assignee: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetMyTasksParams {
+    pub assignee: String,
+    pub limit: Option<usize>,
+}
+
+/// An open task as listed by `get_my_tasks`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignedTask {
+    pub section: String,
+    pub task_id: String,
+    pub title: String,
+    pub status: crate::TaskStatus,
+    pub priority: Option<crate::TaskPriority>,
+    pub updated: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteTaskParams {
     pub section: String,
     pub task_id: String,
+    /// Delete subtasks as well instead of turning them into top-level tasks
+    #[serde(default)]
+    pub cascade: Option<bool>,
+    /// Skip the trash; the task cannot be restored
+    #[serde(default)]
+    pub permanent: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RestoreTaskParams {
+    pub section: String,
+    pub task_id: String,
+}
+
+/// Empties the trash; with `older_than_days`, only of tasks deleted before then
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct EmptyTrashParams {
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+}
+
+/// Makes a task a subtask of `parent`; an absent parent makes it top-level again
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTaskParentParams {
+    pub section: String,
+    pub task_id: String,
+    /// `section.task_id` of the new parent
+    #[serde(default)]
+    pub parent: Option<crate::TaskKey>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetTaskTreeParams {
+    pub section: String,
+    pub task_id: String,
+}
+
+/// A task with its subtasks; `done` and `total` count subtasks at every level below it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskTreeNode {
+    pub section: String,
+    pub task_id: String,
+    pub title: String,
+    pub status: crate::TaskStatus,
+    pub done: usize,
+    pub total: usize,
+    pub children: Vec<TaskTreeNode>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetTaskDetailsParams {
+    pub section: String,
+    pub task_id: String,
+    pub timezone: Option<String>,
+}
+
+/// Newest-first field changes of a task; `limit` caps the number of entries
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetTaskHistoryParams {
+    pub section: String,
+    pub task_id: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CloneSectionParams {
+    pub source: String,
+    pub target: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -137,6 +436,60 @@ pub struct TaskReference {
     pub note: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetSectionFilesParams {
+    pub section: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SectionFile {
+    pub file_path: String,
+    pub task_count: u32,
+}
+
+/// Explorer badge data for one file that has tasks
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileDecoration {
+    pub file_path: String,
+    pub task_count: u32,
+    pub has_blocked: bool,
+    /// Number of the file's tasks in each status
+    pub statuses: std::collections::HashMap<crate::TaskStatus, u32>,
+}
+
+/// Checks that each stored reference line still holds its task's label
+#[derive(Debug, Default, Deserialize)]
+pub struct VerifyReferencesParams {
+    /// Move stale references to the nearest line that still has the label
+    #[serde(default)]
+    pub repair: Option<bool>,
+    /// How many lines away from the recorded one a repair may look
+    #[serde(default)]
+    pub max_distance: Option<u32>,
+    #[serde(default)]
+    pub recognize_keyword_comments: Option<bool>,
+}
+
+/// A stored reference whose line no longer holds the task's label
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StaleReference {
+    pub section: String,
+    pub task_id: String,
+    pub file: String,
+    pub line: u32,
+    /// `line_mismatch`, `label_missing` or `file_unreadable`
+    pub reason: String,
+    /// Where the reference was moved, when repairing found the label nearby
+    pub repaired_line: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VerifyReferencesResult {
+    pub references_checked: u32,
+    pub stale: Vec<StaleReference>,
+    pub repaired: u32,
+}
+
 // Note-related types
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateNoteParams {
@@ -145,6 +498,291 @@ pub struct CreateNoteParams {
     pub section: String,
     pub suggested_task_id: String,
     pub suggested_status: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// File selections the note is about; absolute paths must lie in the workspace
+    #[serde(default)]
+    pub attachments: Option<Vec<crate::task_manager::NoteAttachment>>,
+}
+
+/// Replaces whichever of a note's fields are given
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateNoteParams {
+    pub note_id: String,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub section: Option<String>,
+    pub suggested_task_id: Option<String>,
+    pub suggested_status: Option<String>,
+}
+
+/// Turns a note into a task under its suggested ID
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConvertNoteToTaskParams {
+    pub note_id: String,
+    /// When the suggested ID is taken, convert under the first free alternative
+    /// instead of failing
+    #[serde(default)]
+    pub auto_rename: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConvertNoteToTaskResult {
+    pub note_id: String,
+    pub section: String,
+    pub task_id: String,
+    /// Suggested ID the note had before it was renamed to a free one
+    pub renamed_from: Option<String>,
+}
+
+/// Turns each checklist item of a note into its own task
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConvertNoteToTasksParams {
+    pub note_id: String,
+    pub split: crate::ChecklistSplit,
+    /// Section for the new tasks; the note's own section when omitted
+    pub section: Option<String>,
+    /// Prepended to the slug of each item to form its task ID
+    pub id_prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConvertNoteToTasksResult {
+    pub note_id: String,
+    pub section: String,
+    pub task_ids: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct GetNotesParams {
+    /// Only notes carrying any of these tags
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetNotesForTaskParams {
+    pub section: String,
+    pub task_id: String,
+}
+
+/// Names the section to archive or unarchive
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchiveSectionParams {
+    pub section: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateMilestoneParams {
+    pub id: String,
+    pub title: String,
+    pub due: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Names the milestone to delete; its tasks are kept
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteMilestoneParams {
+    pub id: String,
+}
+
+/// Adds a task to, or removes it from, a milestone
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MilestoneTaskParams {
+    pub milestone_id: String,
+    pub section: String,
+    pub task_id: String,
+}
+
+/// Edits a task's title or description by hand; later scans keep the edited text
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditTaskTextParams {
+    pub section: String,
+    pub task_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Sets the priority of a task; an absent priority clears it
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTaskPriorityParams {
+    pub section: String,
+    pub task_id: String,
+    pub priority: Option<String>,
+}
+
+/// Sets the estimate of a task in minutes; an absent estimate clears it
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTaskEstimateParams {
+    pub section: String,
+    pub task_id: String,
+    pub estimate_minutes: Option<u32>,
+}
+
+/// Logs time spent on a task
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddTimeEntryParams {
+    pub section: String,
+    pub task_id: String,
+    pub minutes: u32,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTaskMetadataParams {
+    pub section: String,
+    pub task_id: String,
+    pub key: String,
+    pub value: Value,
+}
+
+/// Tags to add to or remove from a task; they are trimmed and lowercased
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskTagsParams {
+    pub section: String,
+    pub task_id: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskTagsResult {
+    pub section: String,
+    pub task_id: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddTaskLinkParams {
+    pub section: String,
+    pub task_id: String,
+    /// Absolute http or https URL
+    pub url: String,
+    /// Defaults to the URL
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RemoveTaskLinkParams {
+    pub section: String,
+    pub task_id: String,
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskLinksResult {
+    pub section: String,
+    pub task_id: String,
+    pub links: Vec<crate::TaskLink>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GetTagsParams {
+    /// Only tags starting with this prefix, for autocomplete
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub include_archived: Option<bool>,
+}
+
+/// A tag and the number of tasks or notes carrying it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagUsage {
+    pub tag: String,
+    pub count: usize,
+    pub kind: TagKind,
+}
+
+/// What a counted tag is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    Task,
+    Note,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReorderTaskParams {
+    pub section: String,
+    pub task_id: String,
+    /// Task to place this one directly in front of
+    #[serde(default)]
+    pub before: Option<String>,
+    /// Task to place this one directly behind
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReorderTaskResult {
+    pub section: String,
+    pub task_id: String,
+    pub rank: f64,
+    /// Task IDs of the section in their new order
+    pub order: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CompareSectionsParams {
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AddTaskCommentParams {
+    pub section: String,
+    pub task_id: String,
+    pub author: String,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteTaskCommentParams {
+    pub section: String,
+    pub task_id: String,
+    pub comment_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RemoveTaskMetadataParams {
+    pub section: String,
+    pub task_id: String,
+    pub key: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetDependentsParams {
+    pub section: String,
+    pub task_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskDependencyParams {
+    pub section: String,
+    pub task_id: String,
+    /// Task that has to be done first
+    pub depends_on: crate::TaskKey,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskDependenciesResult {
+    pub section: String,
+    pub task_id: String,
+    pub dependencies: Vec<crate::TaskKey>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskExistsParams {
+    pub section: String,
+    pub task_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskExistsResponse {
+    pub exists: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<crate::task_manager::TaskStatus>,
+    /// Where the task lives now, when it was found through a former ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_to: Option<crate::TaskKey>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -152,6 +790,9 @@ pub struct CreateNoteResponse {
     pub success: bool,
     pub message: String,
     pub note_id: String,
+    /// Validation warnings, such as a suggested task ID that is already taken
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -164,10 +805,75 @@ pub struct DeleteNoteParams {
     pub note_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PinNoteParams {
+    pub note_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GetNoteParams {
+    pub note_id: String,
+}
+
+/// Full-text note search; `converted` restricts to converted or unconverted notes
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SearchNotesParams {
+    pub query: String,
+    pub section: Option<String>,
+    pub converted: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// Names a note and a task to link or unlink
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NoteTaskLinkParams {
+    pub note_id: String,
+    pub section: String,
+    pub task_id: String,
+}
+
+/// Notes to combine, in the order their content is joined
+#[derive(Debug, Deserialize)]
+pub struct MergeNotesParams {
+    pub note_ids: Vec<String>,
+    pub title: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PurgeConvertedNotesParams {
+    /// Only purge notes converted at least this many days ago
+    pub older_than_days: Option<u32>,
+}
+
+/// A file or directory that moved; paths are absolute or relative to the workspace
+#[derive(Debug, Deserialize)]
+pub struct RenameFileReferencesParams {
+    pub old_path: String,
+    pub new_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RenameFileReferencesResult {
+    pub old_path: String,
+    pub new_path: String,
+    pub updated_tasks: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeConvertedNotesResult {
+    pub removed: usize,
+    pub backup_path: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenerateLinkResponse {
     pub success: bool,
     pub link: String,
+    /// Link generated earlier, which may already be pasted somewhere
+    pub previous_link: Option<String>,
+    pub changed: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -185,16 +891,114 @@ pub struct SearchTasksParams {
     pub offset: Option<usize>,
 }
 
+/// Matches above which `bulk_apply` only runs with `confirm: true`
+pub const BULK_APPLY_CONFIRM_THRESHOLD: usize = 10;
+
+/// Edits applied to every task a search matches
+#[derive(Debug, Deserialize)]
+pub struct BulkApplyParams {
+    pub query: String,
+    /// Same filters as `search_tasks`; malformed filters fail the request
+    #[serde(default)]
+    pub filters: Option<crate::search_engine::SearchFilters>,
+    #[serde(default)]
+    pub set_status: Option<String>,
+    #[serde(default)]
+    pub add_tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub set_assignee: Option<String>,
+    /// Required when more than `BULK_APPLY_CONFIRM_THRESHOLD` tasks match
+    #[serde(default)]
+    pub confirm: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkApplyResult {
+    /// Tasks the search matched
+    pub matched: u32,
+    /// Tasks that changed; matches already in the requested state are left alone
+    pub affected: u32,
+    pub tasks: Vec<crate::TaskKey>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GetStatisticsParams {
     pub include_trends: Option<bool>,
     pub section_filter: Option<Vec<String>>,
+    pub assignee_filter: Option<Vec<String>>,
+    pub tag_filter: Option<Vec<String>>,
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub include_archived: Option<bool>,
+    /// Count subtasks in their top-level task's section and leave their parents out
+    #[serde(default)]
+    pub roll_up_subtasks: Option<bool>,
+}
+
+/// Writes the tasks to `path` in one of the `ReportFormat`s
+#[derive(Debug, Deserialize)]
+pub struct ExportTasksParams {
+    pub path: String,
+    /// json (default), ndjson, markdown, csv or html
+    pub format: Option<String>,
+    #[serde(default)]
+    pub sections: Option<Vec<String>>,
+    #[serde(default)]
+    pub status: Option<Vec<crate::TaskStatus>>,
+    /// Keep notes; unset, JSON exports keep them and documents leave them out
+    #[serde(default)]
+    pub include_notes: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportNotesParams {
+    pub path: String,
+    /// Also export notes already converted to tasks
+    #[serde(default)]
+    pub include_converted: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportTasksParams {
+    pub path: String,
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportStatisticsParams {
+    pub path: String,
+    pub format: String,
+    pub fresh: Option<bool>,
+    #[serde(default)]
+    pub include_archived: Option<bool>,
+    /// Report per milestone instead of per section
+    #[serde(default)]
+    pub group_by_milestone: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NormalizeIdsParams {
+    pub style: crate::identifier::IdStyle,
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NormalizeIdsResult {
+    pub dry_run: bool,
+    pub renames: Vec<crate::task_manager::IdRename>,
+    pub collisions: Vec<crate::task_manager::IdCollision>,
+    pub files_updated: u32,
+    pub backup_path: Option<String>,
+    pub errors: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct GetTaskOverviewParams {
     pub include_recent_activity: Option<bool>,
     pub activity_limit: Option<usize>,
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub include_archived: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -207,6 +1011,34 @@ pub struct ValidateTaskParams {
     pub suggest_alternatives: Option<bool>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ValidateProjectParams {
+    /// Violations listed per rule; the rest are only counted
+    pub max_per_rule: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApplyConflictResolutionParams {
+    pub section: String,
+    pub task_id: String,
+    pub resolution: crate::validation::ResolutionAction,
+}
+
+/// Where the task ended up after a conflict resolution
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConflictResolutionResult {
+    pub section: String,
+    pub task_id: String,
+    pub files_updated: u32,
+    pub backup_path: Option<String>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateTasksBatchParams {
+    pub items: Vec<crate::validation::ValidationParams>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GetSuggestionsParams {
     pub partial_query: String,
@@ -224,6 +1056,21 @@ pub struct GetFilteredTasksParams {
     pub statuses: Option<Vec<String>>,
     pub created_after: Option<String>,
     pub updated_after: Option<String>,
+    #[serde(default)]
+    pub priorities: Option<Vec<String>>,
+    /// Tasks carrying any of these tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub assignees: Option<Vec<String>>,
+    /// Metadata fields that must all be present with these values
+    #[serde(default)]
+    pub metadata: Option<std::collections::HashMap<String, Value>>,
+    #[serde(default)]
+    pub include_archived: Option<bool>,
+    /// `"manual"` adds an `order` map listing each section's task IDs by rank
+    #[serde(default)]
+    pub sort_by: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -239,6 +1086,16 @@ pub trait JsonRpcHandler: Send + Sync {
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = JsonRpcResponse> + Send + '_>>;
 }
 
+/// Lets the server share its handler with work running beside it, such as `--watch`
+impl<H: JsonRpcHandler + ?Sized> JsonRpcHandler for std::sync::Arc<H> {
+    fn handle_request(
+        &self,
+        request: JsonRpcRequest,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = JsonRpcResponse> + Send + '_>> {
+        (**self).handle_request(request)
+    }
+}
+
 pub struct JsonRpcServer {
     handler: Box<dyn JsonRpcHandler>,
 }
@@ -249,34 +1106,48 @@ impl JsonRpcServer {
     }
 
     pub async fn run_stdio(&self) -> anyhow::Result<()> {
-        let stdin = tokio::io::stdin();
-        let mut stdout = tokio::io::stdout();
-        let mut reader = BufReader::new(stdin);
-        let mut line = String::new();
-        println!("JSON-RPC server started on stdin/stdout");
+        eprintln!("JSON-RPC server started on stdin/stdout");
+        self.serve(BufReader::new(tokio::io::stdin()), tokio::io::stdout())
+            .await?;
+        eprintln!("JSON-RPC server shutting down");
+        Ok(())
+    }
+
+    /// Answer requests from `reader` until it is exhausted. Each message is
+    /// either one line of JSON or, when it starts with a `Content-Length`
+    /// header, an LSP-style framed body that may span several lines; the
+    /// response is framed the same way as the request
+    pub async fn serve<R, W>(&self, mut reader: R, mut writer: W) -> anyhow::Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
         loop {
-            line.clear();
-            match reader.read_line(&mut line).await {
-                Ok(0) => {
-                    println!("JSON-RPC server shutting down");
-                    break;
-                }
-                Ok(_) => {
-                    let line = line.trim();
-                    if line.is_empty() {
-                        continue;
-                    }
-                    let response = self.process_line(line).await;
-                    let response_json = serde_json::to_string(&response)?;
-                    stdout.write_all(response_json.as_bytes()).await?;
-                    stdout.write_all(b"\n").await?;
-                    stdout.flush().await?;
-                }
+            let (framing, message) = match read_message(&mut reader).await {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
                 Err(e) => {
                     eprintln!("Error reading from stdin: {}", e);
                     break;
                 }
+            };
+            let response = match message {
+                Ok(message) => self.process_line(&message).await,
+                Err(e) => {
+                    eprintln!("Rejected malformed message: {}", e);
+                    Self::error_response(None, JsonRpcError::invalid_request())
+                }
+            };
+            let response_json = serde_json::to_string(&response)?;
+            if framing == Framing::ContentLength {
+                let header = format!("Content-Length: {}\r\n\r\n", response_json.len());
+                writer.write_all(header.as_bytes()).await?;
+                writer.write_all(response_json.as_bytes()).await?;
+            } else {
+                writer.write_all(response_json.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
             }
+            writer.flush().await?;
         }
 
         Ok(())
@@ -324,6 +1195,104 @@ impl JsonRpcServer {
     }
 }
 
+/// How a message was delimited on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Framing {
+    Line,
+    ContentLength,
+}
+
+/// Largest `Content-Length` body the server will read
+const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// A message body, or the framing error that kept it from being read
+type FramedMessage = Result<String, anyhow::Error>;
+
+/// Next non-blank message from `reader`, or `None` at end of input. Bad
+/// framing is returned as the message rather than as an error, after
+/// skipping past it, so the server can answer it and keep reading
+async fn read_message<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> anyhow::Result<Option<(Framing, FramedMessage)>> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let mut length = match content_length(trimmed) {
+            Ok(Some(length)) => Ok(length),
+            Ok(None) => return Ok(Some((Framing::Line, Ok(trimmed.to_string())))),
+            Err(e) => Err(e),
+        };
+
+        // Further headers such as Content-Type run until the blank separator line
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                return Err(anyhow::anyhow!(
+                    "Unexpected end of input in message headers"
+                ));
+            }
+            let header = line.trim();
+            if header.is_empty() {
+                break;
+            }
+            match content_length(header) {
+                Ok(Some(value)) => length = Ok(value),
+                Ok(None) => {}
+                Err(e) => length = Err(e),
+            }
+        }
+        // Without a usable length the body can't be skipped; its lines are
+        // answered as parse errors until the next header comes along
+        let length = match length {
+            Ok(length) => length,
+            Err(e) => return Ok(Some((Framing::ContentLength, Err(e)))),
+        };
+        if length > MAX_CONTENT_LENGTH {
+            let skipped =
+                tokio::io::copy(&mut reader.take(length as u64), &mut tokio::io::sink()).await?;
+            if skipped < length as u64 {
+                return Ok(None);
+            }
+            return Ok(Some((
+                Framing::ContentLength,
+                Err(anyhow::anyhow!(
+                    "Content-Length {} exceeds the {} byte limit",
+                    length,
+                    MAX_CONTENT_LENGTH
+                )),
+            )));
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await?;
+        return Ok(Some((
+            Framing::ContentLength,
+            Ok(String::from_utf8_lossy(&body).into_owned()),
+        )));
+    }
+}
+
+/// Value of a `Content-Length` header line, or `None` for any other line
+fn content_length(line: &str) -> anyhow::Result<Option<usize>> {
+    let Some((name, value)) = line.split_once(':') else {
+        return Ok(None);
+    };
+    if !name.trim().eq_ignore_ascii_case("content-length") {
+        return Ok(None);
+    }
+    value
+        .trim()
+        .parse()
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("Invalid Content-Length header: {}", line))
+}
+
 pub struct JsonRpcClient {
     tx: mpsc::UnboundedSender<String>,
     rx: mpsc::UnboundedReceiver<String>,
@@ -0,0 +1,112 @@
+/*!
+ * Project Configuration Module for Anchora Backend
+ *
+ * Settings stored in `.anchora/config.json`. Every section is optional and
+ * missing values fall back to the built-in defaults.
+ */
+
+use crate::file_parser::ScanConfig;
+use crate::search_engine::SearchConfig;
+use crate::task_manager::{NotesConfig, SectionOrder, TaskTemplate};
+use crate::validation::ValidationConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Project-level configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub validation: ValidationConfig,
+    pub search: SearchConfig,
+    pub notes: NotesConfig,
+    pub scan: ScanConfig,
+    pub responses: ResponseConfig,
+    /// Task templates by name, for `create_task_from_template`
+    pub templates: HashMap<String, TaskTemplate>,
+}
+
+impl ProjectConfig {
+    /// This config with the fields present in `update` replaced. Objects are
+    /// merged key by key, so sections and keys left out of `update` keep
+    /// their current values.
+    pub fn merged(&self, update: serde_json::Value) -> anyhow::Result<ProjectConfig> {
+        let mut merged = serde_json::to_value(self)?;
+        merge_json(&mut merged, update);
+        Ok(serde_json::from_value(merged)?)
+    }
+}
+
+fn merge_json(target: &mut serde_json::Value, update: serde_json::Value) {
+    match (target, update) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(update)) => {
+            for (key, value) in update {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, update) => *target = update,
+    }
+}
+
+/// Extras attached to JSON-RPC responses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResponseConfig {
+    /// Attach `_performance` timings to the methods that opt into them
+    pub performance_metrics: bool,
+    /// How sections are ordered wherever they are listed
+    pub section_order: SectionOrder,
+}
+
+impl Default for ResponseConfig {
+    fn default() -> Self {
+        Self {
+            performance_metrics: true,
+            section_order: SectionOrder::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_merges_over_defaults() {
+        let config: ProjectConfig = serde_json::from_str(
+            r#"{ "validation": { "max_task_id_length": 80, "allowed_reserved": ["default"] } }"#,
+        )
+        .unwrap();
+        assert_eq!(config.validation.max_task_id_length, 80);
+        assert_eq!(
+            config.validation.allowed_reserved,
+            vec!["default".to_string()]
+        );
+        assert_eq!(
+            config.validation.min_task_id_length,
+            ValidationConfig::default().min_task_id_length
+        );
+    }
+
+    #[test]
+    fn test_merged_keeps_sections_left_out_of_the_update() {
+        let mut config = ProjectConfig::default();
+        config.validation.max_task_id_length = 80;
+        config.responses.performance_metrics = false;
+        let merged = config
+            .merged(serde_json::json!({ "responses": { "section_order": "task_count" } }))
+            .unwrap();
+        assert_eq!(merged.validation.max_task_id_length, 80);
+        assert!(!merged.responses.performance_metrics);
+        assert_eq!(merged.responses.section_order, SectionOrder::TaskCount);
+        assert!(
+            config
+                .merged(serde_json::json!({ "scan": { "max_line_length": "long" } }))
+                .is_err()
+        );
+    }
+}
@@ -6,8 +6,17 @@
  */
 
 use crate::communication::JsonRpcError;
+use crate::validation::ValidationFailed;
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Window during which identical consecutive errors are logged only once
+const ERROR_LOG_WINDOW: Duration = Duration::from_secs(5);
+
+static ERROR_LOG_LIMITER: LazyLock<ErrorLogLimiter> =
+    LazyLock::new(|| ErrorLogLimiter::new(ERROR_LOG_WINDOW));
 
 /// Enhanced error information that includes debug context
 #[derive(Debug, Clone)]
@@ -52,6 +61,67 @@ impl ErrorContext {
     }
 }
 
+/// Suppresses identical consecutive errors so a retrying client cannot flood stderr
+pub struct ErrorLogLimiter {
+    window: Duration,
+    last: Mutex<Option<RepeatedError>>,
+}
+
+struct RepeatedError {
+    key: String,
+    logged_at: Instant,
+    suppressed: u32,
+}
+
+impl ErrorLogLimiter {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Returns the lines that should actually be written for this error
+    pub fn filter(&self, key: &str, lines: Vec<String>) -> Vec<String> {
+        self.filter_at(key, lines, Instant::now())
+    }
+
+    fn filter_at(&self, key: &str, lines: Vec<String>, now: Instant) -> Vec<String> {
+        let mut last = self.last.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(previous) = last.as_mut()
+            && previous.key == key
+            && now.duration_since(previous.logged_at) < self.window
+        {
+            previous.suppressed += 1;
+            return Vec::new();
+        }
+
+        let mut output = Vec::with_capacity(lines.len() + 1);
+        if let Some(previous) = last.take()
+            && previous.suppressed > 0
+        {
+            output.push(format!(
+                "[ERROR] {} (repeated {} times)",
+                previous.key, previous.suppressed
+            ));
+        }
+        output.extend(lines);
+        *last = Some(RepeatedError {
+            key: key.to_string(),
+            logged_at: now,
+            suppressed: 0,
+        });
+        output
+    }
+}
+
+/// Write error lines to stderr through the shared rate limiter
+pub fn log_error_lines(key: &str, lines: Vec<String>) {
+    for line in ERROR_LOG_LIMITER.filter(key, lines) {
+        eprintln!("{}", line);
+    }
+}
+
 /// Convert anyhow::Error to JsonRpcError with rich context
 pub fn create_enhanced_error(
     error: &anyhow::Error,
@@ -59,7 +129,7 @@ pub fn create_enhanced_error(
     error_code: i32,
 ) -> JsonRpcError {
     let error_message = format!("{}: {}", context.operation, error);
-    let debug_data = json!({
+    let mut debug_data = json!({
         "operation": context.operation,
         "error_source": error.to_string(),
         "error_chain": format!("{:?}", error),
@@ -73,11 +143,14 @@ pub fn create_enhanced_error(
         "timestamp": chrono::Utc::now().to_rfc3339(),
         "additional_data": context.additional_data
     });
+    if let Some(ValidationFailed(result)) = error.downcast_ref::<ValidationFailed>() {
+        debug_data["validation"] = json!(result);
+    }
     JsonRpcError::custom(error_code, error_message, Some(debug_data))
 }
 
 /// Main macro for handling JSON-RPC method calls with unified error handling
-/// Enhanced for server-side logic migration with performance metrics
+/// Methods opt into a `_performance` object on object results with `performance = <bool>`
 #[macro_export]
 macro_rules! handle_jsonrpc_method {
     (
@@ -85,6 +158,7 @@ macro_rules! handle_jsonrpc_method {
         $method_name:expr,
         $operation:expr,
         $result:expr
+        $(, performance = $performance:expr)?
     ) => {{
         let start_time = std::time::Instant::now();
         let context = $crate::error_macros::ErrorContext::new(
@@ -108,7 +182,8 @@ macro_rules! handle_jsonrpc_method {
                         serde_json::Value::Null
                     }
                 };
-                if $method_name.starts_with("search_") || $method_name.starts_with("get_statistics") || $method_name.starts_with("validate_") {
+                let include_performance = false $(|| $performance)?;
+                if include_performance {
                     if let serde_json::Value::Object(mut obj) = json_value {
                         obj.insert("_performance".to_string(), serde_json::json!({
                             "duration_ms": duration.as_millis(),
@@ -126,8 +201,13 @@ macro_rules! handle_jsonrpc_method {
             Err(error) => {
                 let duration = start_time.elapsed();
                 let enhanced_error = $crate::error_macros::create_enhanced_error(&error, &context, -1);
-                eprintln!("[ERROR] Operation '{}' failed after {:?}: {}", $operation, duration, error);
-                eprintln!("[ERROR] Context: {}:{} in {}", file!(), line!(), module_path!());
+                $crate::error_macros::log_error_lines(
+                    &format!("Operation '{}' failed: {}", $operation, error),
+                    vec![
+                        format!("[ERROR] Operation '{}' failed after {:?}: {}", $operation, duration, error),
+                        format!("[ERROR] Context: {}:{} in {}", file!(), line!(), module_path!()),
+                    ],
+                );
                 $crate::communication::JsonRpcServer::error_response($request_id, enhanced_error)
             }
         }
@@ -178,9 +258,10 @@ macro_rules! handle_simple_method {
         $method_name:expr,
         $operation:expr,
         $async_call:expr
+        $(, performance = $performance:expr)?
     ) => {{
         let result = $async_call.await;
-        handle_jsonrpc_method!($request_id, $method_name, $operation, result)
+        handle_jsonrpc_method!($request_id, $method_name, $operation, result $(, performance = $performance)?)
     }};
 }
 
@@ -193,13 +274,14 @@ macro_rules! handle_parameterized_method {
         $method_name:expr,
         $operation:expr,
         |$params:ident| $async_call:expr
+        $(, performance = $performance:expr)?
     ) => {{
         match $request.params {
             Some(params) => {
                 match serde_json::from_value::<$param_type>(params) {
                     Ok($params) => {
                         let result = $async_call.await;
-                        handle_jsonrpc_method!($request.id, $method_name, $operation, result)
+                        handle_jsonrpc_method!($request.id, $method_name, $operation, result $(, performance = $performance)?)
                     }
                     Err(e) => {
                         let context = $crate::error_macros::ErrorContext::new(
@@ -213,13 +295,15 @@ macro_rules! handle_parameterized_method {
                         .with_request_id($request.id.clone());
                         let error = anyhow::anyhow!("Parameter parsing failed: {}", e);
                         let enhanced_error = $crate::error_macros::create_enhanced_error(&error, &context, -32602);
-                        eprintln!("[ERROR] Parameter parsing failed for {}: {}", $method_name, e);
+                        let message = format!("Parameter parsing failed for {}: {}", $method_name, e);
+                        $crate::error_macros::log_error_lines(&message, vec![format!("[ERROR] {}", message)]);
                         $crate::communication::JsonRpcServer::error_response($request.id, enhanced_error)
                     }
                 }
             }
             None => {
-                eprintln!("[ERROR] Missing required parameters for method: {}", $method_name);
+                let message = format!("Missing required parameters for method: {}", $method_name);
+                $crate::error_macros::log_error_lines(&message, vec![format!("[ERROR] {}", message)]);
                 $crate::communication::JsonRpcServer::error_response(
                     $request.id,
                     $crate::communication::JsonRpcError::invalid_params()
@@ -229,6 +313,48 @@ macro_rules! handle_parameterized_method {
     }};
 }
 
+/// Macro for methods whose parameters may be left out. Missing or null params
+/// reach the call as `None`; params that fail to parse are rejected rather
+/// than treated as missing.
+#[macro_export]
+macro_rules! handle_optional_params_method {
+    (
+        $request:expr,
+        $param_type:ty,
+        $method_name:expr,
+        $operation:expr,
+        |$params:ident| $async_call:expr
+        $(, performance = $performance:expr)?
+    ) => {{
+        let parsed = match $request.params.filter(|params| !params.is_null()) {
+            Some(params) => serde_json::from_value::<$param_type>(params).map(Some),
+            None => Ok(None),
+        };
+        match parsed {
+            Ok($params) => {
+                let result = $async_call.await;
+                handle_jsonrpc_method!($request.id, $method_name, $operation, result $(, performance = $performance)?)
+            }
+            Err(e) => {
+                let context = $crate::error_macros::ErrorContext::new(
+                    &format!("Parse {} parameters", stringify!($param_type)),
+                    file!(),
+                    line!(),
+                    column!(),
+                    module_path!(),
+                )
+                .with_method($method_name)
+                .with_request_id($request.id.clone());
+                let error = anyhow::anyhow!("Parameter parsing failed: {}", e);
+                let enhanced_error = $crate::error_macros::create_enhanced_error(&error, &context, -32602);
+                let message = format!("Parameter parsing failed for {}: {}", $method_name, e);
+                $crate::error_macros::log_error_lines(&message, vec![format!("[ERROR] {}", message)]);
+                $crate::communication::JsonRpcServer::error_response($request.id, enhanced_error)
+            }
+        }
+    }};
+}
+
 /// Enhanced error logging that can be integrated with VSCode extension debug system
 /// Extended for server-side operations monitoring
 pub fn log_error_to_debug_channel(
@@ -548,6 +674,61 @@ mod tests {
         assert!(response.error.is_none());
     }
 
+    #[test]
+    fn test_performance_metrics_are_opt_in() {
+        let response = handle_jsonrpc_method!(
+            Some(json!(1)),
+            "search_tasks",
+            "Search tasks",
+            mock_successful_operation()
+        );
+        assert!(response.result.unwrap().get("_performance").is_none());
+
+        let response = handle_jsonrpc_method!(
+            Some(json!(1)),
+            "test_method",
+            "test_operation",
+            mock_successful_operation(),
+            performance = true
+        );
+        assert_eq!(response.result.unwrap()["_performance"]["operation"], "test_operation");
+    }
+
+    #[test]
+    fn test_repeated_errors_are_coalesced() {
+        let limiter = ErrorLogLimiter::new(Duration::from_secs(5));
+        let start = Instant::now();
+        let lines = || vec!["[ERROR] Operation 'scan' failed: boom".to_string()];
+
+        assert_eq!(limiter.filter_at("scan: boom", lines(), start).len(), 1);
+        for i in 1..=3 {
+            let output = limiter.filter_at("scan: boom", lines(), start + Duration::from_secs(i));
+            assert!(output.is_empty());
+        }
+
+        let output = limiter.filter_at("load: other", vec!["[ERROR] other".to_string()], start + Duration::from_secs(4));
+        assert_eq!(output, vec![
+            "[ERROR] scan: boom (repeated 3 times)".to_string(),
+            "[ERROR] other".to_string(),
+        ]);
+
+        let output = limiter.filter_at("load: other", vec!["[ERROR] other".to_string()], start + Duration::from_secs(10));
+        assert_eq!(output, vec!["[ERROR] other".to_string()]);
+    }
+
+    #[test]
+    fn test_repeated_macro_errors_still_return_responses() {
+        for _ in 0..3 {
+            let response = handle_jsonrpc_method!(
+                Some(json!(1)),
+                "test_method",
+                "test_operation",
+                mock_failing_operation()
+            );
+            assert!(response.error.is_some());
+        }
+    }
+
     #[test]
     fn test_macro_error_case() {
         let response = handle_jsonrpc_method!(
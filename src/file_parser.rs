@@ -1,7 +1,13 @@
-use crate::task_manager::{ProjectData, TaskStatus};
+use crate::identifier::IdentifierMode;
+use crate::task_manager::{IdRename, ProjectData, STATUS_ALIASES, TaskKey, TaskStatus};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+use std::sync::Mutex;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ParsedTaskLabel {
     pub section: String,
     pub task_id: String,
@@ -9,34 +15,190 @@ pub struct ParsedTaskLabel {
     pub description: Option<String>,
     pub note: Option<String>,
 }
+/// Parser behaviour switches
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParserConfig {
+    /// Map plain `// TODO:`, `// FIXME:` and `// HACK:` comments to tasks
+    /// in the `todo`, `fixme` and `hack` sections
+    pub recognize_keyword_comments: bool,
+    /// Grammar for section names and task IDs
+    pub identifier_mode: IdentifierMode,
+    /// Status words accepted on top of the built-in ones
+    pub status_aliases: HashMap<String, TaskStatus>,
+    /// Repository that `gh#123` tokens in label descriptions link to
+    pub repository_url: Option<String>,
+    /// Characters allowed, and dropped, right after the ID of a reference or
+    /// the status of a status update, as in `foo(); // dev:task_1.`
+    pub trailing_punctuation: String,
+    /// Lines longer than this many bytes, such as minified code, are not parsed
+    pub max_line_length: usize,
+    /// Comment syntax by file extension, on top of `default_comment_syntax`
+    pub comment_syntax: HashMap<String, Vec<CommentSyntax>>,
+}
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            recognize_keyword_comments: false,
+            identifier_mode: IdentifierMode::default(),
+            status_aliases: HashMap::new(),
+            repository_url: None,
+            trailing_punctuation: DEFAULT_TRAILING_PUNCTUATION.to_string(),
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            comment_syntax: HashMap::new(),
+        }
+    }
+}
+/// Comment form a file type carries its labels in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentSyntax {
+    /// `// section:task_id: ...`
+    Slashes,
+    /// `<!-- section:task_id: ... -->`
+    Html,
+    /// The file is not parsed for labels at all
+    Ignore,
+}
+impl CommentSyntax {
+    /// Regex alternation matching the comment openers labels follow in these
+    /// syntaxes, or `None` when such files are never read for labels
+    pub fn opener_pattern(syntax: &[CommentSyntax]) -> Option<String> {
+        if syntax.contains(&CommentSyntax::Ignore) {
+            return None;
+        }
+        let openers: Vec<&str> = syntax
+            .iter()
+            .filter_map(|syntax| match syntax {
+                CommentSyntax::Slashes => Some("//"),
+                CommentSyntax::Html => Some("<!--"),
+                CommentSyntax::Ignore => None,
+            })
+            .collect();
+        (!openers.is_empty()).then(|| format!("(?:{})", openers.join("|")))
+    }
+}
+/// Syntax for files whose extension has no entry in the mapping
+const FALLBACK_COMMENT_SYNTAX: &[CommentSyntax] = &[CommentSyntax::Slashes];
+/// Built-in comment syntax by lowercase extension; other extensions use `//`
+pub fn default_comment_syntax() -> HashMap<String, Vec<CommentSyntax>> {
+    [
+        ("md", vec![CommentSyntax::Html]),
+        ("markdown", vec![CommentSyntax::Html]),
+        ("html", vec![CommentSyntax::Html]),
+        ("htm", vec![CommentSyntax::Html]),
+        ("xml", vec![CommentSyntax::Html]),
+        ("vue", vec![CommentSyntax::Slashes, CommentSyntax::Html]),
+        ("svelte", vec![CommentSyntax::Slashes, CommentSyntax::Html]),
+    ]
+    .into_iter()
+    .map(|(extension, syntax)| (extension.to_string(), syntax))
+    .collect()
+}
+/// Punctuation tolerated after label IDs and statuses unless configured otherwise
+pub const DEFAULT_TRAILING_PUNCTUATION: &str = ".,;";
+/// Longest line parsed for labels unless configured otherwise
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 20_000;
 pub struct TaskParser {
     full_definition_regex: Regex,
     with_status_regex: Regex,
     simple_reference_regex: Regex,
     with_note_regex: Regex,
     status_update_regex: Regex,
+    keyword_comment_regex: Regex,
+    near_miss_regex: Regex,
+    block_comment_regex: Regex,
+    /// Aliases from the project config, on top of the ones `TaskStatus` parses
+    custom_status_aliases: HashMap<String, TaskStatus>,
+    comment_syntax: HashMap<String, Vec<CommentSyntax>>,
+    config: ParserConfig,
 }
 impl TaskParser {
     pub fn new() -> anyhow::Result<Self> {
+        Self::with_config(ParserConfig::default())
+    }
+    pub fn with_config(config: ParserConfig) -> anyhow::Result<Self> {
+        let id = config.identifier_mode.pattern();
+        let mut custom_status_aliases: HashMap<String, TaskStatus> = HashMap::new();
+        for (alias, status) in &config.status_aliases {
+            let alias = alias.trim().to_lowercase();
+            if !alias.is_empty() {
+                custom_status_aliases.insert(alias, status.clone());
+            }
+        }
+        // Longest first so that `in_progress` is not cut short by `progress`
+        let mut alternatives: Vec<&str> = STATUS_ALIASES
+            .iter()
+            .map(|(alias, _)| *alias)
+            .chain(custom_status_aliases.keys().map(String::as_str))
+            .collect();
+        alternatives.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        alternatives.dedup();
+        let alternatives = alternatives
+            .into_iter()
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join("|");
+        let trailing = if config.trailing_punctuation.is_empty() {
+            String::new()
+        } else {
+            format!("[{}]?", regex::escape(&config.trailing_punctuation))
+        };
+        let mut comment_syntax = default_comment_syntax();
+        for (extension, syntax) in &config.comment_syntax {
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            comment_syntax.insert(extension, syntax.clone());
+        }
         Ok(Self {
-            full_definition_regex: Regex::new(
-                r"//\s*([\p{L}_][\p{L}\p{N}_]*):([\p{L}_][\p{L}\p{N}_]*):\s+(.+)",
-            )?,
-            with_status_regex: Regex::new(
-                r"//\s*([\p{L}_][\p{L}\p{N}_]*):([\p{L}_][\p{L}\p{N}_]*):([\p{L}_][\p{L}\p{N}_]*):\s+(.+)",
-            )?,
-            simple_reference_regex: Regex::new(
-                r"//\s*([\p{L}_][\p{L}\p{N}_]*):([\p{L}_][\p{L}\p{N}_]*)\s*$",
-            )?,
-            with_note_regex: Regex::new(
-                r"//\s*([\p{L}_][\p{L}\p{N}_]*):([\p{L}_][\p{L}\p{N}_]*):([\p{L}\p{N}_]+)\s*$",
-            )?,
-            status_update_regex: Regex::new(
-                r"(?i)//\s*([\p{L}_][\p{L}\p{N}_]*):([\p{L}_][\p{L}\p{N}_]*):(todo|in_progress|inprogress|progress|done|completed|complete|blocked|block)\s*$",
+            full_definition_regex: Regex::new(&format!(r"//\s*({id}):({id}):\s+(.+)"))?,
+            with_status_regex: Regex::new(&format!(r"//\s*({id}):({id}):({id}):\s+(.+)"))?,
+            simple_reference_regex: Regex::new(&format!(r"//\s*({id}):({id}){trailing}\s*$"))?,
+            with_note_regex: Regex::new(&format!(r"//\s*({id}):({id}):([\p{{L}}\p{{N}}_]+)\s*$"))?,
+            status_update_regex: Regex::new(&format!(
+                r"(?i)//\s*({id}):({id}):({alternatives}){trailing}\s*$"
+            ))?,
+            keyword_comment_regex: Regex::new(r"//\s*(TODO|FIXME|HACK)(?:\([^)]*\))?:\s+(.+)")?,
+            near_miss_regex: Regex::new(
+                r"(?:^|\s)//\s*([\p{L}\p{N}_-]+):(\s*)([\p{L}\p{N}_-]+)(:?)(.*)$",
             )?,
+            block_comment_regex: Regex::new(&format!(r"^(?:/\*+|\*)\s*{id}:{id}\b"))?,
+            custom_status_aliases,
+            comment_syntax,
+            config,
+        })
+    }
+    /// Comment syntax labels are written in for a file, chosen by its extension
+    pub fn comment_syntax(&self, file_path: &str) -> &[CommentSyntax] {
+        Path::new(file_path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.comment_syntax.get(&extension.to_lowercase()))
+            .map_or(FALLBACK_COMMENT_SYNTAX, Vec::as_slice)
+    }
+    /// Parse a line as a label in any of the given comment syntaxes
+    pub fn parse_line_as(&self, line: &str, syntax: &[CommentSyntax]) -> Option<ParsedTaskLabel> {
+        if syntax.contains(&CommentSyntax::Ignore) {
+            return None;
+        }
+        syntax.iter().find_map(|syntax| match syntax {
+            CommentSyntax::Slashes => self.parse_line(line),
+            CommentSyntax::Html => self.parse_html_comment(line),
+            CommentSyntax::Ignore => None,
         })
     }
+    /// A label inside `<!-- ... -->`, read as if it followed `//`. An unclosed
+    /// comment runs to the end of the line.
+    fn parse_html_comment(&self, line: &str) -> Option<ParsedTaskLabel> {
+        if self.is_too_long(line) {
+            return None;
+        }
+        let (_, inner) = line.split_once("<!--")?;
+        let inner = inner.split_once("-->").map_or(inner, |(inner, _)| inner);
+        self.parse_line(&format!("// {}", inner.trim()))
+    }
     pub fn parse_line(&self, line: &str) -> Option<ParsedTaskLabel> {
+        if self.is_too_long(line) {
+            return None;
+        }
         let line = line.trim();
         if line.starts_with("/*")
             || line.ends_with("*/")
@@ -112,37 +274,329 @@ impl TaskParser {
                 note: None,
             });
         }
+        if self.config.recognize_keyword_comments {
+            return self.parse_keyword_comment(line);
+        }
 
         None
     }
+    /// Whether a line is past `max_line_length` and left unparsed
+    pub fn is_too_long(&self, line: &str) -> bool {
+        line.len() > self.config.max_line_length
+    }
+    /// Why a line that does not parse as a label probably meant to be one
+    pub fn near_miss(&self, line: &str) -> Option<(&'static str, String)> {
+        if self.is_too_long(line) || self.parse_line(line).is_some() {
+            return None;
+        }
+        let line = line.trim();
+        if self.block_comment_regex.is_match(line) {
+            return Some((
+                "block_comment",
+                "Labels are only recognized in // comments".to_string(),
+            ));
+        }
+        let captures = self.near_miss_regex.captures(line)?;
+        let section = captures.get(1)?.as_str();
+        let spacing = captures.get(2)?.as_str();
+        let task_id = captures.get(3)?.as_str();
+        let has_colon = !captures.get(4)?.as_str().is_empty();
+        let rest = captures.get(5)?.as_str();
+        if section.contains('-') || task_id.contains('-') {
+            return Some((
+                "hyphenated_id",
+                format!(
+                    "Use '{}:{}' - hyphens are not recognized",
+                    section.replace('-', "_"),
+                    task_id.replace('-', "_")
+                ),
+            ));
+        }
+        if !has_colon {
+            return None;
+        }
+        if !spacing.is_empty() {
+            return Some((
+                "space_after_section",
+                format!("Remove the space after '{}:'", section),
+            ));
+        }
+        let mode = self.config.identifier_mode;
+        if !mode.is_valid(section) || !mode.is_valid(task_id) {
+            return Some((
+                "invalid_identifier",
+                format!(
+                    "Section names and task IDs may contain only {}, and must not start with a digit",
+                    mode.describe()
+                ),
+            ));
+        }
+        if rest.trim().is_empty() {
+            return Some((
+                "missing_description",
+                "Add a description after the last colon, or remove it".to_string(),
+            ));
+        }
+        if !rest.starts_with(char::is_whitespace) {
+            return Some((
+                "missing_space",
+                "Put a space between the colon and the description".to_string(),
+            ));
+        }
+        None
+    }
+    /// Lines of `content` that look like mistyped labels
+    pub fn near_misses(&self, content: &str) -> Vec<LabelNearMiss> {
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        Self::split_lines(content)
+            .enumerate()
+            .filter_map(|(line_number, line)| {
+                let (rule, message) = self.near_miss(line)?;
+                Some(LabelNearMiss {
+                    line: line_number as u32 + 1,
+                    rule: rule.to_string(),
+                    message,
+                })
+            })
+            .collect()
+    }
+    fn parse_keyword_comment(&self, line: &str) -> Option<ParsedTaskLabel> {
+        let captures = self.keyword_comment_regex.captures(line)?;
+        let section = captures.get(1)?.as_str().to_lowercase();
+        let description = captures.get(2)?.as_str().trim().to_string();
+        let task_id = format!("{}_{:08x}", section, Self::stable_hash(&description));
+        Some(ParsedTaskLabel {
+            section,
+            task_id,
+            status: None,
+            description: Some(description),
+            note: None,
+        })
+    }
+    /// FNV-1a, so generated ids survive rescans and compiler upgrades
+    fn stable_hash(text: &str) -> u32 {
+        let mut hash: u32 = 0x811c9dc5;
+        for byte in text.as_bytes() {
+            hash ^= *byte as u32;
+            hash = hash.wrapping_mul(0x01000193);
+        }
+        hash
+    }
     fn parse_status(&self, status_str: &str) -> Option<TaskStatus> {
-        match status_str.to_lowercase().as_str() {
-            "todo" => Some(TaskStatus::Todo),
-            "in_progress" | "inprogress" | "progress" => Some(TaskStatus::InProgress),
-            "done" | "completed" | "complete" => Some(TaskStatus::Done),
-            "blocked" | "block" => Some(TaskStatus::Blocked),
-            _ => None,
+        let status_str = status_str.trim().to_lowercase();
+        match self.custom_status_aliases.get(&status_str) {
+            Some(status) => Some(status.clone()),
+            None => status_str.parse().ok(),
         }
     }
     pub fn scan_file(
         &self,
-        _file_path: &str,
+        file_path: &str,
         content: &str,
     ) -> anyhow::Result<Vec<(u32, ParsedTaskLabel)>> {
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let syntax = self.comment_syntax(file_path);
         let mut results = Vec::new();
-        for (line_number, line) in content.lines().enumerate() {
-            if let Some(parsed_label) = self.parse_line(line) {
+        if syntax.contains(&CommentSyntax::Ignore) {
+            return Ok(results);
+        }
+        for (line_number, line) in Self::split_lines(content).enumerate() {
+            if let Some(parsed_label) = self.parse_line_as(line, syntax) {
                 results.push((line_number as u32 + 1, parsed_label));
             }
         }
         Ok(results)
     }
+    /// Scan text line by line through a reused `line` buffer. Gives the same labels as
+    /// `scan_file` on the whole text, and fails on invalid UTF-8 as `read_to_string` would.
+    /// Lines too long to parse are listed in the result.
+    pub fn scan_reader<R: BufRead>(
+        &self,
+        reader: R,
+        line: &mut String,
+    ) -> std::io::Result<FileScan> {
+        self.scan_reader_as(reader, line, FALLBACK_COMMENT_SYNTAX)
+    }
+    /// `scan_reader` for labels written in the given comment syntaxes
+    pub fn scan_reader_as<R: BufRead>(
+        &self,
+        mut reader: R,
+        line: &mut String,
+        syntax: &[CommentSyntax],
+    ) -> std::io::Result<FileScan> {
+        let mut results = FileScan::default();
+        let mut line_number = 0;
+        let mut first = true;
+        loop {
+            line.clear();
+            if reader.read_line(line)? == 0 {
+                break;
+            }
+            let mut text = line.strip_suffix('\n').unwrap_or(line);
+            if first {
+                text = text.strip_prefix('\u{feff}').unwrap_or(text);
+                first = false;
+            }
+            for part in text.strip_suffix('\r').unwrap_or(text).split('\r') {
+                line_number += 1;
+                if self.is_too_long(part) {
+                    results.skipped_lines.push((line_number, part.len()));
+                } else if let Some(parsed_label) = self.parse_line_as(part, syntax) {
+                    results.labels.push((line_number, parsed_label));
+                }
+            }
+        }
+        Ok(results)
+    }
+    /// Rewrite `section:old_id` labels written in the given comment syntax,
+    /// returning the new text and the number of labels changed
+    pub fn rewrite_task_ids(
+        content: &str,
+        syntax: &[CommentSyntax],
+        renames: &[IdRename],
+    ) -> anyhow::Result<(String, usize)> {
+        let mut content = content.to_string();
+        let Some(opener) = CommentSyntax::opener_pattern(syntax) else {
+            return Ok((content, 0));
+        };
+        let mut changed = 0;
+        for rename in renames {
+            let label_regex = Regex::new(&format!(
+                r"(?m)({}\s*{}:){}([^\p{{L}}\p{{N}}_]|$)",
+                opener,
+                regex::escape(&rename.section),
+                regex::escape(&rename.old_id)
+            ))?;
+            changed += label_regex.find_iter(&content).count();
+            let replacement = format!("${{1}}{}${{2}}", rename.new_id);
+            content = label_regex
+                .replace_all(&content, replacement.as_str())
+                .into_owned();
+        }
+        Ok((content, changed))
+    }
+    /// Point every label for `section:task_id` in `content`, written in the
+    /// given comment syntax, at another section and ID
+    pub fn rewrite_task_reference(
+        content: &str,
+        syntax: &[CommentSyntax],
+        section: &str,
+        task_id: &str,
+        new_section: &str,
+        new_id: &str,
+    ) -> anyhow::Result<(String, usize)> {
+        let Some(opener) = CommentSyntax::opener_pattern(syntax) else {
+            return Ok((content.to_string(), 0));
+        };
+        let label_regex = Regex::new(&format!(
+            r"(?m)({}\s*){}:{}([^\p{{L}}\p{{N}}_]|$)",
+            opener,
+            regex::escape(section),
+            regex::escape(task_id)
+        ))?;
+        let changed = label_regex.find_iter(content).count();
+        let replacement = format!("${{1}}{}:{}${{2}}", new_section, new_id);
+        let content = label_regex
+            .replace_all(content, replacement.as_str())
+            .into_owned();
+        Ok((content, changed))
+    }
+    /// Find labels whose status marker disagrees with the stored task. Unless code
+    /// wins, their status is dropped so that applying the labels keeps the stored one.
+    pub fn reconcile_label_statuses(
+        project_data: &ProjectData,
+        file_path: &str,
+        labels: &mut [(u32, ParsedTaskLabel)],
+        mode: StatusReconciliation,
+    ) -> Vec<StatusDivergence> {
+        let mut divergences = Vec::new();
+        for (line, label) in labels.iter_mut() {
+            let Some(code_status) = label.status.clone() else {
+                continue;
+            };
+            let key = project_data.resolve_alias(&label.section, &label.task_id);
+            let (section, task_id) = key
+                .as_ref()
+                .map(|key| (key.section.as_str(), key.task_id.as_str()))
+                .unwrap_or((&label.section, &label.task_id));
+            let Some(task) = project_data.get_task(section, task_id) else {
+                continue;
+            };
+            if task.status == code_status {
+                continue;
+            }
+            divergences.push(StatusDivergence {
+                section: label.section.clone(),
+                task_id: label.task_id.clone(),
+                file: file_path.to_string(),
+                line: *line,
+                code_status,
+                stored_status: task.status.clone(),
+            });
+            if mode != StatusReconciliation::CodeWins {
+                label.status = None;
+            }
+        }
+        divergences
+    }
+    /// Set the status marker of the `section:task_id` label, written in the given
+    /// comment syntax, on a 1-based line. Returns `None` when that line holds no
+    /// such label with a status.
+    pub fn rewrite_label_status(
+        content: &str,
+        syntax: &[CommentSyntax],
+        line: u32,
+        section: &str,
+        task_id: &str,
+        status: &TaskStatus,
+    ) -> Option<String> {
+        let label_regex = Regex::new(&format!(
+            r"({}\s*{}:{}:)[\p{{L}}\p{{N}}_]+",
+            CommentSyntax::opener_pattern(syntax)?,
+            regex::escape(section),
+            regex::escape(task_id)
+        ))
+        .ok()?;
+        let mut lines: Vec<&str> = content.split_inclusive('\n').collect();
+        let target = lines.get_mut(line.checked_sub(1)? as usize)?;
+        if !label_regex.is_match(target) {
+            return None;
+        }
+        let replacement = format!("${{1}}{}", status.as_str());
+        let rewritten = label_regex
+            .replace(target, replacement.as_str())
+            .into_owned();
+        let mut result = String::with_capacity(content.len());
+        for (index, text) in lines.iter().enumerate() {
+            if index + 1 == line as usize {
+                result.push_str(&rewritten);
+            } else {
+                result.push_str(text);
+            }
+        }
+        Some(result)
+    }
+    /// Split on `\r\n`, `\n` and lone `\r`, matching how editors number lines
+    fn split_lines(content: &str) -> impl Iterator<Item = &str> {
+        content
+            .split('\n')
+            .flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+    }
     pub fn update_project_from_labels(
         &self,
         project_data: &mut ProjectData,
         file_path: &str,
         labels: Vec<(u32, ParsedTaskLabel)>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Vec<DescriptionConflict>> {
+        let mut conflicts = Vec::new();
+        let mut label_texts: HashMap<TaskKey, Vec<String>> = HashMap::new();
+        // Without a usable repository URL labels still sync, just without issue links;
+        // the scan reports the URL once
+        let issues_url = self
+            .config
+            .repository_url
+            .as_deref()
+            .and_then(|repository_url| issues_url(repository_url).ok());
         for (_section_name, section) in &mut project_data.sections {
             for (_task_id, task) in section {
                 if let Some(task_file) = task.files.get_mut(file_path) {
@@ -151,7 +605,20 @@ impl TaskParser {
                 }
             }
         }
-        for (line_number, label) in labels {
+        for (line_number, mut label) in labels {
+            // Labels still using a renamed task's old ID keep pointing at the task
+            if let Some(key) = project_data.resolve_alias(&label.section, &label.task_id) {
+                label.section = key.section;
+                label.task_id = key.task_id;
+            }
+            // A deleted task stays in the trash until restored, whatever its labels say
+            if project_data
+                .get_task(&label.section, &label.task_id)
+                .is_none()
+                && project_data.is_trashed(&label.section, &label.task_id)
+            {
+                continue;
+            }
             if let Some(description) = &label.description {
                 if project_data
                     .get_task(&label.section, &label.task_id)
@@ -164,9 +631,48 @@ impl TaskParser {
                         None,
                     )?;
                 }
+                if project_data.apply_label_text(
+                    &label.section,
+                    &label.task_id,
+                    file_path,
+                    description,
+                )? {
+                    let task = project_data.get_task(&label.section, &label.task_id);
+                    conflicts.push(DescriptionConflict {
+                        section: label.section.clone(),
+                        task_id: label.task_id.clone(),
+                        file: file_path.to_string(),
+                        line: line_number,
+                        label_text: description.clone(),
+                        current_title: task.map(|task| task.title.clone()).unwrap_or_default(),
+                    });
+                }
+                let texts = label_texts
+                    .entry(TaskKey::new(&label.section, &label.task_id))
+                    .or_default();
+                if !texts.contains(description) {
+                    texts.push(description.clone());
+                }
                 if let Some(status) = label.status.clone() {
                     project_data.update_task_status(&label.section, &label.task_id, status)?;
                 }
+                if let Some(issues_url) = &issues_url {
+                    for number in issue_numbers(description) {
+                        let url = format!("{}/{}", issues_url, number);
+                        // Links already attached keep the label they were given
+                        let attached = project_data
+                            .get_task(&label.section, &label.task_id)
+                            .is_some_and(|task| task.links.iter().any(|link| link.url == url));
+                        if !attached {
+                            project_data.add_task_link(
+                                &label.section,
+                                &label.task_id,
+                                &format!("gh#{}", number),
+                                &url,
+                            )?;
+                        }
+                    }
+                }
             }
             project_data.update_task_file(
                 &label.section,
@@ -181,8 +687,189 @@ impl TaskParser {
                 }
             }
         }
+        project_data.record_label_texts(file_path, label_texts);
+
+        Ok(conflicts)
+    }
+}
+/// Labels read from one file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileScan {
+    pub labels: Vec<(u32, ParsedTaskLabel)>,
+    /// Line numbers and byte lengths of lines skipped as too long
+    pub skipped_lines: Vec<(u32, usize)>,
+}
+/// Line buffers shared by scans, so reading a file reuses a buffer instead of
+/// allocating the whole file as a fresh `String`
+#[derive(Debug, Default)]
+pub struct ScanBufferPool {
+    buffers: Mutex<Vec<String>>,
+}
+impl ScanBufferPool {
+    /// Buffers grown past this by a very long line are dropped instead of kept
+    const MAX_RETAINED_CAPACITY: usize = 64 * 1024;
+    /// Buffers kept for reuse, one per concurrent worker
+    const MAX_POOLED_BUFFERS: usize = 8;
 
-        Ok(())
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Borrow a buffer, reusing a returned one when there is one
+    pub fn take(&self) -> String {
+        self.buffers
+            .lock()
+            .ok()
+            .and_then(|mut buffers| buffers.pop())
+            .unwrap_or_default()
+    }
+    /// Hand a buffer back for the next file
+    pub fn give_back(&self, mut buffer: String) {
+        if buffer.capacity() > Self::MAX_RETAINED_CAPACITY {
+            return;
+        }
+        buffer.clear();
+        if let Ok(mut buffers) = self.buffers.lock()
+            && buffers.len() < Self::MAX_POOLED_BUFFERS
+        {
+            buffers.push(buffer);
+        }
+    }
+    /// Read and scan a file through a pooled buffer
+    pub fn scan_path(&self, parser: &TaskParser, path: &Path) -> std::io::Result<FileScan> {
+        let syntax = parser.comment_syntax(&path.to_string_lossy());
+        if syntax.contains(&CommentSyntax::Ignore) {
+            return Ok(FileScan::default());
+        }
+        let file = std::fs::File::open(path)?;
+        let mut line = self.take();
+        let result = parser.scan_reader_as(std::io::BufReader::new(file), &mut line, syntax);
+        self.give_back(line);
+        result
+    }
+}
+/// Issues page of a repository URL such as `https://github.com/owner/repo`
+pub fn issues_url(repository_url: &str) -> anyhow::Result<String> {
+    let issues_url = format!("{}/issues", repository_url.trim().trim_end_matches('/'));
+    crate::task_manager::validate_link_url(&issues_url)?;
+    Ok(issues_url)
+}
+/// Issue numbers of the `gh#123` tokens in a label description
+pub fn issue_numbers(text: &str) -> Vec<u64> {
+    let mut numbers = Vec::new();
+    for (start, _) in text.match_indices("gh#") {
+        let preceded_by_word = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let digits: String = text[start + 3..]
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        if preceded_by_word || digits.is_empty() {
+            continue;
+        }
+        if let Ok(number) = digits.parse()
+            && !numbers.contains(&number)
+        {
+            numbers.push(number);
+        }
+    }
+    numbers
+}
+/// Validation problem found on a task label in source code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelDiagnostic {
+    pub file: String,
+    pub line: u32,
+    pub rule: String,
+    pub message: String,
+}
+/// Line that looks like a task label but is not recognized as one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabelNearMiss {
+    pub line: u32,
+    pub rule: String,
+    pub message: String,
+}
+/// Edited label text that a scan did not apply because the task was edited by hand
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DescriptionConflict {
+    pub section: String,
+    pub task_id: String,
+    pub file: String,
+    pub line: u32,
+    pub label_text: String,
+    /// Manually edited title that was kept
+    pub current_title: String,
+}
+/// Line left unparsed because it exceeds the configured maximum length
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedLine {
+    pub file: String,
+    pub line: u32,
+    /// Length of the line in bytes
+    pub length: usize,
+}
+/// Which side wins when a label's status marker disagrees with the stored status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusReconciliation {
+    /// The stored status follows the label. Every disagreement is still reported,
+    /// so an ordinary status edit in the code shows up as a divergence on the scan
+    /// that applies it.
+    #[default]
+    CodeWins,
+    /// The stored status is kept and the label's marker rewritten to match
+    JsonWins,
+    /// Neither side changes; the disagreement is only reported
+    ReportOnly,
+}
+/// Label whose status marker disagrees with the stored task, as written in the code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusDivergence {
+    pub section: String,
+    pub task_id: String,
+    pub file: String,
+    pub line: u32,
+    pub code_status: TaskStatus,
+    pub stored_status: TaskStatus,
+}
+/// Limits on how much a project scan reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Errors returned in a scan response; the rest are only logged
+    pub max_reported_errors: usize,
+    /// Files whose labels are listed on stdout during a scan
+    pub max_logged_files: u32,
+    /// Extra status words for labels, e.g. `"shipped": "done"`
+    pub status_aliases: HashMap<String, TaskStatus>,
+    /// Repository such as `https://github.com/owner/repo`; `gh#123` in a label
+    /// description then links the task to issue 123
+    pub repository_url: Option<String>,
+    /// Punctuation allowed after label IDs and statuses; empty to require the
+    /// label to end the line
+    pub trailing_punctuation: String,
+    /// Lines longer than this many bytes are skipped rather than parsed
+    pub max_line_length: usize,
+    /// Precedence when a label's status disagrees with the stored one
+    pub reconcile_status: StatusReconciliation,
+    /// Comment syntax by file extension, e.g. `"txt": ["ignore"]`; merged over
+    /// the built-in mapping, where `.md` and `.html` use `<!-- -->`
+    pub comment_syntax: HashMap<String, Vec<CommentSyntax>>,
+}
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            max_reported_errors: 100,
+            max_logged_files: 20,
+            status_aliases: HashMap::new(),
+            repository_url: None,
+            trailing_punctuation: DEFAULT_TRAILING_PUNCTUATION.to_string(),
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            reconcile_status: StatusReconciliation::default(),
+            comment_syntax: HashMap::new(),
+        }
     }
 }
 #[derive(Debug)]
@@ -190,7 +877,14 @@ pub struct ScanResult {
     pub files_scanned: u32,
     pub tasks_found: u32,
     pub tasks_removed: u32,
+    pub files_with_tasks: u32,
     pub errors: Vec<String>,
+    pub diagnostics: Vec<LabelDiagnostic>,
+    pub description_conflicts: Vec<DescriptionConflict>,
+    pub skipped_lines: Vec<SkippedLine>,
+    pub status_divergences: Vec<StatusDivergence>,
+    /// Workspace-relative paths of the files that were read
+    pub scanned_paths: Vec<String>,
 }
 impl ScanResult {
     pub fn new() -> Self {
@@ -198,8 +892,25 @@ impl ScanResult {
             files_scanned: 0,
             tasks_found: 0,
             tasks_removed: 0,
+            files_with_tasks: 0,
             errors: Vec::new(),
+            diagnostics: Vec::new(),
+            description_conflicts: Vec::new(),
+            skipped_lines: Vec::new(),
+            status_divergences: Vec::new(),
+            scanned_paths: Vec::new(),
+        }
+    }
+    /// Keep the first `limit` errors, followed by a summary of the rest.
+    /// Returns how many errors were dropped.
+    pub fn cap_errors(&mut self, limit: usize) -> u32 {
+        if self.errors.len() <= limit {
+            return 0;
         }
+        let omitted = self.errors.len() - limit;
+        self.errors.truncate(limit);
+        self.errors.push(format!("(+{} more)", omitted));
+        omitted as u32
     }
 }
 #[cfg(test)]
@@ -265,6 +976,103 @@ mod tests {
         assert_eq!(parsed.status, Some(TaskStatus::Done));
     }
     #[test]
+    fn test_issue_tokens_become_links() {
+        assert_eq!(
+            issue_numbers("Fix crash (gh#1234, see gh#7 and gh#1234)"),
+            vec![1234, 7]
+        );
+        assert!(issue_numbers("nogh#12 gh# gh#x").is_empty());
+
+        let mut project = ProjectData::new(None);
+        let labels = |parser: &TaskParser| {
+            vec![(
+                3,
+                parser
+                    .parse_line("// dev:crash:todo: Fix crash on save gh#42")
+                    .unwrap(),
+            )]
+        };
+        let parser = TaskParser::new().unwrap();
+        parser
+            .update_project_from_labels(&mut project, "main.rs", labels(&parser))
+            .unwrap();
+        assert!(project.get_task("dev", "crash").unwrap().links.is_empty());
+
+        let parser = TaskParser::with_config(ParserConfig {
+            repository_url: Some("https://github.com/acme/app/".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        for _ in 0..2 {
+            parser
+                .update_project_from_labels(&mut project, "main.rs", labels(&parser))
+                .unwrap();
+        }
+        let links = &project.get_task("dev", "crash").unwrap().links;
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].label, "gh#42");
+        assert_eq!(links[0].url, "https://github.com/acme/app/issues/42");
+
+        // A relabelled link keeps its label through rescans
+        project
+            .add_task_link(
+                "dev",
+                "crash",
+                "Crash report",
+                "https://github.com/acme/app/issues/42",
+            )
+            .unwrap();
+        parser
+            .update_project_from_labels(&mut project, "main.rs", labels(&parser))
+            .unwrap();
+        assert_eq!(
+            project.get_task("dev", "crash").unwrap().links[0].label,
+            "Crash report"
+        );
+
+        // A bad repository URL only skips the issue links
+        project
+            .remove_task_link("dev", "crash", "https://github.com/acme/app/issues/42")
+            .unwrap();
+        let parser = TaskParser::with_config(ParserConfig {
+            repository_url: Some("not a url".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        parser
+            .update_project_from_labels(&mut project, "main.rs", labels(&parser))
+            .unwrap();
+        let task = project.get_task("dev", "crash").unwrap();
+        assert_eq!(task.files["main.rs"].lines, vec![3]);
+        assert!(task.links.is_empty());
+        assert!(issues_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_aliases() {
+        let parser = TaskParser::new().unwrap();
+        let status_of = |line: &str| parser.parse_line(line).and_then(|parsed| parsed.status);
+        assert_eq!(status_of("// dev:t1:wip"), Some(TaskStatus::InProgress));
+        assert_eq!(status_of("// dev:t1:resolved"), Some(TaskStatus::Done));
+        assert_eq!(status_of("// dev:t1:Closed  "), Some(TaskStatus::Done));
+        assert_eq!(status_of("// dev:t1:on_hold"), Some(TaskStatus::Blocked));
+        assert_eq!(status_of("// dev:t1:waiting"), Some(TaskStatus::Blocked));
+        assert_eq!(
+            status_of("// dev:t1:in_progress"),
+            Some(TaskStatus::InProgress)
+        );
+        assert_eq!(status_of("// dev:t1:shipped"), None);
+
+        let parser = TaskParser::with_config(ParserConfig {
+            status_aliases: HashMap::from([("Shipped".to_string(), TaskStatus::Done)]),
+            ..Default::default()
+        })
+        .unwrap();
+        let parsed = parser.parse_line("// dev:t1:shipped").unwrap();
+        assert_eq!(parsed.status, Some(TaskStatus::Done));
+        assert_eq!(parsed.note, None);
+    }
+    #[test]
     fn test_scan_file() {
         let parser = TaskParser::new().unwrap();
         let content = r#"
@@ -282,4 +1090,82 @@ fn main() {
         assert_eq!(results[0].0, 3);
         assert_eq!(results[0].1.section, "dev");
     }
+    #[test]
+    fn test_keyword_comments_are_opt_in() {
+        let parser = TaskParser::new().unwrap();
+        assert!(parser.parse_line("// TODO: handle errors").is_none());
+        let parser = TaskParser::with_config(ParserConfig {
+            recognize_keyword_comments: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let parsed = parser.parse_line("// FIXME(bob): handle errors").unwrap();
+        assert_eq!(parsed.section, "fixme");
+        assert!(parsed.task_id.starts_with("fixme_"));
+        assert_eq!(parsed.description, Some("handle errors".to_string()));
+        let again = parser.parse_line("    // FIXME: handle errors").unwrap();
+        assert_eq!(parsed.task_id, again.task_id);
+        let label = parser
+            .parse_line("// dev:task_1: TODO: handle errors")
+            .unwrap();
+        assert_eq!(label.section, "dev");
+    }
+    #[test]
+    fn test_pooled_scan_matches_whole_file_scan() {
+        let parser = TaskParser::new().unwrap();
+        let dir = tempfile::TempDir::new().unwrap();
+        let files: &[(&str, &[u8])] = &[
+            ("lf.rs", b"// dev:a1: first\nfn x() {}\n// dev:a2\n"),
+            ("crlf.rs", b"// dev:b1: one\r\n\r\n// test:b2: two\r\n"),
+            ("cr.rs", b"// dev:c1\r// dev:c2: lone cr\r\r// dev:c3"),
+            ("bom.rs", "\u{feff}// dev:d1: bom\n// dev:d2".as_bytes()),
+            ("tail.rs", b"fn y() {}\n\n// dev:e1: no newline"),
+            ("empty.rs", b""),
+        ];
+        let pool = ScanBufferPool::new();
+        for (name, bytes) in files {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            let expected = parser
+                .scan_file(name, &std::fs::read_to_string(&path).unwrap())
+                .unwrap();
+            assert_eq!(
+                pool.scan_path(&parser, &path).unwrap().labels,
+                expected,
+                "{name}"
+            );
+        }
+        assert!(!pool.buffers.lock().unwrap().is_empty());
+
+        let invalid = dir.path().join("invalid.rs");
+        std::fs::write(&invalid, b"// dev:f1\n\xff\xfe\n").unwrap();
+        assert!(std::fs::read_to_string(&invalid).is_err());
+        assert!(pool.scan_path(&parser, &invalid).is_err());
+    }
+    #[test]
+    fn test_long_lines_are_skipped() {
+        let parser = TaskParser::with_config(ParserConfig {
+            max_line_length: 1_000,
+            ..ParserConfig::default()
+        })
+        .unwrap();
+        // A minified line full of label look-alikes, the worst case for the regexes
+        let minified = format!(
+            "var a=1;{}// dev:hidden: never parsed",
+            "// x:y:z ".repeat(500_000)
+        );
+        let content = format!("// dev:before: kept\n{}\n// dev:after: kept\n", minified);
+        let scan = parser
+            .scan_reader(std::io::Cursor::new(content.as_bytes()), &mut String::new())
+            .unwrap();
+        let ids: Vec<&str> = scan
+            .labels
+            .iter()
+            .map(|(_, label)| label.task_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["before", "after"]);
+        assert_eq!(scan.skipped_lines, vec![(2, minified.len())]);
+        assert_eq!(parser.scan_file("min.js", &content).unwrap().len(), 2);
+        assert!(parser.near_misses(&content).is_empty());
+    }
 }
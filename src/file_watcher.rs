@@ -1,6 +1,11 @@
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use tokio::sync::mpsc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Semaphore, mpsc};
+use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 #[derive(Debug, Clone)]
@@ -10,12 +15,22 @@ pub enum FileEvent {
     Deleted(PathBuf),
     Renamed { from: PathBuf, to: PathBuf },
 }
+impl FileEvent {
+    /// The path the event leaves behind; the destination for renames
+    pub fn path(&self) -> &Path {
+        match self {
+            FileEvent::Created(p) | FileEvent::Modified(p) | FileEvent::Deleted(p) => p,
+            FileEvent::Renamed { to, .. } => to,
+        }
+    }
+}
 #[derive(Debug, Clone)]
 pub struct WatcherConfig {
     pub file_patterns: Vec<String>,
     pub ignored_dirs: Vec<String>,
     pub max_file_size: u64,
     pub debounce_timeout: u64,
+    pub max_concurrent_rescans: usize,
 }
 impl Default for WatcherConfig {
     fn default() -> Self {
@@ -93,14 +108,43 @@ impl Default for WatcherConfig {
                 "dist".to_string(),
                 "build".to_string(),
                 "__pycache__".to_string(),
+                ".anchora".to_string(),
             ],
             max_file_size: 10 * 1024 * 1024,
             debounce_timeout: 500,
+            max_concurrent_rescans: 4,
         }
     }
 }
+impl WatcherConfig {
+    /// Whether events for `file_path` are worth a rescan: a watched file
+    /// type, outside the ignored directories and not over the size limit
+    pub fn should_process_file(&self, file_path: &Path) -> bool {
+        if let Ok(metadata) = std::fs::metadata(file_path) {
+            if metadata.len() > self.max_file_size {
+                return false;
+            }
+        }
+        for ignored_dir in &self.ignored_dirs {
+            if file_path.components().any(|component| {
+                component.as_os_str().to_str().unwrap_or("") == ignored_dir
+            }) {
+                return false;
+            }
+        }
+        if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
+            for pattern in &self.file_patterns {
+                if FileWatcher::matches_pattern(file_name, pattern) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
 pub struct FileWatcher {
     config: WatcherConfig,
+    rescan_scheduler: RescanScheduler,
     _event_tx: mpsc::UnboundedSender<FileEvent>,
     _watcher: RecommendedWatcher,
 }
@@ -122,8 +166,9 @@ impl FileWatcher {
             Config::default(),
         )?;
         watcher.watch(workspace_path, RecursiveMode::Recursive)?;
-        println!("Started watching directory: {:?}", workspace_path);
+        eprintln!("Started watching directory: {:?}", workspace_path);
         let file_watcher = Self {
+            rescan_scheduler: RescanScheduler::new(config.max_concurrent_rescans),
             config,
             _event_tx: event_tx,
             _watcher: watcher,
@@ -168,26 +213,7 @@ impl FileWatcher {
         }
     }
     pub fn should_process_file(&self, file_path: &Path) -> bool {
-        if let Ok(metadata) = std::fs::metadata(file_path) {
-            if metadata.len() > self.config.max_file_size {
-                return false;
-            }
-        }
-        for ignored_dir in &self.config.ignored_dirs {
-            if file_path.components().any(|component| {
-                component.as_os_str().to_str().unwrap_or("") == ignored_dir
-            }) {
-                return false;
-            }
-        }
-        if let Some(file_name) = file_path.file_name().and_then(|n| n.to_str()) {
-            for pattern in &self.config.file_patterns {
-                if Self::matches_pattern(file_name, pattern) {
-                    return true;
-                }
-            }
-        }
-        false
+        self.config.should_process_file(file_path)
     }
     fn matches_pattern(file_name: &str, pattern: &str) -> bool {
         if pattern == "**/*" {
@@ -206,12 +232,18 @@ impl FileWatcher {
         }
         file_name == pattern
     }
+    pub fn rescan_scheduler(&self) -> &RescanScheduler {
+        &self.rescan_scheduler
+    }
     pub fn get_stats(&self) -> WatcherStats {
         WatcherStats {
             file_patterns_count: self.config.file_patterns.len(),
             ignored_dirs_count: self.config.ignored_dirs.len(),
             max_file_size: self.config.max_file_size,
             debounce_timeout: self.config.debounce_timeout,
+            max_concurrent_rescans: self.config.max_concurrent_rescans,
+            rescan_queue_depth: self.rescan_scheduler.queue_depth(),
+            rescans_in_flight: self.rescan_scheduler.in_flight(),
         }
     }
 }
@@ -221,6 +253,68 @@ pub struct WatcherStats {
     pub ignored_dirs_count: usize,
     pub max_file_size: u64,
     pub debounce_timeout: u64,
+    pub max_concurrent_rescans: usize,
+    pub rescan_queue_depth: usize,
+    pub rescans_in_flight: usize,
+}
+/// Runs file rescans with bounded concurrency.
+///
+/// Paths waiting for a slot are coalesced, so a burst of events for the same
+/// file results in a single queued rescan.
+#[derive(Clone)]
+pub struct RescanScheduler {
+    semaphore: Arc<Semaphore>,
+    pending: Arc<Mutex<HashSet<PathBuf>>>,
+    in_flight: Arc<AtomicUsize>,
+}
+impl RescanScheduler {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            pending: Arc::new(Mutex::new(HashSet::new())),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    /// Queue a rescan of `path`. Returns `None` when the path is already queued.
+    pub fn schedule<F, Fut>(&self, path: PathBuf, rescan: F) -> Option<JoinHandle<()>>
+    where
+        F: FnOnce(PathBuf) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        {
+            let mut pending = self.pending.lock().ok()?;
+            if !pending.insert(path.clone()) {
+                return None;
+            }
+        }
+        let semaphore = self.semaphore.clone();
+        let pending = self.pending.clone();
+        let in_flight = self.in_flight.clone();
+        Some(tokio::spawn(async move {
+            let Ok(_permit) = semaphore.acquire_owned().await else {
+                return;
+            };
+            if let Ok(mut pending) = pending.lock() {
+                pending.remove(&path);
+            }
+            in_flight.fetch_add(1, Ordering::SeqCst);
+            let _in_flight = InFlightGuard(in_flight);
+            rescan(path).await;
+        }))
+    }
+    pub fn queue_depth(&self) -> usize {
+        self.pending.lock().map(|p| p.len()).unwrap_or(0)
+    }
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+}
+/// Decrements the in-flight count when a rescan ends, even if it panics
+struct InFlightGuard(Arc<AtomicUsize>);
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 pub struct EventDebouncer {
     timeout: Duration,
@@ -234,11 +328,7 @@ impl EventDebouncer {
         }
     }
     pub async fn add_event(&mut self, event: FileEvent) -> Option<Vec<FileEvent>> {
-        let path = match &event {
-            FileEvent::Created(p) | FileEvent::Modified(p) | FileEvent::Deleted(p) => p.clone(),
-            FileEvent::Renamed { to, .. } => to.clone(),
-        };
-        self.pending_events.insert(path, event);
+        self.insert(event);
         tokio::time::sleep(self.timeout).await;
         if !self.pending_events.is_empty() {
             let events: Vec<FileEvent> = self.pending_events.drain().map(|(_, event)| event).collect();
@@ -250,6 +340,42 @@ impl EventDebouncer {
     pub fn flush(&mut self) -> Vec<FileEvent> {
         self.pending_events.drain().map(|(_, event)| event).collect()
     }
+    /// Drive the debouncer from an event channel until it closes.
+    ///
+    /// Each event restarts the timeout; once the channel has been quiet for the
+    /// timeout, the latest event per path is handed to `on_flush` as one batch,
+    /// sorted by path. Whatever is pending when the channel closes is flushed too.
+    pub async fn run<F>(mut self, mut events: mpsc::UnboundedReceiver<FileEvent>, mut on_flush: F)
+    where
+        F: FnMut(Vec<FileEvent>),
+    {
+        while let Some(event) = events.recv().await {
+            self.insert(event);
+            let mut deadline = tokio::time::Instant::now() + self.timeout;
+            let closed = loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Some(event) => {
+                            self.insert(event);
+                            deadline = tokio::time::Instant::now() + self.timeout;
+                        }
+                        None => break true,
+                    },
+                    _ = tokio::time::sleep_until(deadline) => break false,
+                }
+            };
+            let mut batch = self.flush();
+            batch.sort_by(|a, b| a.path().cmp(b.path()));
+            on_flush(batch);
+            if closed {
+                return;
+            }
+        }
+    }
+    fn insert(&mut self, event: FileEvent) {
+        self.pending_events
+            .insert(event.path().to_path_buf(), event);
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -273,6 +399,7 @@ mod tests {
         let (tx, _rx) = mpsc::unbounded_channel();
         let dummy_watcher = RecommendedWatcher::new(|_| {}, Config::default()).unwrap();     
         let file_watcher = FileWatcher {
+            rescan_scheduler: RescanScheduler::new(config.max_concurrent_rescans),
             config,
             _event_tx: tx,
             _watcher: dummy_watcher,
@@ -295,4 +422,83 @@ mod tests {
         let result = timeout(Duration::from_millis(200), debouncer.add_event(event)).await;
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+    #[tokio::test(start_paused = true)]
+    async fn test_event_debouncer_run_flushes_quiet_batches() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let recorded = batches.clone();
+        let driver = tokio::spawn(EventDebouncer::new(100).run(rx, move |batch| {
+            let events: Vec<String> = batch
+                .iter()
+                .map(|event| format!("{:?}", event))
+                .collect();
+            recorded.lock().unwrap().push(events);
+        }));
+        let send = |event: FileEvent| tx.send(event).unwrap();
+
+        // A burst with gaps under the timeout coalesces into one batch
+        send(FileEvent::Modified(PathBuf::from("b.rs")));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        send(FileEvent::Created(PathBuf::from("a.rs")));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        send(FileEvent::Modified(PathBuf::from("a.rs")));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(batches.lock().unwrap().is_empty());
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(batches.lock().unwrap().len(), 1);
+
+        send(FileEvent::Deleted(PathBuf::from("c.rs")));
+        drop(tx);
+        driver.await.unwrap();
+        assert_eq!(
+            *batches.lock().unwrap(),
+            vec![
+                vec![
+                    r#"Modified("a.rs")"#.to_string(),
+                    r#"Modified("b.rs")"#.to_string()
+                ],
+                vec![r#"Deleted("c.rs")"#.to_string()],
+            ]
+        );
+    }
+    #[tokio::test]
+    async fn test_rescan_scheduler_limits_concurrency() {
+        let scheduler = RescanScheduler::new(3);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let executed = Arc::new(AtomicUsize::new(0));
+        let mut handles = Vec::new();
+        for i in 0..200 {
+            let path = PathBuf::from(format!("src/file_{}.rs", i % 20));
+            let running = running.clone();
+            let peak = peak.clone();
+            let executed = executed.clone();
+            if let Some(handle) = scheduler.schedule(path, move |_| async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                executed.fetch_add(1, Ordering::SeqCst);
+            }) {
+                handles.push(handle);
+            }
+        }
+        assert_eq!(scheduler.queue_depth(), 20);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(executed.load(Ordering::SeqCst), 20);
+        assert_eq!(scheduler.queue_depth(), 0);
+        assert_eq!(scheduler.in_flight(), 0);
+    }
+    #[tokio::test]
+    async fn test_rescan_scheduler_counts_panicked_rescans_as_done() {
+        let scheduler = RescanScheduler::new(1);
+        let handle = scheduler
+            .schedule(PathBuf::from("src/lib.rs"), |_| async { panic!("rescan failed") })
+            .unwrap();
+        assert!(handle.await.is_err());
+        assert_eq!(scheduler.in_flight(), 0);
+    }
+}
@@ -4,25 +4,257 @@
  * Contains the main business logic for handling JSON-RPC requests
  * and managing task operations.
  */
+use crate::method_docs;
+use crate::report::{self, ReportFilter, ReportFormat};
+use crate::storage::StagedWrites;
+use crate::timezone::{localize_response, parse_timezone};
 use crate::{
-    BasicResponse, CheckConflictsParams, CreateNoteParams, CreateNoteResponse, CreateTaskParams,
-    DeleteNoteParams, DeleteTaskParams, FindTaskReferencesParams, GenerateLinkParams,
-    GenerateLinkResponse, GetSuggestionsParams, GetTasksParams, JsonRpcError, JsonRpcHandler,
-    JsonRpcRequest, JsonRpcResponse, JsonRpcServer, Note, ScanProjectParams, ScanProjectResult,
-    SearchEngine, SearchQuery, SearchTasksParams, StatisticsManager, TaskParser, TaskReference,
-    TaskStatus, UpdateTaskStatusParams, ValidateTaskParams, ValidationEngine, ValidationParams,
-    file_parser,
+    AddTaskCommentParams, AddTaskLinkParams, AddTimeEntryParams, ApplyConflictResolutionParams,
+    ArchiveSectionParams, AssignedTask, BULK_APPLY_CONFIRM_THRESHOLD, BasicResponse,
+    BenchmarkScanParams, BenchmarkScanReport, BulkApplyParams, BulkApplyResult, ChangeSource,
+    ChangeType, CheckConflictsParams, CloneSectionParams, CompareSectionsParams,
+    CompletionForecast, ConflictResolutionResult, ConversionCandidate, ConvertNoteToTaskParams,
+    ConvertNoteToTaskResult, ConvertNoteToTasksParams, ConvertNoteToTasksResult,
+    CreateMilestoneParams, CreateNoteParams, CreateNoteResponse, CreateTaskFromTemplateParams,
+    CreateTaskParams, DeleteMilestoneParams, DeleteNoteParams, DeleteTaskCommentParams,
+    DeleteTaskParams, DescribeMethodParams, EditTaskTextParams, EmptyTrashParams,
+    ExportNotesParams, ExportStatisticsParams, ExportTasksParams, ExtensionTiming, FileDecoration,
+    FileEvent, FileTiming, FindTaskReferencesParams, GenerateLinkParams, GenerateLinkResponse,
+    GetDependentsParams, GetFilteredTasksParams, GetMyTasksParams, GetNoteParams,
+    GetNotesForTaskParams, GetNotesParams, GetSectionFilesParams, GetStatisticsParams,
+    GetSuggestionsParams, GetTagsParams, GetTaskDetailsParams, GetTaskHistoryParams,
+    GetTaskOverviewParams, GetTaskTreeParams, GetTasksParams, IdRename, ImportTasksParams,
+    InitializeParams, InitializeResult, JsonRpcError, JsonRpcHandler, JsonRpcRequest,
+    JsonRpcResponse, JsonRpcServer, MergeNotesParams, MilestoneTaskParams, NormalizeIdsParams,
+    NormalizeIdsResult, Note, NoteSearchQuery, NoteSearchResults, NoteTaskLinkParams, NoteUpdate,
+    NoteValidationParams, PROTOCOL_SCHEMA_VERSION, ParserConfig, PinNoteParams,
+    PreviewFileLabelsParams, PreviewFileLabelsResult, PreviewedLabel, ProjectValidationReport,
+    PurgeConvertedNotesParams, PurgeConvertedNotesResult, RemoveTaskLinkParams,
+    RemoveTaskMetadataParams, RenameFileReferencesParams, RenameFileReferencesResult,
+    ReorderTaskParams, ReorderTaskResult, ResolutionAction, RestoreTaskParams,
+    SaveTaskTemplateParams, ScanFilesParams, ScanFilesResult, ScanProjectParams, ScanProjectResult,
+    SearchEngine, SearchNotesParams, SearchQuery, SearchTasksParams, SectionComparison,
+    SectionFile, SectionOrder, SetTaskAssigneeParams, SetTaskEstimateParams, SetTaskMetadataParams,
+    SetTaskParentParams, SetTaskPriorityParams, StaleReference, StatisticsFilter,
+    StatisticsManager, TagKind, TagUsage, TaskComment, TaskDependenciesResult,
+    TaskDependencyParams, TaskExistsParams, TaskExistsResponse, TaskKey, TaskLinksResult,
+    TaskParser, TaskPriority, TaskReference, TaskStatus, TaskTagsParams, TaskTagsResult,
+    TaskTemplate, TaskTreeNode, TaskUpdate, UpdateNoteParams, UpdateTaskStatusParams,
+    ValidateProjectParams, ValidateTaskParams, ValidateTasksBatchParams, ValidationEngine,
+    ValidationFailed, ValidationParams, VerifyReferencesParams, VerifyReferencesResult,
+    file_parser, file_parser::ScanBufferPool, normalize_tag, search_notes,
+};
+use crate::{
+    handle_jsonrpc_method, handle_optional_params_method, handle_parameterized_method,
+    handle_simple_method,
 };
-use crate::{handle_jsonrpc_method, handle_parameterized_method, handle_simple_method};
 use chrono;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, MutexGuard};
+use tokio::task::JoinHandle;
+/// History entries included in `get_task_details`, newest first
+const TASK_DETAILS_HISTORY_LIMIT: usize = 20;
+/// Lines a `verify_references` repair looks around the recorded line by default
+const DEFAULT_REPAIR_DISTANCE: u32 = 50;
+/// Source files scanned when the client does not pass its own patterns
+fn default_file_patterns() -> Vec<String> {
+    [
+        "**/*.rs",
+        "**/*.ts",
+        "**/*.js",
+        "**/*.py",
+        "**/*.java",
+        "**/*.cpp",
+        "**/*.c",
+        "**/*.h",
+        "**/*.hpp",
+        "**/*.cc",
+        "**/*.cxx",
+        "**/*.go",
+        "**/*.php",
+        "**/*.rb",
+        "**/*.swift",
+        "**/*.kt",
+        "**/*.scala",
+        "**/*.cs",
+        "**/*.fs",
+        "**/*.vb",
+        "**/*.dart",
+        "**/*.elm",
+        "**/*.hs",
+        "**/*.ml",
+        "**/*.clj",
+        "**/*.ex",
+        "**/*.exs",
+        "**/*.erl",
+        "**/*.jl",
+        "**/*.r",
+        "**/*.m",
+        "**/*.mm",
+        "**/*.pl",
+        "**/*.pm",
+        "**/*.lua",
+        "**/*.sh",
+        "**/*.ps1",
+        "**/*.bat",
+        "**/*.cmd",
+        "**/*.jsx",
+        "**/*.tsx",
+        "**/*.vue",
+        "**/*.svelte",
+        "**/*.sql",
+        "**/*.yaml",
+        "**/*.yml",
+        "**/*.toml",
+        "**/*.ini",
+        "**/*.cfg",
+        "**/*.conf",
+        "**/*.dockerfile",
+        "**/*.tf",
+        "**/*.hcl",
+        "**/*.json",
+        "**/*.xml",
+        "**/*.html",
+        "**/*.css",
+        "**/*.scss",
+        "**/*.sass",
+        "**/*.less",
+        "**/*.md",
+        "**/*.rst",
+        "**/*.tex",
+    ]
+    .iter()
+    .map(|pattern| pattern.to_string())
+    .collect()
+}
+
+/// Serialized task listing, with each section's task IDs by rank when `sort_by` is `"manual"`
+fn tasks_response(
+    project_data: &crate::ProjectData,
+    sort_by: Option<&str>,
+    order: SectionOrder,
+) -> anyhow::Result<serde_json::Value> {
+    let mut response = serde_json::to_value(project_data)?;
+    response["section_order"] = serde_json::json!(project_data.ordered_section_names(order));
+    match sort_by {
+        None => {}
+        Some("manual") => {
+            let order: serde_json::Map<String, serde_json::Value> = project_data
+                .sections
+                .keys()
+                .map(|section| {
+                    (
+                        section.clone(),
+                        serde_json::json!(project_data.ranked_task_ids(section)),
+                    )
+                })
+                .collect();
+            response["order"] = serde_json::Value::Object(order);
+        }
+        Some(other) => return Err(anyhow::anyhow!("Unsupported sort_by: {}", other)),
+    }
+    Ok(response)
+}
+
+/// Milliseconds since `start`, with sub-millisecond precision
+fn elapsed_ms(start: std::time::Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Replace a file through a temporary sibling, so it is never left half written
+async fn write_file_atomically(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary = path.with_file_name(format!(".{}.anchora-tmp", file_name));
+    tokio::fs::write(&temporary, content).await?;
+    if let Ok(metadata) = tokio::fs::metadata(path).await {
+        tokio::fs::set_permissions(&temporary, metadata.permissions()).await?;
+    }
+    if let Err(e) = tokio::fs::rename(&temporary, path).await {
+        let _ = tokio::fs::remove_file(&temporary).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Directories never descended into while scanning
+const IGNORED_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    ".git",
+    ".vscode",
+    ".anchora",
+    "dist",
+    "build",
+    "__pycache__",
+    ".idea",
+    "out",
+];
+
+/// Settings shared by every level of a directory scan
+struct ScanOptions {
+    /// Paths in results and errors are relative to this directory
+    workspace_root: PathBuf,
+    file_patterns: Vec<String>,
+    /// Files whose labels are listed on stdout before the output is summarized
+    max_logged_files: u32,
+    reconcile_status: file_parser::StatusReconciliation,
+}
+
+/// Timings collected while a scan is benchmarked
+#[derive(Default)]
+struct ScanProfile {
+    files: Vec<FileProfile>,
+    update_ms: f64,
+}
+
+struct FileProfile {
+    relative_path: String,
+    extension: String,
+    bytes: u64,
+    tasks_found: u32,
+    parse_ms: f64,
+}
+
+/// Bring the search index up to date with storage; `force` rebuilds even when the
+/// tasks file is unchanged. Returns whether the index was rebuilt.
+async fn refresh_search_index(
+    storage: &crate::StorageManager,
+    search_engine: &SearchEngine,
+    force: bool,
+) -> anyhow::Result<bool> {
+    let config = storage.load_config().await?;
+    search_engine.apply_config(&config.search)?;
+    let version = storage.data_version().await;
+    if !force && search_engine.indexed_version() == Some(version) {
+        return Ok(false);
+    }
+    let project_data = storage.load_project_data().await?;
+    search_engine.index_project_at(&project_data, version)?;
+    Ok(true)
+}
+
+/// A task's status and its sorted reference lines per scanned file
+type ReferenceSnapshot = (TaskStatus, Vec<(String, Vec<u32>)>);
+
 pub struct TaskManagerHandler {
+    workspace_path: PathBuf,
     storage: Arc<crate::StorageManager>,
     parser: Arc<TaskParser>,
     search_engine: Arc<SearchEngine>,
     statistics_manager: Arc<StatisticsManager>,
     validation_engine: Arc<ValidationEngine>,
+    /// Line buffers reused across the files of every scan
+    scan_buffers: ScanBufferPool,
+    /// Reject every method except `initialize` until the client has called it
+    require_initialize: bool,
+    initialized: AtomicBool,
+    /// Held by each RPC call and each watch loop pass, so neither saves
+    /// tasks.json over changes the other made after its load
+    mutations: Mutex<()>,
 }
 impl TaskManagerHandler {
     pub fn new(workspace_path: PathBuf) -> anyhow::Result<Self> {
@@ -32,95 +264,192 @@ impl TaskManagerHandler {
         let statistics_manager = Arc::new(StatisticsManager::new(None));
         let validation_engine = Arc::new(ValidationEngine::new(None));
         Ok(Self {
+            workspace_path,
             storage,
             parser,
             search_engine,
             statistics_manager,
             validation_engine,
+            scan_buffers: ScanBufferPool::new(),
+            require_initialize: false,
+            initialized: AtomicBool::new(false),
+            mutations: Mutex::new(()),
+        })
+    }
+    /// Require clients to call `initialize` before any other method
+    pub fn require_initialize(mut self, required: bool) -> Self {
+        self.require_initialize = required;
+        self
+    }
+    pub fn workspace_path(&self) -> &Path {
+        &self.workspace_path
+    }
+    /// Keep RPC calls from touching tasks.json until the guard is dropped;
+    /// `handle_request` takes it for every call
+    pub async fn lock_mutations(&self) -> MutexGuard<'_, ()> {
+        self.mutations.lock().await
+    }
+    async fn initialize(&self, params: InitializeParams) -> anyhow::Result<InitializeResult> {
+        eprintln!(
+            "Client {} initialized with capabilities: {:?}",
+            params.client_version, params.capabilities
+        );
+        self.initialized.store(true, Ordering::SeqCst);
+        Ok(InitializeResult {
+            server_version: crate::VERSION.to_string(),
+            schema_version: PROTOCOL_SCHEMA_VERSION,
+            supported_methods: method_docs::METHODS
+                .iter()
+                .map(|method| method.name.to_string())
+                .collect(),
         })
     }
+    /// Method names with their one-line descriptions
+    async fn list_methods(&self) -> anyhow::Result<serde_json::Value> {
+        let methods: Vec<serde_json::Value> = method_docs::METHODS
+            .iter()
+            .map(|method| {
+                serde_json::json!({
+                    "name": method.name,
+                    "description": method.description
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({ "methods": methods }))
+    }
+    /// Parameters and description of one method
+    async fn describe_method(
+        &self,
+        params: DescribeMethodParams,
+    ) -> anyhow::Result<&'static method_docs::MethodDoc> {
+        method_docs::describe(&params.name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown method: {}", params.name))
+    }
+    /// Whether opted-in methods get `_performance` timings, per the project config
+    async fn performance_metrics_enabled(&self) -> bool {
+        self.storage
+            .load_config()
+            .await
+            .map(|config| config.responses.performance_metrics)
+            .unwrap_or(true)
+    }
+    /// Order sections are listed in by responses and exports
+    async fn section_order(&self) -> anyhow::Result<SectionOrder> {
+        Ok(self.storage.load_config().await?.responses.section_order)
+    }
+    /// Bring the validation engine up to date, reloading project data only after a save
+    async fn refresh_validation(&self) -> anyhow::Result<()> {
+        let config = self.storage.load_config().await?;
+        self.validation_engine.apply_config(config.validation)?;
+        let version = self.storage.data_version().await;
+        if self.validation_engine.context_version() == Some(version) {
+            return Ok(());
+        }
+        let project_data = self.storage.load_project_data().await?;
+        self.validation_engine
+            .update_context_at(project_data, version)
+    }
+    /// Start the periodic index rebuild configured by `search.rebuild_interval_ms`,
+    /// so the index follows the tasks file between requests. It only rebuilds
+    /// when the file changed.
+    pub async fn spawn_index_rebuilder(&self) -> anyhow::Result<Option<JoinHandle<()>>> {
+        let config = self.storage.load_config().await?;
+        let Some(interval_ms) = config.search.rebuild_interval_ms.filter(|ms| *ms > 0) else {
+            return Ok(None);
+        };
+        let storage = self.storage.clone();
+        let search_engine = self.search_engine.clone();
+        Ok(Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let started = Instant::now();
+                match refresh_search_index(&storage, &search_engine, false).await {
+                    Ok(true) => eprintln!(
+                        "[INFO] Background search index rebuild took {:?}",
+                        started.elapsed()
+                    ),
+                    Ok(false) => {}
+                    Err(e) => eprintln!("[WARN] Background search index rebuild failed: {}", e),
+                }
+            }
+        })))
+    }
+    /// Parser matching the project config, reusing the shared one when nothing differs
+    async fn configured_parser(
+        &self,
+        recognize_keyword_comments: bool,
+    ) -> anyhow::Result<Arc<TaskParser>> {
+        let project_config = self.storage.load_config().await?;
+        let parser_config = ParserConfig {
+            recognize_keyword_comments,
+            identifier_mode: project_config.validation.identifier_mode,
+            status_aliases: project_config.scan.status_aliases,
+            repository_url: project_config.scan.repository_url,
+            trailing_punctuation: project_config.scan.trailing_punctuation,
+            max_line_length: project_config.scan.max_line_length,
+            comment_syntax: project_config.scan.comment_syntax,
+        };
+        if parser_config == ParserConfig::default() {
+            Ok(self.parser.clone())
+        } else {
+            Ok(Arc::new(TaskParser::with_config(parser_config)?))
+        }
+    }
     pub async fn scan_project(
         &self,
         params: ScanProjectParams,
     ) -> anyhow::Result<ScanProjectResult> {
         let workspace_path = PathBuf::from(&params.workspace_path);
         let mut project_data = self.storage.load_project_data().await?;
+        let before_scan = project_data.sections.clone();
         let mut scan_result = file_parser::ScanResult::new();
-        let file_patterns = params.file_patterns.unwrap_or_else(|| {
-            vec![
-                "**/*.rs".to_string(),
-                "**/*.ts".to_string(),
-                "**/*.js".to_string(),
-                "**/*.py".to_string(),
-                "**/*.java".to_string(),
-                "**/*.cpp".to_string(),
-                "**/*.c".to_string(),
-                "**/*.h".to_string(),
-                "**/*.hpp".to_string(),
-                "**/*.cc".to_string(),
-                "**/*.cxx".to_string(),
-                "**/*.go".to_string(),
-                "**/*.php".to_string(),
-                "**/*.rb".to_string(),
-                "**/*.swift".to_string(),
-                "**/*.kt".to_string(),
-                "**/*.scala".to_string(),
-                "**/*.cs".to_string(),
-                "**/*.fs".to_string(),
-                "**/*.vb".to_string(),
-                "**/*.dart".to_string(),
-                "**/*.elm".to_string(),
-                "**/*.hs".to_string(),
-                "**/*.ml".to_string(),
-                "**/*.clj".to_string(),
-                "**/*.ex".to_string(),
-                "**/*.exs".to_string(),
-                "**/*.erl".to_string(),
-                "**/*.jl".to_string(),
-                "**/*.r".to_string(),
-                "**/*.m".to_string(),
-                "**/*.mm".to_string(),
-                "**/*.pl".to_string(),
-                "**/*.pm".to_string(),
-                "**/*.lua".to_string(),
-                "**/*.sh".to_string(),
-                "**/*.ps1".to_string(),
-                "**/*.bat".to_string(),
-                "**/*.cmd".to_string(),
-                "**/*.jsx".to_string(),
-                "**/*.tsx".to_string(),
-                "**/*.vue".to_string(),
-                "**/*.svelte".to_string(),
-                "**/*.sql".to_string(),
-                "**/*.yaml".to_string(),
-                "**/*.yml".to_string(),
-                "**/*.toml".to_string(),
-                "**/*.ini".to_string(),
-                "**/*.cfg".to_string(),
-                "**/*.conf".to_string(),
-                "**/*.dockerfile".to_string(),
-                "**/*.tf".to_string(),
-                "**/*.hcl".to_string(),
-                "**/*.json".to_string(),
-                "**/*.xml".to_string(),
-                "**/*.html".to_string(),
-                "**/*.css".to_string(),
-                "**/*.scss".to_string(),
-                "**/*.sass".to_string(),
-                "**/*.less".to_string(),
-                "**/*.md".to_string(),
-                "**/*.rst".to_string(),
-                "**/*.tex".to_string(),
-            ]
-        });
+        let parser = self
+            .configured_parser(params.recognize_keyword_comments.unwrap_or(false))
+            .await?;
+        let file_patterns = params.file_patterns.unwrap_or_else(default_file_patterns);
+        let scan_config = self.storage.load_config().await?.scan;
+        scan_result
+            .errors
+            .extend(Self::repository_url_error(&scan_config));
+        let options = ScanOptions {
+            workspace_root: workspace_path.clone(),
+            file_patterns,
+            max_logged_files: scan_config.max_logged_files,
+            reconcile_status: params
+                .reconcile_status
+                .unwrap_or(scan_config.reconcile_status),
+        };
         self.scan_directory_recursive(
+            &parser,
             &workspace_path,
-            &workspace_path,
-            &file_patterns,
+            &options,
             &mut project_data,
             &mut scan_result,
+            None,
         )
         .await?;
+        if scan_result.files_with_tasks > options.max_logged_files {
+            eprintln!(
+                "Found tasks in {} more files",
+                scan_result.files_with_tasks - options.max_logged_files
+            );
+        }
+        if !scan_result.skipped_lines.is_empty() {
+            eprintln!(
+                "Skipped {} lines longer than {} bytes",
+                scan_result.skipped_lines.len(),
+                scan_config.max_line_length
+            );
+        }
+        let mut converted_notes = self
+            .convert_pasted_notes(&mut project_data, &scan_result.scanned_paths)
+            .await?;
+        converted_notes.sort();
+        if !converted_notes.is_empty() {
+            eprintln!("Converted {} notes to tasks", converted_notes.len());
+        }
         let mut tasks_to_remove = Vec::new();
         for (section_name, section) in &project_data.sections {
             for (task_id, task) in section {
@@ -138,111 +467,674 @@ impl TaskManagerHandler {
             let _ = project_data.delete_task(section, task_id);
             scan_result.tasks_removed += 1;
         }
+        if params.validate_labels.unwrap_or(false) {
+            self.refresh_validation().await?;
+            scan_result.diagnostics = self.label_diagnostics(&project_data);
+        }
+        project_data.record_changes_since(&before_scan, ChangeSource::Scan);
         project_data.rebuild_index();
         self.storage.save_project_data(&project_data).await?;
+        self.record_note_conversions(&project_data, &converted_notes)?;
+        for error in &scan_result.errors {
+            eprintln!("[ERROR] {}", error);
+        }
+        let errors_omitted = scan_result.cap_errors(scan_config.max_reported_errors);
         Ok(ScanProjectResult {
             files_scanned: scan_result.files_scanned,
             tasks_found: scan_result.tasks_found,
             tasks_removed: scan_result.tasks_removed,
             errors: scan_result.errors,
+            errors_omitted,
+            diagnostics: scan_result.diagnostics,
+            description_conflicts: scan_result.description_conflicts,
+            converted_notes,
+            skipped_lines: scan_result.skipped_lines,
+            status_divergences: scan_result.status_divergences,
+        })
+    }
+    /// Run a scan without saving and report where the time goes
+    async fn benchmark_scan(
+        &self,
+        params: Option<BenchmarkScanParams>,
+    ) -> anyhow::Result<BenchmarkScanReport> {
+        let params = params.unwrap_or_default();
+        let started = std::time::Instant::now();
+        let workspace_path = params
+            .workspace_path
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.workspace_path.clone());
+        let file_patterns = params.file_patterns.unwrap_or_else(default_file_patterns);
+        let parser = self
+            .configured_parser(params.recognize_keyword_comments.unwrap_or(false))
+            .await?;
+        let mut project_data = self.storage.load_project_data().await?;
+        // Labels are left as they are, since a benchmark must not write anything
+        let options = ScanOptions {
+            workspace_root: workspace_path.clone(),
+            file_patterns,
+            max_logged_files: 0,
+            reconcile_status: file_parser::StatusReconciliation::ReportOnly,
+        };
+        let mut scan_result = file_parser::ScanResult::new();
+        let mut profile = ScanProfile::default();
+        let walk_started = std::time::Instant::now();
+        self.scan_directory_recursive(
+            &parser,
+            &workspace_path,
+            &options,
+            &mut project_data,
+            &mut scan_result,
+            Some(&mut profile),
+        )
+        .await?;
+        let walk_ms = elapsed_ms(walk_started);
+
+        let parse_ms: f64 = profile.files.iter().map(|file| file.parse_ms).sum();
+        let mut report = BenchmarkScanReport {
+            files_scanned: scan_result.files_scanned,
+            tasks_found: scan_result.tasks_found,
+            total_ms: 0.0,
+            enumeration_ms: (walk_ms - parse_ms - profile.update_ms).max(0.0),
+            parse_ms,
+            update_ms: profile.update_ms,
+            by_extension: Vec::new(),
+            slowest_files: Vec::new(),
+            errors: scan_result.errors,
+        };
+        let mut by_extension: HashMap<String, ExtensionTiming> = HashMap::new();
+        let mut file_timings = Vec::new();
+        for file in profile.files {
+            let timing = by_extension
+                .entry(file.extension.clone())
+                .or_insert_with(|| ExtensionTiming {
+                    extension: file.extension,
+                    files: 0,
+                    tasks_found: 0,
+                    parse_ms: 0.0,
+                });
+            timing.files += 1;
+            timing.tasks_found += file.tasks_found;
+            timing.parse_ms += file.parse_ms;
+            file_timings.push(FileTiming {
+                file: file.relative_path,
+                bytes: file.bytes,
+                parse_ms: file.parse_ms,
+            });
+        }
+
+        file_timings.sort_by(|a, b| b.parse_ms.total_cmp(&a.parse_ms));
+        file_timings.truncate(params.slowest_limit.unwrap_or(10));
+        report.slowest_files = file_timings;
+        report.by_extension = by_extension.into_values().collect();
+        report
+            .by_extension
+            .sort_by(|a, b| b.parse_ms.total_cmp(&a.parse_ms));
+        report.total_ms = elapsed_ms(started);
+        Ok(report)
+    }
+    /// Rescan only the given files and report which tasks changed as a result
+    pub async fn scan_files(&self, params: ScanFilesParams) -> anyhow::Result<ScanFilesResult> {
+        let relative_paths = params
+            .files
+            .iter()
+            .map(|file| self.workspace_relative_path(file))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut project_data = self.storage.load_project_data().await?;
+        let parser = self
+            .configured_parser(params.recognize_keyword_comments.unwrap_or(false))
+            .await?;
+        let mut result = ScanFilesResult {
+            files_scanned: 0,
+            tasks_added: Vec::new(),
+            tasks_updated: Vec::new(),
+            tasks_removed: Vec::new(),
+            errors: Vec::new(),
+            description_conflicts: Vec::new(),
+            converted_notes: Vec::new(),
+            skipped_lines: Vec::new(),
+            status_divergences: Vec::new(),
+        };
+        let scan_config = self.storage.load_config().await?.scan;
+        result
+            .errors
+            .extend(Self::repository_url_error(&scan_config));
+        let reconcile_status = params
+            .reconcile_status
+            .unwrap_or(scan_config.reconcile_status);
+        let before = Self::reference_snapshot(&project_data, &relative_paths);
+        let before_scan = project_data.sections.clone();
+
+        for relative_path in &relative_paths {
+            let scan = match self
+                .scan_buffers
+                .scan_path(&parser, &self.workspace_path.join(relative_path))
+            {
+                Ok(scan) => scan,
+                // A deleted file simply drops all of its references
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Default::default(),
+                Err(e) => {
+                    result
+                        .errors
+                        .push(format!("Failed to read file {}: {}", relative_path, e));
+                    continue;
+                }
+            };
+            result.files_scanned += 1;
+            result
+                .skipped_lines
+                .extend(scan.skipped_lines.into_iter().map(|(line, length)| {
+                    file_parser::SkippedLine {
+                        file: relative_path.clone(),
+                        line,
+                        length,
+                    }
+                }));
+            let mut labels = scan.labels;
+            match Self::reconcile_statuses(
+                &parser,
+                &project_data,
+                &self.workspace_path.join(relative_path),
+                relative_path,
+                &mut labels,
+                reconcile_status,
+            )
+            .await
+            {
+                Ok(divergences) => result.status_divergences.extend(divergences),
+                Err(e) => result.errors.push(format!(
+                    "Failed to rewrite status labels in {}: {}",
+                    relative_path, e
+                )),
+            }
+            match parser.update_project_from_labels(&mut project_data, relative_path, labels) {
+                Ok(conflicts) => result.description_conflicts.extend(conflicts),
+                Err(e) => result.errors.push(format!(
+                    "Error updating project data for {}: {}",
+                    relative_path, e
+                )),
+            }
+        }
+
+        result.converted_notes = self
+            .convert_pasted_notes(&mut project_data, &relative_paths)
+            .await?;
+        result.converted_notes.sort();
+        if !result.converted_notes.is_empty() {
+            eprintln!("Converted {} notes to tasks", result.converted_notes.len());
+        }
+
+        for ((section, task_id), (_, references)) in &before {
+            let referenced_before = references.iter().any(|(_, lines)| !lines.is_empty());
+            let orphaned = project_data
+                .get_task(section, task_id)
+                .is_some_and(|task| task.files.values().all(|file| file.lines.is_empty()));
+            if referenced_before && orphaned {
+                project_data.delete_task(section, task_id)?;
+                result
+                    .tasks_removed
+                    .push(format!("{}:{}", section, task_id));
+            }
+        }
+        let after = Self::reference_snapshot(&project_data, &relative_paths);
+        for (key, snapshot) in &after {
+            match before.get(key) {
+                None => result.tasks_added.push(format!("{}:{}", key.0, key.1)),
+                Some(previous) if previous != snapshot => {
+                    result.tasks_updated.push(format!("{}:{}", key.0, key.1))
+                }
+                Some(_) => {}
+            }
+        }
+        result.tasks_added.sort();
+        result.tasks_updated.sort();
+        result.tasks_removed.sort();
+
+        project_data.record_changes_since(&before_scan, ChangeSource::Scan);
+        project_data.rebuild_index();
+        self.storage.save_project_data(&project_data).await?;
+        self.record_note_conversions(&project_data, &result.converted_notes)?;
+        Ok(result)
+    }
+    /// Status and per-file reference lines of every task, limited to the given files
+    fn reference_snapshot(
+        project_data: &crate::ProjectData,
+        files: &[String],
+    ) -> HashMap<(String, String), ReferenceSnapshot> {
+        let mut snapshot = HashMap::new();
+        for (section_name, section) in &project_data.sections {
+            for (task_id, task) in section {
+                let references = files
+                    .iter()
+                    .filter_map(|file| {
+                        let mut lines = task.files.get(file)?.lines.clone();
+                        lines.sort_unstable();
+                        Some((file.clone(), lines))
+                    })
+                    .collect();
+                snapshot.insert(
+                    (section_name.clone(), task_id.clone()),
+                    (task.status.clone(), references),
+                );
+            }
+        }
+        snapshot
+    }
+    /// Keep task references in step with a file watcher event. Only renames
+    /// need handling here; content changes are picked up by a rescan.
+    pub async fn handle_file_event(&self, event: &FileEvent) -> anyhow::Result<()> {
+        if let FileEvent::Renamed { from, to } = event {
+            self.rename_file_references(RenameFileReferencesParams {
+                old_path: from.to_string_lossy().into_owned(),
+                new_path: to.to_string_lossy().into_owned(),
+            })
+            .await?;
+        }
+        Ok(())
+    }
+    /// Move task references for the renames among `events` whose both paths are
+    /// in the workspace, without rescanning anything
+    pub async fn move_renamed_references(&self, events: &[FileEvent]) -> anyhow::Result<()> {
+        for event in events {
+            if let FileEvent::Renamed { from, to } = event
+                && self.in_workspace(from)
+                && self.in_workspace(to)
+            {
+                self.handle_file_event(event).await?;
+            }
+        }
+        Ok(())
+    }
+    fn in_workspace(&self, path: &Path) -> bool {
+        self.workspace_relative_path(&path.to_string_lossy())
+            .is_ok()
+    }
+    /// Bring storage in line with a batch of debounced watcher events: renames
+    /// move their task references first, then every touched file is rescanned.
+    /// Events for paths outside the workspace are skipped.
+    pub async fn apply_file_events(&self, events: &[FileEvent]) -> anyhow::Result<ScanFilesResult> {
+        self.move_renamed_references(events).await?;
+        let files = events
+            .iter()
+            .filter(|event| self.in_workspace(event.path()))
+            .map(|event| event.path().to_string_lossy().into_owned())
+            .collect();
+        self.scan_files(ScanFilesParams {
+            files,
+            recognize_keyword_comments: None,
+            reconcile_status: None,
+        })
+        .await
+    }
+    async fn rename_file_references(
+        &self,
+        params: RenameFileReferencesParams,
+    ) -> anyhow::Result<RenameFileReferencesResult> {
+        let old_path = self.workspace_relative_path(&params.old_path)?;
+        let new_path = self.workspace_relative_path(&params.new_path)?;
+        let mut project_data = self.storage.load_project_data().await?;
+        let before = project_data.sections.clone();
+        let updated_tasks = project_data.rename_file_references(&old_path, &new_path);
+        if updated_tasks > 0 {
+            project_data.record_changes_since(&before, ChangeSource::Manual);
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(RenameFileReferencesResult {
+            old_path,
+            new_path,
+            updated_tasks,
+        })
+    }
+    /// Resolve a file given by a client to a workspace-relative path, rejecting
+    /// anything that would point outside the workspace
+    fn workspace_relative_path(&self, file: &str) -> anyhow::Result<String> {
+        let path = std::path::Path::new(file);
+        let relative = if path.is_absolute() {
+            path.strip_prefix(&self.workspace_path)
+                .map_err(|_| anyhow::anyhow!("Path is outside the workspace: {}", file))?
+        } else {
+            path
+        };
+        let mut parts = Vec::new();
+        for component in relative.components() {
+            match component {
+                std::path::Component::Normal(part) => parts.push(part.to_string_lossy()),
+                std::path::Component::CurDir => {}
+                _ => return Err(anyhow::anyhow!("Path is outside the workspace: {}", file)),
+            }
+        }
+        if parts.is_empty() {
+            return Err(anyhow::anyhow!("Path does not name a file: {}", file));
+        }
+        Ok(parts.join("/"))
+    }
+    /// Labels and near misses in an unsaved buffer; nothing is stored
+    async fn preview_file_labels(
+        &self,
+        params: PreviewFileLabelsParams,
+    ) -> anyhow::Result<PreviewFileLabelsResult> {
+        let parser = self.configured_parser(false).await?;
+        let labels = parser
+            .scan_file(params.path.as_deref().unwrap_or(""), &params.content)?
+            .into_iter()
+            .map(|(line, label)| PreviewedLabel { line, label })
+            .collect();
+        Ok(PreviewFileLabelsResult {
+            labels,
+            near_misses: parser.near_misses(&params.content),
         })
     }
+    /// Convert notes whose generated link has been pasted into one of the scanned
+    /// files. Unedited links always convert; edited ones only with
+    /// `notes.auto_convert` and enough confidence.
+    async fn convert_pasted_notes(
+        &self,
+        project_data: &mut crate::ProjectData,
+        scanned_paths: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        let config = self.storage.load_config().await?.notes;
+        let threshold = if config.auto_convert {
+            config.conversion_threshold
+        } else {
+            1.0
+        };
+        let scanned_content = self.pending_note_link_content(project_data, scanned_paths);
+        project_data.check_note_conversions(&scanned_content, threshold)
+    }
+    /// Report the tasks of converted notes as recent activity
+    fn record_note_conversions(
+        &self,
+        project_data: &crate::ProjectData,
+        note_ids: &[String],
+    ) -> anyhow::Result<()> {
+        let keys = note_ids
+            .iter()
+            .filter_map(|note_id| project_data.get_note(note_id))
+            .flat_map(|note| &note.converted_tasks);
+        for key in keys {
+            if let Some(task) = project_data.get_task(&key.section, &key.task_id) {
+                self.statistics_manager.record_task_update(TaskUpdate {
+                    section: key.section.clone(),
+                    task_id: key.task_id.clone(),
+                    old_status: None,
+                    new_status: task.status.clone(),
+                    timestamp: chrono::Utc::now(),
+                    change_type: ChangeType::NoteConverted,
+                })?;
+            }
+        }
+        Ok(())
+    }
+    /// Current content of those files that may hold a pasted note link
+    fn pending_note_link_content(
+        &self,
+        project_data: &crate::ProjectData,
+        files: &[String],
+    ) -> Vec<(String, String)> {
+        let cores = project_data.pending_note_link_cores();
+        if cores.is_empty() {
+            return Vec::new();
+        }
+        files
+            .iter()
+            .filter_map(|file| {
+                let content = std::fs::read_to_string(self.workspace_path.join(file)).ok()?;
+                cores
+                    .iter()
+                    .any(|core| content.contains(core.as_str()))
+                    .then(|| (file.clone(), content))
+            })
+            .collect()
+    }
+    /// Workspace-relative paths of every file a default scan would read
+    fn workspace_source_files(&self) -> Vec<String> {
+        let patterns = default_file_patterns();
+        let mut files = Vec::new();
+        let mut directories = vec![self.workspace_path.clone()];
+        while let Some(directory) = directories.pop() {
+            let Ok(entries) = std::fs::read_dir(&directory) else {
+                continue;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                if path.is_dir() {
+                    if !IGNORED_DIRS.contains(&name) {
+                        directories.push(path);
+                    }
+                } else if self.should_scan_file(name, &patterns)
+                    && let Ok(relative) = path.strip_prefix(&self.workspace_path)
+                {
+                    files.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+    /// Run every label occurrence through the validation engine without rejecting it
+    fn label_diagnostics(
+        &self,
+        project_data: &crate::ProjectData,
+    ) -> Vec<file_parser::LabelDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for (section_name, section) in &project_data.sections {
+            for (task_id, task) in section {
+                let errors = self.validation_engine.validate_label(section_name, task_id);
+                if errors.is_empty() {
+                    continue;
+                }
+                for (file, task_file) in &task.files {
+                    for line in &task_file.lines {
+                        diagnostics.extend(errors.iter().map(|error| {
+                            file_parser::LabelDiagnostic {
+                                file: file.clone(),
+                                line: *line,
+                                rule: error.error_type.clone(),
+                                message: error.message.clone(),
+                            }
+                        }));
+                    }
+                }
+            }
+        }
+        diagnostics.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        diagnostics
+    }
     async fn scan_directory_recursive(
         &self,
+        parser: &TaskParser,
         current_path: &PathBuf,
-        workspace_root: &PathBuf,
-        file_patterns: &[String],
+        options: &ScanOptions,
         project_data: &mut crate::ProjectData,
         scan_result: &mut file_parser::ScanResult,
+        mut profile: Option<&mut ScanProfile>,
     ) -> anyhow::Result<()> {
-        let ignored_dirs = [
-            "target",
-            "node_modules",
-            ".git",
-            ".vscode",
-            ".anchora",
-            "dist",
-            "build",
-            "__pycache__",
-            ".idea",
-            "out",
-        ];
-        if let Ok(entries) = std::fs::read_dir(current_path) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                        if ignored_dirs.contains(&dir_name) {
-                            continue;
-                        }
+        let relative = |path: &std::path::Path| {
+            path.strip_prefix(&options.workspace_root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+        let entries = match std::fs::read_dir(current_path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                scan_result.errors.push(format!(
+                    "Cannot read directory {}: {}",
+                    relative(current_path),
+                    e
+                ));
+                return Ok(());
+            }
+        };
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    scan_result.errors.push(format!(
+                        "Cannot read entry in directory {}: {}",
+                        relative(current_path),
+                        e
+                    ));
+                    continue;
+                }
+            };
+            let path = entry.path();
+            if path.is_dir() {
+                if let Some(dir_name) = path.file_name().and_then(|n| n.to_str())
+                    && IGNORED_DIRS.contains(&dir_name)
+                {
+                    continue;
+                }
+                Box::pin(self.scan_directory_recursive(
+                    parser,
+                    &path,
+                    options,
+                    project_data,
+                    scan_result,
+                    profile.as_deref_mut(),
+                ))
+                .await?;
+            } else if path.is_file()
+                && let Some(file_name) = path.file_name().and_then(|n| n.to_str())
+                && self.should_scan_file(file_name, &options.file_patterns)
+            {
+                let relative_path = relative(&path);
+                let parse_started = Instant::now();
+                let mut labels = match self.scan_buffers.scan_path(parser, &path) {
+                    Ok(scan) => {
+                        scan_result
+                            .skipped_lines
+                            .extend(scan.skipped_lines.into_iter().map(|(line, length)| {
+                                file_parser::SkippedLine {
+                                    file: relative_path.clone(),
+                                    line,
+                                    length,
+                                }
+                            }));
+                        scan.labels
                     }
-                    Box::pin(self.scan_directory_recursive(
-                        &path,
-                        workspace_root,
-                        file_patterns,
-                        project_data,
-                        scan_result,
-                    ))
-                    .await?;
-                } else if path.is_file() {
-                    if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                        if self.should_scan_file(file_name, file_patterns) {
-                            if let Ok(content) = std::fs::read_to_string(&path) {
-                                let relative_path = path
-                                    .strip_prefix(workspace_root)
-                                    .unwrap_or(&path)
-                                    .to_string_lossy()
-                                    .replace('\\', "/");
-                                match self.parser.scan_file(&relative_path, &content) {
-                                    Ok(labels) => {
-                                        scan_result.files_scanned += 1;
-                                        scan_result.tasks_found += labels.len() as u32;
+                    Err(e) => {
+                        scan_result
+                            .errors
+                            .push(format!("Cannot read file {}: {}", relative_path, e));
+                        continue;
+                    }
+                };
+                let parse_ms = elapsed_ms(parse_started);
+                let tasks_found = labels.len() as u32;
+                scan_result.files_scanned += 1;
+                scan_result.scanned_paths.push(relative_path.clone());
+                scan_result.tasks_found += tasks_found;
 
-                                        if !labels.is_empty() {
-                                            println!(
-                                                "Found {} tasks in file: {}",
-                                                labels.len(),
-                                                relative_path
-                                            );
-                                            for (line, label) in &labels {
-                                                println!(
-                                                    "  Line {}: {}:{} - {:?}",
-                                                    line,
-                                                    label.section,
-                                                    label.task_id,
-                                                    label
-                                                        .description
-                                                        .as_ref()
-                                                        .unwrap_or(&"No description".to_string())
-                                                );
-                                            }
-                                        }
-                                        if let Err(e) = self.parser.update_project_from_labels(
-                                            project_data,
-                                            &relative_path,
-                                            labels,
-                                        ) {
-                                            scan_result.errors.push(format!(
-                                                "Error updating project data for {}: {}",
-                                                relative_path, e
-                                            ));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        scan_result.errors.push(format!(
-                                            "Error scanning file {}: {}",
-                                            relative_path, e
-                                        ));
-                                    }
-                                }
-                            }
-                        }
+                if !labels.is_empty() {
+                    scan_result.files_with_tasks += 1;
+                }
+                if !labels.is_empty() && scan_result.files_with_tasks <= options.max_logged_files {
+                    eprintln!("Found {} tasks in file: {}", labels.len(), relative_path);
+                    for (line, label) in &labels {
+                        eprintln!(
+                            "  Line {}: {}:{} - {:?}",
+                            line,
+                            label.section,
+                            label.task_id,
+                            label
+                                .description
+                                .as_ref()
+                                .unwrap_or(&"No description".to_string())
+                        );
                     }
                 }
+                let update_started = Instant::now();
+                match Self::reconcile_statuses(
+                    parser,
+                    project_data,
+                    &path,
+                    &relative_path,
+                    &mut labels,
+                    options.reconcile_status,
+                )
+                .await
+                {
+                    Ok(divergences) => scan_result.status_divergences.extend(divergences),
+                    Err(e) => scan_result.errors.push(format!(
+                        "Failed to rewrite status labels in {}: {}",
+                        relative_path, e
+                    )),
+                }
+                match parser.update_project_from_labels(project_data, &relative_path, labels) {
+                    Ok(conflicts) => scan_result.description_conflicts.extend(conflicts),
+                    Err(e) => scan_result.errors.push(format!(
+                        "Error updating project data for {}: {}",
+                        relative_path, e
+                    )),
+                }
+                if let Some(profile) = profile.as_deref_mut() {
+                    profile.update_ms += elapsed_ms(update_started);
+                    profile.files.push(FileProfile {
+                        extension: path
+                            .extension()
+                            .map(|extension| extension.to_string_lossy().to_lowercase())
+                            .unwrap_or_default(),
+                        bytes: entry.metadata().map(|metadata| metadata.len()).unwrap_or(0),
+                        relative_path,
+                        tasks_found,
+                        parse_ms,
+                    });
+                }
             }
         }
         Ok(())
     }
+    /// Scan error for a `scan.repository_url` that issue links cannot be built from
+    fn repository_url_error(scan_config: &file_parser::ScanConfig) -> Option<String> {
+        let repository_url = scan_config.repository_url.as_deref()?;
+        file_parser::issues_url(repository_url).err().map(|e| {
+            format!(
+                "Skipped gh# issue links, scan.repository_url is unusable: {}",
+                e
+            )
+        })
+    }
+    /// Settle label status markers that disagree with stored statuses before the
+    /// labels are applied; with `json_wins` the markers in the file are rewritten,
+    /// unless the file changed after it was scanned
+    async fn reconcile_statuses(
+        parser: &TaskParser,
+        project_data: &crate::ProjectData,
+        path: &std::path::Path,
+        relative_path: &str,
+        labels: &mut [(u32, file_parser::ParsedTaskLabel)],
+        mode: file_parser::StatusReconciliation,
+    ) -> anyhow::Result<Vec<file_parser::StatusDivergence>> {
+        let scanned = labels.to_vec();
+        let divergences =
+            TaskParser::reconcile_label_statuses(project_data, relative_path, labels, mode);
+        if mode == file_parser::StatusReconciliation::JsonWins && !divergences.is_empty() {
+            let mut content = tokio::fs::read_to_string(path).await?;
+            let syntax = parser.comment_syntax(&path.to_string_lossy());
+            let current = parser.scan_reader_as(content.as_bytes(), &mut String::new(), syntax)?;
+            if current.labels != scanned {
+                return Err(anyhow::anyhow!(
+                    "file changed after it was scanned; labels left for the next scan"
+                ));
+            }
+            for divergence in &divergences {
+                if let Some(rewritten) = TaskParser::rewrite_label_status(
+                    &content,
+                    syntax,
+                    divergence.line,
+                    &divergence.section,
+                    &divergence.task_id,
+                    &divergence.stored_status,
+                ) {
+                    content = rewritten;
+                }
+            }
+            write_file_atomically(path, &content).await?;
+        }
+        Ok(divergences)
+    }
     fn should_scan_file(&self, file_name: &str, patterns: &[String]) -> bool {
         for pattern in patterns {
             if pattern.starts_with("**/*.") {
@@ -259,118 +1151,1728 @@ impl TaskManagerHandler {
         }
         false
     }
-    async fn get_tasks(
+    async fn get_tasks(&self, params: Option<GetTasksParams>) -> anyhow::Result<serde_json::Value> {
+        let mut project_data = self.storage.load_project_data().await?;
+        if !params
+            .as_ref()
+            .and_then(|p| p.include_archived)
+            .unwrap_or(false)
+        {
+            project_data.hide_archived_sections();
+        }
+        let sort_by = params.as_ref().and_then(|p| p.sort_by.clone());
+        if let Some(params) = params {
+            if let Some(section) = &params.section {
+                project_data.sections.retain(|name, _| name == section);
+            }
+            if let Some(status) = &params.status {
+                let statuses = status
+                    .values()
+                    .into_iter()
+                    .map(|status| status.parse::<TaskStatus>())
+                    .collect::<anyhow::Result<HashSet<_>>>()?;
+                for tasks in project_data.sections.values_mut() {
+                    tasks.retain(|_, task| statuses.contains(&task.status));
+                }
+            }
+        }
+        project_data.strip_for_listing();
+        tasks_response(
+            &project_data,
+            sort_by.as_deref(),
+            self.section_order().await?,
+        )
+    }
+    async fn get_filtered_tasks(
         &self,
-        _params: Option<GetTasksParams>,
+        params: GetFilteredTasksParams,
     ) -> anyhow::Result<serde_json::Value> {
-        let project_data = self.storage.load_project_data().await?;
-        Ok(serde_json::to_value(&project_data)?)
+        let mut project_data = self.storage.load_project_data().await?;
+        let parse_time = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|value| {
+                    chrono::DateTime::parse_from_rfc3339(value)
+                        .map(|time| time.with_timezone(&chrono::Utc))
+                        .map_err(|e| anyhow::anyhow!("Invalid timestamp '{}': {}", value, e))
+                })
+                .transpose()
+        };
+        let priorities = params
+            .priorities
+            .as_ref()
+            .map(|priorities| {
+                priorities
+                    .iter()
+                    .map(|priority| priority.parse::<TaskPriority>())
+                    .collect::<anyhow::Result<HashSet<_>>>()
+            })
+            .transpose()?;
+        let created_after = parse_time(&params.created_after)?;
+        let updated_after = parse_time(&params.updated_after)?;
+        let statuses = params
+            .statuses
+            .as_ref()
+            .map(|statuses| {
+                statuses
+                    .iter()
+                    .map(|status| status.parse::<TaskStatus>())
+                    .collect::<anyhow::Result<HashSet<_>>>()
+            })
+            .transpose()?;
+        if !params.include_archived.unwrap_or(false) {
+            project_data.hide_archived_sections();
+        }
+        if let Some(sections) = &params.sections {
+            project_data
+                .sections
+                .retain(|name, _| sections.contains(name));
+        }
+        for tasks in project_data.sections.values_mut() {
+            tasks.retain(|_, task| {
+                statuses
+                    .as_ref()
+                    .is_none_or(|statuses| statuses.contains(&task.status))
+                    && priorities.as_ref().is_none_or(|priorities| {
+                        task.priority
+                            .is_some_and(|priority| priorities.contains(&priority))
+                    })
+                    && params
+                        .tags
+                        .as_ref()
+                        .is_none_or(|tags| task.has_any_tag(tags))
+                    && params.assignees.as_ref().is_none_or(|assignees| {
+                        task.assignee
+                            .as_ref()
+                            .is_some_and(|assignee| assignees.contains(assignee))
+                    })
+                    && created_after.is_none_or(|after| task.created > after)
+                    && updated_after.is_none_or(|after| task.updated > after)
+                    && params.metadata.as_ref().is_none_or(|metadata| {
+                        metadata
+                            .iter()
+                            .all(|(key, value)| task.metadata.get(key) == Some(value))
+                    })
+            });
+        }
+        project_data.strip_for_listing();
+        tasks_response(
+            &project_data,
+            params.sort_by.as_deref(),
+            self.section_order().await?,
+        )
     }
-    async fn create_task(&self, params: CreateTaskParams) -> anyhow::Result<serde_json::Value> {
+    async fn archive_section(&self, params: ArchiveSectionParams) -> anyhow::Result<BasicResponse> {
         let mut project_data = self.storage.load_project_data().await?;
-        project_data.add_task(
-            &params.section,
-            &params.task_id,
-            params.title,
-            params.description,
-        )?;
+        project_data.archive_section(&params.section)?;
         self.storage.save_project_data(&project_data).await?;
-        Ok(serde_json::json!({
-            "success": true,
-            "message": format!("Task {}:{} created successfully", params.section, params.task_id)
-        }))
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Section {} archived", params.section),
+        })
     }
-    async fn update_task_status(
+    async fn unarchive_section(
         &self,
-        params: UpdateTaskStatusParams,
-    ) -> anyhow::Result<serde_json::Value> {
+        params: ArchiveSectionParams,
+    ) -> anyhow::Result<BasicResponse> {
         let mut project_data = self.storage.load_project_data().await?;
-        let status = match params.status.to_lowercase().as_str() {
-            "todo" => TaskStatus::Todo,
-            "in_progress" | "inprogress" => TaskStatus::InProgress,
-            "done" | "completed" => TaskStatus::Done,
-            "blocked" => TaskStatus::Blocked,
-            _ => return Err(anyhow::anyhow!("Invalid status: {}", params.status)),
-        };
-        project_data.update_task_status(&params.section, &params.task_id, status)?;
+        project_data.unarchive_section(&params.section)?;
         self.storage.save_project_data(&project_data).await?;
-        Ok(serde_json::json!({
-            "success": true,
-            "message": format!("Task {}:{} status updated to {}", params.section, params.task_id, params.status)
-        }))
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Section {} unarchived", params.section),
+        })
     }
-    async fn delete_task(&self, params: DeleteTaskParams) -> anyhow::Result<serde_json::Value> {
+    async fn create_milestone(
+        &self,
+        params: CreateMilestoneParams,
+    ) -> anyhow::Result<BasicResponse> {
         let mut project_data = self.storage.load_project_data().await?;
-        project_data.delete_task(&params.section, &params.task_id)?;
+        project_data.create_milestone(&params.id, &params.title, params.due)?;
         self.storage.save_project_data(&project_data).await?;
-        Ok(serde_json::json!({
-            "success": true,
-            "message": format!("Task {}:{} deleted successfully", params.section, params.task_id)
-        }))
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Milestone {} created", params.id.trim()),
+        })
     }
-    async fn find_task_references(
+    async fn delete_milestone(
         &self,
-        params: FindTaskReferencesParams,
-    ) -> anyhow::Result<Vec<TaskReference>> {
-        let project_data = self.storage.load_project_data().await?;
-        if let Some(task) = project_data.get_task(&params.section, &params.task_id) {
-            let mut references = Vec::new();
-            for (file_path, task_file) in &task.files {
-                for &line in &task_file.lines {
-                    references.push(TaskReference {
-                        file_path: file_path.clone(),
-                        line,
-                        note: task_file.notes.get(&line).cloned(),
-                    });
-                }
-            }
-            Ok(references)
-        } else {
-            Err(anyhow::anyhow!(
-                "Task not found: {}:{}",
-                params.section,
-                params.task_id
-            ))
+        params: DeleteMilestoneParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.delete_milestone(&params.id)?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Milestone {} deleted", params.id.trim()),
+        })
+    }
+    async fn add_task_to_milestone(
+        &self,
+        params: MilestoneTaskParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let added = project_data.add_task_to_milestone(
+            &params.milestone_id,
+            &params.section,
+            &params.task_id,
+        )?;
+        if added {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(BasicResponse {
+            success: true,
+            message: if added {
+                format!(
+                    "Task {}:{} added to milestone {}",
+                    params.section, params.task_id, params.milestone_id
+                )
+            } else {
+                format!(
+                    "Task {}:{} is already in milestone {}",
+                    params.section, params.task_id, params.milestone_id
+                )
+            },
+        })
+    }
+    async fn remove_task_from_milestone(
+        &self,
+        params: MilestoneTaskParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let removed = project_data.remove_task_from_milestone(
+            &params.milestone_id,
+            &params.section,
+            &params.task_id,
+        )?;
+        if removed {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(BasicResponse {
+            success: true,
+            message: if removed {
+                format!(
+                    "Task {}:{} removed from milestone {}",
+                    params.section, params.task_id, params.milestone_id
+                )
+            } else {
+                format!(
+                    "Task {}:{} is not in milestone {}",
+                    params.section, params.task_id, params.milestone_id
+                )
+            },
+        })
+    }
+    /// Milestones with their member tasks and progress, by due date
+    async fn get_milestones(&self) -> anyhow::Result<serde_json::Value> {
+        let project_data = self.storage.load_project_data().await?;
+        let milestones: Vec<serde_json::Value> =
+            StatisticsManager::milestone_progress(&project_data)
+                .into_iter()
+                .map(|progress| {
+                    let task_refs = &project_data.milestones[&progress.id].task_refs;
+                    serde_json::json!({
+                        "id": progress.id,
+                        "title": progress.title,
+                        "due": progress.due,
+                        "task_refs": task_refs,
+                        "progress": {
+                            "total": progress.total,
+                            "todo": progress.todo,
+                            "in_progress": progress.in_progress,
+                            "done": progress.done,
+                            "blocked": progress.blocked,
+                            "completion_percentage": progress.completion_percentage,
+                            "overdue": progress.overdue
+                        }
+                    })
+                })
+                .collect();
+        Ok(serde_json::json!({ "milestones": milestones }))
+    }
+    async fn edit_task_text(&self, params: EditTaskTextParams) -> anyhow::Result<BasicResponse> {
+        if params.title.is_none() && params.description.is_none() {
+            return Err(anyhow::anyhow!(
+                "Nothing to edit: give a title or a description"
+            ));
+        }
+        if params
+            .title
+            .as_deref()
+            .is_some_and(|title| title.trim().is_empty())
+        {
+            return Err(anyhow::anyhow!("Task title cannot be empty"));
+        }
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| {
+                data.edit_task_text(
+                    &params.section,
+                    &params.task_id,
+                    params.title.clone(),
+                    params.description.clone(),
+                )
+            },
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Task {}:{} text updated", params.section, params.task_id),
+        })
+    }
+    async fn set_task_priority(
+        &self,
+        params: SetTaskPriorityParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let priority = params
+            .priority
+            .as_deref()
+            .map(str::parse::<TaskPriority>)
+            .transpose()?;
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| data.set_task_priority(&params.section, &params.task_id, priority),
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!(
+                "Task {}:{} priority set to {}",
+                params.section,
+                params.task_id,
+                priority.map_or("none", TaskPriority::as_str)
+            ),
+        })
+    }
+    async fn set_task_estimate(
+        &self,
+        params: SetTaskEstimateParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| {
+                data.set_task_estimate(&params.section, &params.task_id, params.estimate_minutes)
+            },
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: match params.estimate_minutes {
+                Some(minutes) => format!(
+                    "Task {}:{} estimated at {} minutes",
+                    params.section, params.task_id, minutes
+                ),
+                None => format!(
+                    "Task {}:{} estimate cleared",
+                    params.section, params.task_id
+                ),
+            },
+        })
+    }
+    async fn add_time_entry(
+        &self,
+        params: AddTimeEntryParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let time_spent_minutes = project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| {
+                data.add_time_entry(
+                    &params.section,
+                    &params.task_id,
+                    params.minutes,
+                    params.note,
+                )
+            },
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        let task = project_data
+            .get_task(&params.section, &params.task_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Task not found: {}:{}", params.section, params.task_id)
+            })?;
+        self.statistics_manager.record_task_update(TaskUpdate {
+            section: params.section.clone(),
+            task_id: params.task_id.clone(),
+            old_status: Some(task.status.clone()),
+            new_status: task.status.clone(),
+            timestamp: chrono::Utc::now(),
+            change_type: ChangeType::Modified,
+        })?;
+        Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Logged {} minutes on task {}:{}", params.minutes, params.section, params.task_id),
+            "time_spent_minutes": time_spent_minutes,
+            "estimate_minutes": task.estimate_minutes
+        }))
+    }
+    async fn set_task_assignee(
+        &self,
+        params: SetTaskAssigneeParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let previous = project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| data.set_task_assignee(&params.section, &params.task_id, params.assignee),
+        )?;
+        let task = project_data
+            .get_task(&params.section, &params.task_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Task not found: {}:{}", params.section, params.task_id)
+            })?;
+        let assignee = task.assignee.clone();
+        if previous != assignee {
+            let status = task.status.clone();
+            self.storage.save_project_data(&project_data).await?;
+            self.statistics_manager.record_task_update(TaskUpdate {
+                section: params.section.clone(),
+                task_id: params.task_id.clone(),
+                old_status: Some(status.clone()),
+                new_status: status,
+                timestamp: chrono::Utc::now(),
+                change_type: ChangeType::Modified,
+            })?;
+        }
+        Ok(BasicResponse {
+            success: true,
+            message: match assignee {
+                Some(assignee) => format!(
+                    "Task {}:{} assigned to {}",
+                    params.section, params.task_id, assignee
+                ),
+                None => format!("Task {}:{} unassigned", params.section, params.task_id),
+            },
+        })
+    }
+    /// Open tasks of one assignee, most urgent and most recently updated first
+    async fn get_my_tasks(&self, params: GetMyTasksParams) -> anyhow::Result<Vec<AssignedTask>> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.hide_archived_sections();
+        let mut tasks: Vec<AssignedTask> = project_data
+            .sections
+            .iter()
+            .flat_map(|(section, tasks)| {
+                tasks
+                    .iter()
+                    .map(move |(task_id, task)| (section, task_id, task))
+            })
+            .filter(|(_, _, task)| {
+                task.status != TaskStatus::Done
+                    && task.assignee.as_deref() == Some(params.assignee.as_str())
+            })
+            .map(|(section, task_id, task)| AssignedTask {
+                section: section.clone(),
+                task_id: task_id.clone(),
+                title: task.title.clone(),
+                status: task.status.clone(),
+                priority: task.priority,
+                updated: task.updated,
+            })
+            .collect();
+        tasks.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| b.updated.cmp(&a.updated))
+        });
+        if let Some(limit) = params.limit {
+            tasks.truncate(limit);
+        }
+        Ok(tasks)
+    }
+    async fn add_task_tags(&self, params: TaskTagsParams) -> anyhow::Result<TaskTagsResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let tags = project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| data.add_task_tags(&params.section, &params.task_id, &params.tags),
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(TaskTagsResult {
+            section: params.section,
+            task_id: params.task_id,
+            tags,
+        })
+    }
+    async fn remove_task_tags(&self, params: TaskTagsParams) -> anyhow::Result<TaskTagsResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let tags = project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| data.remove_task_tags(&params.section, &params.task_id, &params.tags),
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(TaskTagsResult {
+            section: params.section,
+            task_id: params.task_id,
+            tags,
+        })
+    }
+    async fn add_task_link(&self, params: AddTaskLinkParams) -> anyhow::Result<TaskLinksResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let links = project_data.add_task_link(
+            &params.section,
+            &params.task_id,
+            params.label.as_deref().unwrap_or_default(),
+            &params.url,
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(TaskLinksResult {
+            section: params.section,
+            task_id: params.task_id,
+            links,
+        })
+    }
+    async fn remove_task_link(
+        &self,
+        params: RemoveTaskLinkParams,
+    ) -> anyhow::Result<TaskLinksResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let links = project_data.remove_task_link(&params.section, &params.task_id, &params.url)?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(TaskLinksResult {
+            section: params.section,
+            task_id: params.task_id,
+            links,
+        })
+    }
+    /// Every tag in use with the number of tasks or notes carrying it, most used first
+    async fn get_tags(&self, params: Option<GetTagsParams>) -> anyhow::Result<Vec<TagUsage>> {
+        let params = params.unwrap_or_default();
+        let mut project_data = self.storage.load_project_data().await?;
+        if !params.include_archived.unwrap_or(false) {
+            project_data.hide_archived_sections();
+        }
+        let prefix = params.prefix.as_deref().and_then(crate::normalize_tag);
+        let task_tags = project_data
+            .tag_counts()
+            .into_iter()
+            .map(|(tag, count)| (tag, count, TagKind::Task));
+        let note_tags = project_data
+            .note_tag_counts()
+            .into_iter()
+            .map(|(tag, count)| (tag, count, TagKind::Note));
+        let mut tags: Vec<TagUsage> = task_tags
+            .chain(note_tags)
+            .filter(|(tag, _, _)| {
+                prefix
+                    .as_deref()
+                    .is_none_or(|prefix| tag.starts_with(prefix))
+            })
+            .map(|(tag, count, kind)| TagUsage {
+                tag: tag.to_string(),
+                count,
+                kind,
+            })
+            .collect();
+        tags.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.tag.cmp(&b.tag))
+                .then_with(|| a.kind.cmp(&b.kind))
+        });
+        Ok(tags)
+    }
+    async fn reorder_task(&self, params: ReorderTaskParams) -> anyhow::Result<ReorderTaskResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let rank = project_data.reorder_task(
+            &params.section,
+            &params.task_id,
+            params.before.as_deref(),
+            params.after.as_deref(),
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(ReorderTaskResult {
+            order: project_data.ranked_task_ids(&params.section),
+            section: params.section,
+            task_id: params.task_id,
+            rank,
+        })
+    }
+    async fn get_completion_forecast(&self) -> anyhow::Result<CompletionForecast> {
+        let project_data = self.storage.load_project_data().await?;
+        let order = self.section_order().await?;
+        Ok(self
+            .statistics_manager
+            .completion_forecast(&project_data, order))
+    }
+    async fn compare_sections(
+        &self,
+        params: CompareSectionsParams,
+    ) -> anyhow::Result<SectionComparison> {
+        let project_data = self.storage.load_project_data().await?;
+        self.statistics_manager
+            .compare_sections(&project_data, &params.a, &params.b)
+    }
+    async fn add_task_comment(&self, params: AddTaskCommentParams) -> anyhow::Result<TaskComment> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let comment = project_data.add_task_comment(
+            &params.section,
+            &params.task_id,
+            &params.author,
+            &params.text,
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(comment)
+    }
+    async fn delete_task_comment(
+        &self,
+        params: DeleteTaskCommentParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.delete_task_comment(&params.section, &params.task_id, &params.comment_id)?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!(
+                "Comment {} deleted from {}:{}",
+                params.comment_id, params.section, params.task_id
+            ),
+        })
+    }
+    async fn set_task_metadata(
+        &self,
+        params: SetTaskMetadataParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.set_task_metadata(
+            &params.section,
+            &params.task_id,
+            &params.key,
+            params.value,
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!(
+                "Metadata '{}' set on {}:{}",
+                params.key, params.section, params.task_id
+            ),
+        })
+    }
+    async fn remove_task_metadata(
+        &self,
+        params: RemoveTaskMetadataParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let removed =
+            project_data.remove_task_metadata(&params.section, &params.task_id, &params.key)?;
+        if removed.is_none() {
+            return Ok(BasicResponse {
+                success: false,
+                message: format!(
+                    "Metadata '{}' not set on {}:{}",
+                    params.key, params.section, params.task_id
+                ),
+            });
+        }
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!(
+                "Metadata '{}' removed from {}:{}",
+                params.key, params.section, params.task_id
+            ),
+        })
+    }
+    async fn create_task(&self, params: CreateTaskParams) -> anyhow::Result<serde_json::Value> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let overwrite = params.overwrite.unwrap_or(false);
+        let priority = params
+            .priority
+            .as_deref()
+            .map(str::parse::<TaskPriority>)
+            .transpose()?;
+        self.ensure_valid_task(
+            &ValidationParams {
+                section: params.section.clone(),
+                task_id: params.task_id.clone(),
+                title: Some(params.title.clone()),
+                description: params.description.clone(),
+                check_duplicates: Some(true),
+                suggest_alternatives: Some(true),
+            },
+            overwrite,
+        )
+        .await?;
+        project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| {
+                if overwrite {
+                    data.replace_task(
+                        &params.section,
+                        &params.task_id,
+                        params.title,
+                        params.description,
+                    )?;
+                } else {
+                    data.add_task(
+                        &params.section,
+                        &params.task_id,
+                        params.title,
+                        params.description,
+                    )?;
+                }
+                if priority.is_some() {
+                    data.set_task_priority(&params.section, &params.task_id, priority)?;
+                }
+                if params.assignee.is_some() {
+                    data.set_task_assignee(&params.section, &params.task_id, params.assignee)?;
+                }
+                if params.parent.is_some() {
+                    data.set_task_parent(&params.section, &params.task_id, params.parent)?;
+                }
+                Ok(())
+            },
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        self.statistics_manager.record_task_update(TaskUpdate {
+            section: params.section.clone(),
+            task_id: params.task_id.clone(),
+            old_status: None,
+            new_status: TaskStatus::Todo,
+            timestamp: chrono::Utc::now(),
+            change_type: ChangeType::Created,
+        })?;
+        Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Task {}:{} created successfully", params.section, params.task_id)
+        }))
+    }
+    async fn create_task_from_template(
+        &self,
+        params: CreateTaskFromTemplateParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let template = self.find_task_template(&params.template_id).await?;
+        let mut overrides = params.overrides;
+        let mut substitutions = params.substitutions;
+        if template
+            .title
+            .as_deref()
+            .is_some_and(|title| title.contains("{title}"))
+            && let Some(title) = overrides.title.take()
+        {
+            substitutions.entry("title".to_string()).or_insert(title);
+        }
+        if params.section.is_some() {
+            overrides.section = params.section;
+        }
+        let task = template
+            .substitute(&substitutions)
+            .map_err(|e| anyhow::anyhow!("Template {}: {}", params.template_id, e))?
+            .with_overrides(overrides);
+        let section = task.section.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Template {} has no section; pass one in the request",
+                params.template_id
+            )
+        })?;
+        let title = task.title.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Template {} has no title; pass one in overrides",
+                params.template_id
+            )
+        })?;
+        self.ensure_valid_task(
+            &ValidationParams {
+                section: section.clone(),
+                task_id: params.task_id.clone(),
+                title: Some(title.clone()),
+                description: task.description.clone(),
+                check_duplicates: Some(true),
+                suggest_alternatives: Some(true),
+            },
+            false,
+        )
+        .await?;
+        let mut project_data = self.storage.load_project_data().await?;
+        let status = task.status.unwrap_or_default();
+        project_data.track_changes(&section, &params.task_id, ChangeSource::Manual, |data| {
+            data.add_task(&section, &params.task_id, title, task.description)?;
+            if status != TaskStatus::Todo {
+                data.update_task_status(&section, &params.task_id, status.clone())?;
+            }
+            if task.priority.is_some() {
+                data.set_task_priority(&section, &params.task_id, task.priority)?;
+            }
+            if task.assignee.is_some() {
+                data.set_task_assignee(&section, &params.task_id, task.assignee)?;
+            }
+            if !task.tags.is_empty() {
+                data.add_task_tags(&section, &params.task_id, &task.tags)?;
+            }
+            for (key, value) in task.metadata {
+                data.set_task_metadata(&section, &params.task_id, &key, value)?;
+            }
+            Ok(())
+        })?;
+        self.storage.save_project_data(&project_data).await?;
+        self.statistics_manager.record_task_update(TaskUpdate {
+            section: section.clone(),
+            task_id: params.task_id.clone(),
+            old_status: None,
+            new_status: status,
+            timestamp: chrono::Utc::now(),
+            change_type: ChangeType::Created,
+        })?;
+        Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Task {}:{} created from template {}", section, params.task_id, params.template_id),
+            "section": section,
+            "task_id": params.task_id,
+            "task": project_data.get_task(&section, &params.task_id)
+        }))
+    }
+    /// Template by ID from the project config
+    async fn find_task_template(&self, template_id: &str) -> anyhow::Result<TaskTemplate> {
+        self.storage
+            .load_templates()
+            .await?
+            .remove(template_id)
+            .ok_or_else(|| anyhow::anyhow!("Template not found: {}", template_id))
+    }
+    async fn save_task_template(
+        &self,
+        params: SaveTaskTemplateParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let template_id = params.template_id.trim();
+        if template_id.is_empty() {
+            return Err(anyhow::anyhow!("Template ID cannot be empty"));
+        }
+        let mut templates = self.storage.load_templates().await?;
+        let replaced = templates
+            .insert(template_id.to_string(), params.template)
+            .is_some();
+        self.storage.save_templates(&templates).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Template {} saved", template_id),
+            "template_id": template_id,
+            "replaced": replaced,
+            "placeholders": templates[template_id].placeholders()
+        }))
+    }
+    /// Templates from the project config, sorted by ID
+    async fn list_task_templates(&self) -> anyhow::Result<serde_json::Value> {
+        let templates: Vec<serde_json::Value> = self
+            .storage
+            .load_templates()
+            .await?
+            .iter()
+            .map(|(id, template)| {
+                serde_json::json!({
+                    "template_id": id,
+                    "placeholders": template.placeholders(),
+                    "template": template
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({ "templates": templates }))
+    }
+    /// Refuse a task write that validation rejects, returning the full result as the error
+    async fn ensure_valid_task(
+        &self,
+        params: &ValidationParams,
+        allow_existing: bool,
+    ) -> anyhow::Result<()> {
+        self.refresh_validation().await?;
+        let mut result = self.validation_engine.validate_task_creation(params)?;
+        if allow_existing {
+            result.allow_existing();
+        }
+        if result.can_create {
+            Ok(())
+        } else {
+            Err(ValidationFailed(result).into())
+        }
+    }
+    async fn update_task_status(
+        &self,
+        params: UpdateTaskStatusParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let status: TaskStatus = params.status.parse()?;
+        let old_status = project_data
+            .get_task(&params.section, &params.task_id)
+            .map(|task| task.status.clone());
+        project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| data.update_task_status(&params.section, &params.task_id, status.clone()),
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        self.statistics_manager.record_task_update(TaskUpdate {
+            section: params.section.clone(),
+            task_id: params.task_id.clone(),
+            old_status,
+            new_status: status,
+            timestamp: chrono::Utc::now(),
+            change_type: ChangeType::StatusUpdated,
+        })?;
+        let mut response = serde_json::json!({
+            "success": true,
+            "message": format!("Task {}:{} status updated to {}", params.section, params.task_id, params.status)
+        });
+        if project_data
+            .get_task(&params.section, &params.task_id)
+            .is_some_and(|task| task.status == TaskStatus::Done)
+        {
+            let unblocked = project_data.unblocked_dependents(&params.section, &params.task_id);
+            response["unblocked"] = Self::task_summaries(&project_data, &unblocked);
+        }
+        Ok(response)
+    }
+    async fn get_dependents(
+        &self,
+        params: GetDependentsParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let project_data = self.storage.load_project_data().await?;
+        if project_data
+            .get_task(&params.section, &params.task_id)
+            .is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "Task not found: {}:{}",
+                params.section,
+                params.task_id
+            ));
+        }
+        let dependents = project_data.dependents_of(&params.section, &params.task_id);
+        Ok(Self::task_summaries(&project_data, &dependents))
+    }
+    async fn add_task_dependency(
+        &self,
+        params: TaskDependencyParams,
+    ) -> anyhow::Result<TaskDependenciesResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.add_dependency(&params.section, &params.task_id, params.depends_on)?;
+        self.storage.save_project_data(&project_data).await?;
+        Self::dependencies_result(&project_data, params.section, params.task_id)
+    }
+    async fn remove_task_dependency(
+        &self,
+        params: TaskDependencyParams,
+    ) -> anyhow::Result<TaskDependenciesResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.remove_dependency(&params.section, &params.task_id, &params.depends_on)?;
+        self.storage.save_project_data(&project_data).await?;
+        Self::dependencies_result(&project_data, params.section, params.task_id)
+    }
+    fn dependencies_result(
+        project_data: &crate::ProjectData,
+        section: String,
+        task_id: String,
+    ) -> anyhow::Result<TaskDependenciesResult> {
+        let dependencies = project_data
+            .get_task(&section, &task_id)
+            .map(|task| task.dependencies.clone())
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        Ok(TaskDependenciesResult {
+            section,
+            task_id,
+            dependencies,
+        })
+    }
+    fn task_summaries(project_data: &crate::ProjectData, keys: &[TaskKey]) -> serde_json::Value {
+        keys.iter()
+            .filter_map(|key| {
+                let task = project_data.get_task(&key.section, &key.task_id)?;
+                Some(serde_json::json!({
+                    "section": key.section,
+                    "task_id": key.task_id,
+                    "title": task.title,
+                    "status": task.status
+                }))
+            })
+            .collect()
+    }
+    async fn delete_task(&self, params: DeleteTaskParams) -> anyhow::Result<serde_json::Value> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let permanent = params.permanent.unwrap_or(false);
+        let deleted = match (params.cascade.unwrap_or(false), permanent) {
+            (true, true) => {
+                project_data.delete_task_with_subtasks(&params.section, &params.task_id)?
+            }
+            (true, false) => {
+                project_data.trash_task_with_subtasks(&params.section, &params.task_id)?
+            }
+            (false, true) => {
+                project_data.delete_task(&params.section, &params.task_id)?;
+                vec![TaskKey::new(&params.section, &params.task_id)]
+            }
+            (false, false) => {
+                project_data.trash_task(&params.section, &params.task_id)?;
+                vec![TaskKey::new(&params.section, &params.task_id)]
+            }
+        };
+        self.storage.save_project_data(&project_data).await?;
+        Ok(serde_json::json!({
+            "success": true,
+            "message": format!("Task {}:{} deleted successfully", params.section, params.task_id),
+            "deleted": deleted,
+            "trashed": !permanent
+        }))
+    }
+    /// Trashed tasks, most recently deleted first
+    async fn list_trash(&self) -> anyhow::Result<serde_json::Value> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data
+            .trash
+            .sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+        Ok(serde_json::json!({ "trash": project_data.trash }))
+    }
+    async fn restore_task(&self, params: RestoreTaskParams) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.restore_task(&params.section, &params.task_id)?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Task {}:{} restored", params.section, params.task_id),
+        })
+    }
+    async fn empty_trash(
+        &self,
+        params: Option<EmptyTrashParams>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let params = params.unwrap_or_default();
+        let mut project_data = self.storage.load_project_data().await?;
+        let removed = project_data.empty_trash(params.older_than_days);
+        if !removed.is_empty() {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(serde_json::json!({
+            "success": true,
+            "message": format!("{} trashed tasks removed permanently", removed.len()),
+            "removed": removed
+        }))
+    }
+    async fn set_task_parent(&self, params: SetTaskParentParams) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        project_data.track_changes(
+            &params.section,
+            &params.task_id,
+            ChangeSource::Manual,
+            |data| data.set_task_parent(&params.section, &params.task_id, params.parent.clone()),
+        )?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: match params.parent {
+                Some(parent) => format!(
+                    "Task {}:{} is now a subtask of {}:{}",
+                    params.section, params.task_id, parent.section, parent.task_id
+                ),
+                None => format!(
+                    "Task {}:{} is now a top-level task",
+                    params.section, params.task_id
+                ),
+            },
+        })
+    }
+    async fn get_task_tree(&self, params: GetTaskTreeParams) -> anyhow::Result<TaskTreeNode> {
+        let project_data = self.storage.load_project_data().await?;
+        Self::task_tree(
+            &project_data,
+            &TaskKey::new(&params.section, &params.task_id),
+            &mut HashSet::new(),
+        )
+        .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", params.section, params.task_id))
+    }
+    /// Build the subtree below `key`; `visited` guards against parent loops in stored data
+    fn task_tree(
+        project_data: &crate::ProjectData,
+        key: &TaskKey,
+        visited: &mut HashSet<TaskKey>,
+    ) -> Option<TaskTreeNode> {
+        let task = project_data.get_task(&key.section, &key.task_id)?;
+        visited.insert(key.clone());
+        let (done, total) = project_data.subtask_progress(&key.section, &key.task_id);
+        let mut children = Vec::new();
+        for child in project_data.children_of(&key.section, &key.task_id) {
+            if !visited.contains(&child)
+                && let Some(node) = Self::task_tree(project_data, &child, visited)
+            {
+                children.push(node);
+            }
+        }
+        Some(TaskTreeNode {
+            section: key.section.clone(),
+            task_id: key.task_id.clone(),
+            title: task.title.clone(),
+            status: task.status.clone(),
+            done,
+            total,
+            children,
+        })
+    }
+    async fn get_task_details(
+        &self,
+        params: GetTaskDetailsParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let tz = params.timezone.as_deref().map(parse_timezone).transpose()?;
+        let project_data = self.storage.load_project_data().await?;
+        let Some((key, task)) = project_data.lookup_task(&params.section, &params.task_id) else {
+            let suggestion = self
+                .validation_engine
+                .nearest_task(&project_data, &params.section, &params.task_id)
+                .map(|key| format!(" Did you mean '{}:{}'?", key.section, key.task_id))
+                .unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Task not found: {}:{}.{}",
+                params.section,
+                params.task_id,
+                suggestion
+            ));
+        };
+        let time_in_status =
+            self.statistics_manager
+                .get_time_in_status(&key.section, &key.task_id, task)?;
+        let related_notes: Vec<serde_json::Value> = task
+            .related_notes
+            .iter()
+            .filter_map(|id| project_data.get_note(id))
+            .map(|note| serde_json::json!({ "id": note.id, "title": note.title }))
+            .collect();
+        let linked_notes: Vec<serde_json::Value> = project_data
+            .notes_for_task(&key.section, &key.task_id)
+            .into_iter()
+            .map(|note| {
+                serde_json::json!({ "id": note.id, "title": note.title, "created": note.created })
+            })
+            .collect();
+        let mut files: Vec<(&String, &crate::TaskFile)> = task.files.iter().collect();
+        files.sort_by_key(|(file, _)| *file);
+        let references: Vec<serde_json::Value> = files
+            .into_iter()
+            .filter(|(_, task_file)| !task_file.lines.is_empty())
+            .map(|(file, task_file)| {
+                let mut lines = task_file.lines.clone();
+                lines.sort_unstable();
+                let lines: Vec<serde_json::Value> = lines
+                    .into_iter()
+                    .map(|line| serde_json::json!({ "line": line, "note": task_file.notes.get(&line) }))
+                    .collect();
+                serde_json::json!({ "file": file, "lines": lines })
+            })
+            .collect();
+        let dependents = project_data.dependents_of(&key.section, &key.task_id);
+        let history =
+            project_data.task_history(&key.section, &key.task_id, Some(TASK_DETAILS_HISTORY_LIMIT));
+        let mut details = serde_json::json!({
+            "section": key.section,
+            "task_id": key.task_id,
+            "task": task,
+            "references": references,
+            "related_notes": related_notes,
+            "linked_notes": linked_notes,
+            "dependencies": Self::task_summaries(&project_data, &task.dependencies),
+            "dependents": Self::task_summaries(&project_data, &dependents),
+            "history": history,
+            "time_in_status": time_in_status.durations_seconds,
+            "time_in_status_partial": time_in_status.partial
+        });
+        if key.section != params.section || key.task_id != params.task_id {
+            details["renamed_from"] = serde_json::json!({
+                "section": params.section,
+                "task_id": params.task_id
+            });
+            details["deprecation"] = serde_json::json!(format!(
+                "{}:{} was renamed to {}:{}; update references to use the new ID",
+                params.section, params.task_id, key.section, key.task_id
+            ));
+        }
+        Ok(localize_response(details, tz))
+    }
+    /// Former task IDs that still resolve, sorted by old ID
+    async fn list_aliases(&self) -> anyhow::Result<serde_json::Value> {
+        let project_data = self.storage.load_project_data().await?;
+        let mut aliases: Vec<(&String, &String)> = project_data.aliases.iter().collect();
+        aliases.sort();
+        let aliases: Vec<serde_json::Value> = aliases
+            .into_iter()
+            .map(|(old, new)| serde_json::json!({ "from": old, "to": new }))
+            .collect();
+        Ok(serde_json::json!({ "aliases": aliases }))
+    }
+    async fn get_task_history(
+        &self,
+        params: GetTaskHistoryParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        let project_data = self.storage.load_project_data().await?;
+        let key = project_data
+            .resolve_alias(&params.section, &params.task_id)
+            .unwrap_or_else(|| TaskKey::new(&params.section, &params.task_id));
+        let history = project_data.task_history(&key.section, &key.task_id, params.limit);
+        if history.is_empty() && project_data.get_task(&key.section, &key.task_id).is_none() {
+            return Err(anyhow::anyhow!(
+                "Task not found: {}:{}",
+                params.section,
+                params.task_id
+            ));
+        }
+        Ok(serde_json::json!({
+            "section": key.section,
+            "task_id": key.task_id,
+            "history": history
+        }))
+    }
+    async fn clone_section(&self, params: CloneSectionParams) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        self.refresh_validation().await?;
+        if let Some(error) = self
+            .validation_engine
+            .validate_section_name(&params.target)?
+        {
+            return Err(anyhow::anyhow!("{}", error.message));
+        }
+        let before = project_data.sections.clone();
+        let count = project_data.clone_section(&params.source, &params.target)?;
+        project_data.record_changes_since(&before, ChangeSource::Manual);
+        self.storage.save_project_data(&project_data).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!(
+                "Cloned {} tasks from section {} into {}",
+                count, params.source, params.target
+            ),
+        })
+    }
+    async fn find_task_references(
+        &self,
+        params: FindTaskReferencesParams,
+    ) -> anyhow::Result<Vec<TaskReference>> {
+        let project_data = self.storage.load_project_data().await?;
+        if let Some((_, task)) = project_data.lookup_task(&params.section, &params.task_id) {
+            let mut references = Vec::new();
+            for (file_path, task_file) in &task.files {
+                for &line in &task_file.lines {
+                    references.push(TaskReference {
+                        file_path: file_path.clone(),
+                        line,
+                        note: task_file.notes.get(&line).cloned(),
+                    });
+                }
+            }
+            Ok(references)
+        } else {
+            Err(anyhow::anyhow!(
+                "Task not found: {}:{}",
+                params.section,
+                params.task_id
+            ))
+        }
+    }
+    async fn get_section_files(
+        &self,
+        params: GetSectionFilesParams,
+    ) -> anyhow::Result<Vec<SectionFile>> {
+        let project_data = self.storage.load_project_data().await?;
+        Ok(project_data
+            .section_files(&params.section)?
+            .into_iter()
+            .map(|(file_path, task_count)| SectionFile {
+                file_path,
+                task_count: task_count as u32,
+            })
+            .collect())
+    }
+    /// Check every stored reference line against the labels actually in the file,
+    /// optionally moving stale ones to the nearest line that still has the label
+    async fn verify_references(
+        &self,
+        params: Option<VerifyReferencesParams>,
+    ) -> anyhow::Result<VerifyReferencesResult> {
+        let params = params.unwrap_or_default();
+        let repair = params.repair.unwrap_or(false);
+        let max_distance = params.max_distance.unwrap_or(DEFAULT_REPAIR_DISTANCE);
+        let parser = self
+            .configured_parser(params.recognize_keyword_comments.unwrap_or(false))
+            .await?;
+        let mut project_data = self.storage.load_project_data().await?;
+        let mut references: Vec<(TaskKey, String, Vec<u32>)> = project_data
+            .sections
+            .iter()
+            .flat_map(|(section, tasks)| tasks.iter().map(move |(id, task)| (section, id, task)))
+            .flat_map(|(section, task_id, task)| {
+                task.files.iter().map(move |(file, task_file)| {
+                    let mut lines = task_file.lines.clone();
+                    lines.sort_unstable();
+                    (TaskKey::new(section, task_id), file.clone(), lines)
+                })
+            })
+            .collect();
+        references.sort();
+
+        // Line and note of each task's labels per file, `None` when the file cannot be read
+        type FileLabels = HashMap<TaskKey, Vec<(u32, Option<String>)>>;
+        let mut labels_by_file: HashMap<String, Option<FileLabels>> = HashMap::new();
+        let mut result = VerifyReferencesResult {
+            references_checked: 0,
+            stale: Vec::new(),
+            repaired: 0,
+        };
+        for (key, file, lines) in references {
+            let labels = labels_by_file.entry(file.clone()).or_insert_with(|| {
+                let labels = self
+                    .scan_buffers
+                    .scan_path(&parser, &self.workspace_path.join(&file))
+                    .ok()?
+                    .labels;
+                let mut by_task = FileLabels::new();
+                for (label_line, label) in labels {
+                    let label_key = project_data
+                        .resolve_alias(&label.section, &label.task_id)
+                        .unwrap_or_else(|| TaskKey::new(&label.section, &label.task_id));
+                    by_task
+                        .entry(label_key)
+                        .or_default()
+                        .push((label_line, label.note));
+                }
+                Some(by_task)
+            });
+            let notes = project_data
+                .get_task(&key.section, &key.task_id)
+                .and_then(|task| task.files.get(&file))
+                .map(|task_file| task_file.notes.clone())
+                .unwrap_or_default();
+            let label_lines = labels
+                .as_ref()
+                .and_then(|labels| labels.get(&key))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            // A reference holds when its line has the task's label with the same note
+            let holds = |line: u32| {
+                label_lines.iter().any(|(label_line, note)| {
+                    *label_line == line && note.as_ref() == notes.get(&line)
+                })
+            };
+            let mut claimed: Vec<u32> = lines.iter().copied().filter(|line| holds(*line)).collect();
+            let mut moves = Vec::new();
+            for line in lines {
+                result.references_checked += 1;
+                if holds(line) {
+                    continue;
+                }
+                let reason = match labels {
+                    None => "file_unreadable",
+                    Some(_) if label_lines.is_empty() => "label_missing",
+                    Some(_) => "line_mismatch",
+                };
+                let repaired_line = label_lines
+                    .iter()
+                    .filter(|(candidate, note)| {
+                        repair
+                            && note.as_ref() == notes.get(&line)
+                            && !claimed.contains(candidate)
+                            && candidate.abs_diff(line) <= max_distance
+                    })
+                    .map(|(candidate, _)| *candidate)
+                    .min_by_key(|candidate| (candidate.abs_diff(line), *candidate));
+                if let Some(to) = repaired_line {
+                    claimed.push(to);
+                    moves.push((line, to));
+                }
+                result.stale.push(StaleReference {
+                    section: key.section.clone(),
+                    task_id: key.task_id.clone(),
+                    file: file.clone(),
+                    line,
+                    reason: reason.to_string(),
+                    repaired_line,
+                });
+            }
+            if !moves.is_empty() {
+                project_data.move_task_references(&key.section, &key.task_id, &file, &moves)?;
+                result.repaired += moves.len() as u32;
+            }
+        }
+        if result.repaired > 0 {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(result)
+    }
+    /// Decorations for every indexed file, read from the index kept in tasks.json
+    async fn get_all_file_decorations(&self) -> anyhow::Result<Vec<FileDecoration>> {
+        let project_data = self.storage.load_project_data().await?;
+        Ok(project_data
+            .index
+            .file_status_counts()
+            .into_iter()
+            .map(|(file_path, statuses)| FileDecoration {
+                file_path,
+                task_count: statuses.values().sum::<usize>() as u32,
+                has_blocked: statuses.contains_key(&TaskStatus::Blocked),
+                statuses: statuses
+                    .into_iter()
+                    .map(|(status, count)| (status, count as u32))
+                    .collect(),
+            })
+            .collect())
+    }
+    async fn create_note(&self, params: CreateNoteParams) -> anyhow::Result<CreateNoteResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        self.refresh_validation().await?;
+        let validation = self
+            .validation_engine
+            .validate_note_creation(&Self::note_validation_params(&params))?;
+        if !validation.can_create {
+            let messages: Vec<&str> = validation
+                .errors
+                .iter()
+                .map(|error| error.message.as_str())
+                .collect();
+            return Err(anyhow::anyhow!(
+                "Note validation failed: {}",
+                messages.join("; ")
+            ));
+        }
+        let suggested_status = params
+            .suggested_status
+            .as_deref()
+            .map(str::parse::<TaskStatus>)
+            .transpose()?;
+        let note_id = project_data.add_note(
+            params.title.clone(),
+            params.content,
+            params.section,
+            params.suggested_task_id,
+            suggested_status,
+        )?;
+        if params.tags.is_some() {
+            let tags = NoteUpdate {
+                tags: params.tags,
+                ..NoteUpdate::default()
+            };
+            project_data.update_note(&note_id, tags)?;
+        }
+        if let Some(mut attachments) = params.attachments {
+            for attachment in &mut attachments {
+                attachment.file_path = self.workspace_relative_path(&attachment.file_path)?;
+            }
+            project_data.set_note_attachments(&note_id, attachments)?;
+        }
+        self.storage.save_project_data(&project_data).await?;
+        Ok(CreateNoteResponse {
+            success: true,
+            message: format!("Note '{}' created successfully", params.title),
+            note_id,
+            warnings: validation
+                .warnings
+                .into_iter()
+                .map(|warning| warning.message)
+                .collect(),
+        })
+    }
+    async fn validate_note_input(
+        &self,
+        params: NoteValidationParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        self.refresh_validation().await?;
+        let result = self.validation_engine.validate_note_creation(&params)?;
+        Ok(serde_json::to_value(result)?)
+    }
+    fn note_validation_params(params: &CreateNoteParams) -> NoteValidationParams {
+        NoteValidationParams {
+            title: params.title.clone(),
+            content: params.content.clone(),
+            section: params.section.clone(),
+            suggested_task_id: params.suggested_task_id.clone(),
+            suggested_status: params.suggested_status.clone(),
+        }
+    }
+    async fn get_notes(&self, params: Option<GetNotesParams>) -> anyhow::Result<Vec<Note>> {
+        let params = params.unwrap_or_default();
+        let project_data = self.storage.load_project_data().await?;
+        Ok(project_data
+            .get_all_notes()
+            .into_iter()
+            .filter(|note| {
+                params
+                    .tags
+                    .as_ref()
+                    .is_none_or(|tags| note.has_any_tag(tags))
+            })
+            .cloned()
+            .collect())
+    }
+    /// Unconverted notes with generated links and whether each link is in the code yet
+    async fn get_conversion_candidates(&self) -> anyhow::Result<Vec<ConversionCandidate>> {
+        let project_data = self.storage.load_project_data().await?;
+        let threshold = self.storage.load_config().await?.notes.conversion_threshold;
+        let files = if project_data.pending_note_link_cores().is_empty() {
+            Vec::new()
+        } else {
+            self.workspace_source_files()
+        };
+        let scanned_content = self.pending_note_link_content(&project_data, &files);
+        Ok(project_data.conversion_candidates(&scanned_content, threshold))
+    }
+    /// Convert a note by hand. A task already holding the suggested ID is never
+    /// touched: the call fails with alternatives, or with `auto_rename` the note
+    /// moves to the first free alternative first.
+    async fn convert_note_to_task(
+        &self,
+        params: ConvertNoteToTaskParams,
+    ) -> anyhow::Result<ConvertNoteToTaskResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let note = project_data
+            .get_note(&params.note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", params.note_id))?;
+        if note.is_converted {
+            return Err(anyhow::anyhow!("Note is already converted to task"));
+        }
+        let (section, task_id) = (note.section.clone(), note.suggested_task_id.clone());
+        self.refresh_validation().await?;
+        let mut validation = self
+            .validation_engine
+            .validate_task_creation(&ValidationParams {
+                section: section.clone(),
+                task_id: task_id.clone(),
+                title: Some(note.title.clone()),
+                description: None,
+                check_duplicates: Some(true),
+                suggest_alternatives: Some(true),
+            })?;
+        let mut renamed_from = None;
+        if params.auto_rename.unwrap_or(false)
+            && project_data.get_task(&section, &task_id).is_some()
+            && let Some(alternative) = validation.alternative_ids.first().cloned()
+        {
+            validation.allow_existing();
+            let rename = NoteUpdate {
+                suggested_task_id: Some(alternative),
+                ..NoteUpdate::default()
+            };
+            project_data.update_note(&params.note_id, rename)?;
+            renamed_from = Some(task_id);
+        }
+        if !validation.can_create {
+            return Err(ValidationFailed(validation).into());
+        }
+        let task_id = project_data
+            .get_note(&params.note_id)
+            .map(|note| note.suggested_task_id.clone())
+            .unwrap_or_default();
+        project_data.convert_note_to_task(&params.note_id)?;
+        self.storage.save_project_data(&project_data).await?;
+        self.record_note_conversions(&project_data, std::slice::from_ref(&params.note_id))?;
+        Ok(ConvertNoteToTaskResult {
+            note_id: params.note_id,
+            section,
+            task_id,
+            renamed_from,
+        })
+    }
+    /// Split a note into checklist items and create a task for each, all or nothing
+    async fn convert_note_to_tasks(
+        &self,
+        params: ConvertNoteToTasksParams,
+    ) -> anyhow::Result<ConvertNoteToTasksResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let note = project_data
+            .get_note(&params.note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", params.note_id))?;
+        let section = params.section.unwrap_or_else(|| note.section.clone());
+        let titles = params.split.items(&note.content);
+        let task_ids =
+            project_data.checklist_task_ids(&section, params.id_prefix.as_deref(), &titles);
+        for (task_id, title) in task_ids.iter().zip(&titles) {
+            self.ensure_valid_task(
+                &ValidationParams {
+                    section: section.clone(),
+                    task_id: task_id.clone(),
+                    title: Some(title.clone()),
+                    description: None,
+                    check_duplicates: Some(true),
+                    suggest_alternatives: Some(false),
+                },
+                false,
+            )
+            .await?;
+        }
+        let items: Vec<(String, String)> = task_ids.iter().cloned().zip(titles).collect();
+        project_data.convert_note_to_tasks(&params.note_id, &section, &items)?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(ConvertNoteToTasksResult {
+            note_id: params.note_id,
+            section,
+            task_ids,
+        })
+    }
+    async fn update_note(&self, params: UpdateNoteParams) -> anyhow::Result<Note> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let suggested_status = params
+            .suggested_status
+            .as_deref()
+            .map(str::parse::<TaskStatus>)
+            .transpose()?;
+        let update = NoteUpdate {
+            title: params.title,
+            content: params.content,
+            tags: params.tags,
+            section: params.section,
+            suggested_task_id: params.suggested_task_id,
+            suggested_status,
+        };
+        project_data.update_note(&params.note_id, update)?;
+        let note = project_data
+            .get_note(&params.note_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", params.note_id))?;
+        // The merged note has to pass the checks a new note would
+        self.refresh_validation().await?;
+        let validation = self
+            .validation_engine
+            .validate_note_creation(&NoteValidationParams {
+                title: note.title.clone(),
+                content: note.content.clone(),
+                section: note.section.clone(),
+                suggested_task_id: note.suggested_task_id.clone(),
+                suggested_status: params.suggested_status,
+            })?;
+        let messages: Vec<&str> = validation
+            .errors
+            .iter()
+            .map(|error| error.message.as_str())
+            .collect();
+        if !messages.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Note validation failed: {}",
+                messages.join("; ")
+            ));
         }
+        self.storage.save_project_data(&project_data).await?;
+        Ok(note)
     }
-    async fn create_note(&self, params: CreateNoteParams) -> anyhow::Result<CreateNoteResponse> {
-        let mut project_data = self.storage.load_project_data().await?;
-        let suggested_status = if let Some(status_str) = params.suggested_status {
-            match status_str.to_lowercase().as_str() {
-                "todo" => Some(TaskStatus::Todo),
-                "in_progress" | "inprogress" => Some(TaskStatus::InProgress),
-                "done" | "completed" => Some(TaskStatus::Done),
-                "blocked" => Some(TaskStatus::Blocked),
-                _ => return Err(anyhow::anyhow!("Invalid status: {}", status_str)),
-            }
-        } else {
-            None
+    async fn get_note(&self, params: GetNoteParams) -> anyhow::Result<serde_json::Value> {
+        let project_data = self.storage.load_project_data().await?;
+        let note = project_data
+            .get_note(&params.note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", params.note_id))?;
+        let related_tasks: Vec<serde_json::Value> = note
+            .related_tasks
+            .iter()
+            .filter_map(|key| {
+                project_data
+                    .get_task(&key.section, &key.task_id)
+                    .map(|task| {
+                        serde_json::json!({
+                            "section": key.section,
+                            "task_id": key.task_id,
+                            "title": task.title
+                        })
+                    })
+            })
+            .collect();
+        Ok(serde_json::json!({ "note": note, "related_tasks": related_tasks }))
+    }
+    async fn search_notes(&self, params: SearchNotesParams) -> anyhow::Result<NoteSearchResults> {
+        let project_data = self.storage.load_project_data().await?;
+        let query = NoteSearchQuery {
+            query: params.query,
+            section: params.section,
+            converted: params.converted,
+            tags: params.tags,
+            limit: params.limit,
+            offset: params.offset,
         };
-        let note_id = project_data.add_note(
-            params.title.clone(),
-            params.content,
-            params.section,
-            params.suggested_task_id,
-            suggested_status,
+        Ok(search_notes(project_data.get_all_notes(), &query))
+    }
+    async fn link_note_to_task(&self, params: NoteTaskLinkParams) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let linked =
+            project_data.link_note_to_task(&params.note_id, &params.section, &params.task_id)?;
+        if linked {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(BasicResponse {
+            success: true,
+            message: if linked {
+                format!(
+                    "Note {} linked to {}:{}",
+                    params.note_id, params.section, params.task_id
+                )
+            } else {
+                format!(
+                    "Note {} is already linked to {}:{}",
+                    params.note_id, params.section, params.task_id
+                )
+            },
+        })
+    }
+    async fn unlink_note_from_task(
+        &self,
+        params: NoteTaskLinkParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let unlinked = project_data.unlink_note_from_task(
+            &params.note_id,
+            &params.section,
+            &params.task_id,
         )?;
-        self.storage.save_project_data(&project_data).await?;
-        Ok(CreateNoteResponse {
+        if unlinked {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(BasicResponse {
             success: true,
-            message: format!("Note '{}' created successfully", params.title),
-            note_id,
+            message: if unlinked {
+                format!(
+                    "Note {} unlinked from {}:{}",
+                    params.note_id, params.section, params.task_id
+                )
+            } else {
+                format!(
+                    "Note {} was not linked to {}:{}",
+                    params.note_id, params.section, params.task_id
+                )
+            },
         })
     }
-    async fn get_notes(&self) -> anyhow::Result<Vec<Note>> {
+    async fn get_notes_for_task(&self, params: GetNotesForTaskParams) -> anyhow::Result<Vec<Note>> {
+        let project_data = self.storage.load_project_data().await?;
+        Ok(project_data
+            .notes_for_task(&params.section, &params.task_id)
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+    async fn task_exists(&self, params: TaskExistsParams) -> anyhow::Result<TaskExistsResponse> {
         let project_data = self.storage.load_project_data().await?;
-        Ok(project_data.get_all_notes().into_iter().cloned().collect())
+        let found = project_data.lookup_task(&params.section, &params.task_id);
+        Ok(TaskExistsResponse {
+            exists: found.is_some(),
+            status: found.as_ref().map(|(_, task)| task.status.clone()),
+            renamed_to: found
+                .map(|(key, _)| key)
+                .filter(|key| key.section != params.section || key.task_id != params.task_id),
+        })
     }
     async fn generate_task_link(&self, note_id: String) -> anyhow::Result<GenerateLinkResponse> {
         let mut project_data = self.storage.load_project_data().await?;
-        let link = project_data.generate_note_link(&note_id)?;
+        let (link, previous_link) = project_data.generate_note_link(&note_id)?;
         self.storage.save_project_data(&project_data).await?;
         Ok(GenerateLinkResponse {
             success: true,
+            changed: previous_link.as_ref() != Some(&link),
             link,
+            previous_link,
         })
     }
     async fn delete_note(&self, note_id: String) -> anyhow::Result<BasicResponse> {
@@ -382,38 +2884,361 @@ impl TaskManagerHandler {
             message: "Note deleted successfully".to_string(),
         })
     }
+    async fn merge_notes(&self, params: MergeNotesParams) -> anyhow::Result<CreateNoteResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let note_id = project_data.merge_notes(&params.note_ids, params.title)?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(CreateNoteResponse {
+            success: true,
+            message: format!("Merged {} notes", params.note_ids.len()),
+            note_id,
+            warnings: Vec::new(),
+        })
+    }
+    async fn set_note_pinned(
+        &self,
+        note_id: String,
+        pinned: bool,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        if project_data.set_note_pinned(&note_id, pinned)? {
+            self.storage.save_project_data(&project_data).await?;
+        }
+        Ok(BasicResponse {
+            success: true,
+            message: if pinned {
+                "Note pinned"
+            } else {
+                "Note unpinned"
+            }
+            .to_string(),
+        })
+    }
+    async fn purge_converted_notes(
+        &self,
+        params: Option<PurgeConvertedNotesParams>,
+    ) -> anyhow::Result<PurgeConvertedNotesResult> {
+        let params = params.unwrap_or_default();
+        let mut project_data = self.storage.load_project_data().await?;
+        let older_than = params
+            .older_than_days
+            .map(|days| chrono::Duration::days(days.into()));
+        let removed = project_data.purge_converted_notes(older_than);
+        if removed == 0 {
+            return Ok(PurgeConvertedNotesResult {
+                removed,
+                backup_path: None,
+            });
+        }
+        let backup_path = self.storage.create_backup().await?;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(PurgeConvertedNotesResult {
+            removed,
+            backup_path: Some(backup_path.to_string_lossy().to_string()),
+        })
+    }
     async fn search_tasks(&self, params: SearchTasksParams) -> anyhow::Result<serde_json::Value> {
-        let project_data = self.storage.load_project_data().await?;
-        self.search_engine.index_project(&project_data)?;
+        refresh_search_index(&self.storage, &self.search_engine, false).await?;
         let search_query = SearchQuery {
             query: params.query,
             filters: params.filters.and_then(|f| serde_json::from_value(f).ok()),
             limit: params.limit,
             offset: params.offset,
         };
-        let result = self.search_engine.search(&search_query)?;
+        let project_data = self.storage.load_project_data().await?;
+        let result = self.search_engine.search(&search_query, &project_data)?;
         Ok(serde_json::to_value(result)?)
     }
-    async fn get_statistics(&self) -> anyhow::Result<serde_json::Value> {
-        let project_data = self.storage.load_project_data().await?;
+    /// Apply the same edits to every task the search matches, saving once. Tasks
+    /// already in the requested state are not touched.
+    async fn bulk_apply(&self, params: BulkApplyParams) -> anyhow::Result<BulkApplyResult> {
+        if params.query.trim().is_empty() {
+            return Err(anyhow::anyhow!("Query cannot be empty"));
+        }
+        let status = params
+            .set_status
+            .as_deref()
+            .map(str::parse::<TaskStatus>)
+            .transpose()?;
+        let tags = params
+            .add_tags
+            .unwrap_or_default()
+            .iter()
+            .map(|tag| normalize_tag(tag).ok_or_else(|| anyhow::anyhow!("Tag cannot be empty")))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let assignee = params
+            .set_assignee
+            .map(|assignee| assignee.trim().to_string())
+            .filter(|assignee| !assignee.is_empty());
+        if status.is_none() && tags.is_empty() && assignee.is_none() {
+            return Err(anyhow::anyhow!(
+                "Nothing to apply: pass set_status, add_tags or set_assignee"
+            ));
+        }
+
+        refresh_search_index(&self.storage, &self.search_engine, false).await?;
+        let mut project_data = self.storage.load_project_data().await?;
+        let matches = self.search_engine.search(
+            &SearchQuery {
+                query: params.query.clone(),
+                filters: params.filters,
+                limit: Some(usize::MAX),
+                offset: None,
+            },
+            &project_data,
+        )?;
+        let matched = matches.tasks.len();
+        if matched > BULK_APPLY_CONFIRM_THRESHOLD && !params.confirm.unwrap_or(false) {
+            return Err(anyhow::anyhow!(
+                "{} tasks match '{}'; pass confirm: true to change more than {} at once",
+                matched,
+                params.query,
+                BULK_APPLY_CONFIRM_THRESHOLD
+            ));
+        }
+
+        let mut changed = Vec::new();
+        let mut updates = Vec::new();
+        for hit in &matches.tasks {
+            let Some(task) = project_data.get_task(&hit.section, &hit.task_id) else {
+                continue;
+            };
+            let old_status = task.status.clone();
+            let new_status = status.clone().filter(|status| *status != task.status);
+            let missing_tags: Vec<String> = tags
+                .iter()
+                .filter(|tag| !task.tags.contains(tag))
+                .cloned()
+                .collect();
+            let new_assignee = assignee
+                .clone()
+                .filter(|assignee| task.assignee.as_ref() != Some(assignee));
+            if new_status.is_none() && missing_tags.is_empty() && new_assignee.is_none() {
+                continue;
+            }
+            project_data.track_changes(
+                &hit.section,
+                &hit.task_id,
+                ChangeSource::Manual,
+                |data| {
+                    if let Some(status) = new_status.clone() {
+                        data.update_task_status(&hit.section, &hit.task_id, status)?;
+                    }
+                    if !missing_tags.is_empty() {
+                        data.add_task_tags(&hit.section, &hit.task_id, &missing_tags)?;
+                    }
+                    if new_assignee.is_some() {
+                        data.set_task_assignee(&hit.section, &hit.task_id, new_assignee.clone())?;
+                    }
+                    Ok(())
+                },
+            )?;
+            updates.push(TaskUpdate {
+                section: hit.section.clone(),
+                task_id: hit.task_id.clone(),
+                old_status: Some(old_status.clone()),
+                new_status: new_status.clone().unwrap_or(old_status),
+                timestamp: chrono::Utc::now(),
+                change_type: if new_status.is_some() {
+                    ChangeType::StatusUpdated
+                } else {
+                    ChangeType::Modified
+                },
+            });
+            changed.push(TaskKey::new(&hit.section, &hit.task_id));
+        }
+
+        if !changed.is_empty() {
+            self.storage.save_project_data(&project_data).await?;
+            for update in updates {
+                self.statistics_manager.record_task_update(update)?;
+            }
+        }
+        Ok(BulkApplyResult {
+            matched: matched as u32,
+            affected: changed.len() as u32,
+            tasks: changed,
+        })
+    }
+    async fn get_performance_stats(&self) -> anyhow::Result<serde_json::Value> {
+        let mut stats = self.search_engine.get_performance_stats()?;
+        stats["validation_snapshot_builds"] = self.validation_engine.snapshot_builds().into();
+        stats["validation_context_generation"] = self
+            .validation_engine
+            .context_version()
+            .map(|version| version.generation)
+            .into();
+        Ok(stats)
+    }
+    async fn get_statistics(
+        &self,
+        params: Option<GetStatisticsParams>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let tz = params
+            .as_ref()
+            .and_then(|p| p.timezone.as_deref())
+            .map(parse_timezone)
+            .transpose()?;
+        let mut project_data = self.storage.load_project_data().await?;
+        if !params
+            .as_ref()
+            .and_then(|p| p.include_archived)
+            .unwrap_or(false)
+        {
+            project_data.hide_archived_sections();
+        }
+        let filter = params
+            .map(|p| StatisticsFilter {
+                sections: p.section_filter,
+                assignees: p.assignee_filter,
+                tags: p.tag_filter,
+                roll_up_subtasks: p.roll_up_subtasks.unwrap_or(false),
+            })
+            .unwrap_or_default();
+        let order = self.section_order().await?;
         self.statistics_manager
-            .get_statistics(&project_data)
+            .get_filtered_statistics(&project_data, &filter, order)
             .map(|stats| serde_json::to_value(stats).unwrap_or(serde_json::Value::Null))
+            .map(|stats| localize_response(stats, tz))
+    }
+    async fn export_statistics(
+        &self,
+        params: ExportStatisticsParams,
+    ) -> anyhow::Result<BasicResponse> {
+        let mut project_data = self.storage.load_project_data().await?;
+        if !params.include_archived.unwrap_or(false) {
+            project_data.hide_archived_sections();
+        }
+        let order = self.section_order().await?;
+        let stats = if params.fresh.unwrap_or(false) {
+            self.statistics_manager
+                .get_fresh_statistics(&project_data, order)?
+        } else {
+            self.statistics_manager
+                .get_statistics(&project_data, order)?
+        };
+        let by_milestone = params.group_by_milestone.unwrap_or(false);
+        let content = match params.format.to_lowercase().as_str() {
+            "json" if by_milestone => serde_json::to_string_pretty(&serde_json::json!({
+                "project_name": stats.project_name,
+                "generated_at": stats.generated_at,
+                "milestones": stats.overview.milestones
+            }))?,
+            "json" => serde_json::to_string_pretty(&stats)?,
+            "csv" if by_milestone => StatisticsManager::milestones_to_csv(&stats),
+            "csv" => StatisticsManager::to_csv(&stats),
+            _ => return Err(anyhow::anyhow!("Invalid export format: {}", params.format)),
+        };
+        self.storage
+            .write_export(&PathBuf::from(&params.path), &content)
+            .await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Statistics exported to {}", params.path),
+        })
+    }
+    /// Write the tasks in one of the `ReportFormat`s, after applying the filters
+    pub async fn export_tasks(&self, params: ExportTasksParams) -> anyhow::Result<BasicResponse> {
+        let path = PathBuf::from(&params.path);
+        let format: ReportFormat = params.format.as_deref().unwrap_or("json").parse()?;
+        let filter = ReportFilter {
+            sections: params.sections,
+            statuses: params.status,
+            include_notes: params.include_notes,
+        };
+        let mut project_data = self.storage.load_project_data().await?;
+        filter.apply(&mut project_data);
+        let message = match format {
+            ReportFormat::Json => {
+                self.storage
+                    .export_project_data(&project_data, &path, self.section_order().await?)
+                    .await?;
+                format!("Tasks exported to {}", params.path)
+            }
+            ReportFormat::Ndjson => {
+                let count = self
+                    .storage
+                    .export_project_ndjson(&project_data, &path, self.section_order().await?)
+                    .await?;
+                format!("{} tasks exported to {}", count, params.path)
+            }
+            _ => {
+                let order = self.section_order().await?;
+                let include_notes = filter.include_notes.unwrap_or(false);
+                let content = report::render(&project_data, format, order, include_notes)?;
+                self.storage.write_export(&path, &content).await?;
+                format!("Tasks exported to {}", params.path)
+            }
+        };
+        Ok(BasicResponse {
+            success: true,
+            message,
+        })
     }
-    async fn get_task_overview(&self) -> anyhow::Result<serde_json::Value> {
+    async fn export_notes(&self, params: ExportNotesParams) -> anyhow::Result<BasicResponse> {
         let project_data = self.storage.load_project_data().await?;
-        let overview = self.statistics_manager.get_overview(&project_data)?;
-        let recent_activity = self.statistics_manager.get_recent_activity()?;
+        let markdown = project_data.notes_to_markdown(params.include_converted.unwrap_or(false));
+        self.storage
+            .write_export(&PathBuf::from(&params.path), &markdown)
+            .await?;
+        Ok(BasicResponse {
+            success: true,
+            message: format!("Notes exported to {}", params.path),
+        })
+    }
+    async fn import_tasks(&self, params: ImportTasksParams) -> anyhow::Result<BasicResponse> {
+        let path = PathBuf::from(&params.path);
+        let message = match params.format.as_deref().unwrap_or("json") {
+            "json" => {
+                self.storage.import_data(&path).await?;
+                format!("Tasks imported from {}", params.path)
+            }
+            "ndjson" => {
+                let count = self.storage.import_ndjson(&path).await?;
+                format!("{} tasks imported from {}", count, params.path)
+            }
+            format => return Err(anyhow::anyhow!("Invalid import format: {}", format)),
+        };
+        Ok(BasicResponse {
+            success: true,
+            message,
+        })
+    }
+    async fn get_task_overview(
+        &self,
+        params: Option<GetTaskOverviewParams>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let tz = params
+            .as_ref()
+            .and_then(|p| p.timezone.as_deref())
+            .map(parse_timezone)
+            .transpose()?;
+        let mut project_data = self.storage.load_project_data().await?;
+        if !params
+            .as_ref()
+            .and_then(|p| p.include_archived)
+            .unwrap_or(false)
+        {
+            project_data.hide_archived_sections();
+        }
+        let order = self.section_order().await?;
+        let statistics = self
+            .statistics_manager
+            .get_statistics(&project_data, order)?;
+        let overview = &statistics.overview;
         let mut sections_with_tasks = Vec::new();
         for section_summary in &overview.sections {
             let mut section_tasks = Vec::new();
             if let Some(section_data) = project_data.sections.get(&section_summary.name) {
-                for (task_id, task) in section_data {
+                let mut tasks: Vec<(&String, &crate::Task)> = section_data.iter().collect();
+                tasks.sort_by_key(|(task_id, _)| *task_id);
+                for (task_id, task) in tasks {
                     let task_info = serde_json::json!({
                         "id": task_id,
                         "title": task.title,
                         "description": task.description,
                         "status": task.status,
+                        "priority": task.priority,
                         "created": task.created.to_rfc3339(),
                         "updated": task.updated.to_rfc3339(),
                         "fileCount": task.files.len()
@@ -440,36 +3265,29 @@ impl TaskManagerHandler {
                 "done": overview.completed_tasks,
                 "blocked": overview.blocked_tasks
             },
-            "by_section": {},
-            "recent_updates": [],
-            "performance_metrics": {
-                "calculation_time_ms": 0,
-                "cache_hit_rate": 0.0,
-                "last_cache_update": chrono::Utc::now().to_rfc3339(),
-                "total_calculations": 0
-            },
-            "last_calculated": chrono::Utc::now().to_rfc3339(),
-            "trends": {
-                "daily_completions": [],
-                "section_velocity": {},
-                "status_transitions": {},
-                "productivity_score": 75.0
-            }
+            "by_section": statistics.sections,
+            "recent_updates": statistics.recent_activity,
+            "performance_metrics": self.statistics_manager.get_performance_metrics()?,
+            "last_calculated": statistics.generated_at.to_rfc3339(),
+            "trends": statistics.trends
         });
         let complete_overview = serde_json::json!({
             "sections": sections_with_tasks,
             "statistics": task_statistics,
-            "recent_activity": recent_activity,
-            "recommendations": []
+            "recent_activity": statistics.recent_activity,
+            "top_assignees": overview.top_assignees,
+            "top_tags": overview.top_tags,
+            "priorities": overview.priorities,
+            "tasks_with_recent_comments": overview.tasks_with_recent_comments,
+            "milestones": overview.milestones
         });
-        Ok(complete_overview)
+        Ok(localize_response(complete_overview, tz))
     }
     async fn validate_task_input(
         &self,
         params: ValidateTaskParams,
     ) -> anyhow::Result<serde_json::Value> {
-        let project_data = self.storage.load_project_data().await?;
-        self.validation_engine.update_context(project_data)?;
+        self.refresh_validation().await?;
         let validation_params = ValidationParams {
             section: params.section,
             task_id: params.task_id,
@@ -483,6 +3301,120 @@ impl TaskManagerHandler {
             .validate_task_creation(&validation_params)?;
         Ok(serde_json::to_value(result)?)
     }
+    async fn validate_tasks_batch(
+        &self,
+        params: ValidateTasksBatchParams,
+    ) -> anyhow::Result<serde_json::Value> {
+        self.refresh_validation().await?;
+        let result = self.validation_engine.validate_batch(&params.items)?;
+        Ok(serde_json::to_value(result)?)
+    }
+    async fn validate_project(
+        &self,
+        params: Option<ValidateProjectParams>,
+    ) -> anyhow::Result<ProjectValidationReport> {
+        let params = params.unwrap_or_default();
+        self.refresh_validation().await?;
+        self.validation_engine
+            .validate_project(params.max_per_rule.unwrap_or(100))
+    }
+    async fn normalize_ids(
+        &self,
+        params: NormalizeIdsParams,
+    ) -> anyhow::Result<NormalizeIdsResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let (renames, collisions) = project_data.plan_id_normalization(params.style);
+        let mut result = NormalizeIdsResult {
+            dry_run: params.dry_run.unwrap_or(false),
+            renames,
+            collisions,
+            files_updated: 0,
+            backup_path: None,
+            errors: Vec::new(),
+        };
+        if result.dry_run || result.renames.is_empty() {
+            return Ok(result);
+        }
+        let parser = self.configured_parser(false).await?;
+        let mut renames_by_file: HashMap<String, Vec<IdRename>> = HashMap::new();
+        for rename in &result.renames {
+            if let Some(task) = project_data.get_task(&rename.section, &rename.old_id) {
+                for file_path in task.files.keys() {
+                    renames_by_file
+                        .entry(file_path.clone())
+                        .or_default()
+                        .push(rename.clone());
+                }
+            }
+        }
+        // Rewrite every file in memory first so a read failure leaves nothing half-applied
+        let mut file_paths: Vec<String> = renames_by_file.keys().cloned().collect();
+        file_paths.sort();
+        let mut rewrites = Vec::new();
+        for file_path in file_paths {
+            let path = self.workspace_path.join(&file_path);
+            let content = tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read file {}: {}", file_path, e))?;
+            let (rewritten, changed) = TaskParser::rewrite_task_ids(
+                &content,
+                parser.comment_syntax(&file_path),
+                &renames_by_file[&file_path],
+            )?;
+            if changed > 0 {
+                rewrites.push((path, rewritten));
+            }
+        }
+        project_data.apply_id_renames(&result.renames)?;
+
+        let backup_path = self.storage.create_backup().await?;
+        result.backup_path = Some(backup_path.to_string_lossy().to_string());
+        // Files and tasks.json change together or not at all: every rewrite is
+        // staged before any file is replaced, and the store is saved last
+        let mut writes = StagedWrites::new();
+        for (path, rewritten) in &rewrites {
+            writes.stage(path, rewritten).await?;
+        }
+        result.files_updated = writes.commit().await? as u32;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(result)
+    }
+    async fn get_validation_config(&self) -> anyhow::Result<serde_json::Value> {
+        let config = self.storage.load_config().await?;
+        self.validation_engine.apply_config(config.validation)?;
+        let config = self.validation_engine.get_config();
+        let sections: HashMap<&String, _> = config
+            .sections
+            .keys()
+            .map(|section| (section, config.section_rules(section)))
+            .collect();
+        Ok(serde_json::json!({
+            "config": config,
+            "sections": sections,
+            "reserved_names": self.validation_engine.get_reserved_names()
+        }))
+    }
+    /// Merge the supplied fields into the stored config; anything left out
+    /// keeps its stored value
+    async fn set_config(&self, update: serde_json::Value) -> anyhow::Result<BasicResponse> {
+        let config = self
+            .storage
+            .load_config()
+            .await?
+            .merged(update)
+            .map_err(|e| anyhow::anyhow!("Invalid project config: {}", e))?;
+        if let Some(repository_url) = &config.scan.repository_url {
+            file_parser::issues_url(repository_url)
+                .map_err(|e| anyhow::anyhow!("Invalid scan.repository_url: {}", e))?;
+        }
+        self.validation_engine
+            .apply_config(config.validation.clone())?;
+        self.storage.save_config(&config).await?;
+        Ok(BasicResponse {
+            success: true,
+            message: "Project config updated".to_string(),
+        })
+    }
     async fn get_suggestions(
         &self,
         params: GetSuggestionsParams,
@@ -494,13 +3426,118 @@ impl TaskManagerHandler {
         &self,
         params: CheckConflictsParams,
     ) -> anyhow::Result<serde_json::Value> {
-        let project_data = self.storage.load_project_data().await?;
-        self.validation_engine.update_context(project_data)?;
+        self.refresh_validation().await?;
         let result = self
             .validation_engine
             .check_task_conflicts(&params.section, &params.task_id)?;
         Ok(serde_json::to_value(result)?)
     }
+    async fn apply_conflict_resolution(
+        &self,
+        params: ApplyConflictResolutionParams,
+    ) -> anyhow::Result<ConflictResolutionResult> {
+        let mut project_data = self.storage.load_project_data().await?;
+        let task = project_data
+            .get_task(&params.section, &params.task_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Task not found: {}:{}", params.section, params.task_id)
+            })?;
+        let (target_section, target_id) = match &params.resolution {
+            ResolutionAction::RenameTo { new_id } => (params.section.clone(), new_id.clone()),
+            ResolutionAction::MoveToSection { section } => {
+                (section.clone(), params.task_id.clone())
+            }
+            ResolutionAction::MergeInto { section, task_id } => (section.clone(), task_id.clone()),
+        };
+
+        // The project may have changed since the conflict was reported
+        let target_exists = project_data.get_task(&target_section, &target_id).is_some();
+        if let ResolutionAction::MergeInto { .. } = params.resolution {
+            if !target_exists {
+                return Err(anyhow::anyhow!(
+                    "Merge target no longer exists: {}:{}",
+                    target_section,
+                    target_id
+                ));
+            }
+        } else {
+            if target_exists {
+                return Err(anyhow::anyhow!(
+                    "Resolution collides with existing task {}:{}",
+                    target_section,
+                    target_id
+                ));
+            }
+            self.refresh_validation().await?;
+            if let Some(error) = self
+                .validation_engine
+                .validate_label(&target_section, &target_id)
+                .into_iter()
+                .next()
+            {
+                return Err(anyhow::anyhow!("{}", error.message));
+            }
+        }
+
+        // Rewrite every file in memory first so a read failure leaves nothing half-applied
+        let parser = self.configured_parser(false).await?;
+        let mut file_paths: Vec<String> = task.files.keys().cloned().collect();
+        file_paths.sort();
+        let mut rewrites = Vec::new();
+        for file_path in file_paths {
+            let path = self.workspace_path.join(&file_path);
+            let content = tokio::fs::read_to_string(&path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read file {}: {}", file_path, e))?;
+            let (rewritten, changed) = TaskParser::rewrite_task_reference(
+                &content,
+                parser.comment_syntax(&file_path),
+                &params.section,
+                &params.task_id,
+                &target_section,
+                &target_id,
+            )?;
+            if changed > 0 {
+                rewrites.push((path, rewritten));
+            }
+        }
+
+        match params.resolution {
+            ResolutionAction::MergeInto { .. } => project_data.merge_task_into(
+                &params.section,
+                &params.task_id,
+                &target_section,
+                &target_id,
+            )?,
+            _ => project_data.move_task(
+                &params.section,
+                &params.task_id,
+                &target_section,
+                &target_id,
+            )?,
+        }
+
+        let mut result = ConflictResolutionResult {
+            section: target_section.clone(),
+            task_id: target_id.clone(),
+            files_updated: 0,
+            backup_path: None,
+            errors: Vec::new(),
+        };
+        if !rewrites.is_empty() {
+            let backup_path = self.storage.create_backup().await?;
+            result.backup_path = Some(backup_path.to_string_lossy().to_string());
+        }
+        // Files and tasks.json change together or not at all: every rewrite is
+        // staged before any file is replaced, and the store is saved last
+        let mut writes = StagedWrites::new();
+        for (path, rewritten) in &rewrites {
+            writes.stage(path, rewritten).await?;
+        }
+        result.files_updated = writes.commit().await? as u32;
+        self.storage.save_project_data(&project_data).await?;
+        Ok(result)
+    }
 }
 impl JsonRpcHandler for TaskManagerHandler {
     fn handle_request(
@@ -508,23 +3545,299 @@ impl JsonRpcHandler for TaskManagerHandler {
         request: JsonRpcRequest,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = JsonRpcResponse> + Send + '_>> {
         Box::pin(async move {
+            if self.require_initialize
+                && request.method != "initialize"
+                && !self.initialized.load(Ordering::SeqCst)
+            {
+                eprintln!("[ERROR] {} called before initialize", request.method);
+                return JsonRpcServer::error_response(request.id, JsonRpcError::not_initialized());
+            }
+            let _mutations = self.lock_mutations().await;
             match request.method.as_str() {
+                "initialize" => {
+                    handle_parameterized_method!(
+                        request,
+                        InitializeParams,
+                        "initialize",
+                        "Initialize client session",
+                        |params| self.initialize(params)
+                    )
+                }
+                "scan_files" => {
+                    handle_parameterized_method!(
+                        request,
+                        ScanFilesParams,
+                        "scan_files",
+                        "Scan listed files",
+                        |params| self.scan_files(params)
+                    )
+                }
+                "preview_file_labels" => {
+                    handle_parameterized_method!(
+                        request,
+                        PreviewFileLabelsParams,
+                        "preview_file_labels",
+                        "Preview labels in unsaved content",
+                        |params| self.preview_file_labels(params)
+                    )
+                }
+                "benchmark_scan" => {
+                    handle_optional_params_method!(
+                        request,
+                        BenchmarkScanParams,
+                        "benchmark_scan",
+                        "Benchmark project scan",
+                        |params| self.benchmark_scan(params)
+                    )
+                }
                 "scan_project" => {
                     handle_parameterized_method!(
                         request,
-                        ScanProjectParams,
-                        "scan_project",
-                        "Scan project for tasks",
-                        |params| self.scan_project(params)
+                        ScanProjectParams,
+                        "scan_project",
+                        "Scan project for tasks",
+                        |params| self.scan_project(params)
+                    )
+                }
+                "get_tasks" => {
+                    handle_optional_params_method!(
+                        request,
+                        GetTasksParams,
+                        "get_tasks",
+                        "Retrieve project tasks",
+                        |params| self.get_tasks(params)
+                    )
+                }
+                "get_filtered_tasks" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetFilteredTasksParams,
+                        "get_filtered_tasks",
+                        "Retrieve filtered tasks",
+                        |params| self.get_filtered_tasks(params)
+                    )
+                }
+                "archive_section" => {
+                    handle_parameterized_method!(
+                        request,
+                        ArchiveSectionParams,
+                        "archive_section",
+                        "Archive section",
+                        |params| self.archive_section(params)
+                    )
+                }
+                "unarchive_section" => {
+                    handle_parameterized_method!(
+                        request,
+                        ArchiveSectionParams,
+                        "unarchive_section",
+                        "Unarchive section",
+                        |params| self.unarchive_section(params)
+                    )
+                }
+                "set_task_assignee" => {
+                    handle_parameterized_method!(
+                        request,
+                        SetTaskAssigneeParams,
+                        "set_task_assignee",
+                        "Set task assignee",
+                        |params| self.set_task_assignee(params)
+                    )
+                }
+                "get_my_tasks" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetMyTasksParams,
+                        "get_my_tasks",
+                        "Get my tasks",
+                        |params| self.get_my_tasks(params)
+                    )
+                }
+                "add_task_link" => {
+                    handle_parameterized_method!(
+                        request,
+                        AddTaskLinkParams,
+                        "add_task_link",
+                        "Add task link",
+                        |params| self.add_task_link(params)
+                    )
+                }
+                "remove_task_link" => {
+                    handle_parameterized_method!(
+                        request,
+                        RemoveTaskLinkParams,
+                        "remove_task_link",
+                        "Remove task link",
+                        |params| self.remove_task_link(params)
+                    )
+                }
+                "add_task_tags" => {
+                    handle_parameterized_method!(
+                        request,
+                        TaskTagsParams,
+                        "add_task_tags",
+                        "Add task tags",
+                        |params| self.add_task_tags(params)
+                    )
+                }
+                "remove_task_tags" => {
+                    handle_parameterized_method!(
+                        request,
+                        TaskTagsParams,
+                        "remove_task_tags",
+                        "Remove task tags",
+                        |params| self.remove_task_tags(params)
+                    )
+                }
+                "get_tags" => {
+                    handle_optional_params_method!(
+                        request,
+                        GetTagsParams,
+                        "get_tags",
+                        "Get tags",
+                        |params| self.get_tags(params)
+                    )
+                }
+                "create_milestone" => {
+                    handle_parameterized_method!(
+                        request,
+                        CreateMilestoneParams,
+                        "create_milestone",
+                        "Create milestone",
+                        |params| self.create_milestone(params)
+                    )
+                }
+                "delete_milestone" => {
+                    handle_parameterized_method!(
+                        request,
+                        DeleteMilestoneParams,
+                        "delete_milestone",
+                        "Delete milestone",
+                        |params| self.delete_milestone(params)
                     )
                 }
-                "get_tasks" => {
-                    let params = request.params.and_then(|p| serde_json::from_value(p).ok());
+                "add_task_to_milestone" => {
+                    handle_parameterized_method!(
+                        request,
+                        MilestoneTaskParams,
+                        "add_task_to_milestone",
+                        "Add task to milestone",
+                        |params| self.add_task_to_milestone(params)
+                    )
+                }
+                "remove_task_from_milestone" => {
+                    handle_parameterized_method!(
+                        request,
+                        MilestoneTaskParams,
+                        "remove_task_from_milestone",
+                        "Remove task from milestone",
+                        |params| self.remove_task_from_milestone(params)
+                    )
+                }
+                "get_milestones" => {
                     handle_simple_method!(
                         request.id,
-                        "get_tasks",
-                        "Retrieve project tasks",
-                        self.get_tasks(params)
+                        "get_milestones",
+                        "Get milestones",
+                        self.get_milestones()
+                    )
+                }
+                "edit_task_text" => {
+                    handle_parameterized_method!(
+                        request,
+                        EditTaskTextParams,
+                        "edit_task_text",
+                        "Edit task text",
+                        |params| self.edit_task_text(params)
+                    )
+                }
+                "set_task_priority" => {
+                    handle_parameterized_method!(
+                        request,
+                        SetTaskPriorityParams,
+                        "set_task_priority",
+                        "Set task priority",
+                        |params| self.set_task_priority(params)
+                    )
+                }
+                "set_task_estimate" => {
+                    handle_parameterized_method!(
+                        request,
+                        SetTaskEstimateParams,
+                        "set_task_estimate",
+                        "Set task estimate",
+                        |params| self.set_task_estimate(params)
+                    )
+                }
+                "add_time_entry" => {
+                    handle_parameterized_method!(
+                        request,
+                        AddTimeEntryParams,
+                        "add_time_entry",
+                        "Add time entry",
+                        |params| self.add_time_entry(params)
+                    )
+                }
+                "reorder_task" => {
+                    handle_parameterized_method!(
+                        request,
+                        ReorderTaskParams,
+                        "reorder_task",
+                        "Reorder task",
+                        |params| self.reorder_task(params)
+                    )
+                }
+                "compare_sections" => {
+                    handle_parameterized_method!(
+                        request,
+                        CompareSectionsParams,
+                        "compare_sections",
+                        "Compare sections",
+                        |params| self.compare_sections(params)
+                    )
+                }
+                "get_completion_forecast" => {
+                    handle_simple_method!(
+                        request.id,
+                        "get_completion_forecast",
+                        "Get completion forecast",
+                        self.get_completion_forecast()
+                    )
+                }
+                "add_task_comment" => {
+                    handle_parameterized_method!(
+                        request,
+                        AddTaskCommentParams,
+                        "add_task_comment",
+                        "Add task comment",
+                        |params| self.add_task_comment(params)
+                    )
+                }
+                "delete_task_comment" => {
+                    handle_parameterized_method!(
+                        request,
+                        DeleteTaskCommentParams,
+                        "delete_task_comment",
+                        "Delete task comment",
+                        |params| self.delete_task_comment(params)
+                    )
+                }
+                "set_task_metadata" => {
+                    handle_parameterized_method!(
+                        request,
+                        SetTaskMetadataParams,
+                        "set_task_metadata",
+                        "Set task metadata",
+                        |params| self.set_task_metadata(params)
+                    )
+                }
+                "remove_task_metadata" => {
+                    handle_parameterized_method!(
+                        request,
+                        RemoveTaskMetadataParams,
+                        "remove_task_metadata",
+                        "Remove task metadata",
+                        |params| self.remove_task_metadata(params)
                     )
                 }
                 "create_task" => {
@@ -536,6 +3849,32 @@ impl JsonRpcHandler for TaskManagerHandler {
                         |params| self.create_task(params)
                     )
                 }
+                "create_task_from_template" => {
+                    handle_parameterized_method!(
+                        request,
+                        CreateTaskFromTemplateParams,
+                        "create_task_from_template",
+                        "Create task from template",
+                        |params| self.create_task_from_template(params)
+                    )
+                }
+                "save_task_template" => {
+                    handle_parameterized_method!(
+                        request,
+                        SaveTaskTemplateParams,
+                        "save_task_template",
+                        "Save task template",
+                        |params| self.save_task_template(params)
+                    )
+                }
+                "list_task_templates" => {
+                    handle_simple_method!(
+                        request.id,
+                        "list_task_templates",
+                        "List task templates",
+                        self.list_task_templates()
+                    )
+                }
                 "update_task_status" => {
                     handle_parameterized_method!(
                         request,
@@ -554,6 +3893,85 @@ impl JsonRpcHandler for TaskManagerHandler {
                         |params| self.delete_task(params)
                     )
                 }
+                "list_trash" => {
+                    handle_simple_method!(
+                        request.id,
+                        "list_trash",
+                        "List trashed tasks",
+                        self.list_trash()
+                    )
+                }
+                "restore_task" => {
+                    handle_parameterized_method!(
+                        request,
+                        RestoreTaskParams,
+                        "restore_task",
+                        "Restore task from trash",
+                        |params| self.restore_task(params)
+                    )
+                }
+                "empty_trash" => {
+                    handle_optional_params_method!(
+                        request,
+                        EmptyTrashParams,
+                        "empty_trash",
+                        "Empty trash",
+                        |params| self.empty_trash(params)
+                    )
+                }
+                "set_task_parent" => {
+                    handle_parameterized_method!(
+                        request,
+                        SetTaskParentParams,
+                        "set_task_parent",
+                        "Set task parent",
+                        |params| self.set_task_parent(params)
+                    )
+                }
+                "get_task_tree" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetTaskTreeParams,
+                        "get_task_tree",
+                        "Get task tree",
+                        |params| self.get_task_tree(params)
+                    )
+                }
+                "get_task_details" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetTaskDetailsParams,
+                        "get_task_details",
+                        "Get task details",
+                        |params| self.get_task_details(params)
+                    )
+                }
+                "list_aliases" => {
+                    handle_simple_method!(
+                        request.id,
+                        "list_aliases",
+                        "List task aliases",
+                        self.list_aliases()
+                    )
+                }
+                "get_task_history" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetTaskHistoryParams,
+                        "get_task_history",
+                        "Get task history",
+                        |params| self.get_task_history(params)
+                    )
+                }
+                "clone_section" => {
+                    handle_parameterized_method!(
+                        request,
+                        CloneSectionParams,
+                        "clone_section",
+                        "Clone section",
+                        |params| self.clone_section(params)
+                    )
+                }
                 "find_task_references" => {
                     handle_parameterized_method!(
                         request,
@@ -563,6 +3981,41 @@ impl JsonRpcHandler for TaskManagerHandler {
                         |params| async { self.find_task_references(params).await }
                     )
                 }
+                "rename_file_references" => {
+                    handle_parameterized_method!(
+                        request,
+                        RenameFileReferencesParams,
+                        "rename_file_references",
+                        "Rename file references",
+                        |params| self.rename_file_references(params)
+                    )
+                }
+                "get_section_files" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetSectionFilesParams,
+                        "get_section_files",
+                        "Get files referenced by section",
+                        |params| self.get_section_files(params)
+                    )
+                }
+                "get_all_file_decorations" => {
+                    handle_simple_method!(
+                        request.id,
+                        "get_all_file_decorations",
+                        "Get decorations for all files",
+                        self.get_all_file_decorations()
+                    )
+                }
+                "verify_references" => {
+                    handle_optional_params_method!(
+                        request,
+                        VerifyReferencesParams,
+                        "verify_references",
+                        "Verify task references",
+                        |params| self.verify_references(params)
+                    )
+                }
                 "create_note" => {
                     handle_parameterized_method!(
                         request,
@@ -573,11 +4026,128 @@ impl JsonRpcHandler for TaskManagerHandler {
                     )
                 }
                 "get_notes" => {
-                    handle_simple_method!(
-                        request.id,
+                    handle_optional_params_method!(
+                        request,
+                        GetNotesParams,
                         "get_notes",
                         "Retrieve all notes",
-                        self.get_notes()
+                        |params| self.get_notes(params)
+                    )
+                }
+                "get_conversion_candidates" => {
+                    handle_simple_method!(
+                        request.id,
+                        "get_conversion_candidates",
+                        "Get note conversion candidates",
+                        self.get_conversion_candidates()
+                    )
+                }
+                "convert_note_to_task" => {
+                    handle_parameterized_method!(
+                        request,
+                        ConvertNoteToTaskParams,
+                        "convert_note_to_task",
+                        "Convert note to task",
+                        |params| self.convert_note_to_task(params)
+                    )
+                }
+                "convert_note_to_tasks" => {
+                    handle_parameterized_method!(
+                        request,
+                        ConvertNoteToTasksParams,
+                        "convert_note_to_tasks",
+                        "Convert note to tasks",
+                        |params| self.convert_note_to_tasks(params)
+                    )
+                }
+                "update_note" => {
+                    handle_parameterized_method!(
+                        request,
+                        UpdateNoteParams,
+                        "update_note",
+                        "Update note",
+                        |params| self.update_note(params)
+                    )
+                }
+                "get_note" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetNoteParams,
+                        "get_note",
+                        "Get note",
+                        |params| self.get_note(params)
+                    )
+                }
+                "search_notes" => {
+                    handle_parameterized_method!(
+                        request,
+                        SearchNotesParams,
+                        "search_notes",
+                        "Search notes",
+                        |params| self.search_notes(params)
+                    )
+                }
+                "link_note_to_task" => {
+                    handle_parameterized_method!(
+                        request,
+                        NoteTaskLinkParams,
+                        "link_note_to_task",
+                        "Link note to task",
+                        |params| self.link_note_to_task(params)
+                    )
+                }
+                "unlink_note_from_task" => {
+                    handle_parameterized_method!(
+                        request,
+                        NoteTaskLinkParams,
+                        "unlink_note_from_task",
+                        "Unlink note from task",
+                        |params| self.unlink_note_from_task(params)
+                    )
+                }
+                "get_notes_for_task" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetNotesForTaskParams,
+                        "get_notes_for_task",
+                        "Retrieve notes suggested for task",
+                        |params| self.get_notes_for_task(params)
+                    )
+                }
+                "get_dependents" => {
+                    handle_parameterized_method!(
+                        request,
+                        GetDependentsParams,
+                        "get_dependents",
+                        "Retrieve dependent tasks",
+                        |params| self.get_dependents(params)
+                    )
+                }
+                "add_task_dependency" => {
+                    handle_parameterized_method!(
+                        request,
+                        TaskDependencyParams,
+                        "add_task_dependency",
+                        "Add task dependency",
+                        |params| self.add_task_dependency(params)
+                    )
+                }
+                "remove_task_dependency" => {
+                    handle_parameterized_method!(
+                        request,
+                        TaskDependencyParams,
+                        "remove_task_dependency",
+                        "Remove task dependency",
+                        |params| self.remove_task_dependency(params)
+                    )
+                }
+                "task_exists" => {
+                    handle_parameterized_method!(
+                        request,
+                        TaskExistsParams,
+                        "task_exists",
+                        "Check whether task exists",
+                        |params| self.task_exists(params)
                     )
                 }
                 "generate_task_link" => {
@@ -598,29 +4168,114 @@ impl JsonRpcHandler for TaskManagerHandler {
                         |params| self.delete_note(params.note_id)
                     )
                 }
+                "merge_notes" => {
+                    handle_parameterized_method!(
+                        request,
+                        MergeNotesParams,
+                        "merge_notes",
+                        "Merge notes",
+                        |params| self.merge_notes(params)
+                    )
+                }
+                "pin_note" => {
+                    handle_parameterized_method!(
+                        request,
+                        PinNoteParams,
+                        "pin_note",
+                        "Pin note",
+                        |params| self.set_note_pinned(params.note_id, true)
+                    )
+                }
+                "unpin_note" => {
+                    handle_parameterized_method!(
+                        request,
+                        PinNoteParams,
+                        "unpin_note",
+                        "Unpin note",
+                        |params| self.set_note_pinned(params.note_id, false)
+                    )
+                }
+                "purge_converted_notes" => {
+                    handle_optional_params_method!(
+                        request,
+                        PurgeConvertedNotesParams,
+                        "purge_converted_notes",
+                        "Purge converted notes",
+                        |params| self.purge_converted_notes(params)
+                    )
+                }
                 "search_tasks" => {
                     handle_parameterized_method!(
                         request,
                         SearchTasksParams,
                         "search_tasks",
                         "Search tasks with indexing",
-                        |params| self.search_tasks(params)
+                        |params| self.search_tasks(params),
+                        performance = self.performance_metrics_enabled().await
+                    )
+                }
+                "bulk_apply" => {
+                    handle_parameterized_method!(
+                        request,
+                        BulkApplyParams,
+                        "bulk_apply",
+                        "Apply edits to every task matching a search",
+                        |params| self.bulk_apply(params)
                     )
                 }
                 "get_statistics" => {
-                    handle_simple_method!(
-                        request.id,
+                    handle_optional_params_method!(
+                        request,
+                        GetStatisticsParams,
                         "get_statistics",
                         "Get task statistics",
-                        self.get_statistics()
+                        |params| self.get_statistics(params),
+                        performance = self.performance_metrics_enabled().await
+                    )
+                }
+                "export_statistics" => {
+                    handle_parameterized_method!(
+                        request,
+                        ExportStatisticsParams,
+                        "export_statistics",
+                        "Export statistics snapshot",
+                        |params| self.export_statistics(params)
+                    )
+                }
+                "export_tasks" => {
+                    handle_parameterized_method!(
+                        request,
+                        ExportTasksParams,
+                        "export_tasks",
+                        "Export tasks",
+                        |params| self.export_tasks(params)
+                    )
+                }
+                "export_notes" => {
+                    handle_parameterized_method!(
+                        request,
+                        ExportNotesParams,
+                        "export_notes",
+                        "Export notes",
+                        |params| self.export_notes(params)
+                    )
+                }
+                "import_tasks" => {
+                    handle_parameterized_method!(
+                        request,
+                        ImportTasksParams,
+                        "import_tasks",
+                        "Import tasks",
+                        |params| self.import_tasks(params)
                     )
                 }
                 "get_task_overview" => {
-                    handle_simple_method!(
-                        request.id,
+                    handle_optional_params_method!(
+                        request,
+                        GetTaskOverviewParams,
                         "get_task_overview",
                         "Get task overview",
-                        self.get_task_overview()
+                        |params| self.get_task_overview(params)
                     )
                 }
                 "validate_task_input" => {
@@ -629,7 +4284,72 @@ impl JsonRpcHandler for TaskManagerHandler {
                         ValidateTaskParams,
                         "validate_task_input",
                         "Validate task input",
-                        |params| self.validate_task_input(params)
+                        |params| self.validate_task_input(params),
+                        performance = self.performance_metrics_enabled().await
+                    )
+                }
+                "validate_note_input" => {
+                    handle_parameterized_method!(
+                        request,
+                        NoteValidationParams,
+                        "validate_note_input",
+                        "Validate note input",
+                        |params| self.validate_note_input(params),
+                        performance = self.performance_metrics_enabled().await
+                    )
+                }
+                "validate_tasks_batch" => {
+                    handle_parameterized_method!(
+                        request,
+                        ValidateTasksBatchParams,
+                        "validate_tasks_batch",
+                        "Validate task batch",
+                        |params| self.validate_tasks_batch(params),
+                        performance = self.performance_metrics_enabled().await
+                    )
+                }
+                "validate_project" => {
+                    handle_optional_params_method!(
+                        request,
+                        ValidateProjectParams,
+                        "validate_project",
+                        "Validate stored tasks",
+                        |params| self.validate_project(params),
+                        performance = self.performance_metrics_enabled().await
+                    )
+                }
+                "normalize_ids" => {
+                    handle_parameterized_method!(
+                        request,
+                        NormalizeIdsParams,
+                        "normalize_ids",
+                        "Normalize task IDs",
+                        |params| self.normalize_ids(params)
+                    )
+                }
+                "get_performance_stats" => {
+                    handle_simple_method!(
+                        request.id,
+                        "get_performance_stats",
+                        "Get search performance stats",
+                        self.get_performance_stats()
+                    )
+                }
+                "get_validation_config" => {
+                    handle_simple_method!(
+                        request.id,
+                        "get_validation_config",
+                        "Get validation config",
+                        self.get_validation_config()
+                    )
+                }
+                "set_config" => {
+                    handle_parameterized_method!(
+                        request,
+                        serde_json::Value,
+                        "set_config",
+                        "Update project config",
+                        |params| self.set_config(params)
                     )
                 }
                 "get_suggestions" => {
@@ -641,6 +4361,32 @@ impl JsonRpcHandler for TaskManagerHandler {
                         |params| self.get_suggestions(params)
                     )
                 }
+                "apply_conflict_resolution" => {
+                    handle_parameterized_method!(
+                        request,
+                        ApplyConflictResolutionParams,
+                        "apply_conflict_resolution",
+                        "Apply conflict resolution",
+                        |params| self.apply_conflict_resolution(params)
+                    )
+                }
+                "list_methods" => {
+                    handle_simple_method!(
+                        request.id,
+                        "list_methods",
+                        "List methods",
+                        self.list_methods()
+                    )
+                }
+                "describe_method" => {
+                    handle_parameterized_method!(
+                        request,
+                        DescribeMethodParams,
+                        "describe_method",
+                        "Describe method",
+                        |params| self.describe_method(params)
+                    )
+                }
                 "check_task_conflicts" => {
                     handle_parameterized_method!(
                         request,
@@ -0,0 +1,165 @@
+/*!
+ * Identifier Grammar Module for Anchora Backend
+ *
+ * Single definition of what a section name or task ID may look like.
+ * Both the file parser and the validation engine build their patterns from
+ * here, so an ID accepted by validation is always one the scanner can find.
+ */
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+const EXTENDED_PATTERN: &str = r"[\p{L}_][\p{L}\p{N}_]*";
+const STRICT_PATTERN: &str = r"[A-Za-z_][A-Za-z0-9_]*";
+
+static EXTENDED_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("^{}$", EXTENDED_PATTERN)).unwrap());
+static STRICT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("^{}$", STRICT_PATTERN)).unwrap());
+
+/// Which characters identifiers may contain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentifierMode {
+    /// Unicode letters, digits and underscores
+    #[default]
+    Extended,
+    /// ASCII letters, digits and underscores only
+    Strict,
+}
+
+impl IdentifierMode {
+    /// Unanchored regex fragment for embedding in larger patterns
+    pub fn pattern(self) -> &'static str {
+        match self {
+            IdentifierMode::Extended => EXTENDED_PATTERN,
+            IdentifierMode::Strict => STRICT_PATTERN,
+        }
+    }
+
+    /// Check whether the whole string is a valid identifier
+    pub fn is_valid(self, identifier: &str) -> bool {
+        match self {
+            IdentifierMode::Extended => EXTENDED_REGEX.is_match(identifier),
+            IdentifierMode::Strict => STRICT_REGEX.is_match(identifier),
+        }
+    }
+
+    /// Human readable description of the allowed characters
+    pub fn describe(self) -> &'static str {
+        match self {
+            IdentifierMode::Extended => "letters, numbers, and underscores",
+            IdentifierMode::Strict => "ASCII letters, numbers, and underscores",
+        }
+    }
+}
+
+/// Casing convention for task IDs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdStyle {
+    /// `my_task`
+    SnakeCase,
+    /// `myTask`
+    CamelCase,
+    /// `MyTask`
+    PascalCase,
+}
+
+impl IdStyle {
+    /// Rewrite an identifier in this casing convention
+    pub fn apply(self, identifier: &str) -> String {
+        let words = split_words(identifier);
+        match self {
+            IdStyle::SnakeCase => words
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            IdStyle::CamelCase => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    if i == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(word)
+                    }
+                })
+                .collect(),
+            IdStyle::PascalCase => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+/// Split on underscores, hyphens and lower-to-upper case boundaries
+fn split_words(identifier: &str) -> Vec<String> {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_numeric()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_modes() {
+        assert!(IdentifierMode::Extended.is_valid("задача_1"));
+        assert!(!IdentifierMode::Strict.is_valid("задача_1"));
+        for mode in [IdentifierMode::Extended, IdentifierMode::Strict] {
+            assert!(mode.is_valid("_task_1"));
+            assert!(!mode.is_valid("my-task"));
+            assert!(!mode.is_valid("1task"));
+            assert!(!mode.is_valid(""));
+        }
+    }
+
+    #[test]
+    fn test_id_styles() {
+        assert_eq!(IdStyle::SnakeCase.apply("myTask"), "my_task");
+        assert_eq!(
+            IdStyle::SnakeCase.apply("HTTPServer2Fix"),
+            "http_server2_fix"
+        );
+        assert_eq!(IdStyle::SnakeCase.apply("fix-login_page"), "fix_login_page");
+        assert_eq!(IdStyle::CamelCase.apply("my_task_list"), "myTaskList");
+        assert_eq!(IdStyle::PascalCase.apply("my-task"), "MyTask");
+        assert_eq!(IdStyle::SnakeCase.apply("задачаНомер"), "задача_номер");
+    }
+}
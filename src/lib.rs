@@ -1,69 +1,192 @@
+pub mod cli;
 pub mod communication;
+pub mod config;
 pub mod error_macros;
 pub mod file_parser;
 pub mod file_watcher;
 pub mod handler;
+pub mod identifier;
+pub mod method_docs;
+pub mod report;
 pub mod search_engine;
+pub mod similarity;
 pub mod statistics;
 pub mod storage;
 pub mod task_manager;
+pub mod timezone;
 pub mod validation;
+pub mod watch;
 
 pub use task_manager::{
-    Note, ProjectData, ProjectMeta, Task, TaskFile, TaskIndex, TaskSection, TaskStatus,
+    ChangeSource, ChecklistSplit, ConversionCandidate, DescriptionSource, IdCollision, IdRename,
+    MAX_ESTIMATE_MINUTES, MAX_TIME_ENTRY_MINUTES, Milestone, Note, NoteAttachment, NoteUpdate,
+    NotesConfig, ProjectData, ProjectMeta, SectionOrder, Task, TaskChange, TaskComment, TaskFile,
+    TaskIndex, TaskKey, TaskLink, TaskPriority, TaskSection, TaskStatus, TaskTemplate, TimeEntry,
+    TrashedTask, normalize_tag,
 };
 
-pub use file_parser::{ParsedTaskLabel, ScanResult, TaskParser};
+pub use config::{ProjectConfig, ResponseConfig};
+
+pub use cli::{ExportOptions, ListOptions, ListSort, TaskRow};
+
+pub use report::{ReportFilter, ReportFormat};
+
+pub use watch::WatchOptions;
+
+pub use identifier::{IdStyle, IdentifierMode};
+
+pub use file_parser::{
+    CommentSyntax, DescriptionConflict, FileScan, LabelDiagnostic, LabelNearMiss, ParsedTaskLabel,
+    ParserConfig, ScanBufferPool, ScanConfig, ScanResult, SkippedLine, StatusDivergence,
+    StatusReconciliation, TaskParser, default_comment_syntax,
+};
 
 pub use storage::{StorageInfo, StorageManager};
 
 pub use communication::{
+    AddTaskCommentParams,
+    AddTaskLinkParams,
+    AddTimeEntryParams,
+    ApplyConflictResolutionParams,
+    ArchiveSectionParams,
+    AssignedTask,
+    BULK_APPLY_CONFIRM_THRESHOLD,
     BasicResponse,
+    BenchmarkScanParams,
+    BenchmarkScanReport,
+    BulkApplyParams,
+    BulkApplyResult,
     CheckConflictsParams,
+    CloneSectionParams,
+    CompareSectionsParams,
+    ConflictResolutionResult,
+    ConvertNoteToTaskParams,
+    ConvertNoteToTaskResult,
+    ConvertNoteToTasksParams,
+    ConvertNoteToTasksResult,
+    CreateMilestoneParams,
     CreateNoteParams,
     CreateNoteResponse,
+    CreateTaskFromTemplateParams,
     CreateTaskParams,
+    DeleteMilestoneParams,
     DeleteNoteParams,
+    DeleteTaskCommentParams,
     DeleteTaskParams,
+    DescribeMethodParams,
+    EditTaskTextParams,
+    EmptyTrashParams,
+    ExportNotesParams,
+    ExportStatisticsParams,
+    ExportTasksParams,
+    ExtensionTiming,
+    FileDecoration,
+    FileTiming,
     FindTaskReferencesParams,
     GenerateLinkParams,
     GenerateLinkResponse,
+    GetDependentsParams,
     GetFileDecorationsParams,
     GetFilteredTasksParams,
+    GetMyTasksParams,
+    GetNoteParams,
+    GetNotesForTaskParams,
+    GetNotesParams,
+    GetSectionFilesParams,
     GetStatisticsParams,
     GetSuggestionsParams,
+    GetTagsParams,
+    GetTaskDetailsParams,
+    GetTaskHistoryParams,
     GetTaskOverviewParams,
+    GetTaskTreeParams,
     GetTasksParams,
+    ImportTasksParams,
+    InitializeParams,
+    InitializeResult,
     JsonRpcClient,
     JsonRpcError,
     JsonRpcHandler,
     JsonRpcRequest,
     JsonRpcResponse,
     JsonRpcServer,
+    MergeNotesParams,
+    MilestoneTaskParams,
+    NormalizeIdsParams,
+    NormalizeIdsResult,
+    NoteTaskLinkParams,
+    PROTOCOL_SCHEMA_VERSION,
+    PinNoteParams,
+    PreviewFileLabelsParams,
+    PreviewFileLabelsResult,
+    PreviewedLabel,
+    PurgeConvertedNotesParams,
+    PurgeConvertedNotesResult,
+    RemoveTaskLinkParams,
+    RemoveTaskMetadataParams,
+    RenameFileReferencesParams,
+    RenameFileReferencesResult,
+    ReorderTaskParams,
+    ReorderTaskResult,
+    RestoreTaskParams,
+    SaveTaskTemplateParams,
+    ScanFilesParams,
+    ScanFilesResult,
     ScanProjectParams,
     ScanProjectResult,
     // New server-side operation parameters
+    SearchNotesParams,
     SearchTasksParams,
+    SectionFile,
+    SetTaskAssigneeParams,
+    SetTaskEstimateParams,
+    SetTaskMetadataParams,
+    SetTaskParentParams,
+    SetTaskPriorityParams,
+    StaleReference,
+    StatusFilter,
+    TagKind,
+    TagUsage,
+    TaskDependenciesResult,
+    TaskDependencyParams,
+    TaskExistsParams,
+    TaskExistsResponse,
+    TaskLinksResult,
     TaskReference,
+    TaskTagsParams,
+    TaskTagsResult,
+    TaskTreeNode,
+    UpdateNoteParams,
     UpdateTaskStatusParams,
+    ValidateProjectParams,
     ValidateTaskParams,
+    ValidateTasksBatchParams,
+    VerifyReferencesParams,
+    VerifyReferencesResult,
 };
 
-pub use file_watcher::{EventDebouncer, FileEvent, FileWatcher, WatcherConfig, WatcherStats};
+pub use file_watcher::{
+    EventDebouncer, FileEvent, FileWatcher, RescanScheduler, WatcherConfig, WatcherStats,
+};
 
 pub use search_engine::{
-    MatchType, SearchEngine, SearchFilters, SearchQuery, SearchResult, Suggestion, SuggestionType,
-    TaskSearchResult,
+    HighlightSpan, IndexMode, MatchType, NoteSearchQuery, NoteSearchResult, NoteSearchResults,
+    SearchConfig, SearchEngine, SearchFilters, SearchQuery, SearchResult, Suggestion,
+    SuggestionType, TaskSearchResult, search_notes,
 };
 
 pub use statistics::{
-    ActivityType, ChangeType, SectionStats, SectionSummary, StatisticsConfig, StatisticsManager,
-    TaskActivity, TaskOverview, TaskStatistics, TaskUpdate,
+    ActivityType, BreakdownStats, ChangeType, ComparedSection, CompletionEstimate,
+    CompletionForecast, ForecastConfidence, MilestoneProgress, SectionComparison, SectionForecast,
+    SectionStats, SectionSummary, StatisticsConfig, StatisticsFilter, StatisticsManager,
+    TaskActivity, TaskOverview, TaskStatistics, TaskUpdate, TimeInStatus,
 };
 
 pub use validation::{
-    Conflict, ConflictCheck, ValidationConfig, ValidationEngine, ValidationError, ValidationParams,
-    ValidationResult, ValidationWarning,
+    BatchValidationResult, Conflict, ConflictCheck, ConventionRules, NoteValidationParams,
+    ProjectValidationReport, ResolutionAction, RuleReport, RuleViolation, SectionRules,
+    SectionValidationProfile, Severity, TaskLocation, ValidationConfig, ValidationEngine,
+    ValidationError, ValidationFailed, ValidationParams, ValidationResult, ValidationWarning,
 };
 
 pub use handler::TaskManagerHandler;
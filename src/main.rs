@@ -5,9 +5,12 @@
  * Handles command-line arguments and starts the appropriate mode.
  */
 
-use anchora::{JsonRpcServer, ScanProjectParams, TaskManagerHandler};
-use clap::{Arg, Command};
-use std::path::PathBuf;
+use anchora::cli::{self, ExportOptions, ListOptions};
+use anchora::{JsonRpcServer, ScanProjectParams, StorageManager, TaskManagerHandler, WatchOptions};
+use clap::{Arg, ArgMatches, Command};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -27,9 +30,77 @@ async fn main() -> anyhow::Result<()> {
                 .short('m')
                 .long("mode")
                 .value_name("MODE")
-                .help("Execution mode: server, scan")
+                .help("Execution mode: server, scan, list, stats, export, watch")
                 .default_value("server"),
         )
+        .arg(
+            Arg::new("require-initialize")
+                .long("require-initialize")
+                .help("Reject requests until the client calls initialize")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .help("server: keep tasks.json in sync with file changes, as --mode watch does")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("interval-full-rescan")
+                .long("interval-full-rescan")
+                .value_name("SECONDS")
+                .help("watch, server --watch: also rescan the whole workspace this often")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("section")
+                .long("section")
+                .value_name("SECTION")
+                .help("list, stats: only report tasks in this section"),
+        )
+        .arg(
+            Arg::new("status")
+                .long("status")
+                .value_name("STATUS")
+                .help("list, export: only show tasks with this status (todo, in_progress, done, blocked); export takes a comma-separated list"),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("FIELD")
+                .help("list: sort by section, status, updated or title")
+                .default_value("section"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("list, stats: table (default) or json; export: json (default), ndjson, markdown, csv or html"),
+        )
+        .arg(
+            Arg::new("sections")
+                .long("sections")
+                .value_name("SECTIONS")
+                .help("export: comma-separated sections to export"),
+        )
+        .arg(
+            Arg::new("include-notes")
+                .long("include-notes")
+                .help("export: include notes")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .value_name("PATH")
+                .help("export: file to write"),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .help("export: overwrite the output file if it exists")
+                .action(clap::ArgAction::SetTrue),
+        )
         .get_matches();
 
     let workspace_path = PathBuf::from(
@@ -39,23 +110,55 @@ async fn main() -> anyhow::Result<()> {
     );
     let mode = matches.get_one::<String>("mode").unwrap();
 
-    println!("Anchora Task Manager Backend v0.1.0");
-    println!("Workspace: {:?}", workspace_path);
-    println!("Mode: {}", mode);
+    // Reports go straight to stdout, so they skip the banner to stay pipeable
+    let report = match mode.as_str() {
+        "list" => Some(run_list(&workspace_path, &matches).await),
+        "stats" => Some(run_stats(&workspace_path, &matches).await),
+        _ => None,
+    };
+    if let Some(result) = report {
+        if let Err(e) = result {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    eprintln!("Anchora Task Manager Backend v0.1.0");
+    eprintln!("Workspace: {:?}", workspace_path);
+    eprintln!("Mode: {}", mode);
 
-    let handler = TaskManagerHandler::new(workspace_path.clone())?;
+    let handler = TaskManagerHandler::new(workspace_path.clone())?
+        .require_initialize(matches.get_flag("require-initialize"));
 
     match mode.as_str() {
         "server" => {
-            println!("Starting JSON-RPC server...");
-            let server = JsonRpcServer::new(Box::new(handler));
-            server.run_stdio().await?
+            eprintln!("Starting JSON-RPC server...");
+            handler.spawn_index_rebuilder().await?;
+            let handler = Arc::new(handler);
+            let server = JsonRpcServer::new(Box::new(handler.clone()));
+            if matches.get_flag("watch") {
+                let watch = anchora::watch::run(
+                    handler.clone(),
+                    watch_options(&matches),
+                    std::future::pending(),
+                );
+                tokio::select! {
+                    result = server.run_stdio() => result?,
+                    result = watch => result?,
+                }
+            } else {
+                server.run_stdio().await?
+            }
         }
         "scan" => {
             println!("Scanning workspace for tasks...");
             let scan_params = ScanProjectParams {
                 workspace_path: workspace_path.to_string_lossy().to_string(),
                 file_patterns: None,
+                recognize_keyword_comments: None,
+                validate_labels: None,
+                reconcile_status: None,
             };
 
             let result = handler.scan_project(scan_params).await?;
@@ -71,11 +174,132 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
+        "export" => match run_export(&handler, &matches).await {
+            Ok(response) => println!("{}", response.message),
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                std::process::exit(1);
+            }
+        },
+        "watch" => {
+            println!("Watching workspace for changes, press Ctrl-C to stop...");
+            let shutdown = async {
+                let _ = tokio::signal::ctrl_c().await;
+            };
+            anchora::watch::run(Arc::new(handler), watch_options(&matches), shutdown).await?;
+        }
         _ => {
-            eprintln!("Unknown mode: {}. Use 'server' or 'scan'", mode);
+            eprintln!(
+                "Unknown mode: {}. Use 'server', 'scan', 'list', 'stats', 'export' or 'watch'",
+                mode
+            );
             std::process::exit(1);
         }
     }
 
     Ok(())
 }
+
+async fn run_list(workspace_path: &Path, matches: &ArgMatches) -> anyhow::Result<()> {
+    let options = ListOptions {
+        section: matches.get_one::<String>("section").cloned(),
+        status: matches
+            .get_one::<String>("status")
+            .map(|status| status.parse())
+            .transpose()?,
+        sort: matches.get_one::<String>("sort").unwrap().parse()?,
+    };
+    let json = json_output(matches)?;
+
+    let project_data = cli::load_stored_project(workspace_path).await?;
+    let rows = cli::list_tasks(&project_data, &options);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        print!("{}", cli::render_task_table(&rows, chrono::Utc::now()));
+    }
+    Ok(())
+}
+
+async fn run_stats(workspace_path: &Path, matches: &ArgMatches) -> anyhow::Result<()> {
+    let section = matches.get_one::<String>("section").map(String::as_str);
+    let json = json_output(matches)?;
+
+    let project_data = cli::load_stored_project(workspace_path).await?;
+    let order = StorageManager::new(workspace_path)
+        .load_config()
+        .await?
+        .responses
+        .section_order;
+    let stats = cli::project_statistics(&project_data, section, order)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        let stale_tasks = cli::stale_task_count(&project_data, section, chrono::Utc::now());
+        print!("{}", cli::render_statistics(&stats, stale_tasks));
+    }
+    Ok(())
+}
+
+/// Whether `--format` asks for JSON rather than the plain-text report
+fn json_output(matches: &ArgMatches) -> anyhow::Result<bool> {
+    match matches
+        .get_one::<String>("format")
+        .map_or("table", String::as_str)
+    {
+        "table" => Ok(false),
+        "json" => Ok(true),
+        format => anyhow::bail!("Invalid format: {} (expected table or json)", format),
+    }
+}
+
+fn watch_options(matches: &ArgMatches) -> WatchOptions {
+    WatchOptions {
+        full_rescan_interval: matches
+            .get_one::<u64>("interval-full-rescan")
+            .map(|seconds| Duration::from_secs(*seconds)),
+        ..Default::default()
+    }
+}
+
+async fn run_export(
+    handler: &TaskManagerHandler,
+    matches: &ArgMatches,
+) -> anyhow::Result<anchora::BasicResponse> {
+    let comma_list = |name: &str| {
+        matches.get_one::<String>(name).map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+    };
+    let out = matches
+        .get_one::<String>("out")
+        .ok_or_else(|| anyhow::anyhow!("--out is required in export mode"))?;
+    let statuses = comma_list("status")
+        .map(|statuses| {
+            statuses
+                .iter()
+                .map(|status| status.parse())
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .transpose()?;
+    cli::export(
+        handler,
+        ExportOptions {
+            out: PathBuf::from(out),
+            format: matches
+                .get_one::<String>("format")
+                .cloned()
+                .unwrap_or_else(|| "json".to_string()),
+            sections: comma_list("sections"),
+            statuses,
+            include_notes: matches.get_flag("include-notes"),
+            force: matches.get_flag("force"),
+        },
+    )
+    .await
+}
@@ -0,0 +1,1156 @@
+/*!
+ * JSON-RPC Method Documentation
+ *
+ * One entry per method answered by the handler, with the description used in its
+ * logs and the parameters its params struct accepts. Keep in step with `handle_request`;
+ * the integration tests check each parameter against its params struct.
+ */
+
+use serde::Serialize;
+
+/// Whether a method takes a params object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamsMode {
+    Required,
+    Optional,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamDoc {
+    pub name: &'static str,
+    /// JSON type, with `[]` for arrays
+    #[serde(rename = "type")]
+    pub type_name: &'static str,
+    pub required: bool,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MethodDoc {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: ParamsMode,
+    pub parameters: &'static [ParamDoc],
+}
+
+const fn method(
+    name: &'static str,
+    description: &'static str,
+    params: ParamsMode,
+    parameters: &'static [ParamDoc],
+) -> MethodDoc {
+    MethodDoc {
+        name,
+        description,
+        params,
+        parameters,
+    }
+}
+
+const fn param(
+    name: &'static str,
+    type_name: &'static str,
+    required: bool,
+    description: &'static str,
+) -> ParamDoc {
+    ParamDoc {
+        name,
+        type_name,
+        required,
+        description,
+    }
+}
+
+/// Documentation of a method, if the handler answers it
+pub fn describe(name: &str) -> Option<&'static MethodDoc> {
+    METHODS.iter().find(|method| method.name == name)
+}
+
+/// Every method answered by `handle_request`, in dispatch order
+pub static METHODS: &[MethodDoc] = &[
+    method(
+        "initialize",
+        "Initialize client session",
+        ParamsMode::Required,
+        &[
+            param("client_version", "string", true, ""),
+            param("capabilities", "string[]", false, ""),
+        ],
+    ),
+    method(
+        "scan_files",
+        "Scan listed files",
+        ParamsMode::Required,
+        &[
+            param(
+                "files",
+                "string[]",
+                true,
+                "Files relative to the workspace root, or absolute paths inside it",
+            ),
+            param("recognize_keyword_comments", "boolean", false, ""),
+            param(
+                "reconcile_status",
+                "string",
+                false,
+                "code_wins, json_wins or report_only",
+            ),
+        ],
+    ),
+    method(
+        "preview_file_labels",
+        "Preview labels in unsaved content",
+        ParamsMode::Required,
+        &[
+            param("content", "string", true, ""),
+            param(
+                "path",
+                "string",
+                false,
+                "Picks the comment syntax by extension; `//` when absent",
+            ),
+        ],
+    ),
+    method(
+        "benchmark_scan",
+        "Benchmark project scan",
+        ParamsMode::Optional,
+        &[
+            param(
+                "workspace_path",
+                "string",
+                false,
+                "Defaults to the workspace the server was started with",
+            ),
+            param("file_patterns", "string[]", false, ""),
+            param("recognize_keyword_comments", "boolean", false, ""),
+            param(
+                "slowest_limit",
+                "integer",
+                false,
+                "Number of slowest files to list, 10 by default",
+            ),
+        ],
+    ),
+    method(
+        "scan_project",
+        "Scan project for tasks",
+        ParamsMode::Required,
+        &[
+            param("workspace_path", "string", true, ""),
+            param("file_patterns", "string[]", false, ""),
+            param("recognize_keyword_comments", "boolean", false, ""),
+            param(
+                "validate_labels",
+                "boolean",
+                false,
+                "Report labels that break validation rules in `diagnostics`",
+            ),
+            param(
+                "reconcile_status",
+                "string",
+                false,
+                "code_wins, json_wins or report_only",
+            ),
+        ],
+    ),
+    method(
+        "get_tasks",
+        "Retrieve project tasks",
+        ParamsMode::Optional,
+        &[
+            param("section", "string", false, ""),
+            param("status", "string | string[]", false, ""),
+            param(
+                "include_archived",
+                "boolean",
+                false,
+                "Archived sections are left out unless this is set",
+            ),
+            param(
+                "sort_by",
+                "string",
+                false,
+                "`\"manual\"` adds an `order` map listing each section's task IDs by rank",
+            ),
+        ],
+    ),
+    method(
+        "get_filtered_tasks",
+        "Retrieve filtered tasks",
+        ParamsMode::Required,
+        &[
+            param("sections", "string[]", false, ""),
+            param("statuses", "string[]", false, ""),
+            param("created_after", "string", false, ""),
+            param("updated_after", "string", false, ""),
+            param("priorities", "string[]", false, ""),
+            param(
+                "tags",
+                "string[]",
+                false,
+                "Tasks carrying any of these tags",
+            ),
+            param("assignees", "string[]", false, ""),
+            param(
+                "metadata",
+                "object",
+                false,
+                "Metadata fields that must all be present with these values",
+            ),
+            param("include_archived", "boolean", false, ""),
+            param(
+                "sort_by",
+                "string",
+                false,
+                "`\"manual\"` adds an `order` map listing each section's task IDs by rank",
+            ),
+        ],
+    ),
+    method(
+        "archive_section",
+        "Archive section",
+        ParamsMode::Required,
+        &[param("section", "string", true, "")],
+    ),
+    method(
+        "unarchive_section",
+        "Unarchive section",
+        ParamsMode::Required,
+        &[param("section", "string", true, "")],
+    ),
+    method(
+        "set_task_assignee",
+        "Set task assignee",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("assignee", "string", false, ""),
+        ],
+    ),
+    method(
+        "get_my_tasks",
+        "Get my tasks",
+        ParamsMode::Required,
+        &[
+            param("assignee", "string", true, ""),
+            param("limit", "integer", false, ""),
+        ],
+    ),
+    method(
+        "add_task_link",
+        "Add task link",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("url", "string", true, "Absolute http or https URL"),
+            param("label", "string", false, "Defaults to the URL"),
+        ],
+    ),
+    method(
+        "remove_task_link",
+        "Remove task link",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("url", "string", true, ""),
+        ],
+    ),
+    method(
+        "add_task_tags",
+        "Add task tags",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("tags", "string[]", true, ""),
+        ],
+    ),
+    method(
+        "remove_task_tags",
+        "Remove task tags",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("tags", "string[]", true, ""),
+        ],
+    ),
+    method(
+        "get_tags",
+        "Get tags",
+        ParamsMode::Optional,
+        &[
+            param(
+                "prefix",
+                "string",
+                false,
+                "Only tags starting with this prefix, for autocomplete",
+            ),
+            param("include_archived", "boolean", false, ""),
+        ],
+    ),
+    method(
+        "create_milestone",
+        "Create milestone",
+        ParamsMode::Required,
+        &[
+            param("id", "string", true, ""),
+            param("title", "string", true, ""),
+            param("due", "string", false, ""),
+        ],
+    ),
+    method(
+        "delete_milestone",
+        "Delete milestone",
+        ParamsMode::Required,
+        &[param("id", "string", true, "")],
+    ),
+    method(
+        "add_task_to_milestone",
+        "Add task to milestone",
+        ParamsMode::Required,
+        &[
+            param("milestone_id", "string", true, ""),
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "remove_task_from_milestone",
+        "Remove task from milestone",
+        ParamsMode::Required,
+        &[
+            param("milestone_id", "string", true, ""),
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method("get_milestones", "Get milestones", ParamsMode::None, &[]),
+    method(
+        "edit_task_text",
+        "Edit task text",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param(
+                "title",
+                "string",
+                false,
+                "Later scans keep the edited title",
+            ),
+            param("description", "string", false, ""),
+        ],
+    ),
+    method(
+        "set_task_priority",
+        "Set task priority",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("priority", "string", false, ""),
+        ],
+    ),
+    method(
+        "set_task_estimate",
+        "Set task estimate",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("estimate_minutes", "integer", false, ""),
+        ],
+    ),
+    method(
+        "add_time_entry",
+        "Add time entry",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("minutes", "integer", true, ""),
+            param("note", "string", false, ""),
+        ],
+    ),
+    method(
+        "reorder_task",
+        "Reorder task",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param(
+                "before",
+                "string",
+                false,
+                "Task to place this one directly in front of",
+            ),
+            param(
+                "after",
+                "string",
+                false,
+                "Task to place this one directly behind",
+            ),
+        ],
+    ),
+    method(
+        "get_completion_forecast",
+        "Get completion forecast",
+        ParamsMode::None,
+        &[],
+    ),
+    method(
+        "compare_sections",
+        "Compare sections",
+        ParamsMode::Required,
+        &[
+            param("a", "string", true, ""),
+            param("b", "string", true, ""),
+        ],
+    ),
+    method(
+        "add_task_comment",
+        "Add task comment",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("author", "string", true, ""),
+            param("text", "string", true, ""),
+        ],
+    ),
+    method(
+        "delete_task_comment",
+        "Delete task comment",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("comment_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "set_task_metadata",
+        "Set task metadata",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("key", "string", true, ""),
+            param("value", "any", true, ""),
+        ],
+    ),
+    method(
+        "remove_task_metadata",
+        "Remove task metadata",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("key", "string", true, ""),
+        ],
+    ),
+    method(
+        "create_task",
+        "Create new task",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("title", "string", true, ""),
+            param("description", "string", false, ""),
+            param(
+                "overwrite",
+                "boolean",
+                false,
+                "Replace an existing task with the same id instead of failing",
+            ),
+            param("priority", "string", false, ""),
+            param("assignee", "string", false, ""),
+            param(
+                "parent",
+                "string",
+                false,
+                "Create the task as a subtask of this one, as section.task_id",
+            ),
+        ],
+    ),
+    method(
+        "create_task_from_template",
+        "Create task from template",
+        ParamsMode::Required,
+        &[
+            param("template_id", "string", true, ""),
+            param(
+                "section",
+                "string",
+                false,
+                "Section for the task, taking precedence over the template's",
+            ),
+            param("task_id", "string", true, ""),
+            param(
+                "substitutions",
+                "object",
+                false,
+                "Values for the `{placeholder}`s in the template's title and description",
+            ),
+            param(
+                "overrides",
+                "object",
+                false,
+                "Values taking precedence over the template's defaults",
+            ),
+        ],
+    ),
+    method(
+        "save_task_template",
+        "Save task template",
+        ParamsMode::Required,
+        &[
+            param("template_id", "string", true, ""),
+            param("template", "object", true, ""),
+        ],
+    ),
+    method(
+        "list_task_templates",
+        "List task templates",
+        ParamsMode::None,
+        &[],
+    ),
+    method(
+        "update_task_status",
+        "Update task status",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("status", "string", true, ""),
+        ],
+    ),
+    method(
+        "delete_task",
+        "Delete task",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param(
+                "cascade",
+                "boolean",
+                false,
+                "Delete subtasks as well instead of turning them into top-level tasks",
+            ),
+            param(
+                "permanent",
+                "boolean",
+                false,
+                "Skip the trash; the task cannot be restored",
+            ),
+        ],
+    ),
+    method("list_trash", "List trashed tasks", ParamsMode::None, &[]),
+    method(
+        "restore_task",
+        "Restore task from trash",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "empty_trash",
+        "Empty trash",
+        ParamsMode::Optional,
+        &[param("older_than_days", "integer", false, "")],
+    ),
+    method(
+        "set_task_parent",
+        "Set task parent",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param(
+                "parent",
+                "string",
+                false,
+                "section.task_id of the new parent",
+            ),
+        ],
+    ),
+    method(
+        "get_task_tree",
+        "Get task tree",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "get_task_details",
+        "Get task details",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("timezone", "string", false, ""),
+        ],
+    ),
+    method("list_aliases", "List task aliases", ParamsMode::None, &[]),
+    method(
+        "get_task_history",
+        "Get task history",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("limit", "integer", false, ""),
+        ],
+    ),
+    method(
+        "clone_section",
+        "Clone section",
+        ParamsMode::Required,
+        &[
+            param("source", "string", true, ""),
+            param("target", "string", true, ""),
+        ],
+    ),
+    method(
+        "find_task_references",
+        "Find task references",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "rename_file_references",
+        "Rename file references",
+        ParamsMode::Required,
+        &[
+            param("old_path", "string", true, ""),
+            param("new_path", "string", true, ""),
+        ],
+    ),
+    method(
+        "get_section_files",
+        "Get files referenced by section",
+        ParamsMode::Required,
+        &[param("section", "string", true, "")],
+    ),
+    method(
+        "get_all_file_decorations",
+        "Get decorations for all files",
+        ParamsMode::None,
+        &[],
+    ),
+    method(
+        "verify_references",
+        "Verify task references",
+        ParamsMode::Optional,
+        &[
+            param(
+                "repair",
+                "boolean",
+                false,
+                "Move stale references to the nearest line that still has the label",
+            ),
+            param(
+                "max_distance",
+                "integer",
+                false,
+                "How many lines away from the recorded one a repair may look",
+            ),
+            param("recognize_keyword_comments", "boolean", false, ""),
+        ],
+    ),
+    method(
+        "create_note",
+        "Create new note",
+        ParamsMode::Required,
+        &[
+            param("title", "string", true, ""),
+            param("content", "string", true, ""),
+            param("section", "string", true, ""),
+            param("suggested_task_id", "string", true, ""),
+            param("suggested_status", "string", false, ""),
+            param("tags", "string[]", false, ""),
+            param(
+                "attachments",
+                "object[]",
+                false,
+                "File selections: file_path, line_start, line_end, optional snippet",
+            ),
+        ],
+    ),
+    method(
+        "get_notes",
+        "Retrieve all notes",
+        ParamsMode::Optional,
+        &[param(
+            "tags",
+            "string[]",
+            false,
+            "Only notes carrying any of these tags",
+        )],
+    ),
+    method(
+        "get_conversion_candidates",
+        "Get note conversion candidates",
+        ParamsMode::None,
+        &[],
+    ),
+    method(
+        "convert_note_to_task",
+        "Convert note to task",
+        ParamsMode::Required,
+        &[
+            param("note_id", "string", true, ""),
+            param(
+                "auto_rename",
+                "boolean",
+                false,
+                "Use the first free alternative when the suggested ID is taken",
+            ),
+        ],
+    ),
+    method(
+        "convert_note_to_tasks",
+        "Convert note to tasks",
+        ParamsMode::Required,
+        &[
+            param("note_id", "string", true, ""),
+            param("split", "string", true, "lines or bullets"),
+            param(
+                "section",
+                "string",
+                false,
+                "Section for the new tasks; the note's own section when omitted",
+            ),
+            param(
+                "id_prefix",
+                "string",
+                false,
+                "Prepended to the slug of each item to form its task ID",
+            ),
+        ],
+    ),
+    method(
+        "update_note",
+        "Update note",
+        ParamsMode::Required,
+        &[
+            param("note_id", "string", true, ""),
+            param("title", "string", false, ""),
+            param("content", "string", false, ""),
+            param("tags", "string[]", false, ""),
+            param("section", "string", false, ""),
+            param("suggested_task_id", "string", false, ""),
+            param("suggested_status", "string", false, ""),
+        ],
+    ),
+    method(
+        "get_note",
+        "Get note",
+        ParamsMode::Required,
+        &[param("note_id", "string", true, "")],
+    ),
+    method(
+        "search_notes",
+        "Search notes",
+        ParamsMode::Required,
+        &[
+            param("query", "string", true, ""),
+            param("section", "string", false, ""),
+            param("converted", "boolean", false, ""),
+            param("tags", "string[]", false, ""),
+            param("limit", "integer", false, ""),
+            param("offset", "integer", false, ""),
+        ],
+    ),
+    method(
+        "link_note_to_task",
+        "Link note to task",
+        ParamsMode::Required,
+        &[
+            param("note_id", "string", true, ""),
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "unlink_note_from_task",
+        "Unlink note from task",
+        ParamsMode::Required,
+        &[
+            param("note_id", "string", true, ""),
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "get_notes_for_task",
+        "Retrieve notes suggested for task",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "get_dependents",
+        "Retrieve dependent tasks",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "add_task_dependency",
+        "Add task dependency",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param(
+                "depends_on",
+                "string",
+                true,
+                "Task that has to be done first, as section.task_id",
+            ),
+        ],
+    ),
+    method(
+        "remove_task_dependency",
+        "Remove task dependency",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param(
+                "depends_on",
+                "string",
+                true,
+                "Task that has to be done first, as section.task_id",
+            ),
+        ],
+    ),
+    method(
+        "task_exists",
+        "Check whether task exists",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+    method(
+        "generate_task_link",
+        "Generate task link for note",
+        ParamsMode::Required,
+        &[param("note_id", "string", true, "")],
+    ),
+    method(
+        "delete_note",
+        "Delete note",
+        ParamsMode::Required,
+        &[param("note_id", "string", true, "")],
+    ),
+    method(
+        "merge_notes",
+        "Merge notes",
+        ParamsMode::Required,
+        &[
+            param(
+                "note_ids",
+                "string[]",
+                true,
+                "Notes to merge, in content order",
+            ),
+            param("title", "string", true, "Title of the merged note"),
+        ],
+    ),
+    method(
+        "pin_note",
+        "Pin note",
+        ParamsMode::Required,
+        &[param("note_id", "string", true, "")],
+    ),
+    method(
+        "unpin_note",
+        "Unpin note",
+        ParamsMode::Required,
+        &[param("note_id", "string", true, "")],
+    ),
+    method(
+        "purge_converted_notes",
+        "Purge converted notes",
+        ParamsMode::Optional,
+        &[param(
+            "older_than_days",
+            "integer",
+            false,
+            "Only purge notes converted at least this many days ago",
+        )],
+    ),
+    method(
+        "search_tasks",
+        "Search tasks with indexing",
+        ParamsMode::Required,
+        &[
+            param("query", "string", true, ""),
+            param("filters", "any", false, ""),
+            param("limit", "integer", false, ""),
+            param("offset", "integer", false, ""),
+        ],
+    ),
+    method(
+        "bulk_apply",
+        "Apply a status, tags or assignee to every task matching a search",
+        ParamsMode::Required,
+        &[
+            param("query", "string", true, "Search text, as for search_tasks"),
+            param(
+                "filters",
+                "any",
+                false,
+                "Search filters, as for search_tasks",
+            ),
+            param("set_status", "string", false, ""),
+            param("add_tags", "string[]", false, ""),
+            param("set_assignee", "string", false, ""),
+            param(
+                "confirm",
+                "boolean",
+                false,
+                "Required when more than 10 tasks match",
+            ),
+        ],
+    ),
+    method(
+        "get_statistics",
+        "Get task statistics",
+        ParamsMode::Optional,
+        &[
+            param("include_trends", "boolean", false, ""),
+            param("section_filter", "string[]", false, ""),
+            param("assignee_filter", "string[]", false, ""),
+            param("tag_filter", "string[]", false, ""),
+            param("timezone", "string", false, ""),
+            param("include_archived", "boolean", false, ""),
+            param(
+                "roll_up_subtasks",
+                "boolean",
+                false,
+                "Count subtasks in their top-level task's section and leave their parents out",
+            ),
+        ],
+    ),
+    method(
+        "export_statistics",
+        "Export statistics snapshot",
+        ParamsMode::Required,
+        &[
+            param("path", "string", true, ""),
+            param("format", "string", true, ""),
+            param("fresh", "boolean", false, ""),
+            param("include_archived", "boolean", false, ""),
+            param(
+                "group_by_milestone",
+                "boolean",
+                false,
+                "Report per milestone instead of per section",
+            ),
+        ],
+    ),
+    method(
+        "export_tasks",
+        "Export tasks",
+        ParamsMode::Required,
+        &[
+            param("path", "string", true, ""),
+            param(
+                "format",
+                "string",
+                false,
+                "json (default), ndjson, markdown, csv or html",
+            ),
+            param("sections", "string[]", false, "Only export these sections"),
+            param(
+                "status",
+                "string[]",
+                false,
+                "Only export tasks with these statuses",
+            ),
+            param(
+                "include_notes",
+                "boolean",
+                false,
+                "Keep notes; by default only JSON exports include them",
+            ),
+        ],
+    ),
+    method(
+        "export_notes",
+        "Export notes as Markdown",
+        ParamsMode::Required,
+        &[
+            param("path", "string", true, ""),
+            param(
+                "include_converted",
+                "boolean",
+                false,
+                "Also export converted notes",
+            ),
+        ],
+    ),
+    method(
+        "import_tasks",
+        "Import tasks",
+        ParamsMode::Required,
+        &[
+            param("path", "string", true, ""),
+            param("format", "string", false, ""),
+        ],
+    ),
+    method(
+        "get_task_overview",
+        "Get task overview",
+        ParamsMode::Optional,
+        &[
+            param("include_recent_activity", "boolean", false, ""),
+            param("activity_limit", "integer", false, ""),
+            param("timezone", "string", false, ""),
+            param("include_archived", "boolean", false, ""),
+        ],
+    ),
+    method(
+        "validate_task_input",
+        "Validate task input",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("title", "string", false, ""),
+            param("description", "string", false, ""),
+            param("check_duplicates", "boolean", false, ""),
+            param("suggest_alternatives", "boolean", false, ""),
+        ],
+    ),
+    method(
+        "validate_note_input",
+        "Validate note input",
+        ParamsMode::Required,
+        &[
+            param("title", "string", true, ""),
+            param("content", "string", true, ""),
+            param("section", "string", true, ""),
+            param("suggested_task_id", "string", true, ""),
+            param("suggested_status", "string", false, ""),
+        ],
+    ),
+    method(
+        "validate_tasks_batch",
+        "Validate task batch",
+        ParamsMode::Required,
+        &[param("items", "object[]", true, "")],
+    ),
+    method(
+        "validate_project",
+        "Validate stored tasks",
+        ParamsMode::Optional,
+        &[param(
+            "max_per_rule",
+            "integer",
+            false,
+            "Violations listed per rule; the rest are only counted",
+        )],
+    ),
+    method(
+        "normalize_ids",
+        "Normalize task IDs",
+        ParamsMode::Required,
+        &[
+            param("style", "string", true, ""),
+            param("dry_run", "boolean", false, ""),
+        ],
+    ),
+    method(
+        "get_performance_stats",
+        "Get search performance stats",
+        ParamsMode::None,
+        &[],
+    ),
+    method(
+        "get_validation_config",
+        "Get validation config",
+        ParamsMode::None,
+        &[],
+    ),
+    method(
+        "set_config",
+        "Update project config",
+        ParamsMode::Required,
+        &[
+            param("validation", "object", false, ""),
+            param("search", "object", false, ""),
+            param("notes", "object", false, ""),
+            param("scan", "object", false, ""),
+            param("responses", "object", false, ""),
+            param(
+                "templates",
+                "object",
+                false,
+                "Task templates by name, for `create_task_from_template`",
+            ),
+        ],
+    ),
+    method(
+        "get_suggestions",
+        "Get task suggestions",
+        ParamsMode::Required,
+        &[
+            param("partial_query", "string", true, ""),
+            param("context", "string", false, ""),
+        ],
+    ),
+    method(
+        "apply_conflict_resolution",
+        "Apply conflict resolution",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+            param("resolution", "object", true, ""),
+        ],
+    ),
+    method("list_methods", "List methods", ParamsMode::None, &[]),
+    method(
+        "describe_method",
+        "Describe method",
+        ParamsMode::Required,
+        &[param("name", "string", true, "")],
+    ),
+    method(
+        "check_task_conflicts",
+        "Check task conflicts",
+        ParamsMode::Required,
+        &[
+            param("section", "string", true, ""),
+            param("task_id", "string", true, ""),
+        ],
+    ),
+];
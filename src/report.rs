@@ -0,0 +1,394 @@
+/*!
+ * Task Report Module for Anchora Backend
+ *
+ * Renders the project as the documents written by `export_tasks`, shared by
+ * the JSON-RPC method and the `export` mode of the binary.
+ */
+
+use crate::statistics::StatisticsManager;
+use crate::task_manager::{ProjectData, SectionOrder, Task, TaskStatus, escape_markdown_heading};
+
+/// File formats `export_tasks` can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// The project data itself, with saved templates
+    Json,
+    /// One JSON record per line, importable with `import_tasks`
+    Ndjson,
+    Markdown,
+    /// One row per task; notes are never included
+    Csv,
+    Html,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> anyhow::Result<Self> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "ndjson" => Ok(ReportFormat::Ndjson),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "csv" => Ok(ReportFormat::Csv),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(anyhow::anyhow!("Invalid export format: {}", format)),
+        }
+    }
+}
+
+/// Narrows what an export contains; `None` fields leave the data as it is
+#[derive(Debug, Clone, Default)]
+pub struct ReportFilter {
+    pub sections: Option<Vec<String>>,
+    pub statuses: Option<Vec<TaskStatus>>,
+    /// Keep the notes. Unset, JSON exports keep them and documents leave them out.
+    pub include_notes: Option<bool>,
+}
+
+impl ReportFilter {
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_none() && self.statuses.is_none() && self.include_notes.is_none()
+    }
+
+    /// Drop the tasks and notes the filter excludes
+    pub fn apply(&self, project_data: &mut ProjectData) {
+        if self.is_empty() {
+            return;
+        }
+        if let Some(sections) = &self.sections {
+            project_data
+                .sections
+                .retain(|name, _| sections.contains(name));
+            project_data
+                .notes
+                .retain(|_, note| sections.contains(&note.section));
+        }
+        if let Some(statuses) = &self.statuses {
+            for tasks in project_data.sections.values_mut() {
+                tasks.retain(|_, task| statuses.contains(&task.status));
+            }
+        }
+        if self.include_notes == Some(false) {
+            project_data.notes.clear();
+        }
+        project_data.rebuild_index();
+    }
+}
+
+/// Render a document format; JSON and NDJSON are written by the storage layer
+pub fn render(
+    project_data: &ProjectData,
+    format: ReportFormat,
+    order: SectionOrder,
+    include_notes: bool,
+) -> anyhow::Result<String> {
+    match format {
+        ReportFormat::Markdown => Ok(tasks_to_markdown(project_data, order, include_notes)),
+        ReportFormat::Csv => Ok(tasks_to_csv(project_data, order)),
+        ReportFormat::Html => Ok(tasks_to_html(project_data, order, include_notes)),
+        ReportFormat::Json | ReportFormat::Ndjson => Err(anyhow::anyhow!(
+            "{:?} exports are not rendered as documents",
+            format
+        )),
+    }
+}
+
+/// Tasks of each section in ID order
+fn sorted_tasks<'a>(project_data: &'a ProjectData, section: &str) -> Vec<(&'a String, &'a Task)> {
+    let mut tasks: Vec<(&String, &Task)> = project_data.sections[section].iter().collect();
+    tasks.sort_by(|a, b| a.0.cmp(b.0));
+    tasks
+}
+
+/// Markdown checklist per section, followed by the notes when asked for
+pub fn tasks_to_markdown(
+    project_data: &ProjectData,
+    order: SectionOrder,
+    include_notes: bool,
+) -> String {
+    let mut markdown = match &project_data.meta.project_name {
+        Some(name) => format!("# Tasks: {}\n", escape_markdown_heading(name)),
+        None => "# Tasks\n".to_string(),
+    };
+    for section in project_data.ordered_section_names(order) {
+        markdown.push_str(&format!("\n## {}\n\n", escape_markdown_heading(section)));
+        for (task_id, task) in sorted_tasks(project_data, section) {
+            let checkbox = if task.status == TaskStatus::Done {
+                "x"
+            } else {
+                " "
+            };
+            let mut line = format!(
+                "- [{}] `{}` {}",
+                checkbox,
+                task_id,
+                escape_markdown_heading(&task.title)
+            );
+            if !matches!(task.status, TaskStatus::Todo | TaskStatus::Done) {
+                line.push_str(&format!(" ({})", task.status.as_str()));
+            }
+            if let Some(assignee) = &task.assignee {
+                line.push_str(&format!(" · @{}", assignee));
+            }
+            if let Some(estimate) = task.estimate_minutes {
+                line.push_str(&format!(" · estimate {}m", estimate));
+            }
+            if task.time_spent_minutes > 0 {
+                line.push_str(&format!(" · spent {}m", task.time_spent_minutes));
+            }
+            for link in &task.links {
+                line.push_str(&format!(
+                    " · [{}]({})",
+                    escape_markdown_heading(&link.label),
+                    escape_markdown_url(&link.url)
+                ));
+            }
+            markdown.push_str(&line);
+            markdown.push('\n');
+        }
+    }
+    if include_notes && !project_data.notes.is_empty() {
+        markdown.push('\n');
+        markdown.push_str(&project_data.notes_to_markdown(true));
+    }
+    markdown
+}
+
+/// One CSV row per task; links are `label <url>` entries separated by `;`
+pub fn tasks_to_csv(project_data: &ProjectData, order: SectionOrder) -> String {
+    let mut csv = String::from(
+        "section,task_id,status,title,priority,assignee,tags,estimate_minutes,time_spent_minutes,links,created,updated\n",
+    );
+    for section in project_data.ordered_section_names(order) {
+        for (task_id, task) in sorted_tasks(project_data, section) {
+            let links: Vec<String> = task
+                .links
+                .iter()
+                .map(|link| format!("{} <{}>", link.label, link.url))
+                .collect();
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+                StatisticsManager::csv_field(section),
+                StatisticsManager::csv_field(task_id),
+                task.status.as_str(),
+                StatisticsManager::csv_field(&task.title),
+                task.priority.map(|p| p.as_str()).unwrap_or_default(),
+                StatisticsManager::csv_field(task.assignee.as_deref().unwrap_or("")),
+                StatisticsManager::csv_field(&task.tags.join(";")),
+                task.estimate_minutes
+                    .map(|m| m.to_string())
+                    .unwrap_or_default(),
+                task.time_spent_minutes,
+                StatisticsManager::csv_field(&links.join(";")),
+                task.created.to_rfc3339(),
+                task.updated.to_rfc3339()
+            ));
+        }
+    }
+    csv
+}
+
+/// Standalone HTML page with a table per section, followed by the notes when asked for
+pub fn tasks_to_html(
+    project_data: &ProjectData,
+    order: SectionOrder,
+    include_notes: bool,
+) -> String {
+    let title = match &project_data.meta.project_name {
+        Some(name) => format!("Tasks: {}", escape_html(name)),
+        None => "Tasks".to_string(),
+    };
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n",
+        title
+    );
+    for section in project_data.ordered_section_names(order) {
+        html.push_str(&format!("<h2>{}</h2>\n<table>\n", escape_html(section)));
+        html.push_str(
+            "<tr><th>ID</th><th>Status</th><th>Title</th><th>Assignee</th><th>Estimate (min)</th><th>Spent (min)</th><th>Links</th></tr>\n",
+        );
+        for (task_id, task) in sorted_tasks(project_data, section) {
+            let links: Vec<String> = task
+                .links
+                .iter()
+                .map(|link| {
+                    format!(
+                        "<a href=\"{}\">{}</a>",
+                        escape_html(&link.url),
+                        escape_html(&link.label)
+                    )
+                })
+                .collect();
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                escape_html(task_id),
+                task.status.as_str(),
+                escape_html(&task.title),
+                escape_html(task.assignee.as_deref().unwrap_or("")),
+                task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default(),
+                task.time_spent_minutes,
+                links.join(", ")
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    if include_notes && !project_data.notes.is_empty() {
+        let mut notes: Vec<_> = project_data.notes.values().collect();
+        notes.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.id.cmp(&b.id)));
+        html.push_str("<h2>Notes</h2>\n");
+        for note in notes {
+            html.push_str(&format!(
+                "<h3>{}</h3>\n<pre>{}</pre>\n",
+                escape_html(&note.title),
+                escape_html(note.content.trim_end())
+            ));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Percent-encode the characters that would end a Markdown link destination early
+fn escape_markdown_url(url: &str) -> String {
+    url.replace('(', "%28")
+        .replace(')', "%29")
+        .replace('<', "%3C")
+        .replace('>', "%3E")
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> ProjectData {
+        let mut project = ProjectData::new(Some("demo".to_string()));
+        project
+            .add_task("dev", "login", "Login <form>".to_string(), None)
+            .unwrap();
+        project
+            .add_task("dev", "api", "REST, v2".to_string(), None)
+            .unwrap();
+        project
+            .add_task("ui", "theme", "Dark theme".to_string(), None)
+            .unwrap();
+        project
+            .update_task_status("dev", "api", TaskStatus::Done)
+            .unwrap();
+        project
+            .update_task_status("ui", "theme", TaskStatus::InProgress)
+            .unwrap();
+        project
+    }
+
+    fn tracked_fixture() -> ProjectData {
+        let mut project = fixture();
+        project.set_task_estimate("dev", "login", Some(90)).unwrap();
+        project.add_time_entry("dev", "login", 45, None).unwrap();
+        project
+            .add_task_link("dev", "login", "Spec (v2)", "https://example.com/spec_(v2)")
+            .unwrap();
+        project
+            .add_task_link(
+                "dev",
+                "login",
+                "gh#7",
+                "https://github.com/acme/app/issues/7",
+            )
+            .unwrap();
+        project
+    }
+
+    #[test]
+    fn test_documents_list_every_task() {
+        let project = fixture();
+        let markdown = tasks_to_markdown(&project, SectionOrder::Alphabetical, false);
+        assert_eq!(
+            markdown,
+            "# Tasks: demo\n\n## dev\n\n- [x] `api` REST, v2\n- [ ] `login` Login \\<form\\>\n\n## ui\n\n- [ ] `theme` Dark theme (in_progress)\n"
+        );
+
+        let csv = tasks_to_csv(&project, SectionOrder::Alphabetical);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[1].starts_with("dev,api,done,\"REST, v2\",,,,,0,,"));
+
+        let html = tasks_to_html(&project, SectionOrder::Alphabetical, false);
+        assert!(html.contains("<tr><td>login</td><td>todo</td><td>Login &lt;form&gt;</td><td></td><td></td><td>0</td><td></td></tr>"));
+        assert!(!html.contains("<h2>Notes</h2>"));
+
+        // Time tracking and links show up in every document format
+        let project = tracked_fixture();
+        let markdown = tasks_to_markdown(&project, SectionOrder::Alphabetical, false);
+        assert!(markdown.contains(
+            "- [ ] `login` Login \\<form\\> · estimate 90m · spent 45m · [Spec (v2)](https://example.com/spec_%28v2%29) · [gh\\#7](https://github.com/acme/app/issues/7)\n"
+        ));
+
+        let csv = tasks_to_csv(&project, SectionOrder::Alphabetical);
+        let login = csv
+            .lines()
+            .find(|row| row.starts_with("dev,login,"))
+            .unwrap();
+        assert!(login.starts_with(
+            "dev,login,todo,Login <form>,,,,90,45,Spec (v2) <https://example.com/spec_(v2)>;gh#7 <https://github.com/acme/app/issues/7>,"
+        ));
+
+        let html = tasks_to_html(&project, SectionOrder::Alphabetical, false);
+        assert!(html.contains(
+            "<td>90</td><td>45</td><td><a href=\"https://example.com/spec_(v2)\">Spec (v2)</a>, <a href=\"https://github.com/acme/app/issues/7\">gh#7</a></td></tr>"
+        ));
+    }
+
+    #[test]
+    fn test_filter_drops_excluded_tasks_and_notes() {
+        let mut project = fixture();
+        project
+            .add_note(
+                "Idea".to_string(),
+                "text".to_string(),
+                "ui".to_string(),
+                "idea".to_string(),
+                None,
+            )
+            .unwrap();
+
+        let mut filtered = project.clone();
+        ReportFilter {
+            sections: Some(vec!["dev".to_string()]),
+            statuses: Some(vec![TaskStatus::Todo]),
+            include_notes: None,
+        }
+        .apply(&mut filtered);
+        assert_eq!(filtered.sections.len(), 1);
+        assert_eq!(
+            filtered.sections["dev"].keys().collect::<Vec<_>>(),
+            ["login"]
+        );
+        assert!(filtered.notes.is_empty());
+
+        let mut filtered = project.clone();
+        ReportFilter {
+            include_notes: Some(true),
+            ..Default::default()
+        }
+        .apply(&mut filtered);
+        assert_eq!(filtered.notes.len(), 1);
+        assert!(
+            tasks_to_markdown(&filtered, SectionOrder::Alphabetical, true).contains("\n## Idea\n")
+        );
+    }
+}
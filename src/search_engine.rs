@@ -8,11 +8,12 @@
  * - Performance monitoring
  */
 use std::collections::{HashMap, HashSet};
-use std::sync::RwLock;
+use std::sync::{LazyLock, RwLock};
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use crate::task_manager::{ProjectData, TaskStatus, Task};
+use crate::storage::DataVersion;
+use crate::task_manager::{normalize_tag, Note, ProjectData, TaskKey, TaskPriority, TaskStatus, Task};
 use anyhow::Result;
 /// Search query parameters with filtering options
 #[derive(Debug, Deserialize)]
@@ -27,6 +28,13 @@ pub struct SearchQuery {
 pub struct SearchFilters {
     pub sections: Option<Vec<String>>,
     pub statuses: Option<Vec<TaskStatus>>,
+    #[serde(default)]
+    pub priorities: Option<Vec<TaskPriority>>,
+    /// Tasks carrying any of these tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub assignees: Option<Vec<String>>,
     pub include_descriptions: Option<bool>,
     pub file_paths: Option<Vec<String>>,
     pub created_after: Option<DateTime<Utc>>,
@@ -49,12 +57,46 @@ pub struct TaskSearchResult {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    pub priority: Option<TaskPriority>,
+    pub tags: Vec<String>,
+    pub assignee: Option<String>,
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
     pub file_count: u32,
     pub relevance: f32,
     pub match_type: MatchType,
 }
+/// Note search parameters; `converted` keeps only converted (or unconverted) notes
+#[derive(Debug, Deserialize)]
+pub struct NoteSearchQuery {
+    pub query: String,
+    pub section: Option<String>,
+    pub converted: Option<bool>,
+    /// Only notes carrying any of these tags
+    pub tags: Option<Vec<String>>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+/// Ranked page of note matches
+#[derive(Debug, Serialize)]
+pub struct NoteSearchResults {
+    pub notes: Vec<NoteSearchResult>,
+    pub total_count: u32,
+}
+/// Individual note in search results
+#[derive(Debug, Serialize, Clone)]
+pub struct NoteSearchResult {
+    pub note: Note,
+    pub relevance: f32,
+    pub title_highlights: Vec<HighlightSpan>,
+    pub content_highlights: Vec<HighlightSpan>,
+}
+/// Matched range in character offsets, end exclusive
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightSpan {
+    pub start: usize,
+    pub end: usize,
+}
 /// Type of match found during search
 #[derive(Debug, Serialize, Clone)]
 pub enum MatchType {
@@ -85,9 +127,144 @@ pub enum SuggestionType {
     #[serde(rename = "status")]
     Status,
 }
+/// How the search index stores task data
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexMode {
+    /// Full task references with keyword lists, keyed by full task ID
+    #[default]
+    Full,
+    /// Task keys only, with every lookup table holding positions into them; task text is read from the project data
+    Compact,
+}
+/// Search engine configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    pub index_mode: IndexMode,
+    /// Languages whose built-in stop words are kept out of the index (en, ru, de, fr, es)
+    pub stop_word_languages: Vec<String>,
+    /// Additional words kept out of the index
+    pub stop_words: Vec<String>,
+    /// Words longer than this many characters are not indexed
+    pub max_keyword_length: usize,
+    /// Index English words by a crude suffix-stripped stem
+    pub stemming: bool,
+    /// Check for missed changes and rebuild the index in the background this often
+    pub rebuild_interval_ms: Option<u64>,
+}
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            index_mode: IndexMode::default(),
+            stop_word_languages: vec!["en".to_string()],
+            stop_words: Vec::new(),
+            max_keyword_length: 32,
+            stemming: false,
+            rebuild_interval_ms: None,
+        }
+    }
+}
+/// Built-in stop words per language code
+const LANGUAGE_STOP_WORDS: &[(&str, &[&str])] = &[
+    ("en", &[
+        "the", "and", "for", "with", "from", "into", "that", "this", "are", "was", "were", "been",
+        "has", "have", "had", "not", "but", "all", "any", "can", "will", "would", "should", "could",
+        "its", "our", "your", "their", "they", "them", "there", "then", "than", "when", "what",
+        "which", "who", "how", "why", "also", "some", "such", "only", "out", "over", "about", "via",
+    ]),
+    ("ru", &[
+        "и", "в", "во", "не", "что", "он", "на", "я", "с", "со", "как", "а", "то", "все", "она",
+        "так", "его", "но", "да", "ты", "к", "у", "же", "вы", "за", "бы", "по", "только", "ее",
+        "мне", "было", "вот", "от", "меня", "еще", "нет", "о", "из", "ему", "для", "при", "или",
+        "это", "этот", "эта", "эти", "если", "уже", "чтобы", "когда", "также",
+    ]),
+    ("de", &[
+        "der", "die", "das", "und", "oder", "mit", "für", "von", "aus", "auf", "ist", "sind", "ein",
+        "eine", "einen", "nicht", "den", "dem", "des", "auch", "als", "wie", "bei", "nach", "zum", "zur",
+    ]),
+    ("fr", &[
+        "les", "des", "une", "est", "pour", "avec", "dans", "par", "sur", "pas", "que", "qui", "aux",
+        "ces", "son", "ses", "mais", "ou", "et", "du", "au",
+    ]),
+    ("es", &[
+        "los", "las", "una", "uno", "para", "con", "por", "que", "del", "como", "pero", "sus", "este",
+        "esta", "son", "sin", "sobre", "entre", "el", "la", "en", "y",
+    ]),
+];
+/// Decides which words of task text end up in the keyword index
+#[derive(Debug, Clone, PartialEq)]
+struct KeywordFilter {
+    stop_words: HashSet<String>,
+    max_length: usize,
+    stemming: bool,
+}
+impl KeywordFilter {
+    fn new(config: &SearchConfig) -> Self {
+        let mut stop_words: HashSet<String> = config.stop_words.iter().map(|word| word.to_lowercase()).collect();
+        for language in &config.stop_word_languages {
+            match LANGUAGE_STOP_WORDS.iter().find(|(code, _)| code.eq_ignore_ascii_case(language)) {
+                Some((_, words)) => stop_words.extend(words.iter().map(|word| word.to_string())),
+                None => eprintln!("[WARN] No built-in stop words for language '{}'", language),
+            }
+        }
+        Self { stop_words, max_length: config.max_keyword_length, stemming: config.stemming }
+    }
+    fn is_stop_word(&self, word: &str) -> bool {
+        self.stop_words.contains(word)
+    }
+    fn keywords(&self, text: &str) -> impl Iterator<Item = String> + '_ {
+        text.to_lowercase()
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| {
+                let length = word.chars().count();
+                length > 2 && length <= self.max_length && !self.is_stop_word(word)
+            })
+            .map(|word| if self.stemming { stem(word) } else { word }.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+    /// Whole words of the query that every matching task has in the index. None when the
+    /// query has no such words, or one of them is dropped or stemmed by the filter and a
+    /// matching task could lack it.
+    fn exact_keywords(&self, query: &str) -> Option<Vec<String>> {
+        let query = query.to_lowercase();
+        let pieces: Vec<&str> = query.split(char::is_whitespace).collect();
+        // The first and last pieces may be parts of longer words
+        let words: Vec<&str> = pieces
+            .get(1..pieces.len() - 1)
+            .unwrap_or_default()
+            .iter()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| !word.is_empty())
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+        words
+            .into_iter()
+            .map(|word| self.keywords(word).next().filter(|keyword| keyword == word))
+            .collect()
+    }
+}
+/// Strip the common English inflection suffixes, keeping at least three characters
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(stem) = word.strip_suffix(suffix)
+            && stem.chars().count() >= 3
+            && !(suffix == "s" && stem.ends_with('s'))
+        {
+            return stem;
+        }
+    }
+    word
+}
 /// Search index for fast lookups
 #[derive(Debug)]
 struct SearchIndex {
+    mode: IndexMode,
+    keyword_filter: KeywordFilter,
     /// Task ID to full task reference mapping
     task_index: HashMap<String, TaskReference>,
     /// Word to task IDs mapping for full-text search
@@ -98,8 +275,37 @@ struct SearchIndex {
     status_index: HashMap<TaskStatus, HashSet<String>>,
     /// Frequently searched terms for suggestions
     suggestion_cache: HashMap<String, u32>,
+    /// Storage used instead of the maps above in compact mode
+    compact: CompactIndex,
     /// Last update timestamp
     last_updated: DateTime<Utc>,
+    /// Version of the tasks file the index was built from, if known
+    version: Option<DataVersion>,
+}
+/// Memory-bounded index: tasks are stored by key, lookups hold their positions and
+/// task text is read from the project data when results are rendered
+#[derive(Debug, Default)]
+struct CompactIndex {
+    /// Interned section names
+    sections: Vec<String>,
+    /// Task positions per interned section
+    section_tasks: Vec<Vec<u32>>,
+    tasks: Vec<TaskKey>,
+    word_index: HashMap<String, Vec<u32>>,
+    status_index: HashMap<TaskStatus, Vec<u32>>,
+}
+/// Borrowed view of an indexed task, independent of the index mode
+struct TaskView<'a> {
+    section: &'a str,
+    task_id: &'a str,
+    title: &'a str,
+    description: Option<&'a str>,
+    status: &'a TaskStatus,
+    priority: Option<TaskPriority>,
+    tags: &'a [String],
+    assignee: Option<&'a str>,
+    created: DateTime<Utc>,
+    updated: DateTime<Utc>,
 }
 /// Internal task reference for indexing
 #[derive(Debug, Clone)]
@@ -109,6 +315,9 @@ struct TaskReference {
     pub title: String,
     pub description: Option<String>,
     pub status: TaskStatus,
+    pub priority: Option<TaskPriority>,
+    pub tags: Vec<String>,
+    pub assignee: Option<String>,
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
     pub keywords: Vec<String>,
@@ -130,34 +339,65 @@ struct PerformanceStats {
 impl SearchEngine {
     /// Create a new search engine instance
     pub fn new() -> Self {
+        Self::with_config(SearchConfig::default())
+    }
+    /// Create a search engine with the given configuration
+    pub fn with_config(config: SearchConfig) -> Self {
         Self {
-            index: RwLock::new(SearchIndex::new()),
+            index: RwLock::new(SearchIndex::new(&config)),
             performance_stats: RwLock::new(PerformanceStats::default()),
         }
     }
+    /// Switch index storage mode and keyword filtering; takes effect on the next `index_project`
+    pub fn apply_config(&self, config: &SearchConfig) -> Result<()> {
+        let mut index = self.index.write().map_err(|_| anyhow::anyhow!("Failed to acquire write lock on search index"))?;
+        let keyword_filter = KeywordFilter::new(config);
+        if index.mode != config.index_mode || index.keyword_filter != keyword_filter {
+            index.clear();
+            index.mode = config.index_mode;
+            index.keyword_filter = keyword_filter;
+        }
+        Ok(())
+    }
     /// Build search index from project data
     pub fn index_project(&self, project_data: &ProjectData) -> Result<()> {
+        self.build_index(project_data, None)
+    }
+    /// Build search index from project data read at the given version of the tasks file
+    pub fn index_project_at(&self, project_data: &ProjectData, version: DataVersion) -> Result<()> {
+        self.build_index(project_data, Some(version))
+    }
+    /// Version of the tasks file behind the current index, if it was built from storage
+    pub fn indexed_version(&self) -> Option<DataVersion> {
+        self.index.read().ok().and_then(|index| index.version)
+    }
+    fn build_index(&self, project_data: &ProjectData, version: Option<DataVersion>) -> Result<()> {
         let start_time = Instant::now();
         let mut index = self.index.write().map_err(|_| anyhow::anyhow!("Failed to acquire write lock on search index"))?;
         index.clear();
-        for (section_name, section) in &project_data.sections {
-            for (task_id, task) in section {
-                let full_task_id = format!("{}.{}", section_name, task_id);
-                let task_ref = TaskReference::from_task(section_name, task_id, task);
-                index.task_index.insert(full_task_id.clone(), task_ref.clone());
-                index.section_index
-                    .entry(section_name.clone())
-                    .or_insert_with(HashSet::new)
-                    .insert(full_task_id.clone());
-                index.status_index
-                    .entry(task.status.clone())
-                    .or_insert_with(HashSet::new)
-                    .insert(full_task_id.clone());
-                for keyword in &task_ref.keywords {
-                    index.word_index
-                        .entry(keyword.clone())
+        index.version = version;
+        if index.mode == IndexMode::Compact {
+            index.compact = CompactIndex::build(project_data, &index.keyword_filter);
+        } else {
+            for (section_name, section) in &project_data.sections {
+                for (task_id, task) in section {
+                    let full_task_id = format!("{}.{}", section_name, task_id);
+                    let task_ref = TaskReference::from_task(section_name, task_id, task, &index.keyword_filter);
+                    index.task_index.insert(full_task_id.clone(), task_ref.clone());
+                    index.section_index
+                        .entry(section_name.clone())
                         .or_insert_with(HashSet::new)
                         .insert(full_task_id.clone());
+                    index.status_index
+                        .entry(task.status.clone())
+                        .or_insert_with(HashSet::new)
+                        .insert(full_task_id.clone());
+                    for keyword in &task_ref.keywords {
+                        index.word_index
+                            .entry(keyword.clone())
+                            .or_insert_with(HashSet::new)
+                            .insert(full_task_id.clone());
+                    }
                 }
             }
         }
@@ -167,16 +407,22 @@ impl SearchEngine {
             stats.last_index_rebuild = Some(Utc::now());
         }
         let duration = start_time.elapsed();
-        eprintln!("[INFO] Search index rebuilt in {:?} with {} tasks", duration, index.task_index.len());
+        eprintln!("[INFO] Search index rebuilt in {:?} with {} tasks", duration, index.task_count());
         Ok(())
     }
-    /// Perform search with the given query and filters
-    pub fn search(&self, query: &SearchQuery) -> Result<SearchResult> {
+    /// Perform search with the given query and filters. `project_data` supplies the
+    /// task text in compact mode and should be the data the index was built from.
+    pub fn search(&self, query: &SearchQuery, project_data: &ProjectData) -> Result<SearchResult> {
         let start_time = Instant::now();
         let index = self.index.read().map_err(|_| anyhow::anyhow!("Failed to acquire read lock on search index"))?;
         let mut results = Vec::new();
         let query_lower = query.query.to_lowercase();
-        for (_task_id, task_ref) in &index.task_index {
+        // Every task containing the query has each of its whole words indexed
+        let candidates: Vec<TaskView> = match index.keyword_filter.exact_keywords(&query.query) {
+            Some(keywords) => index.tasks_with_keywords(&keywords, project_data),
+            None => index.tasks(project_data).collect(),
+        };
+        for task_ref in candidates {
             let mut matches = false;
             let mut match_type = MatchType::Fuzzy;
             if task_ref.title.to_lowercase().contains(&query_lower) {
@@ -187,18 +433,21 @@ impl SearchEngine {
                     match_type = MatchType::Partial;
                 }
             }
-            if let Some(desc) = &task_ref.description {
-                if desc.to_lowercase().contains(&query_lower) {
-                    matches = true;
-                }
+            if let Some(desc) = task_ref.description
+                && desc.to_lowercase().contains(&query_lower)
+            {
+                matches = true;
             }
             if matches {
                 results.push(TaskSearchResult {
-                    section: task_ref.section.clone(),
-                    task_id: task_ref.task_id.clone(),
-                    title: task_ref.title.clone(),
-                    description: task_ref.description.clone(),
+                    section: task_ref.section.to_string(),
+                    task_id: task_ref.task_id.to_string(),
+                    title: task_ref.title.to_string(),
+                    description: task_ref.description.map(String::from),
                     status: task_ref.status.clone(),
+                    priority: task_ref.priority,
+                    tags: task_ref.tags.to_vec(),
+                    assignee: task_ref.assignee.map(String::from),
                     created: task_ref.created,
                     updated: task_ref.updated,
                     file_count: 1,
@@ -207,10 +456,21 @@ impl SearchEngine {
                 });
             }
         }
+        results.sort_by(|a, b| (&a.section, &a.task_id).cmp(&(&b.section, &b.task_id)));
         if let Some(filters) = &query.filters {
             if let Some(statuses) = &filters.statuses {
                 results.retain(|r| statuses.contains(&r.status));
             }
+            if let Some(priorities) = &filters.priorities {
+                results.retain(|r| r.priority.is_some_and(|priority| priorities.contains(&priority)));
+            }
+            if let Some(tags) = &filters.tags {
+                let tags: Vec<String> = tags.iter().filter_map(|tag| normalize_tag(tag)).collect();
+                results.retain(|r| r.tags.iter().any(|tag| tags.contains(tag)));
+            }
+            if let Some(assignees) = &filters.assignees {
+                results.retain(|r| r.assignee.as_ref().is_some_and(|assignee| assignees.contains(assignee)));
+            }
             if let Some(sections) = &filters.sections {
                 results.retain(|r| sections.contains(&r.section));
             }
@@ -242,20 +502,20 @@ impl SearchEngine {
         let index = self.index.read().map_err(|_| anyhow::anyhow!("Failed to acquire read lock on search index"))?;
         let mut suggestions = Vec::new();
         let query_lower = partial_query.to_lowercase();
-        for section_name in index.section_index.keys() {
+        for (section_name, task_count) in index.sections() {
             if section_name.to_lowercase().starts_with(&query_lower) {
                 suggestions.push(Suggestion {
-                    text: section_name.clone(),
+                    text: section_name.to_string(),
                     suggestion_type: SuggestionType::Section,
                     relevance: 0.9,
-                    frequency: index.section_index.get(section_name).map(|s| s.len() as u32).unwrap_or(0),
+                    frequency: task_count as u32,
                 });
             }
         }
-        for task_ref in index.task_index.values() {
-            if task_ref.task_id.to_lowercase().starts_with(&query_lower) {
+        for task_id in index.task_ids() {
+            if task_id.to_lowercase().starts_with(&query_lower) {
                 suggestions.push(Suggestion {
-                    text: task_ref.task_id.clone(),
+                    text: task_id.to_string(),
                     suggestion_type: SuggestionType::TaskId,
                     relevance: 0.8,
                     frequency: 1,
@@ -286,64 +546,352 @@ impl SearchEngine {
             "cache_hits": stats.cache_hits,
             "index_rebuilds": stats.index_rebuilds,
             "last_index_rebuild": stats.last_index_rebuild,
-            "indexed_tasks": index.task_index.len(),
-            "indexed_words": index.word_index.len(),
-            "indexed_sections": index.section_index.len()
+            "indexed_tasks": index.task_count(),
+            "indexed_words": index.word_count(),
+            "indexed_sections": index.sections().len(),
+            "index_mode": index.mode,
+            "index_memory_bytes": index.memory_bytes()
         }))
     }
 }
 impl SearchIndex {
-    fn new() -> Self {
+    fn new(config: &SearchConfig) -> Self {
         Self {
+            mode: config.index_mode,
+            keyword_filter: KeywordFilter::new(config),
             task_index: HashMap::new(),
             word_index: HashMap::new(),
             section_index: HashMap::new(),
             status_index: HashMap::new(),
             suggestion_cache: HashMap::new(),
+            compact: CompactIndex::default(),
             last_updated: Utc::now(),
+            version: None,
         }
     }
     fn clear(&mut self) {
+        self.version = None;
         self.task_index.clear();
         self.word_index.clear();
         self.section_index.clear();
         self.status_index.clear();
         self.suggestion_cache.clear();
+        self.compact = CompactIndex::default();
+    }
+    /// Indexed tasks. Compact mode skips tasks missing from `project_data`.
+    fn tasks<'a>(&'a self, project_data: &'a ProjectData) -> Box<dyn Iterator<Item = TaskView<'a>> + 'a> {
+        match self.mode {
+            IndexMode::Full => Box::new(self.task_index.values().map(TaskReference::view)),
+            IndexMode::Compact => Box::new(self.compact.tasks.iter().filter_map(|key| compact_view(key, project_data))),
+        }
+    }
+    fn task_ids(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match self.mode {
+            IndexMode::Full => Box::new(self.task_index.values().map(|task_ref| task_ref.task_id.as_str())),
+            IndexMode::Compact => Box::new(self.compact.tasks.iter().map(|key| key.task_id.as_str())),
+        }
+    }
+    /// Tasks indexed under every keyword
+    fn tasks_with_keywords<'a>(&'a self, keywords: &[String], project_data: &'a ProjectData) -> Vec<TaskView<'a>> {
+        match self.mode {
+            IndexMode::Full => {
+                let ids = matching_postings(&self.word_index, keywords, |ids| ids.iter());
+                ids.into_iter()
+                    .filter_map(|id| self.task_index.get(id))
+                    .map(TaskReference::view)
+                    .collect()
+            }
+            IndexMode::Compact => {
+                let positions = matching_postings(&self.compact.word_index, keywords, |positions| positions.iter());
+                positions.into_iter()
+                    .filter_map(|position| compact_view(&self.compact.tasks[*position as usize], project_data))
+                    .collect()
+            }
+        }
+    }
+    /// Section names with their task counts
+    fn sections(&self) -> Vec<(&str, usize)> {
+        match self.mode {
+            IndexMode::Full => self.section_index.iter()
+                .map(|(name, tasks)| (name.as_str(), tasks.len()))
+                .collect(),
+            IndexMode::Compact => self.compact.sections.iter()
+                .zip(&self.compact.section_tasks)
+                .map(|(name, tasks)| (name.as_str(), tasks.len()))
+                .collect(),
+        }
+    }
+    fn task_count(&self) -> usize {
+        match self.mode {
+            IndexMode::Full => self.task_index.len(),
+            IndexMode::Compact => self.compact.tasks.len(),
+        }
+    }
+    fn word_count(&self) -> usize {
+        match self.mode {
+            IndexMode::Full => self.word_index.len(),
+            IndexMode::Compact => self.compact.word_index.len(),
+        }
+    }
+    /// Approximate heap usage of the index in bytes
+    fn memory_bytes(&self) -> usize {
+        match self.mode {
+            IndexMode::Full => {
+                let id_set_bytes = |ids: &HashSet<String>| {
+                    hash_set_bytes::<String>(ids.capacity()) + ids.iter().map(String::capacity).sum::<usize>()
+                };
+                hash_map_bytes::<String, TaskReference>(self.task_index.capacity())
+                    + self.task_index.iter().map(|(key, task_ref)| key.capacity() + task_ref.heap_bytes()).sum::<usize>()
+                    + hash_map_bytes::<String, HashSet<String>>(self.word_index.capacity())
+                    + self.word_index.iter().map(|(word, ids)| word.capacity() + id_set_bytes(ids)).sum::<usize>()
+                    + hash_map_bytes::<String, HashSet<String>>(self.section_index.capacity())
+                    + self.section_index.iter().map(|(name, ids)| name.capacity() + id_set_bytes(ids)).sum::<usize>()
+                    + hash_map_bytes::<TaskStatus, HashSet<String>>(self.status_index.capacity())
+                    + self.status_index.values().map(id_set_bytes).sum::<usize>()
+            }
+            IndexMode::Compact => self.compact.memory_bytes(),
+        }
     }
 }
-impl TaskReference {
-    fn from_task(section: &str, task_id: &str, task: &Task) -> Self {
-        let mut keywords = Vec::new();
-        keywords.extend(
-            task.title
-                .to_lowercase()
-                .split_whitespace()
-                .filter(|w| w.len() > 2)
-                .map(String::from)
-        );
-        if let Some(desc) = &task.description {
-            keywords.extend(
-                desc.to_lowercase()
-                    .split_whitespace()
-                    .filter(|w| w.len() > 2)
-                    .map(String::from)
-            );
+/// Postings of each keyword, intersected across keywords
+fn matching_postings<'a, P, T, I>(word_index: &'a HashMap<String, P>, keywords: &[String], postings: impl Fn(&'a P) -> I) -> HashSet<&'a T>
+where
+    I: Iterator<Item = &'a T>,
+    T: Eq + std::hash::Hash + 'a,
+{
+    let mut matching: Option<HashSet<&T>> = None;
+    for keyword in keywords {
+        let found: HashSet<&T> = word_index.get(keyword)
+            .into_iter()
+            .flat_map(&postings)
+            .filter(|entry| matching.as_ref().is_none_or(|matching| matching.contains(entry)))
+            .collect();
+        matching = Some(found);
+    }
+    matching.unwrap_or_default()
+}
+/// View of a compact index entry with its text from the project data
+fn compact_view<'a>(key: &'a TaskKey, project_data: &'a ProjectData) -> Option<TaskView<'a>> {
+    let task = project_data.get_task(&key.section, &key.task_id)?;
+    Some(TaskView {
+        section: &key.section,
+        task_id: &key.task_id,
+        title: &task.title,
+        description: task.description.as_deref(),
+        status: &task.status,
+        priority: task.priority,
+        tags: &task.tags,
+        assignee: task.assignee.as_deref(),
+        created: task.created,
+        updated: task.updated,
+    })
+}
+impl CompactIndex {
+    fn build(project_data: &ProjectData, keyword_filter: &KeywordFilter) -> Self {
+        let mut index = Self::default();
+        let mut section_names: Vec<&String> = project_data.sections.keys().collect();
+        section_names.sort();
+        for section_name in section_names {
+            index.sections.push(section_name.clone());
+            let mut positions = Vec::new();
+            for (task_id, task) in &project_data.sections[section_name] {
+                let position = index.tasks.len() as u32;
+                positions.push(position);
+                index.status_index.entry(task.status.clone()).or_default().push(position);
+                for keyword in task_keywords(section_name, task_id, task, keyword_filter) {
+                    index.word_index.entry(keyword).or_default().push(position);
+                }
+                index.tasks.push(TaskKey::new(section_name, task_id));
+            }
+            positions.shrink_to_fit();
+            index.section_tasks.push(positions);
+        }
+        for positions in index.word_index.values_mut() {
+            positions.shrink_to_fit();
+        }
+        index.tasks.shrink_to_fit();
+        index
+    }
+    fn memory_bytes(&self) -> usize {
+        let positions_bytes = |positions: &Vec<u32>| positions.capacity() * std::mem::size_of::<u32>();
+        self.sections.capacity() * std::mem::size_of::<String>()
+            + self.sections.iter().map(String::capacity).sum::<usize>()
+            + self.section_tasks.capacity() * std::mem::size_of::<Vec<u32>>()
+            + self.section_tasks.iter().map(positions_bytes).sum::<usize>()
+            + self.tasks.capacity() * std::mem::size_of::<TaskKey>()
+            + self.tasks.iter().map(|key| key.section.capacity() + key.task_id.capacity()).sum::<usize>()
+            + hash_map_bytes::<String, Vec<u32>>(self.word_index.capacity())
+            + self.word_index.iter().map(|(word, positions)| word.capacity() + positions_bytes(positions)).sum::<usize>()
+            + hash_map_bytes::<TaskStatus, Vec<u32>>(self.status_index.capacity())
+            + self.status_index.values().map(positions_bytes).sum::<usize>()
+    }
+}
+fn hash_map_bytes<K, V>(capacity: usize) -> usize {
+    capacity * (std::mem::size_of::<K>() + std::mem::size_of::<V>() + 1)
+}
+fn hash_set_bytes<T>(capacity: usize) -> usize {
+    capacity * (std::mem::size_of::<T>() + 1)
+}
+/// Keyword filtering of the default search config, for text compared outside the index
+static DEFAULT_KEYWORD_FILTER: LazyLock<KeywordFilter> =
+    LazyLock::new(|| KeywordFilter::new(&SearchConfig::default()));
+/// Whether a lowercased word is too common to tell two tasks apart
+pub fn is_stop_word(word: &str) -> bool {
+    DEFAULT_KEYWORD_FILTER.is_stop_word(word)
+}
+/// Lowercased words of free text, without punctuation, short words and stop words
+pub fn normalize_text(text: &str) -> Vec<String> {
+    DEFAULT_KEYWORD_FILTER.keywords(text).collect()
+}
+/// Lowercased words of a note query without punctuation and stop words. Unlike
+/// `normalize_text` short words are kept, since they are matched as substrings.
+fn note_query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query.to_lowercase().split_whitespace() {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        if word.chars().count() > 0 && !is_stop_word(word) && !terms.iter().any(|term| term == word) {
+            terms.push(word.to_string());
+        }
+    }
+    terms
+}
+/// Relevance added to pinned notes, less than a single content hit
+const PINNED_NOTE_BOOST: f32 = 0.25;
+/// Search notes by title and content; every query term must match somewhere.
+/// Title hits weigh more than content hits, pinned notes get a small boost, and
+/// at equal relevance unconverted notes rank above converted ones.
+pub fn search_notes<'a>(notes: impl IntoIterator<Item = &'a Note>, query: &NoteSearchQuery) -> NoteSearchResults {
+    let mut terms = note_query_terms(&query.query);
+    if terms.is_empty() && !query.query.trim().is_empty() {
+        terms.push(query.query.trim().to_lowercase());
+    }
+    let mut results: Vec<NoteSearchResult> = notes
+        .into_iter()
+        .filter(|note| query.section.as_ref().is_none_or(|section| &note.section == section))
+        .filter(|note| query.converted.is_none_or(|converted| note.is_converted == converted))
+        .filter(|note| query.tags.as_ref().is_none_or(|tags| note.has_any_tag(tags)))
+        .filter_map(|note| {
+            let mut relevance = 0.0;
+            let mut title_highlights = Vec::new();
+            let mut content_highlights = Vec::new();
+            for term in &terms {
+                let in_title = find_folded(&note.title, term);
+                let in_content = find_folded(&note.content, term);
+                if in_title.is_empty() && in_content.is_empty() {
+                    return None;
+                }
+                if !in_title.is_empty() {
+                    relevance += 2.0;
+                }
+                if !in_content.is_empty() {
+                    relevance += 1.0;
+                }
+                title_highlights.extend(in_title);
+                content_highlights.extend(in_content);
+            }
+            if !terms.is_empty() {
+                relevance /= terms.len() as f32;
+            }
+            if note.pinned {
+                relevance += PINNED_NOTE_BOOST;
+            }
+            title_highlights.sort_by_key(|span| span.start);
+            content_highlights.sort_by_key(|span| span.start);
+            Some(NoteSearchResult { note: note.clone(), relevance, title_highlights, content_highlights })
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        b.relevance
+            .total_cmp(&a.relevance)
+            .then(a.note.is_converted.cmp(&b.note.is_converted))
+            .then(b.note.updated.cmp(&a.note.updated))
+            .then(a.note.id.cmp(&b.note.id))
+    });
+    let total_count = results.len() as u32;
+    let offset = query.offset.unwrap_or(0).min(results.len());
+    let limit = query.limit.unwrap_or(50);
+    let notes = results.into_iter().skip(offset).take(limit).collect();
+    NoteSearchResults { notes, total_count }
+}
+/// Case-folded occurrences of an already lowercased term, as character spans of the original text
+fn find_folded(text: &str, term: &str) -> Vec<HighlightSpan> {
+    let needle: Vec<char> = term.chars().collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let folded: Vec<(char, usize)> = text
+        .chars()
+        .enumerate()
+        .flat_map(|(index, c)| c.to_lowercase().map(move |lower| (lower, index)))
+        .collect();
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while pos + needle.len() <= folded.len() {
+        if folded[pos..pos + needle.len()].iter().map(|(c, _)| c).eq(needle.iter()) {
+            let start = folded[pos].1;
+            let end = folded[pos + needle.len() - 1].1 + 1;
+            spans.push(HighlightSpan { start, end });
+            pos += needle.len();
+        } else {
+            pos += 1;
         }
-        keywords.push(section.to_lowercase());
-        keywords.push(task_id.to_lowercase());
-        keywords.sort();
-        keywords.dedup();
+    }
+    spans
+}
+/// Lowercased search keywords for a task
+fn task_keywords(section: &str, task_id: &str, task: &Task, keyword_filter: &KeywordFilter) -> Vec<String> {
+    let mut keywords: Vec<String> = keyword_filter.keywords(&task.title).collect();
+    if let Some(desc) = &task.description {
+        keywords.extend(keyword_filter.keywords(desc));
+    }
+    keywords.push(section.to_lowercase());
+    keywords.push(task_id.to_lowercase());
+    keywords.sort();
+    keywords.dedup();
+    keywords
+}
+impl TaskReference {
+    fn from_task(section: &str, task_id: &str, task: &Task, keyword_filter: &KeywordFilter) -> Self {
+        let keywords = task_keywords(section, task_id, task, keyword_filter);
         Self {
             section: section.to_string(),
             task_id: task_id.to_string(),
             title: task.title.clone(),
             description: task.description.clone(),
             status: task.status.clone(),
+            priority: task.priority,
+            tags: task.tags.clone(),
+            assignee: task.assignee.clone(),
             created: task.created,
             updated: task.updated,
             keywords,
         }
     }
+    fn view(&self) -> TaskView<'_> {
+        TaskView {
+            section: &self.section,
+            task_id: &self.task_id,
+            title: &self.title,
+            description: self.description.as_deref(),
+            status: &self.status,
+            priority: self.priority,
+            tags: &self.tags,
+            assignee: self.assignee.as_deref(),
+            created: self.created,
+            updated: self.updated,
+        }
+    }
+    fn heap_bytes(&self) -> usize {
+        self.section.capacity()
+            + self.task_id.capacity()
+            + self.title.capacity()
+            + self.description.as_ref().map_or(0, String::capacity)
+            + self.keywords.capacity() * std::mem::size_of::<String>()
+            + self.keywords.iter().map(String::capacity).sum::<usize>()
+            + self.tags.capacity() * std::mem::size_of::<String>()
+            + self.tags.iter().map(String::capacity).sum::<usize>()
+            + self.assignee.as_ref().map_or(0, String::capacity)
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -356,13 +904,199 @@ mod tests {
         assert_eq!(stats["total_searches"], 0);
     }
     #[test]
+    fn test_compact_index_matches_full_index() {
+        let mut project_data = ProjectData::new(None);
+        for i in 0..200 {
+            let section = ["dev", "ops", "docs"][i % 3];
+            let description = (i % 4 == 0).then(|| format!("Touches the parser module {}", i));
+            project_data.add_task(section, &format!("task_{}", i), format!("Implement feature number {}", i), description).unwrap();
+            if i % 5 == 0 {
+                project_data.update_task_status(section, &format!("task_{}", i), TaskStatus::Done).unwrap();
+            }
+        }
+        let full = SearchEngine::new();
+        let compact = SearchEngine::with_config(SearchConfig { index_mode: IndexMode::Compact, ..SearchConfig::default() });
+        full.index_project(&project_data).unwrap();
+        compact.index_project(&project_data).unwrap();
+        let queries = [
+            ("feature", None, None),
+            ("parser", None, None),
+            ("number 1", Some(SearchFilters {
+                sections: Some(vec!["ops".to_string()]),
+                statuses: Some(vec![TaskStatus::Done]),
+                priorities: None,
+                tags: None,
+                assignees: None,
+                include_descriptions: None,
+                file_paths: None,
+                created_after: None,
+                updated_after: None,
+            }), Some(3)),
+            ("missing", None, None),
+        ];
+        for (text, filters, offset) in queries {
+            let query = SearchQuery { query: text.to_string(), filters, limit: Some(20), offset };
+            let full_result = full.search(&query, &project_data).unwrap();
+            let compact_result = compact.search(&query, &project_data).unwrap();
+            assert_eq!(full_result.total_count, compact_result.total_count);
+            assert_eq!(
+                serde_json::to_value(&full_result.tasks).unwrap(),
+                serde_json::to_value(&compact_result.tasks).unwrap()
+            );
+        }
+        let suggestion_texts = |engine: &SearchEngine| {
+            let mut texts: Vec<String> = engine.get_suggestions("d").unwrap().into_iter().map(|s| s.text).collect();
+            texts.sort();
+            texts
+        };
+        assert_eq!(suggestion_texts(&full), suggestion_texts(&compact));
+        let full_stats = full.get_performance_stats().unwrap();
+        let compact_stats = compact.get_performance_stats().unwrap();
+        assert_eq!(full_stats["indexed_tasks"], compact_stats["indexed_tasks"]);
+        assert_eq!(full_stats["indexed_words"], compact_stats["indexed_words"]);
+        assert_eq!(compact_stats["index_mode"], "compact");
+        assert!(compact_stats["index_memory_bytes"].as_u64().unwrap() < full_stats["index_memory_bytes"].as_u64().unwrap());
+
+        // Compact results render the text of the data passed in, skipping tasks it lacks
+        project_data.get_task_mut("dev", "task_0").unwrap().title = "Implement feature number 0 again".to_string();
+        project_data.delete_task("dev", "task_3").unwrap();
+        let query = SearchQuery { query: "number".to_string(), filters: None, limit: Some(300), offset: None };
+        let result = compact.search(&query, &project_data).unwrap();
+        assert_eq!(result.total_count, 199);
+        let task_0 = result.tasks.iter().find(|task| task.task_id == "task_0").unwrap();
+        assert_eq!(task_0.title, "Implement feature number 0 again");
+    }
+    #[test]
     fn test_task_reference_creation() {
         let mut task = Task::new("Test task".to_string(), Some("Test description".to_string()));
         task.status = TaskStatus::Todo;
-        let task_ref = TaskReference::from_task("test_section", "test_task", &task);
+        let task_ref = TaskReference::from_task("test_section", "test_task", &task, &KeywordFilter::new(&SearchConfig::default()));
         assert_eq!(task_ref.section, "test_section");
         assert_eq!(task_ref.task_id, "test_task");
         assert_eq!(task_ref.title, "Test task");
         assert!(task_ref.keywords.contains(&"test".to_string()));
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_stop_words_and_long_words_are_not_indexed() {
+        let mut task = Task::new(
+            "Refactor the parser and the lexer for speed".to_string(),
+            Some("Tokens like supercalifragilisticexpialidociousness are noise".to_string()),
+        );
+        task.status = TaskStatus::Todo;
+        let filter = KeywordFilter::new(&SearchConfig { stop_words: vec!["Speed".to_string()], ..SearchConfig::default() });
+        let keywords = TaskReference::from_task("dev", "refactor_parser", &task, &filter).keywords;
+        for keyword in ["refactor", "parser", "lexer", "tokens", "noise", "dev", "refactor_parser"] {
+            assert!(keywords.contains(&keyword.to_string()), "missing {}", keyword);
+        }
+        for ignored in ["the", "and", "for", "are", "speed", "supercalifragilisticexpialidociousness"] {
+            assert!(!keywords.contains(&ignored.to_string()), "indexed {}", ignored);
+        }
+
+        let stemming = KeywordFilter::new(&SearchConfig { stemming: true, ..SearchConfig::default() });
+        let keywords: Vec<String> = stemming.keywords("Parsing fixed classes and class tests").collect();
+        assert_eq!(keywords, vec!["pars", "fix", "class", "class", "test"]);
+
+        let engine = SearchEngine::new();
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("dev", "login", "Build the login form".to_string(), None).unwrap();
+        engine.index_project(&project_data).unwrap();
+        let words = engine.get_performance_stats().unwrap()["indexed_words"].as_u64().unwrap();
+        assert_eq!(words, 4);
+    }
+    #[test]
+    fn test_search_looks_up_keywords_in_word_index() {
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("dev", "login", "Build the login form".to_string(), None).unwrap();
+        project_data.add_task("dev", "logout", "Add a logout button".to_string(), Some("Next to the login form".to_string())).unwrap();
+        project_data.add_task("ops", "deploy", "Deploy to staging".to_string(), None).unwrap();
+        for mode in [IndexMode::Full, IndexMode::Compact] {
+            let engine = SearchEngine::with_config(SearchConfig { index_mode: mode, ..SearchConfig::default() });
+            engine.index_project(&project_data).unwrap();
+            let found = |text: &str| -> Vec<String> {
+                let query = SearchQuery { query: text.to_string(), filters: None, limit: None, offset: None };
+                engine.search(&query, &project_data).unwrap().tasks.into_iter().map(|task| task.task_id).collect()
+            };
+            assert_eq!(found("login form"), vec!["login", "logout"]);
+            assert_eq!(found("ogin f"), vec!["login", "logout"]);
+            assert_eq!(found("the login"), vec!["login", "logout"]);
+            assert_eq!(found("form staging"), Vec::<String>::new());
+            assert_eq!(found("to"), vec!["logout", "deploy"]);
+            assert_eq!(found("the login form"), vec!["login", "logout"]);
+        }
+        assert_eq!(normalize_text("They were parsing the input"), vec!["parsing", "input"]);
+    }
+    #[test]
+    fn test_search_finds_parts_of_unindexed_words() {
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("dev", "there", "Fix there bug".to_string(), None).unwrap();
+        project_data.add_task("dev", "i18n", "Handle internationalizationcompatibilitylayer errors".to_string(), None).unwrap();
+        project_data.add_task("dev", "tests", "Run the failing tests".to_string(), None).unwrap();
+        let config = SearchConfig { stop_words: vec!["there".to_string()], stemming: true, ..SearchConfig::default() };
+        for mode in [IndexMode::Full, IndexMode::Compact] {
+            let engine = SearchEngine::with_config(SearchConfig { index_mode: mode, ..config.clone() });
+            engine.index_project(&project_data).unwrap();
+            let found = |text: &str| -> Vec<String> {
+                let query = SearchQuery { query: text.to_string(), filters: None, limit: None, offset: None };
+                engine.search(&query, &project_data).unwrap().tasks.into_iter().map(|task| task.task_id).collect()
+            };
+            assert_eq!(found("ther"), vec!["there"]);
+            assert_eq!(found("compatibility"), vec!["i18n"]);
+            assert_eq!(found("the failing tests"), vec!["tests"]);
+        }
+    }
+    fn note_query(query: &str) -> NoteSearchQuery {
+        NoteSearchQuery { query: query.to_string(), section: None, converted: None, tags: None, limit: None, offset: None }
+    }
+    #[test]
+    fn test_search_notes_folds_unicode_case() {
+        let note = Note::new(
+            "Кэширование ответов".to_string(),
+            "Сбрасывать КЭШ после деплоя".to_string(),
+            "backend".to_string(),
+            "cache_reset".to_string(),
+            None,
+        );
+        let results = search_notes([&note], &note_query("КЭШ"));
+        assert_eq!(results.total_count, 1);
+        let hit = &results.notes[0];
+        assert_eq!(hit.title_highlights, vec![HighlightSpan { start: 0, end: 3 }]);
+        assert_eq!(hit.content_highlights, vec![HighlightSpan { start: 11, end: 14 }]);
+        assert_eq!(hit.relevance, 3.0);
+        assert_eq!(search_notes([&note], &note_query("кэш ДЕПЛОЯ")).total_count, 1);
+        assert_eq!(search_notes([&note], &note_query("кэш релиз")).total_count, 0);
+        // Short terms still have to match
+        assert_eq!(search_notes([&note], &note_query("кэш БД")).total_count, 0);
+        let results = search_notes([&note], &note_query("ш ПО"));
+        assert_eq!(results.total_count, 1);
+        assert_eq!(results.notes[0].content_highlights, vec![HighlightSpan { start: 13, end: 14 }, HighlightSpan { start: 15, end: 17 }]);
+    }
+    #[test]
+    fn test_search_notes_ranks_unconverted_first() {
+        let mut converted = Note::new("Parser cleanup".to_string(), String::new(), "dev".to_string(), "a".to_string(), None);
+        converted.is_converted = true;
+        let open = Note::new("Parser rewrite".to_string(), String::new(), "dev".to_string(), "b".to_string(), None);
+        let weaker = Note::new("Misc".to_string(), "parser notes".to_string(), "dev".to_string(), "c".to_string(), None);
+        let notes = [&converted, &weaker, &open];
+        let results = search_notes(notes, &note_query("parser"));
+        let order: Vec<&str> = results.notes.iter().map(|hit| hit.note.id.as_str()).collect();
+        assert_eq!(order, vec![open.id.as_str(), converted.id.as_str(), weaker.id.as_str()]);
+        let only_converted = search_notes(notes, &NoteSearchQuery { converted: Some(true), ..note_query("parser") });
+        assert_eq!(only_converted.total_count, 1);
+        let paged = search_notes(notes, &NoteSearchQuery { limit: Some(1), offset: Some(1), ..note_query("parser") });
+        assert_eq!(paged.total_count, 3);
+        assert_eq!(paged.notes[0].note.id, converted.id);
+    }
+    #[test]
+    fn test_search_notes_boosts_pinned() {
+        let title_hit = Note::new("Parser rewrite".to_string(), String::new(), "dev".to_string(), "a".to_string(), None);
+        let mut pinned = Note::new("Misc".to_string(), "parser notes".to_string(), "dev".to_string(), "b".to_string(), None);
+        pinned.pinned = true;
+        let mut unpinned = pinned.clone();
+        unpinned.id = "unpinned".to_string();
+        unpinned.pinned = false;
+        let results = search_notes([&unpinned, &title_hit, &pinned], &note_query("parser"));
+        let order: Vec<&str> = results.notes.iter().map(|hit| hit.note.id.as_str()).collect();
+        // The boost lifts a pinned note over an equal match, not over a stronger one
+        assert_eq!(order, vec![title_hit.id.as_str(), pinned.id.as_str(), "unpinned"]);
+    }
+}
@@ -0,0 +1,160 @@
+/*!
+ * String Similarity Module for Anchora Backend
+ *
+ * Edit-distance helpers shared by validation and duplicate detection.
+ */
+
+/// Similarity between two strings in `0.0..=1.0`, based on edit distance
+pub fn similarity(s1: &str, s2: &str) -> f32 {
+    if s1 == s2 {
+        return 1.0;
+    }
+    let longer = s1.chars().count().max(s2.chars().count());
+    if longer == 0 {
+        return 1.0;
+    }
+    let edit_distance = levenshtein_distance(s1, s2);
+    (longer - edit_distance) as f32 / longer as f32
+}
+
+/// Levenshtein distance between two strings, counted in characters
+pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
+    let chars1: Vec<char> = s1.chars().collect();
+    let chars2: Vec<char> = s2.chars().collect();
+    let len1 = chars1.len();
+    let len2 = chars2.len();
+
+    let mut matrix = vec![vec![0; len2 + 1]; len1 + 1];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in matrix[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=len1 {
+        for j in 1..=len2 {
+            let cost = if chars1[i - 1] == chars2[j - 1] { 0 } else { 1 };
+            matrix[i][j] = std::cmp::min(
+                std::cmp::min(matrix[i - 1][j] + 1, matrix[i][j - 1] + 1),
+                matrix[i - 1][j - 1] + cost,
+            );
+        }
+    }
+
+    matrix[len1][len2]
+}
+
+/// Largest edit distance at which `similarity` still exceeds `threshold`,
+/// evaluated with the same arithmetic as `similarity` itself
+fn max_distance(longer: usize, threshold: f32) -> Option<usize> {
+    (0..=longer)
+        .rev()
+        .find(|&distance| (longer - distance) as f32 / longer as f32 > threshold)
+}
+
+/// Sorted trigrams of a string, used as a cheap lower bound on edit distance
+fn trigrams(chars: &[char]) -> Vec<[char; 3]> {
+    let mut grams: Vec<[char; 3]> = chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect();
+    grams.sort_unstable();
+    grams
+}
+
+/// Size of the multiset intersection of two sorted trigram lists
+fn shared_trigrams(a: &[[char; 3]], b: &[[char; 3]]) -> usize {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    shared
+}
+
+struct IndexedId {
+    id: String,
+    length: usize,
+    trigrams: Vec<[char; 3]>,
+}
+
+/// Ids sorted by length so similarity lookups only compute edit distance for
+/// candidates that pass the length and trigram screens
+pub struct SimilarityIndex {
+    entries: Vec<IndexedId>,
+}
+
+impl SimilarityIndex {
+    pub fn new<'a>(ids: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut entries: Vec<IndexedId> = ids
+            .into_iter()
+            .map(|id| {
+                let chars: Vec<char> = id.chars().collect();
+                IndexedId {
+                    id: id.clone(),
+                    length: chars.len(),
+                    trigrams: trigrams(&chars),
+                }
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.length);
+        Self { entries }
+    }
+
+    /// Ids whose `similarity` to `query` is strictly above `threshold`,
+    /// with the same results as comparing against every id
+    pub fn similar_to(&self, query: &str, threshold: f32) -> Vec<(&str, f32)> {
+        let chars: Vec<char> = query.chars().collect();
+        let length = chars.len();
+        let query_trigrams = trigrams(&chars);
+        let (low, high) = if threshold > 0.0 && threshold < 1.0 {
+            (
+                (length as f32 * threshold).floor() as usize,
+                (length as f32 / threshold).ceil() as usize + 1,
+            )
+        } else {
+            (0, usize::MAX)
+        };
+        let start = self.entries.partition_point(|entry| entry.length < low);
+        let end = self.entries.partition_point(|entry| entry.length <= high);
+
+        self.entries[start..end]
+            .iter()
+            .filter(|entry| {
+                let longer = length.max(entry.length);
+                let Some(allowed) = max_distance(longer, threshold) else {
+                    // Two empty ids are identical; anything else cannot pass
+                    return longer == 0;
+                };
+                if length.abs_diff(entry.length) > allowed {
+                    return false;
+                }
+                // Each edit destroys at most three trigrams of the longer string
+                let required = (longer + 1).saturating_sub(3 + 3 * allowed);
+                required == 0 || shared_trigrams(&query_trigrams, &entry.trigrams) >= required
+            })
+            .filter_map(|entry| {
+                let score = similarity(query, &entry.id);
+                (score > threshold).then_some((entry.id.as_str(), score))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_similarity_calculation() {
+        assert_eq!(similarity("test", "test"), 1.0);
+        assert!(similarity("test", "tast") > 0.5);
+        assert!(similarity("hello", "world") < 0.5);
+        assert_eq!(levenshtein_distance("задача", "задачи"), 1);
+    }
+}
@@ -8,21 +8,38 @@
  * - Intelligent caching
  */
 
-use crate::task_manager::{ProjectData, Task, TaskStatus};
+use crate::task_manager::{
+    ProjectData, SectionOrder, Task, TaskFile, TaskKey, TaskPriority, TaskStatus,
+};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::RwLock;
 
 /// Main statistics structure
 #[derive(Debug, Serialize, Clone)]
 pub struct TaskStatistics {
     pub overview: TaskOverview,
+    #[serde(serialize_with = "sorted_map")]
     pub sections: HashMap<String, SectionStats>,
     pub recent_activity: Vec<TaskActivity>,
     pub trends: StatsTrends,
     pub generated_at: DateTime<Utc>,
+    pub project_name: Option<String>,
+    #[serde(serialize_with = "sorted_map")]
+    pub by_assignee: HashMap<String, BreakdownStats>,
+    #[serde(serialize_with = "sorted_map")]
+    pub by_tag: HashMap<String, BreakdownStats>,
+}
+
+/// Serialize a map with its keys in sorted order, so output does not vary between runs
+fn sorted_map<S: Serializer, V: Serialize>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
 }
 
 /// High-level task overview
@@ -34,8 +51,95 @@ pub struct TaskOverview {
     pub blocked_tasks: u32,
     pub completion_rate: f32,
     pub sections: Vec<SectionSummary>,
+    pub top_assignees: Vec<BreakdownStats>,
+    pub top_tags: Vec<BreakdownStats>,
+    /// Tasks per priority, most urgent first, tasks without one under `(none)`
+    pub priorities: Vec<BreakdownStats>,
+    /// Tasks commented on within the last `RECENT_COMMENT_DAYS` days
+    pub tasks_with_recent_comments: u32,
+    /// Milestones by due date, undated ones last
+    pub milestones: Vec<MilestoneProgress>,
+}
+
+/// Completion of a milestone, counted over its member tasks that are still present
+#[derive(Debug, Serialize, Clone)]
+pub struct MilestoneProgress {
+    pub id: String,
+    pub title: String,
+    pub due: Option<DateTime<Utc>>,
+    pub total: u32,
+    pub todo: u32,
+    pub in_progress: u32,
+    pub done: u32,
+    pub blocked: u32,
+    pub completion_percentage: f32,
+    /// Past its due date with tasks still open
+    pub overdue: bool,
+}
+
+/// How far back a comment still counts as recent in the overview
+pub const RECENT_COMMENT_DAYS: i64 = 7;
+
+/// Task counts for one assignee or tag
+#[derive(Debug, Serialize, Clone)]
+pub struct BreakdownStats {
+    pub name: String,
+    pub total: u32,
+    pub done: u32,
+    pub completion_rate: f32,
 }
 
+/// Bucket used for tasks without an assignee or tags
+pub const NONE_BUCKET: &str = "(none)";
+
+/// Restricts statistics to a subset of tasks
+#[derive(Debug, Clone, Default)]
+pub struct StatisticsFilter {
+    pub sections: Option<Vec<String>>,
+    pub assignees: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    /// Count each task tree once: subtasks count in their top-level task's section
+    /// and tasks that have subtasks are left out
+    pub roll_up_subtasks: bool,
+}
+
+impl StatisticsFilter {
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_none()
+            && self.assignees.is_none()
+            && self.tags.is_none()
+            && !self.roll_up_subtasks
+    }
+
+    fn matches(&self, section: &str, task: &Task) -> bool {
+        if let Some(sections) = &self.sections
+            && !sections.iter().any(|s| s == section)
+        {
+            return false;
+        }
+        if let Some(assignees) = &self.assignees {
+            let assignee = task.assignee.as_deref().unwrap_or(NONE_BUCKET);
+            if !assignees.iter().any(|a| a == assignee) {
+                return false;
+            }
+        }
+        if let Some(tags) = &self.tags {
+            let matched = if task.tags.is_empty() {
+                tags.iter().any(|t| t == NONE_BUCKET)
+            } else {
+                task.tags.iter().any(|t| tags.contains(t))
+            };
+            if !matched {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// How many files `SectionStats::most_active_files` lists
+pub const MOST_ACTIVE_FILES_LIMIT: usize = 5;
+
 /// Statistics for a specific section
 #[derive(Debug, Serialize, Clone)]
 pub struct SectionStats {
@@ -47,16 +151,101 @@ pub struct SectionStats {
     pub blocked: u32,
     pub completion_rate: f32,
     pub avg_completion_time_days: Option<f32>,
+    /// Files referenced by the most unfinished tasks, busiest first
     pub most_active_files: Vec<String>,
+    pub avg_time_in_status_seconds: HashMap<TaskStatus, f64>,
+    /// Sum of the section's task estimates
+    pub estimate_minutes: u32,
+    /// Time logged against the section's tasks
+    pub time_spent_minutes: u32,
+}
+
+/// One side of a section comparison
+#[derive(Debug, Serialize, Clone)]
+pub struct ComparedSection {
+    #[serde(flatten)]
+    pub stats: SectionStats,
+    /// Mean days since the section's tasks were created
+    pub avg_age_days: f32,
+    /// Distinct files referenced by the section's tasks
+    pub referenced_files: u32,
+}
+
+/// Side-by-side comparison of two sections
+#[derive(Debug, Serialize, Clone)]
+pub struct SectionComparison {
+    pub a: ComparedSection,
+    pub b: ComparedSection,
+    /// Files referenced by tasks of both sections, sorted
+    pub shared_files: Vec<String>,
+    /// Completion rate of `a` minus that of `b`, in percentage points
+    pub completion_rate_delta: f32,
+}
+
+/// How far a completion forecast can be trusted
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastConfidence {
+    High,
+    Medium,
+    Low,
+    /// Work remains but nothing was completed over the trend window
+    NoEstimate,
+}
+
+/// Projected completion of the remaining tasks at the current velocity
+#[derive(Debug, Serialize, Clone)]
+pub struct CompletionEstimate {
+    /// Tasks not yet done
+    pub remaining_tasks: u32,
+    /// Completions per week over the trend window
+    pub velocity_per_week: f32,
+    /// Absent when there is no estimate
+    pub estimated_completion: Option<DateTime<Utc>>,
+    pub confidence: ForecastConfidence,
+}
+
+/// Completion estimate for one section
+#[derive(Debug, Serialize, Clone)]
+pub struct SectionForecast {
+    pub section: String,
+    #[serde(flatten)]
+    pub estimate: CompletionEstimate,
+}
+
+/// Projected finish dates per section and for the whole project
+#[derive(Debug, Serialize, Clone)]
+pub struct CompletionForecast {
+    pub overall: CompletionEstimate,
+    pub sections: Vec<SectionForecast>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Cumulative time a task spent in each status
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct TimeInStatus {
+    pub durations_seconds: HashMap<TaskStatus, i64>,
+    /// Set when the task existed before its history was recorded,
+    /// so only the observed intervals are counted
+    pub partial: bool,
 }
 
 /// Trends and historical data
 #[derive(Debug, Serialize, Clone)]
 pub struct StatsTrends {
+    /// Tasks moved to done over the last 7 days
     pub completion_trend_7d: f32,
+    /// Tasks created over the last 7 days
     pub creation_trend_7d: f32,
+    /// Share of the last 7 days' creations and completions that were completions, 0-100.
+    /// Falls back to the overall completion rate when nothing happened that week.
     pub productivity_score: f32,
+    /// Sections with the most recorded updates over the trend window, busiest first
     pub busiest_sections: Vec<String>,
+    /// Time logged against any task over the last 7 days
+    pub time_logged_minutes_7d: u32,
+    /// Completions per week for each section over the trend window
+    pub section_velocity: HashMap<String, f32>,
 }
 
 /// Task update record for tracking changes
@@ -81,6 +270,9 @@ pub enum ChangeType {
     Deleted,
     #[serde(rename = "modified")]
     Modified,
+    /// A note was converted into the task
+    #[serde(rename = "note_converted")]
+    NoteConverted,
 }
 
 /// Summary of a section for overview
@@ -114,6 +306,8 @@ pub enum ActivityType {
     StatusChanged,
     #[serde(rename = "section_updated")]
     SectionUpdated,
+    #[serde(rename = "note_converted")]
+    NoteConverted,
 }
 
 /// Cached statistic entry
@@ -177,35 +371,33 @@ impl StatisticsManager {
         }
     }
 
-    /// Get task statistics with caching
-    pub fn get_statistics(&self, project_data: &ProjectData) -> Result<TaskStatistics> {
+    /// Get task statistics with caching, listing sections in the given order
+    pub fn get_statistics(
+        &self,
+        project_data: &ProjectData,
+        order: SectionOrder,
+    ) -> Result<TaskStatistics> {
         let start_time = std::time::Instant::now();
-        let cache_key = self.generate_cache_key(project_data);
+        let cache_key = self.generate_cache_key(project_data, order);
 
+        // A single write lock: taking it while holding a read lock would deadlock
+        if let Ok(mut cache) = self.cached_stats.write()
+            && let Some(entry) = cache.get_mut(&cache_key)
         {
-            if let Ok(cache) = self.cached_stats.read() {
-                if let Some(cached) = cache.get(&cache_key) {
-                    let age = Utc::now().signed_duration_since(cached.created_at);
-                    if age.num_seconds() < self.config.cache_ttl_seconds as i64 {
-                        {
-                            if let Ok(mut cache_write) = self.cached_stats.write() {
-                                if let Some(entry) = cache_write.get_mut(&cache_key) {
-                                    entry.access_count += 1;
-                                    entry.last_accessed = Utc::now();
-                                }
-                            }
-                        }
-
-                        self.update_cache_hit_rate(true);
-                        eprintln!("[DEBUG] Statistics cache hit for key: {}", cache_key);
-                        return Ok(cached.data.clone());
-                    }
-                }
+            let age = Utc::now().signed_duration_since(entry.created_at);
+            if age.num_seconds() < self.config.cache_ttl_seconds as i64 {
+                entry.access_count += 1;
+                entry.last_accessed = Utc::now();
+                let data = entry.data.clone();
+                drop(cache);
+                self.update_cache_hit_rate(true);
+                eprintln!("[DEBUG] Statistics cache hit for key: {}", cache_key);
+                return Ok(data);
             }
         }
 
         self.update_cache_hit_rate(false);
-        let stats = self.calculate_statistics(project_data)?;
+        let stats = self.calculate_statistics(project_data, order)?;
 
         if let Ok(mut cache) = self.cached_stats.write() {
             cache.insert(
@@ -229,16 +421,142 @@ impl StatisticsManager {
         Ok(stats)
     }
 
+    /// Calculate statistics without consulting or populating the cache
+    pub fn get_fresh_statistics(
+        &self,
+        project_data: &ProjectData,
+        order: SectionOrder,
+    ) -> Result<TaskStatistics> {
+        self.update_cache_hit_rate(false);
+        self.calculate_statistics(project_data, order)
+    }
+
+    /// Get statistics restricted by the given filter. Filtered results are never cached.
+    pub fn get_filtered_statistics(
+        &self,
+        project_data: &ProjectData,
+        filter: &StatisticsFilter,
+        order: SectionOrder,
+    ) -> Result<TaskStatistics> {
+        if filter.is_empty() {
+            return self.get_statistics(project_data, order);
+        }
+        let mut filtered = if filter.roll_up_subtasks {
+            Self::roll_up_subtasks(project_data)
+        } else {
+            project_data.clone()
+        };
+        for (section_name, section) in filtered.sections.iter_mut() {
+            section.retain(|_, task| filter.matches(section_name, task));
+        }
+        filtered.sections.retain(|_, section| !section.is_empty());
+        self.get_fresh_statistics(&filtered, order)
+    }
+
+    /// Render statistics as a flat CSV table with one row per section and a summary row
+    pub fn to_csv(stats: &TaskStatistics) -> String {
+        let project_name = Self::csv_field(stats.project_name.as_deref().unwrap_or(""));
+        let generated_at = stats.generated_at.to_rfc3339();
+        let mut csv = String::from(
+            "project,generated_at,section,total,todo,in_progress,done,blocked,completion_rate,estimate_minutes,time_spent_minutes\n",
+        );
+
+        let section_names = stats.overview.sections.iter().map(|summary| &summary.name);
+        let (mut todo, mut in_progress, mut done, mut blocked) = (0u32, 0u32, 0u32, 0u32);
+        let (mut estimate_minutes, mut time_spent_minutes) = (0u32, 0u32);
+        for name in section_names {
+            let section = &stats.sections[name];
+            todo += section.todo;
+            in_progress += section.in_progress;
+            done += section.done;
+            blocked += section.blocked;
+            estimate_minutes = estimate_minutes.saturating_add(section.estimate_minutes);
+            time_spent_minutes = time_spent_minutes.saturating_add(section.time_spent_minutes);
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{:.2},{},{}\n",
+                project_name,
+                generated_at,
+                Self::csv_field(name),
+                section.total,
+                section.todo,
+                section.in_progress,
+                section.done,
+                section.blocked,
+                section.completion_rate,
+                section.estimate_minutes,
+                section.time_spent_minutes
+            ));
+        }
+
+        csv.push_str(&format!(
+            "{},{},TOTAL,{},{},{},{},{},{:.2},{},{}\n",
+            project_name,
+            generated_at,
+            stats.overview.total_tasks,
+            todo,
+            in_progress,
+            done,
+            blocked,
+            stats.overview.completion_rate,
+            estimate_minutes,
+            time_spent_minutes
+        ));
+        csv
+    }
+
+    /// CSV rows per milestone instead of per section
+    pub fn milestones_to_csv(stats: &TaskStatistics) -> String {
+        let project_name = Self::csv_field(stats.project_name.as_deref().unwrap_or(""));
+        let generated_at = stats.generated_at.to_rfc3339();
+        let mut csv = String::from(
+            "project,generated_at,milestone,title,due,total,todo,in_progress,done,blocked,completion_rate\n",
+        );
+        for milestone in &stats.overview.milestones {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{:.2}\n",
+                project_name,
+                generated_at,
+                Self::csv_field(&milestone.id),
+                Self::csv_field(&milestone.title),
+                milestone
+                    .due
+                    .map(|due| due.to_rfc3339())
+                    .unwrap_or_default(),
+                milestone.total,
+                milestone.todo,
+                milestone.in_progress,
+                milestone.done,
+                milestone.blocked,
+                milestone.completion_percentage
+            ));
+        }
+        csv
+    }
+
+    /// Quote a CSV field when it contains separators or quotes
+    pub(crate) fn csv_field(value: &str) -> String {
+        if value.contains(',') || value.contains('"') || value.contains('\n') {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
     /// Get task overview (simplified statistics)
-    pub fn get_overview(&self, project_data: &ProjectData) -> Result<TaskOverview> {
+    pub fn get_overview(
+        &self,
+        project_data: &ProjectData,
+        order: SectionOrder,
+    ) -> Result<TaskOverview> {
         let mut total_tasks = 0u32;
         let mut completed_tasks = 0u32;
         let mut in_progress_tasks = 0u32;
         let mut blocked_tasks = 0u32;
         let mut sections = Vec::new();
 
-        for (section_name, section) in &project_data.sections {
-            let section_stats = self.calculate_section_stats(section)?;
+        for section_name in project_data.ordered_section_names(order) {
+            let section_stats =
+                self.calculate_section_stats(&project_data.sections[section_name])?;
 
             total_tasks += section_stats.total;
             completed_tasks += section_stats.done;
@@ -268,9 +586,189 @@ impl StatisticsManager {
             blocked_tasks,
             completion_rate,
             sections,
+            top_assignees: Self::top_entries(Self::calculate_assignee_breakdown(project_data)),
+            top_tags: Self::top_entries(Self::calculate_tag_breakdown(project_data)),
+            priorities: Self::calculate_priority_breakdown(project_data),
+            tasks_with_recent_comments: Self::count_recently_commented(project_data),
+            milestones: Self::milestone_progress(project_data),
         })
     }
 
+    /// Progress of every milestone from its member tasks' statuses
+    pub fn milestone_progress(project_data: &ProjectData) -> Vec<MilestoneProgress> {
+        let now = Utc::now();
+        let mut milestones: Vec<MilestoneProgress> = project_data
+            .milestones
+            .values()
+            .map(|milestone| {
+                let (mut todo, mut in_progress, mut done, mut blocked) = (0u32, 0u32, 0u32, 0u32);
+                for key in milestone.task_keys() {
+                    match project_data
+                        .get_task(&key.section, &key.task_id)
+                        .map(|task| &task.status)
+                    {
+                        Some(TaskStatus::Todo) => todo += 1,
+                        Some(TaskStatus::InProgress) => in_progress += 1,
+                        Some(TaskStatus::Done) => done += 1,
+                        Some(TaskStatus::Blocked) => blocked += 1,
+                        None => {}
+                    }
+                }
+                let total = todo + in_progress + done + blocked;
+                MilestoneProgress {
+                    id: milestone.id.clone(),
+                    title: milestone.title.clone(),
+                    due: milestone.due,
+                    total,
+                    todo,
+                    in_progress,
+                    done,
+                    blocked,
+                    completion_percentage: if total > 0 {
+                        done as f32 / total as f32 * 100.0
+                    } else {
+                        0.0
+                    },
+                    overdue: milestone.due.is_some_and(|due| due < now) && done < total,
+                }
+            })
+            .collect();
+        milestones
+            .sort_by(|a, b| (a.due.is_none(), a.due, &a.id).cmp(&(b.due.is_none(), b.due, &b.id)));
+        milestones
+    }
+
+    fn count_recently_commented(project_data: &ProjectData) -> u32 {
+        let since = Utc::now() - chrono::Duration::days(RECENT_COMMENT_DAYS);
+        project_data
+            .sections
+            .values()
+            .flat_map(|section| section.values())
+            .filter(|task| task.comments.iter().any(|comment| comment.created >= since))
+            .count() as u32
+    }
+
+    /// Tasks and completion rate per assignee, unassigned tasks under `(none)`
+    fn calculate_assignee_breakdown(project_data: &ProjectData) -> HashMap<String, BreakdownStats> {
+        let mut breakdown = HashMap::new();
+        for task in project_data.sections.values().flat_map(|s| s.values()) {
+            let assignee = task.assignee.as_deref().unwrap_or(NONE_BUCKET);
+            Self::count_in_bucket(&mut breakdown, assignee, task);
+        }
+        breakdown
+    }
+
+    /// Tasks and completion rate per tag, untagged tasks under `(none)`
+    fn calculate_tag_breakdown(project_data: &ProjectData) -> HashMap<String, BreakdownStats> {
+        let mut breakdown = HashMap::new();
+        for task in project_data.sections.values().flat_map(|s| s.values()) {
+            if task.tags.is_empty() {
+                Self::count_in_bucket(&mut breakdown, NONE_BUCKET, task);
+                continue;
+            }
+            let mut seen = std::collections::HashSet::new();
+            for tag in &task.tags {
+                if seen.insert(tag) {
+                    Self::count_in_bucket(&mut breakdown, tag, task);
+                }
+            }
+        }
+        breakdown
+    }
+
+    /// Copy of the project with subtasks moved into their top-level task's section and
+    /// tasks that have subtasks dropped, so every tree is counted once through its leaves
+    fn roll_up_subtasks(project_data: &ProjectData) -> ProjectData {
+        let parents: std::collections::HashSet<&TaskKey> = project_data
+            .sections
+            .values()
+            .flat_map(|section| section.values())
+            .filter_map(|task| task.parent.as_ref())
+            .collect();
+        let root_section = |section: &str, task: &Task| -> String {
+            let mut root = section.to_string();
+            let mut parent = task.parent.clone();
+            let mut seen = std::collections::HashSet::new();
+            while let Some(key) = parent {
+                if !seen.insert(key.clone()) {
+                    break;
+                }
+                let Some(parent_task) = project_data.get_task(&key.section, &key.task_id) else {
+                    break;
+                };
+                root = key.section;
+                parent = parent_task.parent.clone();
+            }
+            root
+        };
+        let mut rolled = project_data.clone();
+        rolled.sections = HashMap::new();
+        for (section_name, section) in &project_data.sections {
+            for (task_id, task) in section {
+                if parents.contains(&TaskKey::new(section_name, task_id)) {
+                    continue;
+                }
+                let root = root_section(section_name, task);
+                // Qualify moved subtasks so they cannot collide with the root section's own IDs
+                let id = if root == *section_name {
+                    task_id.clone()
+                } else {
+                    format!("{}.{}", section_name, task_id)
+                };
+                rolled
+                    .sections
+                    .entry(root)
+                    .or_default()
+                    .insert(id, task.clone());
+            }
+        }
+        rolled
+    }
+
+    /// Tasks and completion rate per priority, ordered from critical down to unprioritized
+    fn calculate_priority_breakdown(project_data: &ProjectData) -> Vec<BreakdownStats> {
+        let mut breakdown = HashMap::new();
+        for task in project_data.sections.values().flat_map(|s| s.values()) {
+            let priority = task.priority.map_or(NONE_BUCKET, TaskPriority::as_str);
+            Self::count_in_bucket(&mut breakdown, priority, task);
+        }
+        let order = [
+            TaskPriority::Critical.as_str(),
+            TaskPriority::High.as_str(),
+            TaskPriority::Medium.as_str(),
+            TaskPriority::Low.as_str(),
+            NONE_BUCKET,
+        ];
+        order
+            .iter()
+            .filter_map(|name| breakdown.remove(*name))
+            .collect()
+    }
+
+    fn count_in_bucket(breakdown: &mut HashMap<String, BreakdownStats>, name: &str, task: &Task) {
+        let entry = breakdown
+            .entry(name.to_string())
+            .or_insert_with(|| BreakdownStats {
+                name: name.to_string(),
+                total: 0,
+                done: 0,
+                completion_rate: 0.0,
+            });
+        entry.total += 1;
+        if task.status == TaskStatus::Done {
+            entry.done += 1;
+        }
+        entry.completion_rate = (entry.done as f32 / entry.total as f32) * 100.0;
+    }
+
+    /// Five largest buckets, ties broken by name
+    fn top_entries(breakdown: HashMap<String, BreakdownStats>) -> Vec<BreakdownStats> {
+        let mut entries: Vec<BreakdownStats> = breakdown.into_values().collect();
+        entries.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        entries.truncate(5);
+        entries
+    }
+
     /// Record a task update for trend analysis
     pub fn record_task_update(&self, update: TaskUpdate) -> Result<()> {
         if let Ok(mut history) = self.update_history.write() {
@@ -284,17 +782,26 @@ impl StatisticsManager {
     }
 
     /// Calculate statistics for the given project data
-    fn calculate_statistics(&self, project_data: &ProjectData) -> Result<TaskStatistics> {
-        let overview = self.get_overview(project_data)?;
+    fn calculate_statistics(
+        &self,
+        project_data: &ProjectData,
+        order: SectionOrder,
+    ) -> Result<TaskStatistics> {
+        let started = std::time::Instant::now();
+        let overview = self.get_overview(project_data, order)?;
         let mut sections = HashMap::new();
 
         for (section_name, section) in &project_data.sections {
-            let section_stats = self.calculate_section_stats(section)?;
+            let mut section_stats = self.calculate_section_stats(section)?;
+            section_stats.name = section_name.clone();
+            section_stats.avg_time_in_status_seconds =
+                self.calculate_avg_time_in_status(section_name, section)?;
             sections.insert(section_name.clone(), section_stats);
         }
 
         let recent_activity = self.get_recent_activity()?;
-        let trends = self.calculate_trends()?;
+        let trends = self.calculate_trends(project_data, &overview)?;
+        self.record_calculation_time(started.elapsed());
 
         Ok(TaskStatistics {
             overview,
@@ -302,6 +809,53 @@ impl StatisticsManager {
             recent_activity,
             trends,
             generated_at: Utc::now(),
+            project_name: project_data.meta.project_name.clone(),
+            by_assignee: Self::calculate_assignee_breakdown(project_data),
+            by_tag: Self::calculate_tag_breakdown(project_data),
+        })
+    }
+
+    /// Compare two sections by status counts, completion, task age and shared files
+    pub fn compare_sections(
+        &self,
+        project_data: &ProjectData,
+        a: &str,
+        b: &str,
+    ) -> Result<SectionComparison> {
+        let compared = |name: &str| -> Result<(ComparedSection, BTreeSet<String>)> {
+            let section = project_data
+                .sections
+                .get(name)
+                .ok_or_else(|| anyhow::anyhow!("Section not found: {}", name))?;
+            let mut stats = self.calculate_section_stats(section)?;
+            stats.name = name.to_string();
+            let now = Utc::now();
+            let total_age_days: f32 = section
+                .values()
+                .map(|task| (now - task.created).num_seconds() as f32 / 86_400.0)
+                .sum();
+            let files: BTreeSet<String> = section
+                .values()
+                .flat_map(|task| task.files.keys().cloned())
+                .collect();
+            let side = ComparedSection {
+                avg_age_days: if section.is_empty() {
+                    0.0
+                } else {
+                    total_age_days / section.len() as f32
+                },
+                referenced_files: files.len() as u32,
+                stats,
+            };
+            Ok((side, files))
+        };
+        let (a, files_a) = compared(a)?;
+        let (b, files_b) = compared(b)?;
+        Ok(SectionComparison {
+            shared_files: files_a.intersection(&files_b).cloned().collect(),
+            completion_rate_delta: a.stats.completion_rate - b.stats.completion_rate,
+            a,
+            b,
         })
     }
 
@@ -312,9 +866,19 @@ impl StatisticsManager {
         let mut in_progress = 0u32;
         let mut done = 0u32;
         let mut blocked = 0u32;
+        let mut estimate_minutes = 0u32;
+        let mut time_spent_minutes = 0u32;
+        let mut open_tasks_by_file: HashMap<&str, u32> = HashMap::new();
 
         for task in section.values() {
             total += 1;
+            if task.status != TaskStatus::Done {
+                for file in task.files.keys() {
+                    *open_tasks_by_file.entry(file.as_str()).or_default() += 1;
+                }
+            }
+            estimate_minutes = estimate_minutes.saturating_add(task.estimate_minutes.unwrap_or(0));
+            time_spent_minutes = time_spent_minutes.saturating_add(task.time_spent_minutes);
             match task.status {
                 TaskStatus::Todo => todo += 1,
                 TaskStatus::InProgress => in_progress += 1,
@@ -328,6 +892,13 @@ impl StatisticsManager {
         } else {
             0.0
         };
+        let mut active_files: Vec<(&str, u32)> = open_tasks_by_file.into_iter().collect();
+        active_files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let most_active_files = active_files
+            .into_iter()
+            .take(MOST_ACTIVE_FILES_LIMIT)
+            .map(|(file, _)| file.to_string())
+            .collect();
 
         Ok(SectionStats {
             name: "".to_string(), // Will be set by caller
@@ -338,22 +909,108 @@ impl StatisticsManager {
             blocked,
             completion_rate,
             avg_completion_time_days: None,
-            most_active_files: vec![],
+            most_active_files,
+            avg_time_in_status_seconds: HashMap::new(),
+            estimate_minutes,
+            time_spent_minutes,
         })
     }
 
-    /// Get recent activity from update history
+    /// Time spent in each status by a task, derived from the update history
+    pub fn get_time_in_status(
+        &self,
+        section: &str,
+        task_id: &str,
+        task: &Task,
+    ) -> Result<TimeInStatus> {
+        let history = self
+            .update_history
+            .read()
+            .map_err(|_| anyhow::anyhow!("Failed to read update history"))?;
+        let updates: Vec<&TaskUpdate> = history
+            .iter()
+            .filter(|u| u.section == section && u.task_id == task_id)
+            .collect();
+        Ok(Self::compute_time_in_status(&updates, task, Utc::now()))
+    }
+
+    /// Walk a task's status changes in order, attributing each interval to the status it was in
+    pub fn compute_time_in_status(
+        updates: &[&TaskUpdate],
+        task: &Task,
+        now: DateTime<Utc>,
+    ) -> TimeInStatus {
+        let mut updates: Vec<&&TaskUpdate> = updates
+            .iter()
+            .filter(|u| {
+                matches!(
+                    u.change_type,
+                    ChangeType::Created | ChangeType::StatusUpdated
+                )
+            })
+            .collect();
+        updates.sort_by_key(|u| u.timestamp);
+
+        let mut result = TimeInStatus {
+            durations_seconds: HashMap::new(),
+            partial: !matches!(updates.first(), Some(u) if matches!(u.change_type, ChangeType::Created)),
+        };
+        let Some(first) = updates.first() else {
+            return result;
+        };
+
+        let mut current_status = first.new_status.clone();
+        let mut since = first.timestamp;
+        for update in updates.iter().skip(1) {
+            *result
+                .durations_seconds
+                .entry(current_status.clone())
+                .or_insert(0) += update.timestamp.signed_duration_since(since).num_seconds();
+            current_status = update.new_status.clone();
+            since = update.timestamp;
+        }
+        if current_status != task.status {
+            result.partial = true;
+        }
+        *result.durations_seconds.entry(current_status).or_insert(0) +=
+            now.signed_duration_since(since).num_seconds().max(0);
+        result
+    }
+
+    /// Average time per status across the tasks of a section that have observed time in it
+    fn calculate_avg_time_in_status(
+        &self,
+        section_name: &str,
+        section: &HashMap<String, Task>,
+    ) -> Result<HashMap<TaskStatus, f64>> {
+        let mut totals: HashMap<TaskStatus, (i64, u32)> = HashMap::new();
+        for (task_id, task) in section {
+            let time_in_status = self.get_time_in_status(section_name, task_id, task)?;
+            for (status, seconds) in time_in_status.durations_seconds {
+                let entry = totals.entry(status).or_insert((0, 0));
+                entry.0 += seconds;
+                entry.1 += 1;
+            }
+        }
+        Ok(totals
+            .into_iter()
+            .map(|(status, (seconds, count))| (status, seconds as f64 / count as f64))
+            .collect())
+    }
+
+    /// Get the 10 most recent entries of the update history, newest first
     pub fn get_recent_activity(&self) -> Result<Vec<TaskActivity>> {
         let mut activities = Vec::new();
 
         if let Ok(history) = self.update_history.read() {
-            for update in history.iter().take(10) {
+            for update in history.iter().rev().take(10) {
                 let activity_type = match update.change_type {
                     ChangeType::Created => ActivityType::TaskCreated,
                     ChangeType::StatusUpdated if update.new_status == TaskStatus::Done => {
                         ActivityType::TaskCompleted
                     }
                     ChangeType::StatusUpdated => ActivityType::StatusChanged,
+                    ChangeType::NoteConverted => ActivityType::NoteConverted,
                     _ => ActivityType::SectionUpdated,
                 };
 
@@ -367,6 +1024,7 @@ impl StatisticsManager {
                             ChangeType::StatusUpdated => "status updated",
                             ChangeType::Deleted => "deleted",
                             ChangeType::Modified => "modified",
+                            ChangeType::NoteConverted => "converted from a note",
                         }
                     ),
                     timestamp: update.timestamp,
@@ -380,24 +1038,283 @@ impl StatisticsManager {
         Ok(activities)
     }
 
-    /// Calculate trends from historical data
-    fn calculate_trends(&self) -> Result<StatsTrends> {
-        // Simple implementation for now
+    /// Calculate trends from task creation dates and the stored task history
+    fn calculate_trends(
+        &self,
+        project_data: &ProjectData,
+        overview: &TaskOverview,
+    ) -> Result<StatsTrends> {
+        let now = Utc::now();
+        let week_ago = now - chrono::Duration::days(7);
+        let window_start =
+            now - chrono::Duration::days(self.config.trend_analysis_days.max(1) as i64);
+        let created = project_data
+            .sections
+            .values()
+            .flat_map(|section| section.values())
+            .filter(|task| task.created >= week_ago)
+            .count() as f32;
+        let time_logged_minutes_7d = project_data
+            .sections
+            .values()
+            .flat_map(|section| section.values())
+            .flat_map(|task| &task.time_entries)
+            .filter(|entry| entry.logged_at >= week_ago)
+            .fold(0u32, |total, entry| total.saturating_add(entry.minutes));
+
+        let mut completed = 0.0;
+        let mut updates_by_section: HashMap<&str, u32> = HashMap::new();
+        for (section, change) in project_data.section_history() {
+            if change.timestamp < window_start {
+                continue;
+            }
+            *updates_by_section.entry(section).or_default() += 1;
+            if change.is_completion() && change.timestamp >= week_ago {
+                completed += 1.0;
+            }
+        }
+        let mut busiest: Vec<(&str, u32)> = updates_by_section.into_iter().collect();
+        busiest.sort_by_key(|&(section, updates)| (std::cmp::Reverse(updates), section));
+        let busiest_sections = busiest
+            .into_iter()
+            .take(5)
+            .map(|(section, _)| section.to_string())
+            .collect();
+
+        let productivity_score = if created + completed > 0.0 {
+            completed / (created + completed) * 100.0
+        } else {
+            overview.completion_rate
+        };
         Ok(StatsTrends {
-            completion_trend_7d: 0.0,
-            creation_trend_7d: 0.0,
-            productivity_score: 75.0,
-            busiest_sections: vec![],
+            completion_trend_7d: completed,
+            creation_trend_7d: created,
+            productivity_score,
+            busiest_sections,
+            time_logged_minutes_7d,
+            section_velocity: self.section_velocity(project_data),
         })
     }
 
-    /// Generate cache key for project data
-    fn generate_cache_key(&self, project_data: &ProjectData) -> String {
-        format!(
-            "stats_{}_{}",
-            project_data.sections.len(),
-            project_data.meta.last_updated.timestamp()
-        )
+    /// Fold one calculation into the running count and average
+    fn record_calculation_time(&self, elapsed: std::time::Duration) {
+        if let Ok(mut stats) = self.performance_stats.write() {
+            stats.total_calculations += 1;
+            let elapsed_ms = elapsed.as_secs_f64() * 1000.0;
+            stats.avg_calculation_time_ms +=
+                (elapsed_ms - stats.avg_calculation_time_ms) / stats.total_calculations as f64;
+        }
+    }
+
+    /// Tasks moved to done per week in each section over the last
+    /// `trend_analysis_days`, zero for sections without completions
+    pub fn section_velocity(&self, project_data: &ProjectData) -> HashMap<String, f32> {
+        let mut velocity: HashMap<String, f32> = project_data
+            .sections
+            .keys()
+            .map(|section| (section.clone(), 0.0))
+            .collect();
+        let window_days = self.config.trend_analysis_days.max(1);
+        let since = Utc::now() - chrono::Duration::days(window_days as i64);
+        let weeks = window_days as f32 / 7.0;
+        for (section, change) in project_data.section_history() {
+            if change.is_completion() && change.timestamp >= since {
+                *velocity.entry(section.clone()).or_insert(0.0) += 1.0 / weeks;
+            }
+        }
+        velocity
+    }
+
+    /// Completions in each section per week of the trend window, most recent week first
+    fn weekly_completions(&self, project_data: &ProjectData) -> HashMap<String, Vec<u32>> {
+        let window_days = self.config.trend_analysis_days.max(1) as i64;
+        let weeks = ((window_days + 6) / 7) as usize;
+        let now = Utc::now();
+        let since = now - chrono::Duration::days(window_days);
+        let mut completions: HashMap<String, Vec<u32>> = project_data
+            .sections
+            .keys()
+            .map(|section| (section.clone(), vec![0; weeks]))
+            .collect();
+        for (section, change) in project_data.section_history() {
+            if !change.is_completion() || change.timestamp < since {
+                continue;
+            }
+            if let Some(buckets) = completions.get_mut(section) {
+                let week = ((now - change.timestamp).num_days() / 7) as usize;
+                buckets[week.min(weeks - 1)] += 1;
+            }
+        }
+        completions
+    }
+
+    /// Project when each section, and the project as a whole, runs out of
+    /// open tasks at its current velocity. Confidence drops as the weekly
+    /// completion counts vary more around their mean.
+    pub fn completion_forecast(
+        &self,
+        project_data: &ProjectData,
+        order: SectionOrder,
+    ) -> CompletionForecast {
+        let now = Utc::now();
+        let velocity = self.section_velocity(project_data);
+        let weekly = self.weekly_completions(project_data);
+        let remaining = |section: &str| {
+            project_data.sections[section]
+                .values()
+                .filter(|task| task.status != TaskStatus::Done)
+                .count() as u32
+        };
+
+        let sections: Vec<SectionForecast> = project_data
+            .ordered_section_names(order)
+            .into_iter()
+            .map(|section| SectionForecast {
+                section: section.clone(),
+                estimate: Self::estimate(
+                    now,
+                    remaining(section),
+                    velocity.get(section).copied().unwrap_or(0.0),
+                    weekly.get(section).map(Vec::as_slice).unwrap_or_default(),
+                ),
+            })
+            .collect();
+
+        let mut overall_weekly: Vec<u32> = Vec::new();
+        for buckets in weekly.values() {
+            overall_weekly.resize(buckets.len(), 0);
+            for (total, count) in overall_weekly.iter_mut().zip(buckets) {
+                *total += count;
+            }
+        }
+        let overall = Self::estimate(
+            now,
+            sections.iter().map(|s| s.estimate.remaining_tasks).sum(),
+            velocity.values().sum(),
+            &overall_weekly,
+        );
+        CompletionForecast {
+            overall,
+            sections,
+            generated_at: now,
+        }
+    }
+
+    fn estimate(
+        now: DateTime<Utc>,
+        remaining_tasks: u32,
+        velocity_per_week: f32,
+        weekly: &[u32],
+    ) -> CompletionEstimate {
+        let (estimated_completion, confidence) = if remaining_tasks == 0 {
+            (Some(now), ForecastConfidence::High)
+        } else if velocity_per_week <= 0.0 {
+            (None, ForecastConfidence::NoEstimate)
+        } else {
+            let weeks = remaining_tasks as f64 / velocity_per_week as f64;
+            let seconds = (weeks * 7.0 * 24.0 * 3600.0).round() as i64;
+            (
+                Some(now + chrono::Duration::seconds(seconds)),
+                Self::forecast_confidence(weekly),
+            )
+        };
+        CompletionEstimate {
+            remaining_tasks,
+            velocity_per_week,
+            estimated_completion,
+            confidence,
+        }
+    }
+
+    /// Grade by the coefficient of variation of the weekly completions
+    fn forecast_confidence(weekly: &[u32]) -> ForecastConfidence {
+        if weekly.is_empty() {
+            return ForecastConfidence::Low;
+        }
+        let count = weekly.len() as f32;
+        let mean = weekly.iter().sum::<u32>() as f32 / count;
+        let variance = weekly
+            .iter()
+            .map(|&week| (week as f32 - mean).powi(2))
+            .sum::<f32>()
+            / count;
+        match variance.sqrt() / mean {
+            cv if cv <= 0.5 => ForecastConfidence::High,
+            cv if cv <= 1.0 => ForecastConfidence::Medium,
+            _ => ForecastConfidence::Low,
+        }
+    }
+
+    /// Cache key hashed from everything the statistics are computed from,
+    /// so it changes exactly when the statistics would
+    fn generate_cache_key(&self, project_data: &ProjectData, order: SectionOrder) -> String {
+        let mut hasher = DefaultHasher::new();
+        order.hash(&mut hasher);
+        project_data.meta.project_name.hash(&mut hasher);
+        let mut section_names: Vec<&String> = project_data.sections.keys().collect();
+        section_names.sort();
+        for section_name in section_names {
+            let section = &project_data.sections[section_name];
+            section_name.hash(&mut hasher);
+            section.len().hash(&mut hasher);
+            let mut task_ids: Vec<&String> = section.keys().collect();
+            task_ids.sort();
+            for task_id in task_ids {
+                let task = &section[task_id];
+                task_id.hash(&mut hasher);
+                task.status.hash(&mut hasher);
+                task.priority.hash(&mut hasher);
+                task.assignee.hash(&mut hasher);
+                task.tags.hash(&mut hasher);
+                task.created.hash(&mut hasher);
+                task.estimate_minutes.hash(&mut hasher);
+                task.time_spent_minutes.hash(&mut hasher);
+                for entry in &task.time_entries {
+                    (entry.minutes, entry.logged_at).hash(&mut hasher);
+                }
+                for comment in &task.comments {
+                    comment.created.hash(&mut hasher);
+                }
+                let mut files: Vec<(&String, &TaskFile)> = task.files.iter().collect();
+                files.sort_by_key(|(path, _)| *path);
+                for (path, file) in files {
+                    (path, &file.lines).hash(&mut hasher);
+                }
+            }
+        }
+        let mut milestone_ids: Vec<&String> = project_data.milestones.keys().collect();
+        milestone_ids.sort();
+        for milestone_id in milestone_ids {
+            let milestone = &project_data.milestones[milestone_id];
+            (
+                &milestone.id,
+                &milestone.title,
+                milestone.due,
+                &milestone.task_refs,
+            )
+                .hash(&mut hasher);
+        }
+        // Trends and forecasts come from the stored task history
+        let mut history_keys: Vec<&String> = project_data.history.keys().collect();
+        history_keys.sort();
+        for key in history_keys {
+            let entries = &project_data.history[key];
+            (
+                key,
+                entries.len(),
+                entries.last().map(|change| change.timestamp),
+            )
+                .hash(&mut hasher);
+        }
+        // Recent activity comes from the update history
+        if let Ok(history) = self.update_history.read() {
+            history.len().hash(&mut hasher);
+            history
+                .last()
+                .map(|update| update.timestamp)
+                .hash(&mut hasher);
+        }
+        format!("stats_{:016x}", hasher.finish())
     }
 
     /// Update cache hit rate statistics
@@ -462,7 +1379,7 @@ impl StatisticsManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::task_manager::Task;
+    use crate::task_manager::{ChangeSource, Task, TaskChange};
     use std::collections::HashMap;
 
     #[test]
@@ -506,4 +1423,465 @@ mod tests {
 
         assert!(manager.record_task_update(update).is_ok());
     }
+
+    #[test]
+    fn test_compare_sections() {
+        let manager = StatisticsManager::new(None);
+        let mut project = ProjectData::new(None);
+        for (section, task_id, status, file) in [
+            ("api", "login", TaskStatus::Done, "src/auth.rs"),
+            ("api", "logout", TaskStatus::Todo, "src/session.rs"),
+            ("api", "refresh", TaskStatus::Blocked, "src/auth.rs"),
+            ("ui", "login_form", TaskStatus::Done, "src/auth.rs"),
+            ("ui", "menu", TaskStatus::Done, "src/menu.rs"),
+            (
+                "ui",
+                "logout_button",
+                TaskStatus::InProgress,
+                "src/session.rs",
+            ),
+            ("ui", "theme", TaskStatus::Todo, "src/theme.rs"),
+        ] {
+            project
+                .add_task(section, task_id, task_id.to_string(), None)
+                .unwrap();
+            let task = project.get_task_mut(section, task_id).unwrap();
+            task.status = status;
+            task.add_file(file.to_string(), 1, None);
+        }
+
+        let comparison = manager.compare_sections(&project, "api", "ui").unwrap();
+        assert_eq!(comparison.a.stats.name, "api");
+        assert_eq!((comparison.a.stats.total, comparison.a.stats.done), (3, 1));
+        assert_eq!(comparison.a.stats.blocked, 1);
+        assert_eq!(comparison.a.referenced_files, 2);
+        assert_eq!((comparison.b.stats.total, comparison.b.stats.done), (4, 2));
+        assert_eq!(comparison.b.stats.in_progress, 1);
+        assert_eq!(comparison.b.referenced_files, 4);
+        assert_eq!(
+            comparison.shared_files,
+            vec!["src/auth.rs".to_string(), "src/session.rs".to_string()]
+        );
+        assert!((comparison.completion_rate_delta - (100.0 / 3.0 - 50.0)).abs() < 0.01);
+        assert!(comparison.a.avg_age_days < 1.0);
+
+        let error = manager
+            .compare_sections(&project, "api", "missing")
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Section not found: missing");
+    }
+
+    /// Add a done task whose history says it was completed `days_ago`
+    fn complete_task(project: &mut ProjectData, section: &str, task_id: &str, days_ago: i64) {
+        project
+            .add_task(section, task_id, task_id.to_string(), None)
+            .unwrap();
+        project
+            .update_task_status(section, task_id, TaskStatus::Done)
+            .unwrap();
+        project.record_changes(
+            section,
+            task_id,
+            vec![TaskChange {
+                field: "status".to_string(),
+                old: Some(serde_json::json!(TaskStatus::InProgress)),
+                new: Some(serde_json::json!(TaskStatus::Done)),
+                timestamp: Utc::now() - chrono::Duration::days(days_ago),
+                source: ChangeSource::Manual,
+            }],
+        );
+    }
+
+    #[test]
+    fn test_section_velocity_over_trend_window() {
+        let manager = StatisticsManager::new(Some(StatisticsConfig {
+            trend_analysis_days: 28,
+            ..Default::default()
+        }));
+        let mut project = ProjectData::new(None);
+        for section in ["api", "ui", "docs"] {
+            project
+                .add_task(section, "task", "Task".to_string(), None)
+                .unwrap();
+        }
+        for days_ago in [1, 8, 15, 22] {
+            complete_task(&mut project, "api", &format!("task_{}", days_ago), days_ago);
+        }
+        complete_task(&mut project, "ui", "task_3", 3);
+        complete_task(&mut project, "ui", "task_10", 10);
+        // Outside the four-week window
+        complete_task(&mut project, "ui", "task_40", 40);
+
+        let velocity = manager.section_velocity(&project);
+        assert_eq!(velocity["api"], 1.0);
+        assert_eq!(velocity["ui"], 0.5);
+        assert_eq!(velocity["docs"], 0.0);
+        let stats = manager
+            .get_fresh_statistics(&project, SectionOrder::Alphabetical)
+            .unwrap();
+        assert_eq!(stats.trends.section_velocity["api"], 1.0);
+    }
+
+    #[test]
+    fn test_completion_forecast() {
+        let manager = StatisticsManager::new(Some(StatisticsConfig {
+            trend_analysis_days: 28,
+            ..Default::default()
+        }));
+        let mut project = ProjectData::new(None);
+        for task_id in ["a", "b", "c", "d", "done"] {
+            project
+                .add_task("api", task_id, task_id.to_string(), None)
+                .unwrap();
+        }
+        project
+            .update_task_status("api", "done", TaskStatus::Done)
+            .unwrap();
+        project
+            .add_task("docs", "guide", "Guide".to_string(), None)
+            .unwrap();
+        // Two completions a week, every week
+        for days_ago in [1, 2, 8, 9, 15, 16, 22, 23] {
+            complete_task(&mut project, "api", &format!("task_{}", days_ago), days_ago);
+        }
+
+        let forecast = manager.completion_forecast(&project, SectionOrder::Alphabetical);
+        let api = &forecast.sections[0].estimate;
+        assert_eq!(forecast.sections[0].section, "api");
+        assert_eq!((api.remaining_tasks, api.velocity_per_week), (4, 2.0));
+        assert_eq!(api.confidence, ForecastConfidence::High);
+        let days = (api.estimated_completion.unwrap() - forecast.generated_at).num_days();
+        assert_eq!(days, 14);
+
+        let docs = &forecast.sections[1].estimate;
+        assert_eq!(docs.remaining_tasks, 1);
+        assert_eq!(docs.estimated_completion, None);
+        assert_eq!(docs.confidence, ForecastConfidence::NoEstimate);
+
+        // Five open tasks at two a week
+        assert_eq!(forecast.overall.remaining_tasks, 5);
+        let days =
+            (forecast.overall.estimated_completion.unwrap() - forecast.generated_at).num_days();
+        assert_eq!(days, 17);
+    }
+
+    #[test]
+    fn test_forecast_confidence_follows_variance() {
+        assert_eq!(
+            StatisticsManager::forecast_confidence(&[3, 3, 2, 3]),
+            ForecastConfidence::High
+        );
+        assert_eq!(
+            StatisticsManager::forecast_confidence(&[4, 0, 2, 1]),
+            ForecastConfidence::Medium
+        );
+        assert_eq!(
+            StatisticsManager::forecast_confidence(&[8, 0, 0, 0]),
+            ForecastConfidence::Low
+        );
+    }
+
+    #[test]
+    fn test_milestone_progress() {
+        let mut project = ProjectData::new(None);
+        let statuses = [
+            ("t1", TaskStatus::Done),
+            ("t2", TaskStatus::Done),
+            ("t3", TaskStatus::InProgress),
+            ("t4", TaskStatus::Blocked),
+        ];
+        for (id, status) in statuses {
+            project.add_task("dev", id, id.to_string(), None).unwrap();
+            project.update_task_status("dev", id, status).unwrap();
+        }
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+        project.create_milestone("beta", "Beta", None).unwrap();
+        project
+            .create_milestone("alpha", "Alpha", Some(yesterday))
+            .unwrap();
+        project
+            .create_milestone("done", "Done", Some(yesterday))
+            .unwrap();
+        for id in ["t1", "t2", "t3", "t4"] {
+            project.add_task_to_milestone("alpha", "dev", id).unwrap();
+        }
+        project.add_task_to_milestone("done", "dev", "t1").unwrap();
+
+        let progress = StatisticsManager::milestone_progress(&project);
+        let ids: Vec<&str> = progress.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["alpha", "done", "beta"]);
+        assert_eq!(
+            (
+                progress[0].total,
+                progress[0].done,
+                progress[0].in_progress,
+                progress[0].blocked
+            ),
+            (4, 2, 1, 1)
+        );
+        assert_eq!(progress[0].completion_percentage, 50.0);
+        assert!(progress[0].overdue);
+        assert_eq!(progress[1].completion_percentage, 100.0);
+        assert!(!progress[1].overdue);
+        assert_eq!(progress[2].total, 0);
+        assert_eq!(progress[2].completion_percentage, 0.0);
+
+        let manager = StatisticsManager::new(None);
+        let stats = manager
+            .get_statistics(&project, SectionOrder::Alphabetical)
+            .unwrap();
+        assert_eq!(stats.overview.milestones.len(), 3);
+        project
+            .update_task_status("dev", "t3", TaskStatus::Done)
+            .unwrap();
+        let stats = manager
+            .get_statistics(&project, SectionOrder::Alphabetical)
+            .unwrap();
+        assert_eq!(stats.overview.milestones[0].completion_percentage, 75.0);
+        let csv = StatisticsManager::milestones_to_csv(&stats);
+        assert!(csv.starts_with("project,generated_at,milestone,"));
+        assert_eq!(csv.lines().count(), 4);
+    }
+
+    #[test]
+    fn test_assignee_and_tag_breakdowns() {
+        let manager = StatisticsManager::new(None);
+        let mut project = ProjectData::new(Some("breakdown".to_string()));
+        let fixtures = [
+            (
+                "t1",
+                Some("alice"),
+                vec!["backend", "api"],
+                TaskStatus::Done,
+            ),
+            ("t2", Some("alice"), vec!["backend"], TaskStatus::Todo),
+            ("t3", Some("bob"), vec!["api", "frontend"], TaskStatus::Done),
+            ("t4", Some("carol"), vec![], TaskStatus::InProgress),
+            ("t5", None, vec!["frontend"], TaskStatus::Todo),
+        ];
+        for (id, assignee, tags, status) in fixtures {
+            project.add_task("dev", id, id.to_string(), None).unwrap();
+            let task = project.get_task_mut("dev", id).unwrap();
+            task.assignee = assignee.map(String::from);
+            task.tags = tags.into_iter().map(String::from).collect();
+            task.status = status;
+        }
+
+        let stats = manager
+            .get_fresh_statistics(&project, SectionOrder::Alphabetical)
+            .unwrap();
+        assert_eq!(stats.by_assignee.len(), 4);
+        assert_eq!(stats.by_assignee["alice"].total, 2);
+        assert_eq!(stats.by_assignee["alice"].completion_rate, 50.0);
+        assert_eq!(stats.by_assignee["bob"].done, 1);
+        assert_eq!(stats.by_assignee["carol"].done, 0);
+        assert_eq!(stats.by_assignee[NONE_BUCKET].total, 1);
+        assert_eq!(stats.by_tag["api"].total, 2);
+        assert_eq!(stats.by_tag["api"].completion_rate, 100.0);
+        assert_eq!(stats.by_tag["backend"].total, 2);
+        assert_eq!(stats.by_tag["frontend"].total, 2);
+        assert_eq!(stats.by_tag[NONE_BUCKET].total, 1);
+        assert_eq!(stats.overview.top_assignees[0].name, "alice");
+        assert!(stats.overview.top_tags.len() <= 5);
+
+        let filter = StatisticsFilter {
+            tags: Some(vec!["api".to_string()]),
+            ..Default::default()
+        };
+        let filtered = manager
+            .get_filtered_statistics(&project, &filter, SectionOrder::Alphabetical)
+            .unwrap();
+        assert_eq!(filtered.overview.total_tasks, 2);
+        assert!(!filtered.by_assignee.contains_key("carol"));
+    }
+
+    #[test]
+    fn test_time_in_status_from_timeline() {
+        let start = Utc::now() - chrono::Duration::hours(10);
+        let update =
+            |hours: i64, old: Option<TaskStatus>, new: TaskStatus, change_type| TaskUpdate {
+                section: "dev".to_string(),
+                task_id: "task1".to_string(),
+                old_status: old,
+                new_status: new,
+                timestamp: start + chrono::Duration::hours(hours),
+                change_type,
+            };
+        let timeline = [
+            update(0, None, TaskStatus::Todo, ChangeType::Created),
+            update(
+                2,
+                Some(TaskStatus::Todo),
+                TaskStatus::InProgress,
+                ChangeType::StatusUpdated,
+            ),
+            update(
+                5,
+                Some(TaskStatus::InProgress),
+                TaskStatus::Blocked,
+                ChangeType::StatusUpdated,
+            ),
+            update(
+                6,
+                Some(TaskStatus::Blocked),
+                TaskStatus::InProgress,
+                ChangeType::StatusUpdated,
+            ),
+            update(
+                9,
+                Some(TaskStatus::InProgress),
+                TaskStatus::Done,
+                ChangeType::StatusUpdated,
+            ),
+        ];
+        let mut task = Task::new("Test task".to_string(), None);
+        task.status = TaskStatus::Done;
+        let now = start + chrono::Duration::hours(10);
+
+        let refs: Vec<&TaskUpdate> = timeline.iter().collect();
+        let result = StatisticsManager::compute_time_in_status(&refs, &task, now);
+        assert!(!result.partial);
+        assert_eq!(result.durations_seconds[&TaskStatus::Todo], 2 * 3600);
+        assert_eq!(result.durations_seconds[&TaskStatus::InProgress], 6 * 3600);
+        assert_eq!(result.durations_seconds[&TaskStatus::Blocked], 3600);
+        assert_eq!(result.durations_seconds[&TaskStatus::Done], 3600);
+
+        // Without the creation record only the observed intervals are counted
+        let result = StatisticsManager::compute_time_in_status(&refs[2..], &task, now);
+        assert!(result.partial);
+        assert!(!result.durations_seconds.contains_key(&TaskStatus::Todo));
+        assert_eq!(result.durations_seconds[&TaskStatus::InProgress], 3 * 3600);
+        assert_eq!(result.durations_seconds[&TaskStatus::Blocked], 3600);
+
+        let manager = StatisticsManager::new(None);
+        for update in timeline {
+            manager.record_task_update(update).unwrap();
+        }
+        let mut project = ProjectData::new(None);
+        project
+            .add_task("dev", "task1", "Test task".to_string(), None)
+            .unwrap();
+        project
+            .update_task_status("dev", "task1", TaskStatus::Done)
+            .unwrap();
+        let stats = manager
+            .get_fresh_statistics(&project, SectionOrder::Alphabetical)
+            .unwrap();
+        let averages = &stats.sections["dev"].avg_time_in_status_seconds;
+        assert_eq!(averages[&TaskStatus::Blocked], 3600.0);
+        let json = serde_json::to_value(&stats).unwrap();
+        assert!(json["sections"]["dev"]["avg_time_in_status_seconds"]["in_progress"].is_number());
+    }
+
+    #[test]
+    fn test_estimates_and_logged_time() {
+        let manager = StatisticsManager::new(None);
+        let mut project = ProjectData::new(None);
+        project
+            .add_task("api", "login", "Login".to_string(), None)
+            .unwrap();
+        project
+            .add_task("api", "logout", "Logout".to_string(), None)
+            .unwrap();
+        project
+            .set_task_estimate("api", "login", Some(120))
+            .unwrap();
+        project
+            .set_task_estimate("api", "logout", Some(30))
+            .unwrap();
+        project.add_time_entry("api", "login", 90, None).unwrap();
+        project.add_time_entry("api", "logout", 45, None).unwrap();
+        // Logged ten days ago: counted in the section total, not in the trailing week
+        let task = project.get_task_mut("api", "logout").unwrap();
+        task.time_entries[0].logged_at = Utc::now() - chrono::Duration::days(10);
+
+        let stats = manager
+            .get_fresh_statistics(&project, SectionOrder::Alphabetical)
+            .unwrap();
+        assert_eq!(stats.sections["api"].estimate_minutes, 150);
+        assert_eq!(stats.sections["api"].time_spent_minutes, 135);
+        assert_eq!(stats.trends.time_logged_minutes_7d, 90);
+        let csv = StatisticsManager::to_csv(&stats);
+        assert!(
+            csv.lines()
+                .next()
+                .unwrap()
+                .ends_with(",estimate_minutes,time_spent_minutes")
+        );
+        assert!(
+            csv.lines()
+                .any(|line| line.contains(",TOTAL,") && line.ends_with(",150,135"))
+        );
+    }
+
+    #[test]
+    fn test_cache_key_follows_task_content() {
+        let manager = StatisticsManager::new(None);
+        let build = |status: TaskStatus| {
+            let mut project = ProjectData::new(Some("keys".to_string()));
+            for task_id in ["login", "logout"] {
+                project
+                    .add_task("api", task_id, task_id.to_string(), None)
+                    .unwrap();
+            }
+            project.update_task_status("api", "login", status).unwrap();
+            project
+        };
+        let mut todo = build(TaskStatus::Todo);
+        let mut done = build(TaskStatus::Done);
+        // Same section count and the same timestamps, different task state
+        done.meta.last_updated = todo.meta.last_updated;
+        for task_id in ["login", "logout"] {
+            let created = todo.get_task("api", task_id).unwrap().created;
+            done.get_task_mut("api", task_id).unwrap().created = created;
+        }
+        assert_ne!(
+            manager.generate_cache_key(&todo, SectionOrder::Alphabetical),
+            manager.generate_cache_key(&done, SectionOrder::Alphabetical)
+        );
+
+        let key = manager.generate_cache_key(&todo, SectionOrder::Alphabetical);
+        todo.meta.last_updated = Utc::now() + chrono::Duration::seconds(5);
+        assert_eq!(
+            manager.generate_cache_key(&todo, SectionOrder::Alphabetical),
+            key
+        );
+        assert_ne!(
+            manager.generate_cache_key(&todo, SectionOrder::TaskCount),
+            key
+        );
+        todo.add_time_entry("api", "login", 15, None).unwrap();
+        assert_ne!(
+            manager.generate_cache_key(&todo, SectionOrder::Alphabetical),
+            key
+        );
+    }
+
+    #[test]
+    fn test_moving_a_label_refreshes_most_active_files() {
+        let manager = StatisticsManager::new(None);
+        let mut project = ProjectData::new(Some("files".to_string()));
+        project
+            .add_task("api", "login", "Login".to_string(), None)
+            .unwrap();
+        let label = |line: u32| TaskFile {
+            lines: vec![line],
+            notes: HashMap::new(),
+            label_texts: Vec::new(),
+        };
+        let task = project.get_task_mut("api", "login").unwrap();
+        task.files.insert("src/auth.rs".to_string(), label(3));
+        let most_active_files = |project: &ProjectData| {
+            let stats = manager
+                .get_statistics(project, SectionOrder::Alphabetical)
+                .unwrap();
+            stats.sections["api"].most_active_files.clone()
+        };
+        assert_eq!(most_active_files(&project), vec!["src/auth.rs"]);
+
+        let task = project.get_task_mut("api", "login").unwrap();
+        task.files.remove("src/auth.rs");
+        task.files.insert("src/session.rs".to_string(), label(3));
+        assert_eq!(most_active_files(&project), vec!["src/session.rs"]);
+    }
 }
@@ -1,9 +1,19 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
 use tokio::fs as async_fs;
-use crate::task_manager::ProjectData;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use crate::config::ProjectConfig;
+use crate::task_manager::{Milestone, Note, ProjectData, ProjectMeta, SectionOrder, Task, TaskChange, TaskTemplate, TrashedTask};
 pub struct StorageManager {
     anchora_dir: PathBuf,
     tasks_file: PathBuf,
+    config_file: PathBuf,
+    /// Bumped on every write to the tasks file, so caches know when to reload
+    generation: AtomicU64,
 }
 
 impl StorageManager {
@@ -11,17 +21,37 @@ impl StorageManager {
     pub fn new(workspace_path: &Path) -> Self {
         let anchora_dir = workspace_path.join(".anchora");
         let tasks_file = anchora_dir.join("tasks.json");
+        let config_file = anchora_dir.join("config.json");
 
         Self {
             anchora_dir,
             tasks_file,
+            config_file,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Counter identifying the current version of the project data
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Version of the tasks file for keying caches. Read it before loading the
+    /// data it describes, so a write in between only causes an extra reload.
+    pub async fn data_version(&self) -> DataVersion {
+        let generation = self.generation();
+        let metadata = async_fs::metadata(&self.tasks_file).await.ok();
+        DataVersion {
+            generation,
+            modified: metadata.as_ref().and_then(|metadata| metadata.modified().ok()),
+            len: metadata.map(|metadata| metadata.len()),
         }
     }
 
     pub async fn initialize(&self) -> anyhow::Result<()> {
         if !self.anchora_dir.exists() {
             async_fs::create_dir_all(&self.anchora_dir).await?;
-            println!("Created .anchora directory: {:?}", self.anchora_dir);
+            eprintln!("Created .anchora directory: {:?}", self.anchora_dir);
         }
         Ok(())
     }
@@ -36,20 +66,79 @@ impl StorageManager {
                 
             return Ok(ProjectData::new(project_name));
         }
-        let content = async_fs::read_to_string(&self.tasks_file).await?;
-        let project_data: ProjectData = serde_json::from_str(&content)?;
-        println!("Loaded project data from: {:?}", self.tasks_file);
+        let project_data = self.read_project_data().await?;
+        eprintln!("Loaded project data from: {:?}", self.tasks_file);
         Ok(project_data)
     }
 
+    /// Stored project data, failing when tasks.json is missing instead of
+    /// starting an empty project. Logs nothing, for callers whose output is parsed.
+    pub async fn load_existing_project_data(&self) -> anyhow::Result<ProjectData> {
+        if !self.tasks_file.exists() {
+            return Err(anyhow::anyhow!(
+                "No task data found at {} (scan the workspace first)",
+                self.tasks_file.display()
+            ));
+        }
+        self.read_project_data().await
+    }
+
+    async fn read_project_data(&self) -> anyhow::Result<ProjectData> {
+        let content = async_fs::read_to_string(&self.tasks_file)
+            .await
+            .with_context(|| format!("Failed to read {}", self.tasks_file.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("{} is not valid task data", self.tasks_file.display()))
+    }
+
     pub async fn save_project_data(&self, project_data: &ProjectData) -> anyhow::Result<()> {
         self.initialize().await?;
         let json_content = serde_json::to_string_pretty(project_data)?;
         async_fs::write(&self.tasks_file, json_content).await?;
-        println!("Saved project data to: {:?}", self.tasks_file);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        eprintln!("Saved project data to: {:?}", self.tasks_file);
+        Ok(())
+    }
+
+    pub async fn load_config(&self) -> anyhow::Result<ProjectConfig> {
+        if !self.config_file.exists() {
+            return Ok(ProjectConfig::default());
+        }
+        let content = async_fs::read_to_string(&self.config_file).await?;
+        let config: ProjectConfig = serde_json::from_str(&content)?;
+        Ok(config)
+    }
+
+    pub async fn save_config(&self, config: &ProjectConfig) -> anyhow::Result<()> {
+        self.initialize().await?;
+        let json_content = serde_json::to_string_pretty(config)?;
+        async_fs::write(&self.config_file, json_content).await?;
+        eprintln!("Saved project config to: {:?}", self.config_file);
         Ok(())
     }
 
+    /// Task templates from the project config, by template ID
+    pub async fn load_templates(&self) -> anyhow::Result<BTreeMap<String, TaskTemplate>> {
+        Ok(self.load_config().await?.templates.into_iter().collect())
+    }
+
+    /// Replace the templates in the project config, leaving the rest of it as it is
+    pub async fn save_templates(&self, templates: &BTreeMap<String, TaskTemplate>) -> anyhow::Result<()> {
+        let mut config = self.load_config().await?;
+        config.templates = templates.clone().into_iter().collect();
+        self.save_config(&config).await
+    }
+
+    /// Add imported templates to the store, replacing those with the same ID
+    async fn merge_templates(&self, imported: BTreeMap<String, TaskTemplate>) -> anyhow::Result<()> {
+        if imported.is_empty() {
+            return Ok(());
+        }
+        let mut templates = self.load_templates().await?;
+        templates.extend(imported);
+        self.save_templates(&templates).await
+    }
+
     pub async fn create_backup(&self) -> anyhow::Result<PathBuf> {
         if !self.tasks_file.exists() {
             return Err(anyhow::anyhow!("Tasks file does not exist"));
@@ -58,7 +147,7 @@ impl StorageManager {
         let backup_name = format!("tasks_backup_{}.json", timestamp);
         let backup_path = self.anchora_dir.join(backup_name);
         async_fs::copy(&self.tasks_file, &backup_path).await?;
-        println!("Created backup: {:?}", backup_path);
+        eprintln!("Created backup: {:?}", backup_path);
         Ok(backup_path)
     }
 
@@ -89,7 +178,7 @@ impl StorageManager {
         let to_remove = backups.len() - keep_count;
         for backup in backups.iter().take(to_remove) {
             async_fs::remove_file(backup).await?;
-            println!("Removed old backup: {:?}", backup);
+            eprintln!("Removed old backup: {:?}", backup);
         }
         Ok(())
     }
@@ -102,7 +191,8 @@ impl StorageManager {
             self.create_backup().await?;
         }
         async_fs::copy(backup_path, &self.tasks_file).await?;
-        println!("Restored from backup: {:?}", backup_path);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        eprintln!("Restored from backup: {:?}", backup_path);
         Ok(())
     }
     
@@ -113,7 +203,7 @@ impl StorageManager {
         match self.load_project_data().await {
             Ok(_) => Ok(true),
             Err(e) => {
-                println!("Data integrity check failed: {}", e);
+                eprintln!("Data integrity check failed: {}", e);
                 Ok(false)
             }
         }
@@ -139,11 +229,161 @@ impl StorageManager {
         Ok(info)
     }
 
+    /// Write the project as JSON, with the saved templates under a top-level `templates` key
+    /// and the section names in the configured order under `section_order`
     pub async fn export_data(&self, export_path: &Path) -> anyhow::Result<()> {
         let project_data = self.load_project_data().await?;
-        let json_content = serde_json::to_string_pretty(&project_data)?;
-        async_fs::write(export_path, json_content).await?;
-        println!("Exported data to: {:?}", export_path);
+        let order = self.load_config().await?.responses.section_order;
+        self.export_project_data(&project_data, export_path, order).await
+    }
+
+    /// `export_data` for a project that may have been filtered after loading
+    pub async fn export_project_data(&self, project_data: &ProjectData, export_path: &Path, order: SectionOrder) -> anyhow::Result<()> {
+        let mut export = serde_json::to_value(project_data)?;
+        export["section_order"] = serde_json::to_value(project_data.ordered_section_names(order))?;
+        let templates = self.load_templates().await?;
+        if !templates.is_empty() {
+            export["templates"] = serde_json::to_value(&templates)?;
+        }
+        let json_content = serde_json::to_string_pretty(&export)?;
+        self.write_export(export_path, &json_content).await
+    }
+
+    /// Write the project as NDJSON, one record per line, serializing a line at a time.
+    /// Returns the number of tasks written.
+    pub async fn export_ndjson(&self, export_path: &Path) -> anyhow::Result<usize> {
+        Self::validate_export_path(export_path)?;
+        let project_data = self.load_project_data().await?;
+        let order = self.load_config().await?.responses.section_order;
+        self.export_project_ndjson(&project_data, export_path, order).await
+    }
+
+    /// `export_ndjson` for a project that may have been filtered after loading. Sections
+    /// are written in the given order.
+    pub async fn export_project_ndjson(&self, project_data: &ProjectData, export_path: &Path, order: SectionOrder) -> anyhow::Result<usize> {
+        Self::validate_export_path(export_path)?;
+        let mut writer = tokio::io::BufWriter::new(async_fs::File::create(export_path).await?);
+        let mut write_record = async |record: NdjsonRecord<'_>| -> anyhow::Result<()> {
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            Ok(())
+        };
+        write_record(NdjsonRecord::Meta {
+            meta: Cow::Borrowed(&project_data.meta),
+            archived_sections: Cow::Borrowed(&project_data.archived_sections),
+            aliases: Cow::Borrowed(&project_data.aliases),
+            history: Cow::Borrowed(&project_data.history),
+            milestones: Cow::Borrowed(&project_data.milestones),
+        }).await?;
+        let mut task_count = 0;
+        for section in project_data.ordered_section_names(order) {
+            write_record(NdjsonRecord::Section { name: Cow::Borrowed(section) }).await?;
+            let tasks = &project_data.sections[section];
+            let mut task_ids: Vec<&String> = tasks.keys().collect();
+            task_ids.sort();
+            for task_id in task_ids {
+                write_record(NdjsonRecord::Task {
+                    section: Cow::Borrowed(section),
+                    task_id: Cow::Borrowed(task_id),
+                    task: Box::new(Cow::Borrowed(&tasks[task_id])),
+                }).await?;
+                task_count += 1;
+            }
+        }
+        let mut note_ids: Vec<&String> = project_data.notes.keys().collect();
+        note_ids.sort();
+        for note_id in note_ids {
+            write_record(NdjsonRecord::Note { note: Cow::Borrowed(&project_data.notes[note_id]) }).await?;
+        }
+        for entry in &project_data.trash {
+            write_record(NdjsonRecord::Trashed { entry: Box::new(Cow::Borrowed(entry)) }).await?;
+        }
+        for (id, template) in &self.load_templates().await? {
+            write_record(NdjsonRecord::Template { id: Cow::Borrowed(id), template: Cow::Borrowed(template) }).await?;
+        }
+        writer.flush().await?;
+        eprintln!("Exported {} tasks as NDJSON to: {:?}", task_count, export_path);
+        Ok(task_count)
+    }
+
+    /// Replace the project with one read line by line from an NDJSON export.
+    /// Returns the number of tasks imported.
+    pub async fn import_ndjson(&self, import_path: &Path) -> anyhow::Result<usize> {
+        if !import_path.exists() {
+            return Err(anyhow::anyhow!("Import file does not exist: {:?}", import_path));
+        }
+        let reader = tokio::io::BufReader::new(async_fs::File::open(import_path).await?);
+        let mut lines = reader.lines();
+        let mut project_data = ProjectData::new(None);
+        let mut templates = BTreeMap::new();
+        let mut task_count = 0;
+        let mut line_number = 0;
+        while let Some(line) = lines.next_line().await? {
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: NdjsonRecord = serde_json::from_str(&line)
+                .map_err(|e| anyhow::anyhow!("Invalid NDJSON record on line {}: {}", line_number, e))?;
+            match record {
+                NdjsonRecord::Meta { meta, archived_sections, aliases, history, milestones } => {
+                    project_data.meta = meta.into_owned();
+                    project_data.archived_sections = archived_sections.into_owned();
+                    project_data.aliases = aliases.into_owned();
+                    project_data.history = history.into_owned();
+                    project_data.milestones = milestones.into_owned();
+                }
+                NdjsonRecord::Section { name } => {
+                    project_data.sections.entry(name.into_owned()).or_default();
+                }
+                NdjsonRecord::Task { section, task_id, task } => {
+                    project_data.sections.entry(section.into_owned()).or_default()
+                        .insert(task_id.into_owned(), (*task).into_owned());
+                    task_count += 1;
+                }
+                NdjsonRecord::Note { note } => {
+                    let note = note.into_owned();
+                    project_data.notes.insert(note.id.clone(), note);
+                }
+                NdjsonRecord::Trashed { entry } => {
+                    project_data.trash.push((*entry).into_owned());
+                }
+                NdjsonRecord::Template { id, template } => {
+                    templates.insert(id.into_owned(), template.into_owned());
+                }
+            }
+        }
+        project_data.rebuild_index();
+        if self.tasks_file.exists() {
+            self.create_backup().await?;
+        }
+        self.save_project_data(&project_data).await?;
+        self.merge_templates(templates).await?;
+        eprintln!("Imported {} tasks from NDJSON: {:?}", task_count, import_path);
+        Ok(task_count)
+    }
+
+    pub async fn write_export(&self, export_path: &Path, content: &str) -> anyhow::Result<()> {
+        Self::validate_export_path(export_path)?;
+        async_fs::write(export_path, content).await?;
+        eprintln!("Exported data to: {:?}", export_path);
+        Ok(())
+    }
+
+    fn validate_export_path(export_path: &Path) -> anyhow::Result<()> {
+        if export_path.as_os_str().is_empty() {
+            return Err(anyhow::anyhow!("Export path is empty"));
+        }
+        if export_path.is_dir() {
+            return Err(anyhow::anyhow!("Export path is a directory: {:?}", export_path));
+        }
+        if let Some(parent) = export_path.parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.is_dir()
+        {
+            return Err(anyhow::anyhow!("Export directory does not exist: {:?}", parent));
+        }
         Ok(())
     }
     
@@ -155,13 +395,63 @@ impl StorageManager {
             self.create_backup().await?;
         }
         let content = async_fs::read_to_string(import_path).await?;
-        let project_data: ProjectData = serde_json::from_str(&content)?;
+        let mut import: serde_json::Value = serde_json::from_str(&content)?;
+        let templates = import.as_object_mut().and_then(|import| import.remove("templates"));
+        let project_data: ProjectData = serde_json::from_value(import)?;
         self.save_project_data(&project_data).await?;
-        println!("Imported data from: {:?}", import_path);
+        if let Some(templates) = templates {
+            self.merge_templates(serde_json::from_value(templates)?).await?;
+        }
+        eprintln!("Imported data from: {:?}", import_path);
         Ok(())
     }
 }
 
+/// One line of an NDJSON export. Borrowed when writing, owned when reading.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum NdjsonRecord<'a> {
+    Meta {
+        meta: Cow<'a, ProjectMeta>,
+        #[serde(default)]
+        archived_sections: Cow<'a, HashSet<String>>,
+        #[serde(default)]
+        aliases: Cow<'a, HashMap<String, String>>,
+        #[serde(default)]
+        history: Cow<'a, HashMap<String, Vec<TaskChange>>>,
+        #[serde(default)]
+        milestones: Cow<'a, HashMap<String, Milestone>>,
+    },
+    Section {
+        name: Cow<'a, str>,
+    },
+    Task {
+        section: Cow<'a, str>,
+        task_id: Cow<'a, str>,
+        task: Box<Cow<'a, Task>>,
+    },
+    Note {
+        note: Cow<'a, Note>,
+    },
+    Trashed {
+        entry: Box<Cow<'a, TrashedTask>>,
+    },
+    Template {
+        id: Cow<'a, str>,
+        template: Cow<'a, TaskTemplate>,
+    },
+}
+
+/// One version of the tasks file. The generation covers writes made through
+/// this manager; modification time and length cover writes from anywhere
+/// else, such as the CLI, watch mode or another server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataVersion {
+    pub generation: u64,
+    pub modified: Option<std::time::SystemTime>,
+    pub len: Option<u64>,
+}
+
 #[derive(Debug)]
 pub struct StorageInfo {
     pub anchora_dir_exists: bool,
@@ -171,6 +461,62 @@ pub struct StorageInfo {
     pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Source file rewrites written beside their targets first and moved into
+/// place only once every one of them has been written. Dropping the set
+/// before `commit` removes the temporary files and leaves the targets alone.
+#[derive(Debug, Default)]
+pub struct StagedWrites {
+    /// Temporary file and the target it replaces
+    staged: Vec<(PathBuf, PathBuf)>,
+}
+
+impl StagedWrites {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn stage(&mut self, target: &Path, contents: &str) -> anyhow::Result<()> {
+        let file_name = target
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Not a file: {}", target.display()))?;
+        let temp = target.with_file_name(format!(".{}.anchora-tmp", file_name.to_string_lossy()));
+        async_fs::write(&temp, contents)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write file {}: {}", target.display(), e))?;
+        self.staged.push((temp, target.to_path_buf()));
+        Ok(())
+    }
+
+    /// Move every staged file over its target, returning how many were replaced
+    pub async fn commit(mut self) -> anyhow::Result<usize> {
+        let mut replaced = 0;
+        while let Some((temp, target)) = self.staged.pop() {
+            if let Err(e) = async_fs::rename(&temp, &target).await {
+                let _ = async_fs::remove_file(&temp).await;
+                return Err(anyhow::anyhow!("Failed to replace file {}: {}", target.display(), e));
+            }
+            replaced += 1;
+        }
+        Ok(replaced)
+    }
+}
+
+impl Drop for StagedWrites {
+    fn drop(&mut self) {
+        for (temp, _) in &self.staged {
+            let _ = std::fs::remove_file(temp);
+        }
+    }
+}
+
+/// Replace a file's contents so readers see either the old or the new text
+pub async fn write_file_atomic(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let mut writes = StagedWrites::new();
+    writes.stage(path, contents).await?;
+    writes.commit().await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,4 +552,27 @@ mod tests {
         let backups = storage.list_backups().await.unwrap();
         assert_eq!(backups.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_staged_writes_touch_nothing_until_committed() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("a.rs");
+        let second = temp_dir.path().join("b.rs");
+        std::fs::write(&first, "old a").unwrap();
+        std::fs::write(&second, "old b").unwrap();
+
+        let mut writes = StagedWrites::new();
+        writes.stage(&first, "new a").await.unwrap();
+        assert!(writes.stage(&temp_dir.path().join("missing/c.rs"), "new c").await.is_err());
+        drop(writes);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "old a");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        let mut writes = StagedWrites::new();
+        writes.stage(&first, "new a").await.unwrap();
+        writes.stage(&second, "new b").await.unwrap();
+        assert_eq!(writes.commit().await.unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), "new b");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+}
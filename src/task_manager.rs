@@ -1,7 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use uuid::Uuid;
+use crate::identifier::IdStyle;
+use crate::similarity::similarity;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum TaskStatus {
@@ -15,6 +17,52 @@ pub enum TaskStatus {
     Blocked,
 }
 
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "todo",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Done => "done",
+            TaskStatus::Blocked => "blocked",
+        }
+    }
+}
+
+/// How urgent a task is, from least to most
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskPriority {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl TaskPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TaskPriority::Low => "low",
+            TaskPriority::Medium => "medium",
+            TaskPriority::High => "high",
+            TaskPriority::Critical => "critical",
+        }
+    }
+}
+
+impl std::str::FromStr for TaskPriority {
+    type Err = anyhow::Error;
+
+    fn from_str(priority: &str) -> anyhow::Result<Self> {
+        match priority.to_lowercase().as_str() {
+            "low" => Ok(TaskPriority::Low),
+            "medium" => Ok(TaskPriority::Medium),
+            "high" => Ok(TaskPriority::High),
+            "critical" => Ok(TaskPriority::Critical),
+            _ => Err(anyhow::anyhow!("Invalid priority: {}", priority)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Note {
     pub id: String,
@@ -28,6 +76,31 @@ pub struct Note {
     pub is_converted: bool,
     pub converted_at: Option<DateTime<Utc>>,
     pub generated_link: Option<String>,
+    /// Tasks this note was converted into or discusses
+    #[serde(default)]
+    pub related_tasks: Vec<TaskKey>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tasks created when the note was converted
+    #[serde(default)]
+    pub converted_tasks: Vec<TaskKey>,
+    /// Listed ahead of unpinned notes
+    #[serde(default)]
+    pub pinned: bool,
+    /// Code the note was written about
+    #[serde(default)]
+    pub attachments: Vec<NoteAttachment>,
+}
+
+/// Lines of a workspace file attached to a note, 1-based and inclusive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoteAttachment {
+    /// Workspace-relative, `/`-separated
+    pub file_path: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
 impl Note {
@@ -51,21 +124,25 @@ impl Note {
             is_converted: false,
             converted_at: None,
             generated_link: None,
+            related_tasks: Vec::new(),
+            tags: Vec::new(),
+            converted_tasks: Vec::new(),
+            pinned: false,
+            attachments: Vec::new(),
         }
     }
 
+    /// Whether the note carries any of the given tags, compared after normalization
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        self.tags.iter().any(|tag| tags.iter().any(|wanted| normalize_tag(wanted).as_deref() == Some(tag.as_str())))
+    }
+
     pub fn generate_task_link(&mut self) -> String {
-        let status_str = match self.suggested_status {
-            TaskStatus::Todo => "todo",
-            TaskStatus::InProgress => "in_progress",
-            TaskStatus::Done => "done",
-            TaskStatus::Blocked => "blocked",
-        };
         let link = format!(
             "// {}:{}:{}: {}",
             self.section,
             self.suggested_task_id,
-            status_str,
+            self.suggested_status.as_str(),
             self.title
         );
         self.generated_link = Some(link.clone());
@@ -73,6 +150,30 @@ impl Note {
         link
     }
 
+    /// Confidence from 0.0 to 1.0 that `content` contains this note's generated link.
+    /// Whitespace changes are ignored, and a line naming the link's `section:task_id`
+    /// scores by how closely the rest of the line still matches the link.
+    pub fn link_match_confidence(&self, content: &str) -> f32 {
+        let Some(link) = &self.generated_link else {
+            return 0.0;
+        };
+        let link = normalize_whitespace(link);
+        if normalize_whitespace(content).contains(&link) {
+            return 1.0;
+        }
+        let core = format!("{}:{}", self.section, self.suggested_task_id);
+        let Some(link_rest) = link.find(&core).map(|start| link[start + core.len()..].to_lowercase()) else {
+            return 0.0;
+        };
+        content.lines()
+            .filter_map(|line| {
+                let start = find_identifier(line, &core)?;
+                let rest = normalize_whitespace(&line[start + core.len()..]).to_lowercase();
+                Some(0.6 + 0.4 * similarity(&rest, &link_rest))
+            })
+            .fold(0.0, f32::max)
+    }
+
     pub fn mark_as_converted(&mut self) {
         self.is_converted = true;
         self.converted_at = Some(Utc::now());
@@ -80,16 +181,322 @@ impl Note {
     }
 }
 
+/// Collapse whitespace runs so reformatted text still compares equal
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Byte offset of `identifier` in `text`, ignoring matches inside a longer identifier
+fn find_identifier(text: &str, identifier: &str) -> Option<usize> {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(identifier)
+        .map(|(start, _)| start)
+        .find(|&start| {
+            !text[..start].chars().next_back().is_some_and(is_identifier_char)
+                && !text[start + identifier.len()..].chars().next().is_some_and(is_identifier_char)
+        })
+}
+
+/// Order in which sections are listed in responses and exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionOrder {
+    #[default]
+    Alphabetical,
+    /// Oldest first, by the creation time of a section's earliest task
+    Creation,
+    /// Most tasks first
+    TaskCount,
+}
+
+/// Note fields to change; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct NoteUpdate {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub section: Option<String>,
+    pub suggested_task_id: Option<String>,
+    pub suggested_status: Option<TaskStatus>,
+}
+
+/// How note content is split into checklist items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistSplit {
+    /// Every non-empty line
+    Lines,
+    /// Lines starting with `-` or `*`
+    Bullets,
+}
+
+impl ChecklistSplit {
+    /// Item texts from note content, trimmed, in order
+    pub fn items(self, content: &str) -> Vec<String> {
+        content.lines()
+            .map(str::trim)
+            .filter_map(|line| match self {
+                ChecklistSplit::Lines => Some(line),
+                ChecklistSplit::Bullets => line.strip_prefix('-').or_else(|| line.strip_prefix('*')),
+            })
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// Words of an item kept in a generated task ID
+const CHECKLIST_SLUG_WORDS: usize = 5;
+
+/// Lowercased words of `text` joined by underscores, for use in a task ID
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(CHECKLIST_SLUG_WORDS)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// How closely an unconverted note's generated link currently matches scanned code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionCandidate {
+    pub note_id: String,
+    pub section: String,
+    pub suggested_task_id: String,
+    /// Best link match confidence over the scanned files, 0.0 when the link is nowhere
+    pub confidence: f32,
+    /// File holding the best match
+    pub file: Option<String>,
+    /// Whether the confidence reaches the conversion threshold
+    pub ready: bool,
+}
+
+/// Settings for turning notes into tasks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotesConfig {
+    /// Also convert a note when an edited version of its generated link shows up
+    /// in scanned code. Off unless the project opts in; the unedited link always
+    /// converts the note.
+    pub auto_convert: bool,
+    /// Minimum link match confidence (0.0-1.0) needed to convert a note
+    pub conversion_threshold: f32,
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        Self {
+            auto_convert: false,
+            conversion_threshold: 0.75,
+        }
+    }
+}
+
+/// External reference from a task, such as an issue or a design doc
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskLink {
+    pub label: String,
+    pub url: String,
+}
+
+/// Accept only absolute http and https URLs with a host
+pub fn validate_link_url(url: &str) -> anyhow::Result<()> {
+    let lower = url.to_ascii_lowercase();
+    let rest = ["https://", "http://"]
+        .iter()
+        .find_map(|scheme| lower.strip_prefix(scheme))
+        .ok_or_else(|| anyhow::anyhow!("Only http and https links are allowed: {}", url))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if host.is_empty() || url.chars().any(char::is_whitespace) {
+        return Err(anyhow::anyhow!("Invalid link URL: {}", url));
+    }
+    Ok(())
+}
+
+/// Defaults for tasks created from a named template, also used for the caller's overrides
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskTemplate {
+    pub section: Option<String>,
+    /// Title, where `{title}` is replaced by the title the caller passes
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<TaskStatus>,
+    pub priority: Option<TaskPriority>,
+    pub tags: Vec<String>,
+    pub assignee: Option<String>,
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+impl TaskTemplate {
+    /// Template defaults with the caller's values laid over them. Tags are combined and
+    /// metadata merged; the title is left unset when the template needs one and none was given.
+    pub fn with_overrides(&self, overrides: TaskTemplate) -> TaskTemplate {
+        let title = match (&self.title, overrides.title) {
+            (Some(pattern), Some(title)) if pattern.contains("{title}") => Some(pattern.replace("{title}", &title)),
+            (Some(pattern), None) if pattern.contains("{title}") => None,
+            (template_title, title) => title.or_else(|| template_title.clone()),
+        };
+        let mut tags = self.tags.clone();
+        tags.extend(overrides.tags);
+        let mut metadata = self.metadata.clone();
+        metadata.extend(overrides.metadata);
+        TaskTemplate {
+            section: overrides.section.or_else(|| self.section.clone()),
+            title,
+            description: overrides.description.or_else(|| self.description.clone()),
+            status: overrides.status.or_else(|| self.status.clone()),
+            priority: overrides.priority.or(self.priority),
+            tags,
+            assignee: overrides.assignee.or_else(|| self.assignee.clone()),
+            metadata,
+        }
+    }
+
+    /// Names of the `{placeholder}`s in the title and description, sorted and deduplicated
+    pub fn placeholders(&self) -> Vec<String> {
+        let mut names: Vec<String> = [&self.title, &self.description].into_iter()
+            .flatten()
+            .flat_map(|text| placeholder_spans(text).into_iter().map(|(start, end)| text[start + 1..end - 1].to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Copy with every placeholder in the title and description replaced by its value.
+    /// Fails naming the placeholders that have no value.
+    pub fn substitute(&self, values: &HashMap<String, String>) -> anyhow::Result<TaskTemplate> {
+        let missing: Vec<String> = self.placeholders().into_iter()
+            .filter(|name| !values.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!("Missing values for template placeholders: {}", missing.join(", ")));
+        }
+        let fill = |text: &Option<String>| text.as_ref().map(|text| {
+            let mut filled = String::with_capacity(text.len());
+            let mut copied = 0;
+            for (start, end) in placeholder_spans(text) {
+                filled.push_str(&text[copied..start]);
+                filled.push_str(&values[&text[start + 1..end - 1]]);
+                copied = end;
+            }
+            filled.push_str(&text[copied..]);
+            filled
+        });
+        Ok(TaskTemplate {
+            title: fill(&self.title),
+            description: fill(&self.description),
+            ..self.clone()
+        })
+    }
+}
+
+/// History entries kept per task; older ones are dropped first
+const MAX_TASK_HISTORY: usize = 200;
+
+/// Key of a task in `ProjectData::aliases` and `history`, in the same form as the index uses
+fn alias_key(section: &str, task_id: &str) -> String {
+    TaskKey::new(section, task_id).to_string()
+}
+
+/// Byte ranges of `{name}` placeholders, where a name is letters, digits and underscores
+fn placeholder_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut search_from = 0;
+    while let Some(offset) = text[search_from..].find('{') {
+        let start = search_from + offset;
+        let name_len = text[start + 1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len() - start - 1);
+        let end = start + 1 + name_len;
+        if name_len > 0 && text[end..].starts_with('}') {
+            spans.push((start, end + 1));
+            search_from = end + 1;
+        } else {
+            search_from = start + 1;
+        }
+    }
+    spans
+}
+
 impl Default for TaskStatus {
     fn default() -> Self {
         TaskStatus::Todo
     }
 }
 
+/// Every spelling a status is parsed from, compared case-insensitively
+pub const STATUS_ALIASES: &[(&str, TaskStatus)] = &[
+    ("todo", TaskStatus::Todo),
+    ("in_progress", TaskStatus::InProgress),
+    ("inprogress", TaskStatus::InProgress),
+    ("progress", TaskStatus::InProgress),
+    ("wip", TaskStatus::InProgress),
+    ("done", TaskStatus::Done),
+    ("completed", TaskStatus::Done),
+    ("complete", TaskStatus::Done),
+    ("closed", TaskStatus::Done),
+    ("resolved", TaskStatus::Done),
+    ("blocked", TaskStatus::Blocked),
+    ("block", TaskStatus::Blocked),
+    ("on_hold", TaskStatus::Blocked),
+    ("waiting", TaskStatus::Blocked),
+];
+
+impl std::str::FromStr for TaskStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(status: &str) -> anyhow::Result<Self> {
+        let lowercase = status.to_lowercase();
+        STATUS_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lowercase)
+            .map(|(_, parsed)| parsed.clone())
+            .ok_or_else(|| anyhow::anyhow!("Invalid status: {}", status))
+    }
+}
+
+/// Gap between the ranks given to new and renormalized tasks
+pub const RANK_STEP: f64 = 1024.0;
+/// Neighbouring ranks closer than this make the section renormalize
+const MIN_RANK_GAP: f64 = 1e-9;
+
+/// Longest comment accepted, in characters
+pub const MAX_COMMENT_LENGTH: usize = 4000;
+/// Comments kept per task; the oldest are dropped beyond this
+pub const MAX_COMMENTS_PER_TASK: usize = 200;
+
+/// Discussion entry on a task, kept out of scans and bulk listings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskComment {
+    pub id: String,
+    pub author: String,
+    pub text: String,
+    pub created: DateTime<Utc>,
+}
+
+/// Time logged against a task with `add_time_entry`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub minutes: u32,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub logged_at: DateTime<Utc>,
+}
+
+/// Longest single time entry accepted, one day
+pub const MAX_TIME_ENTRY_MINUTES: u32 = 24 * 60;
+/// Largest estimate accepted, a thousand hours
+pub const MAX_ESTIMATE_MINUTES: u32 = 1000 * 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskFile {
     pub lines: Vec<u32>,
     pub notes: HashMap<u32, String>,
+    /// Label texts this file gave the task at the last scan
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label_texts: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +507,252 @@ pub struct Task {
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
     pub files: HashMap<String, TaskFile>,
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: Option<TaskPriority>,
+    /// Tasks that have to be finished before this one
+    #[serde(default)]
+    pub dependencies: Vec<TaskKey>,
+    /// Task this one is a subtask of
+    #[serde(default)]
+    pub parent: Option<TaskKey>,
+    /// Free-form fields such as story points or an epic link
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub comments: Vec<TaskComment>,
+    /// Manual position within the section, lower first
+    #[serde(default)]
+    pub rank: Option<f64>,
+    /// IDs of the notes this task was converted from or is discussed in
+    #[serde(default)]
+    pub related_notes: Vec<String>,
+    #[serde(default)]
+    pub links: Vec<TaskLink>,
+    /// Expected effort in minutes
+    #[serde(default)]
+    pub estimate_minutes: Option<u32>,
+    /// Sum of the minutes in `time_entries`
+    #[serde(default)]
+    pub time_spent_minutes: u32,
+    #[serde(default)]
+    pub time_entries: Vec<TimeEntry>,
+    /// Whether scans may rewrite the title from the code label
+    #[serde(default)]
+    pub description_source: DescriptionSource,
+}
+
+/// Identifies a task by section and ID, written as a `section.task_id` string
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskKey {
+    pub section: String,
+    pub task_id: String,
+}
+
+impl TaskKey {
+    pub fn new(section: &str, task_id: &str) -> Self {
+        Self {
+            section: section.to_string(),
+            task_id: task_id.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for TaskKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.section, self.task_id)
+    }
+}
+
+impl std::str::FromStr for TaskKey {
+    type Err = anyhow::Error;
+
+    fn from_str(key: &str) -> anyhow::Result<Self> {
+        match key.split_once('.') {
+            Some((section, task_id)) if !section.is_empty() && !task_id.is_empty() => Ok(Self::new(section, task_id)),
+            _ => Err(anyhow::anyhow!("Invalid task key '{}', expected section.task_id", key)),
+        }
+    }
+}
+
+impl Serialize for TaskKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskKey {
+    /// Also reads the `section:task_id` string and `{section, task_id}` object earlier versions wrote
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Text(String),
+            Fields { section: String, task_id: String },
+        }
+        match Stored::deserialize(deserializer)? {
+            Stored::Text(key) => key
+                .parse()
+                .or_else(|error| match key.split_once(':') {
+                    Some((section, task_id)) if !section.is_empty() && !task_id.is_empty() => Ok(Self::new(section, task_id)),
+                    _ => Err(error),
+                })
+                .map_err(serde::de::Error::custom),
+            Stored::Fields { section, task_id } => Ok(Self { section, task_id }),
+        }
+    }
+}
+
+/// Deleted task kept for `restore_task`, with its files and timestamps intact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredTrashedTask")]
+pub struct TrashedTask {
+    pub key: TaskKey,
+    pub task: Task,
+    pub deleted_at: DateTime<Utc>,
+    /// Milestones the task belonged to, rejoined on restore
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub milestones: Vec<String>,
+    /// Tasks that depended on this one, made to depend on it again on restore
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependents: Vec<TaskKey>,
+}
+
+/// A trash entry as read from tasks.json, where earlier versions wrote the key
+/// as separate `section` and `task_id` fields
+#[derive(Deserialize)]
+struct StoredTrashedTask {
+    #[serde(default)]
+    key: Option<TaskKey>,
+    #[serde(default)]
+    section: String,
+    #[serde(default)]
+    task_id: String,
+    task: Task,
+    deleted_at: DateTime<Utc>,
+    #[serde(default)]
+    milestones: Vec<String>,
+    #[serde(default)]
+    dependents: Vec<TaskKey>,
+}
+
+impl From<StoredTrashedTask> for TrashedTask {
+    fn from(stored: StoredTrashedTask) -> Self {
+        Self {
+            key: stored.key.unwrap_or_else(|| TaskKey::new(&stored.section, &stored.task_id)),
+            task: stored.task,
+            deleted_at: stored.deleted_at,
+            milestones: stored.milestones,
+            dependents: stored.dependents,
+        }
+    }
+}
+
+/// Release or goal grouping tasks across sections
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Milestone {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub due: Option<DateTime<Utc>>,
+    /// Member tasks as `section:task_id`, in the order they were added
+    #[serde(default)]
+    pub task_refs: Vec<String>,
+}
+
+impl Milestone {
+    /// Member tasks, skipping refs that are not `section:task_id`
+    pub fn task_keys(&self) -> Vec<TaskKey> {
+        self.task_refs.iter()
+            .filter_map(|task_ref| task_ref.split_once(':'))
+            .map(|(section, task_id)| TaskKey::new(section, task_id))
+            .collect()
+    }
+}
+
+/// Reference to a task in `Milestone::task_refs`
+fn milestone_ref(section: &str, task_id: &str) -> String {
+    format!("{}:{}", section, task_id)
+}
+
+/// Where a recorded task change came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSource {
+    Manual,
+    Scan,
+}
+
+/// Where a task's title and description come from. Scans only rewrite label-derived text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptionSource {
+    Manual,
+    #[default]
+    Label,
+}
+
+/// One field-level change in a task's history. `old` is absent for a new task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskChange {
+    pub field: String,
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+    pub timestamp: DateTime<Utc>,
+    pub source: ChangeSource,
+}
+
+impl TaskChange {
+    /// A task moving into or out of the trash
+    fn trashed(trashed: bool) -> TaskChange {
+        TaskChange {
+            field: "trashed".to_string(),
+            old: Some(serde_json::json!(!trashed)),
+            new: Some(serde_json::json!(trashed)),
+            timestamp: Utc::now(),
+            source: ChangeSource::Manual,
+        }
+    }
+
+    /// A task given a new ID within its section
+    fn renamed(old_id: &str, new_id: &str) -> TaskChange {
+        TaskChange {
+            field: "task_id".to_string(),
+            old: Some(serde_json::json!(old_id)),
+            new: Some(serde_json::json!(new_id)),
+            timestamp: Utc::now(),
+            source: ChangeSource::Manual,
+        }
+    }
+
+    /// Whether this change moved a task to done
+    pub fn is_completion(&self) -> bool {
+        self.field == "status" && self.new == Some(serde_json::json!(TaskStatus::Done))
+    }
+
+    /// Changes from `before` to `after`, or a single `created` entry for a new task.
+    /// File references are summarized as one `references` entry listing the referencing files.
+    pub fn diff(before: Option<&Task>, after: &Task, source: ChangeSource) -> Vec<TaskChange> {
+        let timestamp = Utc::now();
+        let change = |field: &str, old: Option<serde_json::Value>, new: serde_json::Value| TaskChange {
+            field: field.to_string(),
+            old: old.filter(|value| !value.is_null()),
+            new: Some(new).filter(|value| !value.is_null()),
+            timestamp,
+            source,
+        };
+        let Some(before) = before else {
+            return vec![change("created", None, serde_json::json!(after.title))];
+        };
+        before.tracked_fields().into_iter()
+            .zip(after.tracked_fields())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| change(field, Some(old), new))
+            .collect()
+    }
 }
 
 impl Task {
@@ -112,6 +765,20 @@ impl Task {
             created: now,
             updated: now,
             files: HashMap::new(),
+            assignee: None,
+            tags: Vec::new(),
+            priority: None,
+            dependencies: Vec::new(),
+            parent: None,
+            metadata: HashMap::new(),
+            comments: Vec::new(),
+            rank: None,
+            related_notes: Vec::new(),
+            links: Vec::new(),
+            estimate_minutes: None,
+            time_spent_minutes: 0,
+            time_entries: Vec::new(),
+            description_source: DescriptionSource::default(),
         }
     }
 
@@ -119,6 +786,7 @@ impl Task {
         let task_file = self.files.entry(file_path).or_insert_with(|| TaskFile {
             lines: Vec::new(),
             notes: HashMap::new(),
+            label_texts: Vec::new(),
         });
         if !task_file.lines.contains(&line) {
             task_file.lines.push(line);
@@ -133,14 +801,82 @@ impl Task {
         self.status = status;
         self.updated = Utc::now();
     }
+
+    /// Fields recorded in the task history, in a fixed order
+    fn tracked_fields(&self) -> [(&'static str, serde_json::Value); 11] {
+        let mut references: Vec<&String> = self.files.iter()
+            .filter(|(_, task_file)| !task_file.lines.is_empty())
+            .map(|(file, _)| file)
+            .collect();
+        references.sort();
+        [
+            ("title", serde_json::json!(self.title)),
+            ("description", serde_json::json!(self.description)),
+            ("status", serde_json::json!(self.status)),
+            ("priority", serde_json::json!(self.priority)),
+            ("assignee", serde_json::json!(self.assignee)),
+            ("tags", serde_json::json!(self.tags)),
+            ("parent", serde_json::json!(self.parent.as_ref().map(ToString::to_string))),
+            ("dependencies", serde_json::json!(self.dependencies)),
+            ("estimate_minutes", serde_json::json!(self.estimate_minutes)),
+            ("time_spent_minutes", serde_json::json!(self.time_spent_minutes)),
+            ("references", serde_json::json!(references)),
+        ]
+    }
+
+    /// Whether the task carries any of the given tags, compared after normalization
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        self.tags.iter().any(|tag| tags.iter().any(|wanted| normalize_tag(wanted).as_deref() == Some(tag.as_str())))
+    }
+}
+
+/// Canonical form of a tag: trimmed and lowercased. Returns `None` for blank tags.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        None
+    } else {
+        Some(tag.to_lowercase())
+    }
+}
+
+/// Escape a title so Markdown renders it as plain text on a single heading line
+pub(crate) fn escape_markdown_heading(title: &str) -> String {
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return "(untitled)".to_string();
+    }
+    let mut escaped = String::with_capacity(title.len());
+    for c in title.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '!' | '|' | '~') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
 pub type TaskSection = HashMap<String, Task>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskIndex {
-    pub files: HashMap<String, Vec<String>>,
-    pub tasks_by_status: HashMap<TaskStatus, Vec<String>>,
+    #[serde(serialize_with = "serialize_sorted_sets")]
+    pub files: HashMap<String, HashSet<String>>,
+    #[serde(serialize_with = "serialize_sorted_sets")]
+    pub tasks_by_status: HashMap<TaskStatus, HashSet<String>>,
+}
+
+/// Write each set as a sorted array, keeping the array form tasks.json has always had
+fn serialize_sorted_sets<K, S>(map: &HashMap<K, HashSet<String>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    K: Serialize,
+    S: serde::Serializer,
+{
+    serializer.collect_map(map.iter().map(|(key, ids)| {
+        let mut ids: Vec<&String> = ids.iter().collect();
+        ids.sort();
+        (key, ids)
+    }))
 }
 
 impl TaskIndex {
@@ -152,24 +888,97 @@ impl TaskIndex {
         }
     }
 
+    /// Index a task, replacing whatever was indexed for it before
     pub fn update_task(&mut self, section: &str, task_id: &str, task: &Task) {
-        let full_task_id = format!("{}.{}", section, task_id);
+        self.remove_task(section, task_id);
+        self.insert_task(section, task_id, task);
+    }
+
+    /// Index a task known not to be indexed yet. Unlike `update_task` it does not
+    /// scan every file and status for an earlier entry, so full rebuilds stay linear.
+    fn insert_task(&mut self, section: &str, task_id: &str, task: &Task) {
+        let full_task_id = alias_key(section, task_id);
         for file_path in task.files.keys() {
             self.files
                 .entry(file_path.clone())
-                .or_insert_with(Vec::new)
-                .push(full_task_id.clone());
+                .or_default()
+                .insert(full_task_id.clone());
         }
         self.tasks_by_status
             .entry(task.status.clone())
-            .or_insert_with(Vec::new)
-            .push(full_task_id);
+            .or_default()
+            .insert(full_task_id);
+    }
+
+    /// Drop a task from the index, along with files and statuses left without tasks
+    pub fn remove_task(&mut self, section: &str, task_id: &str) {
+        let full_task_id = alias_key(section, task_id);
+        self.files.retain(|_, ids| {
+            ids.remove(&full_task_id);
+            !ids.is_empty()
+        });
+        self.tasks_by_status.retain(|_, ids| {
+            ids.remove(&full_task_id);
+            !ids.is_empty()
+        });
     }
 
     pub fn clear(&mut self) {
         self.files.clear();
         self.tasks_by_status.clear();
     }
+
+    /// Files referenced by the indexed tasks of a section, with how many of them reference each
+    pub fn section_files(&self, section: &str) -> Vec<(String, usize)> {
+        let in_section = |full_task_id: &String| {
+            full_task_id.parse::<TaskKey>().is_ok_and(|key| key.section == section)
+        };
+        let mut files: Vec<(String, usize)> = self.files
+            .iter()
+            .map(|(file_path, ids)| (file_path.clone(), ids.iter().filter(|id| in_section(id)).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Number of tasks in each status for every indexed file, sorted by path.
+    /// Reads only the index, never the tasks themselves.
+    pub fn file_status_counts(&self) -> Vec<(String, HashMap<TaskStatus, usize>)> {
+        let status_of: HashMap<&String, &TaskStatus> = self.tasks_by_status
+            .iter()
+            .flat_map(|(status, task_ids)| task_ids.iter().map(move |id| (id, status)))
+            .collect();
+        let mut files: Vec<(String, HashMap<TaskStatus, usize>)> = self.files
+            .iter()
+            .filter_map(|(file_path, task_ids)| {
+                let mut counts = HashMap::new();
+                for status in task_ids.iter().filter_map(|id| status_of.get(id)) {
+                    *counts.entry((*status).clone()).or_insert(0) += 1;
+                }
+                (!counts.is_empty()).then(|| (file_path.clone(), counts))
+            })
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        files
+    }
+}
+
+/// Task ID change planned by `ProjectData::plan_id_normalization`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdRename {
+    pub section: String,
+    pub old_id: String,
+    pub new_id: String,
+}
+
+/// Rename that was skipped because its target ID is not free
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdCollision {
+    pub section: String,
+    pub old_id: String,
+    pub new_id: String,
+    pub reason: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -199,6 +1008,20 @@ pub struct ProjectData {
     pub index: TaskIndex,
     #[serde(default)]
     pub notes: HashMap<String, Note>,
+    /// Sections hidden from default listings; their tasks are kept untouched
+    #[serde(default)]
+    pub archived_sections: HashSet<String>,
+    /// Soft-deleted tasks, outside every listing, search and statistic
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<TrashedTask>,
+    /// Former `section.task_id` of renamed, moved or merged tasks, mapped to the current one
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Field-level changes by `section.task_id`, oldest first
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub history: HashMap<String, Vec<TaskChange>>,
+    #[serde(default)]
+    pub milestones: HashMap<String, Milestone>,
 }
 
 impl ProjectData {
@@ -210,16 +1033,36 @@ impl ProjectData {
             sections: HashMap::new(),
             index: TaskIndex::new(),
             notes: HashMap::new(),
+            archived_sections: HashSet::new(),
+            trash: Vec::new(),
+            aliases: HashMap::new(),
+            history: HashMap::new(),
+            milestones: HashMap::new(),
         }
     }
 
     pub fn add_task(&mut self, section: &str, task_id: &str, title: String, description: Option<String>) -> anyhow::Result<()> {
-        let task = Task::new(title, description);
+        if self.get_task(section, task_id).is_some() {
+            return Err(anyhow::anyhow!("Task already exists: {}:{}", section, task_id));
+        }
+        self.replace_task(section, task_id, title, description)
+    }
+
+    /// Insert a task, discarding any existing task with the same id
+    pub fn replace_task(&mut self, section: &str, task_id: &str, title: String, description: Option<String>) -> anyhow::Result<()> {
+        let mut task = Task::new(title, description);
+        let last_rank = self.sections.get(section)
+            .into_iter()
+            .flat_map(|tasks| tasks.iter())
+            .filter(|(id, _)| id.as_str() != task_id)
+            .filter_map(|(_, task)| task.rank)
+            .max_by(f64::total_cmp);
+        task.rank = Some(last_rank.unwrap_or(0.0) + RANK_STEP);
         self.sections
             .entry(section.to_string())
             .or_insert_with(HashMap::new)
-            .insert(task_id.to_string(), task.clone());
-        self.index.update_task(section, task_id, &task);
+            .insert(task_id.to_string(), task);
+        self.reindex_task(section, task_id);
         self.meta.last_updated = Utc::now();
         Ok(())
     }
@@ -232,50 +1075,1336 @@ impl ProjectData {
         self.sections.get_mut(section)?.get_mut(task_id)
     }
 
-    pub fn update_task_file(&mut self, section: &str, task_id: &str, file_path: String, line: u32, note: Option<String>) -> anyhow::Result<()> {
-        if let Some(task) = self.get_task_mut(section, task_id) {
-            task.add_file(file_path, line, note);
-            self.meta.last_updated = Utc::now();
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id))
+    /// Current coordinates of a renamed task, when no task lives at the old ones
+    pub fn resolve_alias(&self, section: &str, task_id: &str) -> Option<TaskKey> {
+        if self.get_task(section, task_id).is_some() {
+            return None;
         }
+        let key: TaskKey = self.aliases.get(&alias_key(section, task_id))?.parse().ok()?;
+        self.get_task(&key.section, &key.task_id).map(|_| key)
     }
 
-    pub fn update_task_status(&mut self, section: &str, task_id: &str, status: TaskStatus) -> anyhow::Result<()> {
-        if let Some(task) = self.get_task_mut(section, task_id) {
-            task.update_status(status);
-            self.meta.last_updated = Utc::now();
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id))
-        }
+    /// Task by its current or a former ID, with the coordinates it lives at now
+    pub fn lookup_task(&self, section: &str, task_id: &str) -> Option<(TaskKey, &Task)> {
+        let key = self.resolve_alias(section, task_id).unwrap_or_else(|| TaskKey::new(section, task_id));
+        let task = self.get_task(&key.section, &key.task_id)?;
+        Some((key, task))
     }
 
-    pub fn delete_task(&mut self, section: &str, task_id: &str) -> anyhow::Result<()> {
-        if !self.sections.contains_key(section) {
-            return Err(anyhow::anyhow!("Section not found: {}", section));
+    /// Append changes to a task's history, keeping the newest `MAX_TASK_HISTORY` entries
+    pub fn record_changes(&mut self, section: &str, task_id: &str, changes: Vec<TaskChange>) {
+        if changes.is_empty() {
+            return;
         }
-        let section_tasks = self.sections.get_mut(section).unwrap();
-        if !section_tasks.contains_key(task_id) {
-            return Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id));
+        let entries = self.history.entry(alias_key(section, task_id)).or_default();
+        entries.extend(changes);
+        if entries.len() > MAX_TASK_HISTORY {
+            entries.drain(..entries.len() - MAX_TASK_HISTORY);
+        }
+    }
+
+    /// Run an edit of one task and record the fields it changed
+    pub fn track_changes<R>(&mut self, section: &str, task_id: &str, source: ChangeSource, edit: impl FnOnce(&mut Self) -> anyhow::Result<R>) -> anyhow::Result<R> {
+        let before = self.get_task(section, task_id).cloned();
+        let result = edit(self)?;
+        if let Some(after) = self.get_task(section, task_id) {
+            let changes = TaskChange::diff(before.as_ref(), after, source);
+            self.record_changes(section, task_id, changes);
+        }
+        Ok(result)
+    }
+
+    /// Record what changed on every live task since `before`, one summarized entry per field
+    pub fn record_changes_since(&mut self, before: &HashMap<String, TaskSection>, source: ChangeSource) {
+        let mut changed = Vec::new();
+        for (section_name, section) in &self.sections {
+            for (task_id, task) in section {
+                let previous = before.get(section_name).and_then(|tasks| tasks.get(task_id));
+                let changes = TaskChange::diff(previous, task, source);
+                if !changes.is_empty() {
+                    changed.push((section_name.clone(), task_id.clone(), changes));
+                }
+            }
+        }
+        for (section, task_id, changes) in changed {
+            self.record_changes(&section, &task_id, changes);
+        }
+    }
+
+    /// Recorded changes of every live task, paired with the task's section
+    pub fn section_history(&self) -> impl Iterator<Item = (&String, &TaskChange)> {
+        self.sections.iter().flat_map(move |(section_name, section)| {
+            section
+                .keys()
+                .filter_map(move |task_id| self.history.get(&alias_key(section_name, task_id)))
+                .flatten()
+                .map(move |change| (section_name, change))
+        })
+    }
+
+    /// History of a task by its current or a former ID, newest first
+    pub fn task_history(&self, section: &str, task_id: &str, limit: Option<usize>) -> Vec<&TaskChange> {
+        let key = self.resolve_alias(section, task_id).unwrap_or_else(|| TaskKey::new(section, task_id));
+        self.history.get(&alias_key(&key.section, &key.task_id))
+            .map(|entries| entries.iter().rev().take(limit.unwrap_or(usize::MAX)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remember old → new coordinates, redirecting earlier aliases of the old ones too.
+    /// Aliases whose old coordinates hold a task again stop redirecting.
+    fn record_aliases(&mut self, renames: &[(TaskKey, TaskKey)]) {
+        let renamed: HashMap<String, String> = renames.iter()
+            .map(|(old, new)| (alias_key(&old.section, &old.task_id), alias_key(&new.section, &new.task_id)))
+            .collect();
+        for target in self.aliases.values_mut() {
+            if let Some(new) = renamed.get(target) {
+                *target = new.clone();
+            }
+        }
+        for (old, new) in &renamed {
+            if let Some(mut moved) = self.history.remove(old) {
+                let entries = self.history.entry(new.clone()).or_default();
+                moved.append(entries);
+                moved.sort_by_key(|change| change.timestamp);
+                *entries = moved;
+            }
+        }
+        self.aliases.extend(renamed);
+        let sections = &self.sections;
+        self.aliases.retain(|old, new| {
+            let live = old.parse::<TaskKey>()
+                .is_ok_and(|key| sections.get(&key.section).is_some_and(|tasks| tasks.contains_key(&key.task_id)));
+            old != new && !live
+        });
+    }
+
+    /// Move a task's references in `file` by `(from, to)` line pairs, taking their notes along.
+    /// All moves apply at once, so a reference may move onto a line another one leaves.
+    pub fn move_task_references(&mut self, section: &str, task_id: &str, file: &str, moves: &[(u32, u32)]) -> anyhow::Result<()> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let task_file = task.files.get_mut(file)
+            .ok_or_else(|| anyhow::anyhow!("Task {}:{} has no references in {}", section, task_id, file))?;
+        if let Some((from, _)) = moves.iter().find(|(from, _)| !task_file.lines.contains(from)) {
+            return Err(anyhow::anyhow!("Task {}:{} has no reference at {}:{}", section, task_id, file, from));
+        }
+        let moved_notes: Vec<(u32, Option<String>)> = moves.iter()
+            .map(|(from, to)| (*to, task_file.notes.remove(from)))
+            .collect();
+        let mut lines = Vec::with_capacity(task_file.lines.len());
+        for line in &task_file.lines {
+            let line = moves.iter().find(|(from, _)| from == line).map_or(*line, |(_, to)| *to);
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+        task_file.lines = lines;
+        for (to, note) in moved_notes {
+            if let Some(note) = note {
+                task_file.notes.insert(to, note);
+            }
+        }
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    pub fn update_task_file(&mut self, section: &str, task_id: &str, file_path: String, line: u32, note: Option<String>) -> anyhow::Result<()> {
+        if let Some(task) = self.get_task_mut(section, task_id) {
+            task.add_file(file_path, line, note);
+            self.reindex_task(section, task_id);
+            self.meta.last_updated = Utc::now();
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id))
+        }
+    }
+
+    pub fn update_task_status(&mut self, section: &str, task_id: &str, status: TaskStatus) -> anyhow::Result<()> {
+        if let Some(task) = self.get_task_mut(section, task_id) {
+            task.update_status(status);
+            self.reindex_task(section, task_id);
+            self.meta.last_updated = Utc::now();
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id))
+        }
+    }
+
+    /// Edit a task's title or description by hand. Later scans keep the edited text.
+    pub fn edit_task_text(&mut self, section: &str, task_id: &str, title: Option<String>, description: Option<String>) -> anyhow::Result<()> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        if let Some(title) = title {
+            task.title = title;
+        }
+        if description.is_some() {
+            task.description = description;
+        }
+        task.description_source = DescriptionSource::Manual;
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Apply the text `file_path` labels a task with. Label-derived titles follow edits to the
+    /// label; a manually edited task keeps its text. Only a file that labelled the task before
+    /// can edit it, and a text that some file still labels the task with is not an edit, so
+    /// files that disagree leave the title alone. Returns true when an edited label was ignored
+    /// because of a manual edit, which is reported once per label change as long as the scan
+    /// records the text with `record_label_texts`.
+    pub fn apply_label_text(&mut self, section: &str, task_id: &str, file_path: &str, text: &str) -> anyhow::Result<bool> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let labelled_before = task.files.get(file_path).is_some_and(|file| !file.label_texts.is_empty());
+        if !labelled_before || task.files.values().any(|file| file.label_texts.iter().any(|known| known == text)) {
+            return Ok(false);
+        }
+        if task.description_source == DescriptionSource::Manual {
+            return Ok(task.title != text);
+        }
+        task.title = text.to_string();
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(false)
+    }
+
+    /// Replace the label texts recorded for `file_path` with the ones its latest scan found
+    pub fn record_label_texts(&mut self, file_path: &str, mut texts: HashMap<TaskKey, Vec<String>>) {
+        for (section_name, section) in &mut self.sections {
+            for (task_id, task) in section.iter_mut() {
+                if let Some(task_file) = task.files.get_mut(file_path) {
+                    task_file.label_texts = texts
+                        .remove(&TaskKey::new(section_name, task_id))
+                        .unwrap_or_default();
+                }
+            }
+        }
+    }
+
+    pub fn delete_task(&mut self, section: &str, task_id: &str) -> anyhow::Result<()> {
+        if !self.sections.contains_key(section) {
+            return Err(anyhow::anyhow!("Section not found: {}", section));
+        }
+        let section_tasks = self.sections.get_mut(section).unwrap();
+        if !section_tasks.contains_key(task_id) {
+            return Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id));
         }
         section_tasks.remove(task_id);
         if section_tasks.is_empty() {
             self.sections.remove(section);
         }
+        let key = TaskKey::new(section, task_id);
+        for task in self.sections.values_mut().flat_map(|tasks| tasks.values_mut()) {
+            if task.parent.as_ref() == Some(&key) {
+                task.parent = None;
+                task.updated = Utc::now();
+            }
+            if task.dependencies.contains(&key) {
+                task.dependencies.retain(|dependency| *dependency != key);
+                task.updated = Utc::now();
+            }
+        }
+        for note in self.notes.values_mut() {
+            note.related_tasks.retain(|related| *related != key);
+        }
+        let task_ref = milestone_ref(section, task_id);
+        for milestone in self.milestones.values_mut() {
+            milestone.task_refs.retain(|member| *member != task_ref);
+        }
+        self.meta.last_updated = Utc::now();
+        self.index.remove_task(section, task_id);
+        Ok(())
+    }
+
+    /// Delete a task together with its subtasks at every level. Returns the removed tasks.
+    pub fn delete_task_with_subtasks(&mut self, section: &str, task_id: &str) -> anyhow::Result<Vec<TaskKey>> {
+        self.remove_with_subtasks(section, task_id, Self::delete_task)
+    }
+
+    /// Move a task and its subtasks at every level to the trash. Returns the trashed tasks.
+    pub fn trash_task_with_subtasks(&mut self, section: &str, task_id: &str) -> anyhow::Result<Vec<TaskKey>> {
+        self.remove_with_subtasks(section, task_id, Self::trash_task)
+    }
+
+    fn remove_with_subtasks(
+        &mut self,
+        section: &str,
+        task_id: &str,
+        remove: fn(&mut Self, &str, &str) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Vec<TaskKey>> {
+        if self.get_task(section, task_id).is_none() {
+            return Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id));
+        }
+        let mut removed = vec![TaskKey::new(section, task_id)];
+        removed.extend(self.descendants_of(section, task_id));
+        for key in removed.iter().rev() {
+            remove(self, &key.section, &key.task_id)?;
+        }
+        Ok(removed)
+    }
+
+    /// Delete a task but keep a copy in the trash, replacing an older copy of the same task.
+    /// Links from other tasks and notes are dropped as with `delete_task`.
+    pub fn trash_task(&mut self, section: &str, task_id: &str) -> anyhow::Result<()> {
+        let task = self.get_task(section, task_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let task_ref = milestone_ref(section, task_id);
+        let mut milestones: Vec<String> = self.milestones.values()
+            .filter(|milestone| milestone.task_refs.contains(&task_ref))
+            .map(|milestone| milestone.id.clone())
+            .collect();
+        milestones.sort();
+        let key = TaskKey::new(section, task_id);
+        let mut dependents: Vec<TaskKey> = self.sections.iter()
+            .flat_map(|(section_name, tasks)| tasks.iter().map(move |(id, task)| (section_name, id, task)))
+            .filter(|(_, _, task)| task.dependencies.contains(&key))
+            .map(|(section_name, id, _)| TaskKey::new(section_name, id))
+            .collect();
+        dependents.sort();
+        self.delete_task(section, task_id)?;
+        self.trash.retain(|entry| entry.key != key);
+        self.trash.push(TrashedTask { key, task, deleted_at: Utc::now(), milestones, dependents });
+        self.record_changes(section, task_id, vec![TaskChange::trashed(true)]);
+        Ok(())
+    }
+
+    pub fn is_trashed(&self, section: &str, task_id: &str) -> bool {
+        self.trash.iter().any(|entry| entry.key.section == section && entry.key.task_id == task_id)
+    }
+
+    /// Move a task out of the trash. Its parent is kept if it still exists, its
+    /// links to notes that still exist are restored on both sides, it rejoins
+    /// the milestones that still exist and tasks that depended on it do again.
+    pub fn restore_task(&mut self, section: &str, task_id: &str) -> anyhow::Result<()> {
+        if self.get_task(section, task_id).is_some() {
+            return Err(anyhow::anyhow!("Task already exists: {}:{}", section, task_id));
+        }
+        let position = self.trash.iter()
+            .position(|entry| entry.key.section == section && entry.key.task_id == task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not in trash: {}:{}", section, task_id))?;
+        let TrashedTask { key, mut task, milestones, dependents, .. } = self.trash.remove(position);
+        if let Some(parent) = &task.parent && self.get_task(&parent.section, &parent.task_id).is_none() {
+            task.parent = None;
+        }
+        task.related_notes.retain(|note_id| self.notes.contains_key(note_id));
+        for note_id in &task.related_notes {
+            if let Some(note) = self.notes.get_mut(note_id) && !note.related_tasks.contains(&key) {
+                note.related_tasks.push(key.clone());
+            }
+        }
+        let task_ref = milestone_ref(&key.section, &key.task_id);
+        for milestone_id in milestones {
+            if let Some(milestone) = self.milestones.get_mut(&milestone_id) && !milestone.task_refs.contains(&task_ref) {
+                milestone.task_refs.push(task_ref.clone());
+            }
+        }
+        for dependent in dependents {
+            if let Some(dependent) = self.get_task_mut(&dependent.section, &dependent.task_id)
+                && !dependent.dependencies.contains(&key)
+            {
+                dependent.dependencies.push(key.clone());
+                dependent.updated = Utc::now();
+            }
+        }
+        self.sections.entry(key.section.clone()).or_default().insert(key.task_id.clone(), task);
+        self.reindex_task(&key.section, &key.task_id);
+        self.record_changes(&key.section, &key.task_id, vec![TaskChange::trashed(false)]);
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Permanently drop trashed tasks, only those deleted more than `older_than_days` ago
+    /// when given. Returns the dropped tasks.
+    pub fn empty_trash(&mut self, older_than_days: Option<u32>) -> Vec<TaskKey> {
+        let cutoff = older_than_days.map(|days| Utc::now() - Duration::days(days as i64));
+        let (dropped, kept): (Vec<TrashedTask>, Vec<TrashedTask>) = std::mem::take(&mut self.trash)
+            .into_iter()
+            .partition(|entry| cutoff.is_none_or(|cutoff| entry.deleted_at < cutoff));
+        self.trash = kept;
+        let dropped: Vec<TaskKey> = dropped.into_iter().map(|entry| entry.key).collect();
+        for key in &dropped {
+            if self.get_task(&key.section, &key.task_id).is_none() {
+                self.history.remove(&alias_key(&key.section, &key.task_id));
+            }
+        }
+        if !dropped.is_empty() {
+            self.meta.last_updated = Utc::now();
+        }
+        dropped
+    }
+
+    pub fn section_files(&self, section: &str) -> anyhow::Result<Vec<(String, usize)>> {
+        let tasks = self.sections
+            .get(section)
+            .ok_or_else(|| anyhow::anyhow!("Section not found: {}", section))?;
+        if !self.archived_sections.contains(section) {
+            return Ok(self.index.section_files(section));
+        }
+        // The index leaves archived sections out
+        let mut counts: HashMap<&String, usize> = HashMap::new();
+        for task in tasks.values() {
+            for file_path in task.files.keys() {
+                *counts.entry(file_path).or_insert(0) += 1;
+            }
+        }
+        let mut files: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(file_path, count)| (file_path.clone(), count))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    pub fn clone_section(&mut self, source: &str, target: &str) -> anyhow::Result<usize> {
+        if self.sections.contains_key(target) {
+            return Err(anyhow::anyhow!("Section already exists: {}", target));
+        }
+        let source_tasks = self.sections.get(source)
+            .ok_or_else(|| anyhow::anyhow!("Section not found: {}", source))?;
+        let cloned: TaskSection = source_tasks
+            .iter()
+            .map(|(task_id, task)| {
+                let mut copy = Task::new(task.title.clone(), task.description.clone());
+                copy.assignee = task.assignee.clone();
+                copy.tags = task.tags.clone();
+                (task_id.clone(), copy)
+            })
+            .collect();
+        let count = cloned.len();
+        self.sections.insert(target.to_string(), cloned);
         self.meta.last_updated = Utc::now();
         self.rebuild_index();
+        Ok(count)
+    }
+
+    pub fn archive_section(&mut self, section: &str) -> anyhow::Result<()> {
+        if !self.sections.contains_key(section) {
+            return Err(anyhow::anyhow!("Section not found: {}", section));
+        }
+        if !self.archived_sections.insert(section.to_string()) {
+            return Err(anyhow::anyhow!("Section already archived: {}", section));
+        }
+        for task_id in self.sections[section].keys() {
+            self.index.remove_task(section, task_id);
+        }
+        self.meta.last_updated = Utc::now();
         Ok(())
     }
 
-    pub fn rebuild_index(&mut self) {
-        self.index.clear();
-        for (section_name, section) in &self.sections {
-            for (task_id, task) in section {
-                self.index.update_task(section_name, task_id, task);
+    pub fn unarchive_section(&mut self, section: &str) -> anyhow::Result<()> {
+        if !self.archived_sections.remove(section) {
+            return Err(anyhow::anyhow!("Section is not archived: {}", section));
+        }
+        if let Some(tasks) = self.sections.get(section) {
+            for (task_id, task) in tasks {
+                self.index.insert_task(section, task_id, task);
+            }
+        }
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    pub fn is_section_archived(&self, section: &str) -> bool {
+        self.archived_sections.contains(section)
+    }
+
+    /// Section names in the given order, ties broken alphabetically
+    pub fn ordered_section_names(&self, order: SectionOrder) -> Vec<&String> {
+        let mut names: Vec<&String> = self.sections.keys().collect();
+        names.sort();
+        match order {
+            SectionOrder::Alphabetical => {}
+            SectionOrder::Creation => names.sort_by_cached_key(|name| {
+                let first_created = self.sections[*name].values().map(|task| task.created).min();
+                (first_created.is_none(), first_created)
+            }),
+            SectionOrder::TaskCount => names.sort_by_key(|name| std::cmp::Reverse(self.sections[*name].len())),
+        }
+        names
+    }
+
+    /// Drop archived sections from this copy of the data, for responses that leave them out
+    pub fn hide_archived_sections(&mut self) {
+        let archived = &self.archived_sections;
+        self.sections.retain(|name, _| !archived.contains(name));
+    }
+
+    pub fn create_milestone(&mut self, id: &str, title: &str, due: Option<DateTime<Utc>>) -> anyhow::Result<()> {
+        let id = id.trim();
+        let title = title.trim();
+        if id.is_empty() {
+            return Err(anyhow::anyhow!("Milestone ID cannot be empty"));
+        }
+        if title.is_empty() {
+            return Err(anyhow::anyhow!("Milestone title cannot be empty"));
+        }
+        if self.milestones.contains_key(id) {
+            return Err(anyhow::anyhow!("Milestone already exists: {}", id));
+        }
+        self.milestones.insert(id.to_string(), Milestone {
+            id: id.to_string(),
+            title: title.to_string(),
+            due,
+            task_refs: Vec::new(),
+        });
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Remove a milestone; its tasks are left as they are
+    pub fn delete_milestone(&mut self, id: &str) -> anyhow::Result<Milestone> {
+        let id = id.trim();
+        let milestone = self.milestones.remove(id)
+            .ok_or_else(|| anyhow::anyhow!("Milestone not found: {}", id))?;
+        self.meta.last_updated = Utc::now();
+        Ok(milestone)
+    }
+
+    /// Add a task, resolving renamed IDs, to a milestone. Returns false if it was already a member.
+    pub fn add_task_to_milestone(&mut self, milestone_id: &str, section: &str, task_id: &str) -> anyhow::Result<bool> {
+        let (key, _) = self.lookup_task(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let milestone = self.milestones.get_mut(milestone_id)
+            .ok_or_else(|| anyhow::anyhow!("Milestone not found: {}", milestone_id))?;
+        let task_ref = milestone_ref(&key.section, &key.task_id);
+        if milestone.task_refs.contains(&task_ref) {
+            return Ok(false);
+        }
+        milestone.task_refs.push(task_ref);
+        self.meta.last_updated = Utc::now();
+        Ok(true)
+    }
+
+    /// Take a task out of a milestone. Returns false if it was not a member.
+    pub fn remove_task_from_milestone(&mut self, milestone_id: &str, section: &str, task_id: &str) -> anyhow::Result<bool> {
+        let key = self.resolve_alias(section, task_id).unwrap_or_else(|| TaskKey::new(section, task_id));
+        let milestone = self.milestones.get_mut(milestone_id)
+            .ok_or_else(|| anyhow::anyhow!("Milestone not found: {}", milestone_id))?;
+        let task_ref = milestone_ref(&key.section, &key.task_id);
+        let before = milestone.task_refs.len();
+        milestone.task_refs.retain(|member| *member != task_ref);
+        let removed = milestone.task_refs.len() != before;
+        if removed {
+            self.meta.last_updated = Utc::now();
+        }
+        Ok(removed)
+    }
+
+    /// Work out which task IDs change under the given style and which cannot
+    pub fn plan_id_normalization(&self, style: IdStyle) -> (Vec<IdRename>, Vec<IdCollision>) {
+        let mut renames = Vec::new();
+        let mut collisions = Vec::new();
+        let mut section_names: Vec<&String> = self.sections.keys().collect();
+        section_names.sort();
+        for section_name in section_names {
+            let mut by_target: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+            for task_id in self.sections[section_name].keys() {
+                by_target.entry(style.apply(task_id)).or_default().push(task_id);
+            }
+            for (target, mut sources) in by_target {
+                sources.sort();
+                let target_stays = sources.iter().any(|id| **id == target);
+                let movers: Vec<&String> = sources.into_iter().filter(|id| **id != target).collect();
+                if movers.is_empty() {
+                    continue;
+                }
+                let reason = if target.is_empty() {
+                    Some("ID has no letters or digits to normalize".to_string())
+                } else if target_stays {
+                    Some(format!("Task '{}' already exists", target))
+                } else if movers.len() > 1 {
+                    let names: Vec<&str> = movers.iter().map(|id| id.as_str()).collect();
+                    Some(format!("IDs {} all normalize to '{}'", names.join(", "), target))
+                } else {
+                    None
+                };
+                for old_id in movers {
+                    match &reason {
+                        Some(reason) => collisions.push(IdCollision {
+                            section: section_name.clone(),
+                            old_id: old_id.clone(),
+                            new_id: target.clone(),
+                            reason: reason.clone(),
+                        }),
+                        None => renames.push(IdRename {
+                            section: section_name.clone(),
+                            old_id: old_id.clone(),
+                            new_id: target.clone(),
+                        }),
+                    }
+                }
+            }
+        }
+        (renames, collisions)
+    }
+
+    /// Apply planned renames, carrying notes and dependencies that point at the old IDs along
+    pub fn apply_id_renames(&mut self, renames: &[IdRename]) -> anyhow::Result<()> {
+        let mut moved = Vec::with_capacity(renames.len());
+        for rename in renames {
+            let task = self.sections.get_mut(&rename.section)
+                .and_then(|section| section.remove(&rename.old_id))
+                .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", rename.section, rename.old_id))?;
+            moved.push((rename, task));
+        }
+        for (rename, task) in moved {
+            let section = self.sections.entry(rename.section.clone()).or_default();
+            if section.contains_key(&rename.new_id) {
+                return Err(anyhow::anyhow!("Task already exists: {}:{}", rename.section, rename.new_id));
+            }
+            section.insert(rename.new_id.clone(), task);
+        }
+        for rename in renames {
+            self.retarget_references(&rename.section, &rename.old_id, &rename.section, &rename.new_id);
+        }
+        let aliases: Vec<(TaskKey, TaskKey)> = renames.iter()
+            .map(|rename| (TaskKey::new(&rename.section, &rename.old_id), TaskKey::new(&rename.section, &rename.new_id)))
+            .collect();
+        self.record_aliases(&aliases);
+        for rename in renames {
+            self.index.remove_task(&rename.section, &rename.old_id);
+            self.reindex_task(&rename.section, &rename.new_id);
+            self.record_changes(&rename.section, &rename.new_id, vec![TaskChange::renamed(&rename.old_id, &rename.new_id)]);
+        }
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    pub fn add_task_comment(&mut self, section: &str, task_id: &str, author: &str, text: &str) -> anyhow::Result<TaskComment> {
+        let author = author.trim();
+        let text = text.trim();
+        if author.is_empty() {
+            return Err(anyhow::anyhow!("Comment author cannot be empty"));
+        }
+        if text.is_empty() {
+            return Err(anyhow::anyhow!("Comment text cannot be empty"));
+        }
+        if text.chars().count() > MAX_COMMENT_LENGTH {
+            return Err(anyhow::anyhow!("Comment is longer than {} characters", MAX_COMMENT_LENGTH));
+        }
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let comment = TaskComment {
+            id: Uuid::new_v4().to_string(),
+            author: author.to_string(),
+            text: text.to_string(),
+            created: Utc::now(),
+        };
+        task.comments.push(comment.clone());
+        if task.comments.len() > MAX_COMMENTS_PER_TASK {
+            let excess = task.comments.len() - MAX_COMMENTS_PER_TASK;
+            task.comments.drain(..excess);
+        }
+        self.meta.last_updated = Utc::now();
+        Ok(comment)
+    }
+
+    pub fn delete_task_comment(&mut self, section: &str, task_id: &str, comment_id: &str) -> anyhow::Result<()> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let count = task.comments.len();
+        task.comments.retain(|comment| comment.id != comment_id);
+        if task.comments.len() == count {
+            return Err(anyhow::anyhow!("Comment not found: {}", comment_id));
+        }
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Drop comments, the trash and task history from this copy of the data, for
+    /// listings that return every task
+    pub fn strip_for_listing(&mut self) {
+        for task in self.sections.values_mut().flat_map(|section| section.values_mut()) {
+            task.comments.clear();
+        }
+        self.trash.clear();
+        self.history.clear();
+    }
+
+    /// Attach a link to a task, relabelling it when the URL is already attached.
+    /// Returns the task's links; a blank label falls back to the URL.
+    pub fn add_task_link(&mut self, section: &str, task_id: &str, label: &str, url: &str) -> anyhow::Result<Vec<TaskLink>> {
+        let url = url.trim();
+        validate_link_url(url)?;
+        let label = match label.trim() {
+            "" => url,
+            label => label,
+        };
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let changed = match task.links.iter_mut().find(|link| link.url == url) {
+            Some(link) if link.label == label => false,
+            Some(link) => {
+                link.label = label.to_string();
+                true
+            }
+            None => {
+                task.links.push(TaskLink { label: label.to_string(), url: url.to_string() });
+                true
+            }
+        };
+        let links = task.links.clone();
+        if changed {
+            task.updated = Utc::now();
+            self.meta.last_updated = Utc::now();
+        }
+        Ok(links)
+    }
+
+    /// Detach the link with this URL from a task, returning the remaining links
+    pub fn remove_task_link(&mut self, section: &str, task_id: &str, url: &str) -> anyhow::Result<Vec<TaskLink>> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let count = task.links.len();
+        task.links.retain(|link| link.url != url.trim());
+        if task.links.len() == count {
+            return Err(anyhow::anyhow!("Link not found on {}:{}: {}", section, task_id, url));
+        }
+        task.updated = Utc::now();
+        let links = task.links.clone();
+        self.meta.last_updated = Utc::now();
+        Ok(links)
+    }
+
+    /// Add tags to a task, skipping ones it already has. Returns the task's tags afterwards.
+    pub fn add_task_tags(&mut self, section: &str, task_id: &str, tags: &[String]) -> anyhow::Result<Vec<String>> {
+        let tags = tags.iter()
+            .map(|tag| normalize_tag(tag).ok_or_else(|| anyhow::anyhow!("Tag cannot be empty")))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        for tag in tags {
+            if !task.tags.contains(&tag) {
+                task.tags.push(tag);
+            }
+        }
+        task.updated = Utc::now();
+        let result = task.tags.clone();
+        self.meta.last_updated = Utc::now();
+        Ok(result)
+    }
+
+    /// Remove tags from a task. Returns the task's tags afterwards.
+    pub fn remove_task_tags(&mut self, section: &str, task_id: &str, tags: &[String]) -> anyhow::Result<Vec<String>> {
+        let tags: Vec<String> = tags.iter().filter_map(|tag| normalize_tag(tag)).collect();
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        task.tags.retain(|tag| !tags.contains(tag));
+        task.updated = Utc::now();
+        let result = task.tags.clone();
+        self.meta.last_updated = Utc::now();
+        Ok(result)
+    }
+
+    /// Number of tasks carrying each tag
+    pub fn tag_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for task in self.sections.values().flat_map(|section| section.values()) {
+            for tag in &task.tags {
+                *counts.entry(tag.as_str()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Render notes as a Markdown document, newest first. Titles are escaped
+    /// so they stay plain heading text; content is copied verbatim.
+    pub fn notes_to_markdown(&self, include_converted: bool) -> String {
+        let mut notes: Vec<&Note> = self
+            .notes
+            .values()
+            .filter(|note| include_converted || !note.is_converted)
+            .collect();
+        notes.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.id.cmp(&b.id)));
+
+        let mut markdown = String::from("# Notes\n");
+        for note in notes {
+            let mut metadata = vec![
+                format!("Section: `{}`", note.section),
+                format!("Suggested ID: `{}`", note.suggested_task_id),
+                format!("Status: {}", note.suggested_status.as_str()),
+                format!("Created: {}", note.created.format("%Y-%m-%d %H:%M UTC")),
+            ];
+            if note.is_converted {
+                metadata.push("Converted".to_string());
             }
+            markdown.push_str(&format!(
+                "\n## {}\n\n{}\n",
+                escape_markdown_heading(&note.title),
+                metadata.join(" · ")
+            ));
+            let content = note.content.trim_end();
+            if !content.is_empty() {
+                markdown.push_str(&format!("\n{}\n", content));
+            }
+        }
+        markdown
+    }
+
+    /// Number of notes carrying each tag
+    pub fn note_tag_counts(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for tag in self.notes.values().flat_map(|note| &note.tags) {
+            *counts.entry(tag.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn set_task_priority(&mut self, section: &str, task_id: &str, priority: Option<TaskPriority>) -> anyhow::Result<()> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        task.priority = priority;
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Set or clear a task's estimate, rejecting zero and estimates over `MAX_ESTIMATE_MINUTES`
+    pub fn set_task_estimate(&mut self, section: &str, task_id: &str, estimate_minutes: Option<u32>) -> anyhow::Result<()> {
+        if let Some(minutes) = estimate_minutes
+            && !(1..=MAX_ESTIMATE_MINUTES).contains(&minutes)
+        {
+            return Err(anyhow::anyhow!("Estimate must be between 1 and {} minutes, got {}", MAX_ESTIMATE_MINUTES, minutes));
+        }
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        task.estimate_minutes = estimate_minutes;
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Log time against a task. Returns the task's new total.
+    pub fn add_time_entry(&mut self, section: &str, task_id: &str, minutes: u32, note: Option<String>) -> anyhow::Result<u32> {
+        if !(1..=MAX_TIME_ENTRY_MINUTES).contains(&minutes) {
+            return Err(anyhow::anyhow!("Time entry must be between 1 and {} minutes, got {}", MAX_TIME_ENTRY_MINUTES, minutes));
+        }
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        task.time_spent_minutes = task.time_spent_minutes.checked_add(minutes)
+            .ok_or_else(|| anyhow::anyhow!("Time spent on {}:{} would overflow", section, task_id))?;
+        let now = Utc::now();
+        task.time_entries.push(TimeEntry {
+            minutes,
+            note: note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty()),
+            logged_at: now,
+        });
+        task.updated = now;
+        let total = task.time_spent_minutes;
+        self.meta.last_updated = now;
+        Ok(total)
+    }
+
+    /// Assign a task, or unassign it when `assignee` is blank. Returns the previous assignee.
+    pub fn set_task_assignee(&mut self, section: &str, task_id: &str, assignee: Option<String>) -> anyhow::Result<Option<String>> {
+        let assignee = assignee
+            .map(|assignee| assignee.trim().to_string())
+            .filter(|assignee| !assignee.is_empty());
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let previous = std::mem::replace(&mut task.assignee, assignee);
+        if previous != task.assignee {
+            task.updated = Utc::now();
+            self.meta.last_updated = Utc::now();
+        }
+        Ok(previous)
+    }
+
+    pub fn set_task_metadata(&mut self, section: &str, task_id: &str, key: &str, value: serde_json::Value) -> anyhow::Result<()> {
+        if key.trim().is_empty() {
+            return Err(anyhow::anyhow!("Metadata key cannot be empty"));
+        }
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        task.metadata.insert(key.to_string(), value);
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Remove a metadata field, returning its previous value
+    pub fn remove_task_metadata(&mut self, section: &str, task_id: &str, key: &str) -> anyhow::Result<Option<serde_json::Value>> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let removed = task.metadata.remove(key);
+        if removed.is_some() {
+            task.updated = Utc::now();
+            self.meta.last_updated = Utc::now();
+        }
+        Ok(removed)
+    }
+
+    /// Record that `section:task_id` cannot start before `depends_on` is finished
+    pub fn add_dependency(&mut self, section: &str, task_id: &str, depends_on: TaskKey) -> anyhow::Result<()> {
+        if self.get_task(&depends_on.section, &depends_on.task_id).is_none() {
+            return Err(anyhow::anyhow!("Task not found: {}:{}", depends_on.section, depends_on.task_id));
+        }
+        let key = TaskKey::new(section, task_id);
+        if depends_on == key {
+            return Err(anyhow::anyhow!("A task cannot depend on itself"));
+        }
+        if self.depends_on_transitively(&depends_on, &key) {
+            return Err(anyhow::anyhow!(
+                "{}:{} already depends on {}:{}; adding this would create a cycle",
+                depends_on.section, depends_on.task_id, section, task_id
+            ));
+        }
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        if !task.dependencies.contains(&depends_on) {
+            task.dependencies.push(depends_on);
+            task.updated = Utc::now();
         }
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Drop `depends_on` from a task's dependencies
+    pub fn remove_dependency(&mut self, section: &str, task_id: &str, depends_on: &TaskKey) -> anyhow::Result<()> {
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let count = task.dependencies.len();
+        task.dependencies.retain(|key| key != depends_on);
+        if task.dependencies.len() == count {
+            return Err(anyhow::anyhow!(
+                "{}:{} does not depend on {}:{}",
+                section, task_id, depends_on.section, depends_on.task_id
+            ));
+        }
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Whether `target` is reachable from `from` by following dependencies
+    fn depends_on_transitively(&self, from: &TaskKey, target: &TaskKey) -> bool {
+        let mut pending = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(key) = pending.pop() {
+            if key == target {
+                return true;
+            }
+            if !seen.insert(key) {
+                continue;
+            }
+            if let Some(task) = self.get_task(&key.section, &key.task_id) {
+                pending.extend(task.dependencies.iter());
+            }
+        }
+        false
+    }
+
+    /// Task IDs of a section in manual order; tasks without a rank follow, by ID
+    pub fn ranked_task_ids(&self, section: &str) -> Vec<String> {
+        let Some(tasks) = self.sections.get(section) else {
+            return Vec::new();
+        };
+        let mut ordered: Vec<(&String, Option<f64>)> = tasks.iter().map(|(id, task)| (id, task.rank)).collect();
+        ordered.sort_by(|(a_id, a_rank), (b_id, b_rank)| match (a_rank, b_rank) {
+            (Some(a), Some(b)) => a.total_cmp(b).then_with(|| a_id.cmp(b_id)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a_id.cmp(b_id),
+        });
+        ordered.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Space the ranks of a section evenly, keeping the current order
+    fn renormalize_ranks(&mut self, section: &str) {
+        let order = self.ranked_task_ids(section);
+        if let Some(tasks) = self.sections.get_mut(section) {
+            for (position, task_id) in order.iter().enumerate() {
+                if let Some(task) = tasks.get_mut(task_id) {
+                    task.rank = Some(RANK_STEP * (position + 1) as f64);
+                }
+            }
+        }
+    }
+
+    /// Rank placing a task between its new neighbours, or `None` when they are too close together
+    fn rank_between(&self, section: &str, task_id: &str, before: Option<&str>, after: Option<&str>) -> anyhow::Result<Option<f64>> {
+        let order: Vec<String> = self.ranked_task_ids(section).into_iter().filter(|id| id != task_id).collect();
+        let position = |id: &str| order.iter().position(|other| other == id).unwrap_or_default();
+        let rank_at = |position: usize| order.get(position).and_then(|id| self.get_task(section, id)).and_then(|task| task.rank);
+        let (lower, upper) = match (after, before) {
+            (Some(after), Some(before)) => {
+                if position(after) + 1 != position(before) {
+                    return Err(anyhow::anyhow!("Tasks {} and {} are not adjacent", after, before));
+                }
+                (rank_at(position(after)), rank_at(position(before)))
+            }
+            (Some(after), None) => (rank_at(position(after)), rank_at(position(after) + 1)),
+            (None, Some(before)) => {
+                let upper = position(before);
+                (upper.checked_sub(1).and_then(rank_at), rank_at(upper))
+            }
+            (None, None) => return Err(anyhow::anyhow!("Either before or after must be given")),
+        };
+        Ok(match (lower, upper) {
+            (Some(lower), Some(upper)) => (upper - lower > 2.0 * MIN_RANK_GAP).then_some((lower + upper) / 2.0),
+            (Some(lower), None) => Some(lower + RANK_STEP),
+            (None, Some(upper)) => Some(upper - RANK_STEP),
+            (None, None) => Some(RANK_STEP),
+        })
+    }
+
+    /// Move a task directly before `before` and/or after `after` in its section.
+    /// Returns the task's new rank.
+    pub fn reorder_task(&mut self, section: &str, task_id: &str, before: Option<&str>, after: Option<&str>) -> anyhow::Result<f64> {
+        if self.get_task(section, task_id).is_none() {
+            return Err(anyhow::anyhow!("Task not found: {}:{}", section, task_id));
+        }
+        for neighbour in before.iter().chain(after.iter()) {
+            if *neighbour == task_id {
+                return Err(anyhow::anyhow!("Task {} cannot be placed relative to itself", task_id));
+            }
+            if self.get_task(section, neighbour).is_none() {
+                return Err(anyhow::anyhow!("Task not found: {}:{}", section, neighbour));
+            }
+        }
+        if self.sections[section].values().any(|task| task.rank.is_none()) {
+            self.renormalize_ranks(section);
+        }
+        let rank = match self.rank_between(section, task_id, before, after)? {
+            Some(rank) => rank,
+            None => {
+                self.renormalize_ranks(section);
+                self.rank_between(section, task_id, before, after)?
+                    .ok_or_else(|| anyhow::anyhow!("Could not find a rank for {}:{}", section, task_id))?
+            }
+        };
+        if let Some(task) = self.get_task_mut(section, task_id) {
+            task.rank = Some(rank);
+        }
+        self.meta.last_updated = Utc::now();
+        Ok(rank)
+    }
+
+    /// Make a task a subtask of `parent`, or a top-level task again when `parent` is `None`
+    pub fn set_task_parent(&mut self, section: &str, task_id: &str, parent: Option<TaskKey>) -> anyhow::Result<()> {
+        if let Some(parent) = &parent {
+            if self.get_task(&parent.section, &parent.task_id).is_none() {
+                return Err(anyhow::anyhow!("Task not found: {}:{}", parent.section, parent.task_id));
+            }
+            let key = TaskKey::new(section, task_id);
+            let mut ancestor = Some(parent.clone());
+            while let Some(current) = ancestor {
+                if current == key {
+                    return Err(anyhow::anyhow!("A task cannot be a subtask of itself or of its own subtasks"));
+                }
+                ancestor = self.get_task(&current.section, &current.task_id).and_then(|task| task.parent.clone());
+            }
+        }
+        let task = self.get_task_mut(section, task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        task.parent = parent;
+        task.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Direct subtasks of `section:task_id`
+    pub fn children_of(&self, section: &str, task_id: &str) -> Vec<TaskKey> {
+        let key = TaskKey::new(section, task_id);
+        let mut children: Vec<TaskKey> = self.sections.iter()
+            .flat_map(|(section_name, tasks)| tasks.iter().map(move |(id, task)| (section_name, id, task)))
+            .filter(|(_, _, task)| task.parent.as_ref() == Some(&key))
+            .map(|(section_name, id, _)| TaskKey::new(section_name, id))
+            .collect();
+        children.sort();
+        children
+    }
+
+    /// Subtasks of `section:task_id` at every level, parents before their children
+    pub fn descendants_of(&self, section: &str, task_id: &str) -> Vec<TaskKey> {
+        let root = TaskKey::new(section, task_id);
+        let mut descendants = self.children_of(section, task_id);
+        let mut next = 0;
+        while next < descendants.len() {
+            let key = descendants[next].clone();
+            for child in self.children_of(&key.section, &key.task_id) {
+                if child != root && !descendants.contains(&child) {
+                    descendants.push(child);
+                }
+            }
+            next += 1;
+        }
+        descendants
+    }
+
+    /// Finished and total subtasks below a task, counting every level
+    pub fn subtask_progress(&self, section: &str, task_id: &str) -> (usize, usize) {
+        let descendants = self.descendants_of(section, task_id);
+        let done = descendants.iter()
+            .filter(|key| self.get_task(&key.section, &key.task_id).is_some_and(|task| task.status == TaskStatus::Done))
+            .count();
+        (done, descendants.len())
+    }
+
+    /// Tasks that list `section:task_id` among their dependencies
+    pub fn dependents_of(&self, section: &str, task_id: &str) -> Vec<TaskKey> {
+        let key = TaskKey::new(section, task_id);
+        let mut dependents: Vec<TaskKey> = self.sections.iter()
+            .flat_map(|(section_name, tasks)| tasks.iter().map(move |(id, task)| (section_name, id, task)))
+            .filter(|(_, _, task)| task.dependencies.contains(&key))
+            .map(|(section_name, id, _)| TaskKey::new(section_name, id))
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
+    /// Unfinished dependents of `section:task_id` whose dependencies are now all done
+    pub fn unblocked_dependents(&self, section: &str, task_id: &str) -> Vec<TaskKey> {
+        let is_done = |key: &TaskKey| {
+            self.get_task(&key.section, &key.task_id)
+                .is_none_or(|task| task.status == TaskStatus::Done)
+        };
+        self.dependents_of(section, task_id)
+            .into_iter()
+            .filter(|key| !is_done(key))
+            .filter(|key| {
+                self.get_task(&key.section, &key.task_id)
+                    .is_some_and(|task| task.dependencies.iter().all(is_done))
+            })
+            .collect()
+    }
+
+    /// Move a task to another section and/or ID, carrying notes that point at it along
+    pub fn move_task(&mut self, section: &str, task_id: &str, new_section: &str, new_id: &str) -> anyhow::Result<()> {
+        if self.get_task(new_section, new_id).is_some() {
+            return Err(anyhow::anyhow!("Task already exists: {}:{}", new_section, new_id));
+        }
+        let task = self.take_task(section, task_id)?;
+        self.sections.entry(new_section.to_string()).or_default().insert(new_id.to_string(), task);
+        self.retarget_references(section, task_id, new_section, new_id);
+        self.record_aliases(&[(TaskKey::new(section, task_id), TaskKey::new(new_section, new_id))]);
+        self.index.remove_task(section, task_id);
+        self.reindex_task(new_section, new_id);
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Fold a task into another one, combining their references, tags, dependencies and notes
+    pub fn merge_task_into(&mut self, section: &str, task_id: &str, target_section: &str, target_id: &str) -> anyhow::Result<()> {
+        if section == target_section && task_id == target_id {
+            return Err(anyhow::anyhow!("Cannot merge a task into itself"));
+        }
+        if self.get_task(target_section, target_id).is_none() {
+            return Err(anyhow::anyhow!("Task not found: {}:{}", target_section, target_id));
+        }
+        let source = self.take_task(section, task_id)?;
+        let target = self.get_task_mut(target_section, target_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", target_section, target_id))?;
+        for (file_path, file) in source.files {
+            let target_file = target.files.entry(file_path).or_insert_with(|| TaskFile {
+                lines: Vec::new(),
+                notes: HashMap::new(),
+                label_texts: Vec::new(),
+            });
+            for line in file.lines {
+                if !target_file.lines.contains(&line) {
+                    target_file.lines.push(line);
+                }
+            }
+            target_file.notes.extend(file.notes);
+            for text in file.label_texts {
+                if !target_file.label_texts.contains(&text) {
+                    target_file.label_texts.push(text);
+                }
+            }
+        }
+        for tag in source.tags {
+            if !target.tags.contains(&tag) {
+                target.tags.push(tag);
+            }
+        }
+        for note_id in source.related_notes {
+            if !target.related_notes.contains(&note_id) {
+                target.related_notes.push(note_id);
+            }
+        }
+        let target_key = TaskKey::new(target_section, target_id);
+        for dependency in source.dependencies {
+            if dependency != target_key && !target.dependencies.contains(&dependency) {
+                target.dependencies.push(dependency);
+            }
+        }
+        if target.description.is_none() {
+            target.description = source.description;
+        }
+        target.updated = Utc::now();
+        self.retarget_references(section, task_id, target_section, target_id);
+        self.record_aliases(&[(TaskKey::new(section, task_id), TaskKey::new(target_section, target_id))]);
+        self.index.remove_task(section, task_id);
+        self.reindex_task(target_section, target_id);
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Remove a task, dropping its section once empty
+    fn take_task(&mut self, section: &str, task_id: &str) -> anyhow::Result<Task> {
+        let section_tasks = self.sections.get_mut(section)
+            .ok_or_else(|| anyhow::anyhow!("Section not found: {}", section))?;
+        let task = section_tasks.remove(task_id)
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        if section_tasks.is_empty() {
+            self.sections.remove(section);
+        }
+        Ok(task)
+    }
+
+    /// Point notes, dependencies and subtasks at a task's new coordinates
+    fn retarget_references(&mut self, section: &str, task_id: &str, new_section: &str, new_id: &str) {
+        let old_key = TaskKey::new(section, task_id);
+        let new_key = TaskKey::new(new_section, new_id);
+        for (section_name, tasks) in &mut self.sections {
+            for (id, task) in tasks {
+                let is_target = *section_name == new_section && *id == new_id;
+                if task.parent.as_ref() == Some(&old_key) {
+                    task.parent = (!is_target).then(|| new_key.clone());
+                }
+                if !task.dependencies.contains(&old_key) {
+                    continue;
+                }
+                task.dependencies.retain(|key| *key != old_key && *key != new_key);
+                if !is_target {
+                    task.dependencies.push(new_key.clone());
+                }
+            }
+        }
+        for note in self.notes.values_mut() {
+            if note.section == section && note.suggested_task_id == task_id {
+                note.section = new_section.to_string();
+                note.suggested_task_id = new_id.to_string();
+                note.updated = Utc::now();
+            }
+            if note.related_tasks.contains(&old_key) {
+                note.related_tasks.retain(|key| *key != old_key && *key != new_key);
+                note.related_tasks.push(new_key.clone());
+            }
+        }
+        let (old_ref, new_ref) = (milestone_ref(section, task_id), milestone_ref(new_section, new_id));
+        for milestone in self.milestones.values_mut() {
+            if let Some(position) = milestone.task_refs.iter().position(|member| *member == old_ref) {
+                if milestone.task_refs.contains(&new_ref) {
+                    milestone.task_refs.remove(position);
+                } else {
+                    milestone.task_refs[position] = new_ref.clone();
+                }
+            }
+        }
+    }
+
+    /// Point file references at a renamed file, or at the files under a renamed directory.
+    /// Lines and notes move with the reference. Returns the number of tasks updated.
+    pub fn rename_file_references(&mut self, old_path: &str, new_path: &str) -> usize {
+        let old_prefix = format!("{}/", old_path.trim_end_matches('/'));
+        let renamed = |path: &str| {
+            if path == old_path {
+                Some(new_path.to_string())
+            } else {
+                path.strip_prefix(&old_prefix)
+                    .map(|rest| format!("{}/{}", new_path.trim_end_matches('/'), rest))
+            }
+        };
+        let mut updated = 0;
+        for task in self.sections.values_mut().flat_map(|section| section.values_mut()) {
+            let moved: Vec<(String, String)> = task.files.keys()
+                .filter_map(|path| renamed(path).map(|new| (path.clone(), new)))
+                .collect();
+            if moved.is_empty() {
+                continue;
+            }
+            for (old, new) in moved {
+                let Some(file) = task.files.remove(&old) else { continue };
+                match task.files.get_mut(&new) {
+                    Some(target) => {
+                        for line in file.lines {
+                            if !target.lines.contains(&line) {
+                                target.lines.push(line);
+                            }
+                        }
+                        target.lines.sort_unstable();
+                        target.notes.extend(file.notes);
+                    }
+                    None => {
+                        task.files.insert(new, file);
+                    }
+                }
+            }
+            task.updated = Utc::now();
+            updated += 1;
+        }
+        if updated > 0 {
+            self.meta.last_updated = Utc::now();
+            self.rebuild_index();
+        }
+        updated
+    }
+
+    /// Bring the index up to date for one task, dropping it when the task is gone
+    /// or its section is archived
+    fn reindex_task(&mut self, section: &str, task_id: &str) {
+        match self.sections.get(section).and_then(|tasks| tasks.get(task_id)) {
+            Some(task) if !self.archived_sections.contains(section) => {
+                self.index.update_task(section, task_id, task)
+            }
+            _ => self.index.remove_task(section, task_id),
+        }
+    }
+
+    /// Tasks the index should cover, which leaves out archived sections
+    fn indexed_tasks(&self) -> impl Iterator<Item = (&String, &String, &Task)> {
+        self.sections
+            .iter()
+            .filter(|(section_name, _)| !self.archived_sections.contains(*section_name))
+            .flat_map(|(section_name, section)| {
+                section.iter().map(move |(task_id, task)| (section_name, task_id, task))
+            })
+    }
+
+    /// Differences between the stored index and the tasks, empty when the index is current
+    pub fn index_consistency_check(&self) -> Vec<String> {
+        let mut expected = TaskIndex::new();
+        for (section_name, task_id, task) in self.indexed_tasks() {
+            expected.insert_task(section_name, task_id, task);
+        }
+        let mut issues = Vec::new();
+        let mut compare = |kind: &str, name: String, indexed: Option<&HashSet<String>>, actual: Option<&HashSet<String>>| {
+            let empty = HashSet::new();
+            let (indexed, actual) = (indexed.unwrap_or(&empty), actual.unwrap_or(&empty));
+            let mut missing: Vec<&String> = actual.difference(indexed).collect();
+            let mut stale: Vec<&String> = indexed.difference(actual).collect();
+            missing.sort();
+            stale.sort();
+            for id in missing {
+                issues.push(format!("{} {}: {} is not indexed", kind, name, id));
+            }
+            for id in stale {
+                issues.push(format!("{} {}: {} is indexed but does not belong there", kind, name, id));
+            }
+        };
+        let mut files: Vec<&String> = self.index.files.keys().chain(expected.files.keys()).collect();
+        files.sort();
+        files.dedup();
+        for file in files {
+            compare("File", file.clone(), self.index.files.get(file), expected.files.get(file));
+        }
+        for status in [TaskStatus::Todo, TaskStatus::InProgress, TaskStatus::Done, TaskStatus::Blocked] {
+            let name = format!("{:?}", status);
+            compare("Status", name, self.index.tasks_by_status.get(&status), expected.tasks_by_status.get(&status));
+        }
+        issues
+    }
+
+    pub fn rebuild_index(&mut self) {
+        let mut index = TaskIndex::new();
+        for (section_name, task_id, task) in self.indexed_tasks() {
+            index.insert_task(section_name, task_id, task);
+        }
+        self.index = index;
     }
 
     pub fn add_note(
@@ -291,56 +2420,228 @@ impl ProjectData {
         if self.notes.contains_key(&note_id) {
             return Err(anyhow::anyhow!("Note with ID '{}' already exists", note_id));
         }
-        self.notes.insert(note_id.clone(), note);
+        self.notes.insert(note_id.clone(), note);
+        self.meta.last_updated = Utc::now();
+        Ok(note_id)
+    }
+
+    pub fn get_note(&self, id: &str) -> Option<&Note> {
+        self.notes.get(id)
+    }
+
+    pub fn get_note_mut(&mut self, id: &str) -> Option<&mut Note> {
+        self.notes.get_mut(id)
+    }
+
+    /// Apply the given note fields. A generated link is regenerated when the
+    /// section, suggested task ID or suggested status it was built from changes;
+    /// returns whether that happened.
+    pub fn update_note(&mut self, id: &str, update: NoteUpdate) -> anyhow::Result<bool> {
+        let tags = update.tags.map(|tags| {
+            let mut normalized = Vec::new();
+            for tag in tags {
+                let tag = normalize_tag(&tag).ok_or_else(|| anyhow::anyhow!("Tag cannot be empty"))?;
+                if !normalized.contains(&tag) {
+                    normalized.push(tag);
+                }
+            }
+            anyhow::Ok(normalized)
+        }).transpose()?;
+        let note = self.notes.get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", id))?;
+        let link_fields = (note.section.clone(), note.suggested_task_id.clone(), note.suggested_status.clone());
+        if let Some(title) = update.title {
+            note.title = title;
+        }
+        if let Some(content) = update.content {
+            note.content = content;
+        }
+        if let Some(tags) = tags {
+            note.tags = tags;
+        }
+        if let Some(section) = update.section {
+            note.section = section;
+        }
+        if let Some(task_id) = update.suggested_task_id {
+            note.suggested_task_id = task_id;
+        }
+        if let Some(status) = update.suggested_status {
+            note.suggested_status = status;
+        }
+        let regenerated = note.generated_link.is_some()
+            && !note.is_converted
+            && link_fields != (note.section.clone(), note.suggested_task_id.clone(), note.suggested_status.clone());
+        if regenerated {
+            note.generate_task_link();
+        }
+        note.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(regenerated)
+    }
+
+    pub fn delete_note(&mut self, id: &str) -> anyhow::Result<()> {
+        if !self.notes.contains_key(id) {
+            return Err(anyhow::anyhow!("Note with ID '{}' not found", id));
+        }
+        self.notes.remove(id);
+        self.forget_notes(&[id]);
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Combine unconverted notes into a new one, in the given order, and delete
+    /// them. The merged note takes its target from the first note, the earliest
+    /// `created`, and the union of tags and task links. Returns the new note's ID.
+    pub fn merge_notes(&mut self, ids: &[String], new_title: String) -> anyhow::Result<String> {
+        let mut source_ids: Vec<&str> = Vec::new();
+        for id in ids {
+            if !source_ids.contains(&id.as_str()) {
+                source_ids.push(id);
+            }
+        }
+        if source_ids.len() < 2 {
+            return Err(anyhow::anyhow!("At least two notes are needed to merge"));
+        }
+        if new_title.trim().is_empty() {
+            return Err(anyhow::anyhow!("Merged note title cannot be empty"));
+        }
+        let mut sources = Vec::new();
+        for id in &source_ids {
+            let note = self.notes.get(*id)
+                .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", id))?;
+            if note.is_converted {
+                return Err(anyhow::anyhow!("Note '{}' is already converted", id));
+            }
+            sources.push(note);
+        }
+
+        let first = sources[0];
+        let mut merged = Note::new(
+            new_title,
+            sources.iter().map(|note| note.content.trim()).filter(|content| !content.is_empty()).collect::<Vec<_>>().join("\n\n---\n\n"),
+            first.section.clone(),
+            first.suggested_task_id.clone(),
+            Some(first.suggested_status.clone()),
+        );
+        merged.created = sources.iter().map(|note| note.created).min().unwrap_or(merged.created);
+        merged.pinned = sources.iter().any(|note| note.pinned);
+        for note in &sources {
+            for tag in &note.tags {
+                if !merged.tags.contains(tag) {
+                    merged.tags.push(tag.clone());
+                }
+            }
+            for key in &note.related_tasks {
+                if !merged.related_tasks.contains(key) {
+                    merged.related_tasks.push(key.clone());
+                }
+            }
+            for attachment in &note.attachments {
+                if !merged.attachments.contains(attachment) {
+                    merged.attachments.push(attachment.clone());
+                }
+            }
+        }
+
+        let merged_id = merged.id.clone();
+        for task in self.sections.values_mut().flat_map(|tasks| tasks.values_mut()) {
+            if task.related_notes.iter().any(|id| source_ids.contains(&id.as_str())) {
+                task.related_notes.retain(|id| !source_ids.contains(&id.as_str()));
+                task.related_notes.push(merged_id.clone());
+            }
+        }
+        for id in &source_ids {
+            self.notes.remove(*id);
+        }
+        self.notes.insert(merged_id.clone(), merged);
         self.meta.last_updated = Utc::now();
-        Ok(note_id)
+        Ok(merged_id)
     }
 
-    pub fn get_note(&self, id: &str) -> Option<&Note> {
-        self.notes.get(id)
+    /// Drop links from tasks to notes that no longer exist
+    fn forget_notes(&mut self, note_ids: &[&str]) {
+        for task in self.sections.values_mut().flat_map(|tasks| tasks.values_mut()) {
+            task.related_notes.retain(|id| !note_ids.contains(&id.as_str()));
+        }
     }
 
-    pub fn get_note_mut(&mut self, id: &str) -> Option<&mut Note> {
-        self.notes.get_mut(id)
+    /// Link a note and a task in both directions. Returns false when they were already linked.
+    pub fn link_note_to_task(&mut self, note_id: &str, section: &str, task_id: &str) -> anyhow::Result<bool> {
+        let task = self.sections.get_mut(section).and_then(|tasks| tasks.get_mut(task_id))
+            .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+        let note = self.notes.get_mut(note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", note_id))?;
+        let key = TaskKey::new(section, task_id);
+        let mut linked = false;
+        if !note.related_tasks.contains(&key) {
+            note.related_tasks.push(key);
+            linked = true;
+        }
+        if !task.related_notes.iter().any(|id| id == note_id) {
+            task.related_notes.push(note_id.to_string());
+            linked = true;
+        }
+        if linked {
+            self.meta.last_updated = Utc::now();
+        }
+        Ok(linked)
     }
 
-    pub fn update_note(
-        &mut self,
-        id: &str,
-        title: Option<String>,
-        content: Option<String>,
-    ) -> anyhow::Result<()> {
-        let note = self.notes.get_mut(id)
-            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", id))?;
-        if let Some(title) = title {
-            note.title = title;
+    /// Remove the link between a note and a task. Returns false when they were not linked.
+    pub fn unlink_note_from_task(&mut self, note_id: &str, section: &str, task_id: &str) -> anyhow::Result<bool> {
+        let key = TaskKey::new(section, task_id);
+        let note = self.notes.get_mut(note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", note_id))?;
+        let linked_tasks = note.related_tasks.len();
+        note.related_tasks.retain(|related| *related != key);
+        let mut unlinked = note.related_tasks.len() != linked_tasks;
+        if let Some(task) = self.get_task_mut(section, task_id) {
+            let linked_notes = task.related_notes.len();
+            task.related_notes.retain(|id| id != note_id);
+            unlinked |= task.related_notes.len() != linked_notes;
         }
-        if let Some(content) = content {
-            note.content = content;
+        if unlinked {
+            self.meta.last_updated = Utc::now();
         }
-        note.updated = Utc::now();
-        self.meta.last_updated = Utc::now();
-        Ok(())
+        Ok(unlinked)
     }
 
-    pub fn delete_note(&mut self, id: &str) -> anyhow::Result<()> {
-        if !self.notes.contains_key(id) {
-            return Err(anyhow::anyhow!("Note with ID '{}' not found", id));
+    /// Remove converted notes, only those converted longer than `older_than` ago when given.
+    /// Returns the number of notes removed.
+    pub fn purge_converted_notes(&mut self, older_than: Option<Duration>) -> usize {
+        let cutoff = older_than.map(|age| Utc::now() - age);
+        let purged: Vec<String> = self.notes.iter()
+            .filter(|(_, note)| {
+                note.is_converted
+                    && match (cutoff, note.converted_at) {
+                        (None, _) => true,
+                        (Some(cutoff), Some(converted_at)) => converted_at < cutoff,
+                        (Some(_), None) => false,
+                    }
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &purged {
+            self.notes.remove(id);
         }
-        self.notes.remove(id);
-        self.meta.last_updated = Utc::now();
-        Ok(())
+        if !purged.is_empty() {
+            self.forget_notes(&purged.iter().map(String::as_str).collect::<Vec<_>>());
+            self.meta.last_updated = Utc::now();
+        }
+        purged.len()
     }
 
-    pub fn generate_note_link(&mut self, note_id: &str) -> anyhow::Result<String> {
+    /// Generate, or regenerate, a note's task link. Returns the new link and the one it replaced.
+    pub fn generate_note_link(&mut self, note_id: &str) -> anyhow::Result<(String, Option<String>)> {
         let note = self.notes.get_mut(note_id)
             .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", note_id))?;
         if note.is_converted {
             return Err(anyhow::anyhow!("Note is already converted to task"));
         }
+        let previous = note.generated_link.clone();
         let link = note.generate_task_link();
         self.meta.last_updated = Utc::now();
-        Ok(link)
+        Ok((link, previous))
     }
 
     pub fn convert_note_to_task(&mut self, note_id: &str) -> anyhow::Result<()> {
@@ -350,35 +2651,205 @@ impl ProjectData {
             return Err(anyhow::anyhow!("Note is already converted to task"));
         }
         let note_clone = note.clone();
-        let task = Task::new(note_clone.title, Some(note_clone.content));
-        self.sections
-            .entry(note_clone.section.clone())
-            .or_insert_with(HashMap::new)
-            .insert(note_clone.suggested_task_id.clone(), task.clone());
+        // The pasted link may already have been scanned into a task; keep its references
         if let Some(task) = self.get_task_mut(&note_clone.section, &note_clone.suggested_task_id) {
+            if task.description.is_none() {
+                task.description = Some(note_clone.content);
+            }
+            for tag in note_clone.tags {
+                if !task.tags.contains(&tag) {
+                    task.tags.push(tag);
+                }
+            }
+            for attachment in &note_clone.attachments {
+                task.add_file(attachment.file_path.clone(), attachment.line_start, None);
+            }
+        } else {
+            let mut task = Task::new(note_clone.title, Some(note_clone.content));
             task.update_status(note_clone.suggested_status);
+            task.tags = note_clone.tags;
+            for attachment in &note_clone.attachments {
+                task.add_file(attachment.file_path.clone(), attachment.line_start, None);
+            }
+            self.sections
+                .entry(note_clone.section.clone())
+                .or_default()
+                .insert(note_clone.suggested_task_id.clone(), task);
         }
         if let Some(note) = self.notes.get_mut(note_id) {
             note.mark_as_converted();
+            note.converted_tasks = vec![TaskKey::new(&note_clone.section, &note_clone.suggested_task_id)];
         }
+        self.link_note_to_task(note_id, &note_clone.section, &note_clone.suggested_task_id)?;
         self.meta.last_updated = Utc::now();
         self.rebuild_index();
         Ok(())
     }
 
+    /// Task IDs for checklist items: `prefix` plus a slug of each item, with a
+    /// numeric suffix where the ID is taken in `section` or by an earlier item
+    pub fn checklist_task_ids(&self, section: &str, prefix: Option<&str>, items: &[String]) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::with_capacity(items.len());
+        for item in items {
+            let base = match (prefix.filter(|prefix| !prefix.is_empty()), slugify(item)) {
+                (Some(prefix), slug) if slug.is_empty() => prefix.to_string(),
+                (Some(prefix), slug) => format!("{}_{}", prefix.trim_end_matches('_'), slug),
+                (None, slug) => slug,
+            };
+            let taken = |id: &str| self.get_task(section, id).is_some() || ids.iter().any(|other| other == id);
+            let mut id = base.clone();
+            let mut suffix = 2;
+            while taken(&id) {
+                id = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            ids.push(id);
+        }
+        ids
+    }
+
+    /// Create one task per `(task_id, title)` in `section`, link each back to the note
+    /// and mark the note converted. Nothing changes when any task cannot be created.
+    pub fn convert_note_to_tasks(&mut self, note_id: &str, section: &str, items: &[(String, String)]) -> anyhow::Result<()> {
+        let note = self.notes.get(note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", note_id))?;
+        if note.is_converted {
+            return Err(anyhow::anyhow!("Note is already converted to task"));
+        }
+        if items.is_empty() {
+            return Err(anyhow::anyhow!("Note has no checklist items"));
+        }
+        let (status, tags, attachments) = (note.suggested_status.clone(), note.tags.clone(), note.attachments.clone());
+        let mut staged = self.clone();
+        for (task_id, title) in items {
+            staged.add_task(section, task_id, title.clone(), None)?;
+            let task = staged.get_task_mut(section, task_id)
+                .ok_or_else(|| anyhow::anyhow!("Task not found: {}:{}", section, task_id))?;
+            task.update_status(status.clone());
+            task.tags = tags.clone();
+            for attachment in &attachments {
+                task.add_file(attachment.file_path.clone(), attachment.line_start, None);
+            }
+            staged.link_note_to_task(note_id, section, task_id)?;
+        }
+        if let Some(note) = staged.notes.get_mut(note_id) {
+            note.mark_as_converted();
+            note.converted_tasks = items.iter().map(|(task_id, _)| TaskKey::new(section, task_id)).collect();
+        }
+        staged.rebuild_index();
+        *self = staged;
+        Ok(())
+    }
+
+    /// Replace a note's attachments, rejecting empty paths and line ranges
+    /// that are not 1-based or run backwards
+    pub fn set_note_attachments(&mut self, id: &str, attachments: Vec<NoteAttachment>) -> anyhow::Result<()> {
+        for attachment in &attachments {
+            if attachment.file_path.trim().is_empty() {
+                return Err(anyhow::anyhow!("Attachment file path cannot be empty"));
+            }
+            if attachment.line_start == 0 || attachment.line_end < attachment.line_start {
+                return Err(anyhow::anyhow!(
+                    "Invalid attachment lines {}-{} in {}",
+                    attachment.line_start,
+                    attachment.line_end,
+                    attachment.file_path
+                ));
+            }
+        }
+        let note = self.notes.get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", id))?;
+        note.attachments = attachments;
+        note.updated = Utc::now();
+        self.meta.last_updated = Utc::now();
+        Ok(())
+    }
+
+    /// Pinned notes first, then most recently updated
     pub fn get_all_notes(&self) -> Vec<&Note> {
-        self.notes.values().collect()
+        let mut notes: Vec<&Note> = self.notes.values().collect();
+        notes.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then(b.updated.cmp(&a.updated))
+                .then(a.id.cmp(&b.id))
+        });
+        notes
+    }
+
+    /// Pin or unpin a note, returning whether the flag changed. Leaves
+    /// `updated` alone so the note keeps its place in the recency order.
+    pub fn set_note_pinned(&mut self, id: &str, pinned: bool) -> anyhow::Result<bool> {
+        let note = self.notes.get_mut(id)
+            .ok_or_else(|| anyhow::anyhow!("Note with ID '{}' not found", id))?;
+        if note.pinned == pinned {
+            return Ok(false);
+        }
+        note.pinned = pinned;
+        self.meta.last_updated = Utc::now();
+        Ok(true)
     }
 
-    pub fn check_note_conversions(&mut self, scanned_content: &[(String, String)]) -> anyhow::Result<Vec<String>> {
+    /// Unconverted notes that suggest the given task as their target, oldest first
+    pub fn notes_for_task(&self, section: &str, task_id: &str) -> Vec<&Note> {
+        let mut notes: Vec<&Note> = self.notes.values()
+            .filter(|note| !note.is_converted && note.section == section && note.suggested_task_id == task_id)
+            .collect();
+        notes.sort_by_key(|note| note.created);
+        notes
+    }
+
+    /// `section:task_id` of every unconverted note that has generated a link; a
+    /// file can only hold one of those links if it contains its core
+    pub fn pending_note_link_cores(&self) -> Vec<String> {
+        let mut cores: Vec<String> = self.notes.values()
+            .filter(|note| !note.is_converted && note.generated_link.is_some())
+            .map(|note| format!("{}:{}", note.section, note.suggested_task_id))
+            .collect();
+        cores.sort();
+        cores.dedup();
+        cores
+    }
+
+    /// Every unconverted note with a generated link, scored against the scanned content.
+    /// Ready notes come first, then by descending confidence.
+    pub fn conversion_candidates(&self, scanned_content: &[(String, String)], threshold: f32) -> Vec<ConversionCandidate> {
+        let mut candidates: Vec<ConversionCandidate> = self.notes.values()
+            .filter(|note| !note.is_converted && note.generated_link.is_some())
+            .map(|note| {
+                let (confidence, file) = scanned_content.iter()
+                    .map(|(file, content)| (note.link_match_confidence(content), file))
+                    .filter(|(confidence, _)| *confidence > 0.0)
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .map_or((0.0, None), |(confidence, file)| (confidence, Some(file.clone())));
+                ConversionCandidate {
+                    note_id: note.id.clone(),
+                    section: note.section.clone(),
+                    suggested_task_id: note.suggested_task_id.clone(),
+                    confidence,
+                    file,
+                    ready: confidence >= threshold,
+                }
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.ready.cmp(&a.ready)
+                .then(b.confidence.total_cmp(&a.confidence))
+                .then_with(|| a.note_id.cmp(&b.note_id))
+        });
+        candidates
+    }
+
+    /// Convert notes whose generated link was pasted into the scanned content with
+    /// at least `threshold` confidence
+    pub fn check_note_conversions(&mut self, scanned_content: &[(String, String)], threshold: f32) -> anyhow::Result<Vec<String>> {
         let mut converted_notes = Vec::new();
         for (note_id, note) in self.notes.clone() {
             if note.is_converted || note.generated_link.is_none() {
                 continue;
             }
-            let generated_link = note.generated_link.as_ref().unwrap();
             for (_, content) in scanned_content {
-                if content.contains(generated_link) {
+                if note.link_match_confidence(content) >= threshold {
                     if let Err(e) = self.convert_note_to_task(&note_id) {
                         eprintln!("Error converting note to task: {}", e);
                     } else {
@@ -411,6 +2882,39 @@ mod tests {
         assert_eq!(task.title, "Test task");
     }
 
+    #[test]
+    fn test_priority_round_trip() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        project.add_task("dev", "logout", "Logout".to_string(), None).unwrap();
+        project.set_task_priority("dev", "login", Some("High".parse().unwrap())).unwrap();
+        
+        let json = serde_json::to_string(&project).unwrap();
+        assert!(json.contains(r#""priority":"high""#));
+        let loaded: ProjectData = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_task("dev", "login").unwrap().priority, Some(TaskPriority::High));
+        assert_eq!(loaded.get_task("dev", "logout").unwrap().priority, None);
+        
+        let legacy = r#"{"title":"Old","description":null,"status":"todo","created":"2024-01-01T00:00:00Z","updated":"2024-01-01T00:00:00Z","files":{}}"#;
+        let task: Task = serde_json::from_str(legacy).unwrap();
+        assert_eq!(task.priority, None);
+        assert!("urgent".parse::<TaskPriority>().is_err());
+        assert!(TaskPriority::Critical > TaskPriority::Low);
+    }
+
+    #[test]
+    fn test_add_task_refuses_existing_id() {
+        let mut project = ProjectData::new(Some("test-project".to_string()));
+        project.add_task("dev", "task_1", "Test task".to_string(), None).unwrap();
+        project.update_task_file("dev", "task_1", "src/main.rs".to_string(), 3, None).unwrap();
+        assert!(project.add_task("dev", "task_1", "Other".to_string(), None).is_err());
+        let task = project.get_task("dev", "task_1").unwrap();
+        assert_eq!(task.title, "Test task");
+        assert!(task.files.contains_key("src/main.rs"));
+        project.replace_task("dev", "task_1", "Other".to_string(), None).unwrap();
+        assert_eq!(project.get_task("dev", "task_1").unwrap().title, "Other");
+    }
+
     #[test]
     fn test_delete_task() {
         let mut project = ProjectData::new(Some("test-project".to_string()));
@@ -423,4 +2927,668 @@ mod tests {
         let result = project.delete_task("nonexistent", "task_1");
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_renamed_tasks_resolve_through_aliases() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        project.add_task("dev", "auth", "Auth".to_string(), None).unwrap();
+
+        project.move_task("dev", "login", "dev", "sign_in").unwrap();
+        assert!(project.get_task("dev", "login").is_none());
+        let (key, task) = project.lookup_task("dev", "login").unwrap();
+        assert_eq!(key, TaskKey::new("dev", "sign_in"));
+        assert_eq!(task.title, "Login");
+
+        // Renaming again keeps the oldest ID pointing at the current one
+        project.move_task("dev", "sign_in", "auth_ui", "sign_in").unwrap();
+        assert_eq!(project.resolve_alias("dev", "login"), Some(TaskKey::new("auth_ui", "sign_in")));
+        assert_eq!(project.resolve_alias("dev", "sign_in"), Some(TaskKey::new("auth_ui", "sign_in")));
+
+        project.merge_task_into("auth_ui", "sign_in", "dev", "auth").unwrap();
+        assert_eq!(project.lookup_task("dev", "login").unwrap().0, TaskKey::new("dev", "auth"));
+
+        // A new task at an old ID takes precedence over the alias
+        project.add_task("dev", "login", "New login".to_string(), None).unwrap();
+        assert_eq!(project.resolve_alias("dev", "login"), None);
+        assert_eq!(project.lookup_task("dev", "login").unwrap().1.title, "New login");
+        project.move_task("dev", "login", "dev", "login_v2").unwrap();
+        assert!(!project.aliases.values().any(|target| target == "dev.login"));
+        assert_eq!(project.aliases["dev.login"], "dev.login_v2");
+        assert!(project.lookup_task("dev", "missing").is_none());
+    }
+
+    #[test]
+    fn test_trash_and_restore_task() {
+        let mut project = ProjectData::new(None);
+        for task_id in ["epic", "part"] {
+            project.add_task("dev", task_id, task_id.to_string(), None).unwrap();
+        }
+        project.set_task_parent("dev", "part", Some(TaskKey::new("dev", "epic"))).unwrap();
+        project.update_task_file("dev", "part", "src/lib.rs".to_string(), 7, None).unwrap();
+        let created = project.get_task("dev", "part").unwrap().created;
+
+        let trashed = project.trash_task_with_subtasks("dev", "epic").unwrap();
+        assert_eq!(trashed, vec![TaskKey::new("dev", "epic"), TaskKey::new("dev", "part")]);
+        assert!(project.get_task("dev", "part").is_none());
+        assert!(project.index.files.is_empty());
+        assert!(project.is_trashed("dev", "part"));
+        assert!(project.restore_task("dev", "missing").is_err());
+
+        project.restore_task("dev", "part").unwrap();
+        let part = project.get_task("dev", "part").unwrap();
+        assert_eq!(part.created, created);
+        assert_eq!(part.files["src/lib.rs"].lines, vec![7]);
+        assert_eq!(part.parent, None);
+        assert_eq!(project.index.files["src/lib.rs"], HashSet::from(["dev.part".to_string()]));
+        assert!(project.restore_task("dev", "part").is_err());
+
+        project.add_task("dev", "release", "release".to_string(), None).unwrap();
+        project.get_task_mut("dev", "release").unwrap().dependencies = vec![TaskKey::new("dev", "part")];
+        project.trash_task("dev", "part").unwrap();
+        assert!(project.get_task("dev", "release").unwrap().dependencies.is_empty());
+        project.restore_task("dev", "part").unwrap();
+        assert_eq!(project.get_task("dev", "release").unwrap().dependencies, vec![TaskKey::new("dev", "part")]);
+        project.delete_task("dev", "release").unwrap();
+
+        project.trash[0].deleted_at = Utc::now() - Duration::days(40);
+        project.trash_task("dev", "part").unwrap();
+        assert_eq!(project.empty_trash(Some(30)), vec![TaskKey::new("dev", "epic")]);
+        assert_eq!(project.empty_trash(None), vec![TaskKey::new("dev", "part")]);
+        assert!(project.trash.is_empty());
+    }
+
+    #[test]
+    fn test_subtasks_follow_their_parent() {
+        let mut project = ProjectData::new(None);
+        for task_id in ["epic", "part_a", "part_b", "detail"] {
+            project.add_task("dev", task_id, task_id.to_string(), None).unwrap();
+        }
+        project.set_task_parent("dev", "part_a", Some(TaskKey::new("dev", "epic"))).unwrap();
+        project.set_task_parent("dev", "part_b", Some(TaskKey::new("dev", "epic"))).unwrap();
+        project.set_task_parent("dev", "detail", Some(TaskKey::new("dev", "part_a"))).unwrap();
+        assert!(project.set_task_parent("dev", "epic", Some(TaskKey::new("dev", "detail"))).is_err());
+        project.update_task_status("dev", "detail", TaskStatus::Done).unwrap();
+        assert_eq!(project.subtask_progress("dev", "epic"), (1, 3));
+
+        project.move_task("dev", "epic", "v2", "epic").unwrap();
+        assert_eq!(project.children_of("v2", "epic"), vec![TaskKey::new("dev", "part_a"), TaskKey::new("dev", "part_b")]);
+
+        project.delete_task("dev", "part_b").unwrap();
+        let removed = project.delete_task_with_subtasks("dev", "part_a").unwrap();
+        assert_eq!(removed, vec![TaskKey::new("dev", "part_a"), TaskKey::new("dev", "detail")]);
+        assert!(project.children_of("v2", "epic").is_empty());
+    }
+
+    #[test]
+    fn test_task_references_are_written_as_strings() {
+        let mut task = Task::new("Part".to_string(), None);
+        task.parent = Some(TaskKey::new("dev", "epic"));
+        task.dependencies = vec![TaskKey::new("api", "auth")];
+        let mut value = serde_json::to_value(&task).unwrap();
+        assert_eq!(value["parent"], "dev.epic");
+        assert_eq!(value["dependencies"], serde_json::json!(["api.auth"]));
+
+        // Both forms written by earlier versions still load
+        value["parent"] = serde_json::json!({ "section": "dev", "task_id": "epic" });
+        value["dependencies"] = serde_json::json!(["api:auth"]);
+        let task: Task = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(task.parent, Some(TaskKey::new("dev", "epic")));
+        assert_eq!(task.dependencies, vec![TaskKey::new("api", "auth")]);
+        value["parent"] = serde_json::json!("epic");
+        assert!(serde_json::from_value::<Task>(value.clone()).is_err());
+
+        value["parent"] = serde_json::Value::Null;
+        let trashed = serde_json::json!({ "section": "dev", "task_id": "part", "task": value, "deleted_at": Utc::now() });
+        let trashed: TrashedTask = serde_json::from_value(trashed).unwrap();
+        assert_eq!(trashed.key, TaskKey::new("dev", "part"));
+        assert_eq!(serde_json::to_value(&trashed).unwrap()["key"], "dev.part");
+    }
+
+    fn manual_order(project: &ProjectData) -> Vec<String> {
+        project.ranked_task_ids("ui")
+    }
+
+    #[test]
+    fn test_reorder_task_between_neighbours_and_to_the_ends() {
+        let mut project = ProjectData::new(None);
+        for task_id in ["a", "b", "c", "d"] {
+            project.add_task("ui", task_id, task_id.to_uppercase(), None).unwrap();
+        }
+        assert_eq!(manual_order(&project), vec!["a", "b", "c", "d"]);
+
+        let rank = project.reorder_task("ui", "d", None, Some("a")).unwrap();
+        assert_eq!(rank, RANK_STEP * 1.5);
+        assert_eq!(manual_order(&project), vec!["a", "d", "b", "c"]);
+
+        project.reorder_task("ui", "c", Some("a"), None).unwrap();
+        assert_eq!(manual_order(&project), vec!["c", "a", "d", "b"]);
+
+        project.reorder_task("ui", "c", None, Some("b")).unwrap();
+        assert_eq!(manual_order(&project), vec!["a", "d", "b", "c"]);
+
+        project.reorder_task("ui", "b", Some("d"), Some("a")).unwrap();
+        assert_eq!(manual_order(&project), vec!["a", "b", "d", "c"]);
+        assert!(project.reorder_task("ui", "b", Some("a"), Some("c")).is_err());
+        assert!(project.reorder_task("ui", "b", None, None).is_err());
+        assert!(project.reorder_task("ui", "b", Some("b"), None).is_err());
+
+        project.add_task("ui", "e", "E".to_string(), None).unwrap();
+        assert_eq!(manual_order(&project).last().unwrap(), "e");
+    }
+
+    #[test]
+    fn test_reorder_task_renormalizes_crowded_ranks() {
+        let mut project = ProjectData::new(None);
+        for (task_id, rank) in [("a", 1.0), ("b", 1.0 + 1e-10), ("c", 2.0), ("d", 3.0)] {
+            project.add_task("ui", task_id, task_id.to_string(), None).unwrap();
+            project.get_task_mut("ui", task_id).unwrap().rank = Some(rank);
+        }
+        project.reorder_task("ui", "c", Some("b"), Some("a")).unwrap();
+        assert_eq!(manual_order(&project), vec!["a", "c", "b", "d"]);
+        let ranks: Vec<f64> = manual_order(&project).iter().map(|id| project.get_task("ui", id).unwrap().rank.unwrap()).collect();
+        assert_eq!(ranks, vec![RANK_STEP, RANK_STEP * 1.5, RANK_STEP * 2.0, RANK_STEP * 4.0]);
+    }
+
+    #[test]
+    fn test_note_links_follow_conversion_moves_and_deletes() {
+        let mut project = ProjectData::new(None);
+        let note_id = project.add_note("Login".to_string(), "Build it".to_string(), "dev".to_string(), "login".to_string(), None).unwrap();
+        project.convert_note_to_task(&note_id).unwrap();
+        assert_eq!(project.get_task("dev", "login").unwrap().related_notes, vec![note_id.clone()]);
+        assert_eq!(project.get_note(&note_id).unwrap().related_tasks, vec![TaskKey::new("dev", "login")]);
+
+        project.move_task("dev", "login", "auth", "login").unwrap();
+        assert_eq!(project.get_note(&note_id).unwrap().related_tasks, vec![TaskKey::new("auth", "login")]);
+
+        project.add_task("auth", "logout", "Logout".to_string(), None).unwrap();
+        assert!(project.link_note_to_task(&note_id, "auth", "logout").unwrap());
+        assert!(!project.link_note_to_task(&note_id, "auth", "logout").unwrap());
+        project.delete_task("auth", "logout").unwrap();
+        assert_eq!(project.get_note(&note_id).unwrap().related_tasks, vec![TaskKey::new("auth", "login")]);
+
+        project.delete_note(&note_id).unwrap();
+        assert!(project.get_task("auth", "login").unwrap().related_notes.is_empty());
+    }
+
+    #[test]
+    fn test_note_link_follows_edits() {
+        let mut project = ProjectData::new(None);
+        let note_id = project.add_note("Login".to_string(), "".to_string(), "dev".to_string(), "login".to_string(), None).unwrap();
+        assert!(!project.update_note(&note_id, NoteUpdate { suggested_task_id: Some("signin".to_string()), ..NoteUpdate::default() }).unwrap());
+        assert_eq!(project.get_note(&note_id).unwrap().generated_link, None);
+
+        let (link, previous) = project.generate_note_link(&note_id).unwrap();
+        assert_eq!((link.as_str(), previous), ("// dev:signin:todo: Login", None));
+        assert!(!project.update_note(&note_id, NoteUpdate { title: Some("Sign in".to_string()), ..NoteUpdate::default() }).unwrap());
+        let update = NoteUpdate { section: Some("auth".to_string()), suggested_status: Some(TaskStatus::InProgress), ..NoteUpdate::default() };
+        assert!(project.update_note(&note_id, update).unwrap());
+        let note = project.get_note(&note_id).unwrap();
+        assert_eq!(note.generated_link.as_deref(), Some("// auth:signin:in_progress: Sign in"));
+
+        // The stale paste no longer converts the note; the regenerated link does
+        let stale = vec![("a.rs".to_string(), link)];
+        assert!(project.check_note_conversions(&stale, 0.75).unwrap().is_empty());
+        let (link, previous) = project.generate_note_link(&note_id).unwrap();
+        assert_eq!(previous.as_deref(), Some(link.as_str()));
+        project.check_note_conversions(&[("a.rs".to_string(), link)], 0.75).unwrap();
+        assert!(project.get_task("auth", "signin").is_some());
+        assert!(project.get_task("dev", "signin").is_none());
+    }
+
+    #[test]
+    fn test_checklist_items_and_ids() {
+        let content = "Plan:\n- Add login form\n  * Reset password!\n\n-\n- add login form\n";
+        assert_eq!(ChecklistSplit::Bullets.items(content), vec!["Add login form", "Reset password!", "add login form"]);
+        assert_eq!(ChecklistSplit::Lines.items(content).len(), 5);
+
+        let mut project = ProjectData::new(None);
+        project.add_task("auth", "auth_reset_password", "Reset".to_string(), None).unwrap();
+        let items = ChecklistSplit::Bullets.items(content);
+        assert_eq!(
+            project.checklist_task_ids("auth", Some("auth_"), &items),
+            vec!["auth_add_login_form", "auth_reset_password_2", "auth_add_login_form_2"]
+        );
+        assert_eq!(project.checklist_task_ids("auth", None, &["Ünïcode — ok".to_string()]), vec!["ünïcode_ok"]);
+    }
+
+    #[test]
+    fn test_note_attachments_become_task_references() {
+        let mut project = ProjectData::new(None);
+        let attachment = |file: &str, start, end| NoteAttachment {
+            file_path: file.to_string(),
+            line_start: start,
+            line_end: end,
+            snippet: None,
+        };
+        let note_id = project.add_note("Slow query".to_string(), "".to_string(), "db".to_string(), "slow_query".to_string(), None).unwrap();
+        assert!(project.set_note_attachments(&note_id, vec![attachment("src/db.rs", 0, 3)]).is_err());
+        assert!(project.set_note_attachments(&note_id, vec![attachment("src/db.rs", 9, 3)]).is_err());
+        assert!(project.set_note_attachments(&note_id, vec![attachment(" ", 1, 1)]).is_err());
+        assert!(project.get_note(&note_id).unwrap().attachments.is_empty());
+        project.set_note_attachments(&note_id, vec![attachment("src/db.rs", 40, 52), attachment("src/api.rs", 7, 7)]).unwrap();
+
+        project.convert_note_to_task(&note_id).unwrap();
+        let task = project.get_task("db", "slow_query").unwrap();
+        assert_eq!(task.files["src/db.rs"].lines, vec![40]);
+        assert_eq!(task.files["src/api.rs"].lines, vec![7]);
+
+        // A task already scanned from a pasted link keeps its label line too
+        let scanned = project.add_note("Index".to_string(), "".to_string(), "db".to_string(), "index".to_string(), None).unwrap();
+        project.set_note_attachments(&scanned, vec![attachment("src/db.rs", 12, 14)]).unwrap();
+        project.add_task("db", "index", "Index".to_string(), None).unwrap();
+        project.update_task_file("db", "index", "src/db.rs".to_string(), 3, None).unwrap();
+        project.convert_note_to_task(&scanned).unwrap();
+        assert_eq!(project.get_task("db", "index").unwrap().files["src/db.rs"].lines, vec![3, 12]);
+    }
+
+    #[test]
+    fn test_merge_notes() {
+        let mut project = ProjectData::new(None);
+        let first = project.add_note("Standup".to_string(), "Cache misses\n".to_string(), "perf".to_string(), "cache".to_string(), None).unwrap();
+        let second = project.add_note("Retro".to_string(), "Warm the cache on boot".to_string(), "ops".to_string(), "warmup".to_string(), None).unwrap();
+        let created = Utc::now() - chrono::Duration::days(2);
+        project.notes.get_mut(&second).unwrap().created = created;
+        project.update_note(&second, NoteUpdate { tags: Some(vec!["perf".to_string()]), ..NoteUpdate::default() }).unwrap();
+        project.add_task("perf", "latency", "Latency".to_string(), None).unwrap();
+        project.link_note_to_task(&second, "perf", "latency").unwrap();
+
+        let merged_id = project.merge_notes(&[first.clone(), second.clone()], "Caching".to_string()).unwrap();
+        let merged = project.get_note(&merged_id).unwrap();
+        assert_eq!(merged.title, "Caching");
+        assert_eq!(merged.content, "Cache misses\n\n---\n\nWarm the cache on boot");
+        assert_eq!((merged.section.as_str(), merged.suggested_task_id.as_str()), ("perf", "cache"));
+        assert_eq!(merged.created, created);
+        assert_eq!(merged.tags, vec!["perf"]);
+        assert_eq!(merged.related_tasks, vec![TaskKey::new("perf", "latency")]);
+        assert_eq!(project.get_task("perf", "latency").unwrap().related_notes, vec![merged_id.clone()]);
+        assert!(project.get_note(&first).is_none() && project.get_note(&second).is_none());
+        assert_eq!(project.notes.len(), 1);
+
+        let other = project.add_note("Other".to_string(), "".to_string(), "dev".to_string(), "other".to_string(), None).unwrap();
+        assert!(project.merge_notes(&[other.clone(), "missing".to_string()], "x".to_string()).is_err());
+        project.convert_note_to_task(&merged_id).unwrap();
+        assert!(project.merge_notes(&[other.clone(), merged_id], "x".to_string()).is_err());
+        assert!(project.merge_notes(&[other.clone(), other], "x".to_string()).is_err());
+        assert_eq!(project.notes.len(), 2);
+    }
+
+    #[test]
+    fn test_notes_order_pinned_first_then_recent() {
+        let mut project = ProjectData::new(None);
+        let mut ids = Vec::new();
+        for (title, hours_ago) in [("old", 3), ("newest", 1), ("middle", 2)] {
+            let id = project.add_note(title.to_string(), "".to_string(), "dev".to_string(), title.to_string(), None).unwrap();
+            project.notes.get_mut(&id).unwrap().updated = Utc::now() - chrono::Duration::hours(hours_ago);
+            ids.push(id);
+        }
+        let titles = |project: &ProjectData| project.get_all_notes().iter().map(|note| note.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&project), vec!["newest", "middle", "old"]);
+
+        let updated = project.notes[&ids[0]].updated;
+        assert!(project.set_note_pinned(&ids[0], true).unwrap());
+        assert!(!project.set_note_pinned(&ids[0], true).unwrap());
+        assert_eq!(project.notes[&ids[0]].updated, updated);
+        assert_eq!(titles(&project), vec!["old", "newest", "middle"]);
+        assert!(project.set_note_pinned(&ids[0], false).unwrap());
+        assert_eq!(titles(&project), vec!["newest", "middle", "old"]);
+        assert!(project.set_note_pinned("missing", true).is_err());
+    }
+
+    #[test]
+    fn test_notes_to_markdown() {
+        let mut project = ProjectData::new(None);
+        let day = |d: u32| chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 3, d, 9, 30, 0).unwrap();
+        let fixtures = [
+            ("Retro *action* items", "- [ ] fix CI\n- [ ] `cargo fmt`\n\n", "ops", "ci_fix", 1, false),
+            ("# Login form", "## Fields\nEmail and password", "dev", "login_form", 3, false),
+            ("Old idea", "", "dev", "old_idea", 2, true),
+        ];
+        for (title, content, section, task_id, created, converted) in fixtures {
+            let id = project.add_note(title.to_string(), content.to_string(), section.to_string(), task_id.to_string(), None).unwrap();
+            let note = project.notes.get_mut(&id).unwrap();
+            note.created = day(created);
+            note.is_converted = converted;
+        }
+
+        let expected = "# Notes\n\
+            \n## \\# Login form\n\n\
+            Section: `dev` · Suggested ID: `login_form` · Status: todo · Created: 2026-03-03 09:30 UTC\n\
+            \n## Fields\nEmail and password\n\
+            \n## Retro \\*action\\* items\n\n\
+            Section: `ops` · Suggested ID: `ci_fix` · Status: todo · Created: 2026-03-01 09:30 UTC\n\
+            \n- [ ] fix CI\n- [ ] `cargo fmt`\n";
+        assert_eq!(project.notes_to_markdown(false), expected);
+
+        let all = project.notes_to_markdown(true);
+        assert!(all.contains("\n## Old idea\n\nSection: `dev` · Suggested ID: `old_idea` · Status: todo · Created: 2026-03-02 09:30 UTC · Converted\n"));
+        assert!(all.find("Old idea").unwrap() < all.find("Retro").unwrap());
+    }
+
+    #[test]
+    fn test_note_tags_carry_over_on_conversion() {
+        let mut project = ProjectData::new(None);
+        let fresh = project.add_note("Login".to_string(), "".to_string(), "dev".to_string(), "login".to_string(), None).unwrap();
+        let tags = vec!["Idea".to_string(), "idea".to_string(), "UI".to_string()];
+        project.update_note(&fresh, NoteUpdate { tags: Some(tags), ..NoteUpdate::default() }).unwrap();
+        assert_eq!(project.get_note(&fresh).unwrap().tags, vec!["idea", "ui"]);
+        assert!(project.update_note(&fresh, NoteUpdate { tags: Some(vec![" ".to_string()]), ..NoteUpdate::default() }).is_err());
+        project.convert_note_to_task(&fresh).unwrap();
+        assert_eq!(project.get_task("dev", "login").unwrap().tags, vec!["idea", "ui"]);
+
+        project.add_task("dev", "logout", "Logout".to_string(), None).unwrap();
+        project.add_task_tags("dev", "logout", &["ui".to_string()]).unwrap();
+        let scanned = project.add_note("Logout".to_string(), "".to_string(), "dev".to_string(), "logout".to_string(), None).unwrap();
+        let tags = vec!["meeting".to_string(), "ui".to_string()];
+        project.update_note(&scanned, NoteUpdate { tags: Some(tags), ..NoteUpdate::default() }).unwrap();
+        project.convert_note_to_task(&scanned).unwrap();
+        assert_eq!(project.get_task("dev", "logout").unwrap().tags, vec!["ui", "meeting"]);
+        assert_eq!(project.note_tag_counts().get("ui"), Some(&2));
+    }
+
+    #[test]
+    fn test_purging_notes_unlinks_their_tasks() {
+        let mut project = ProjectData::new(None);
+        let note_id = project.add_note("Login".to_string(), "Build it".to_string(), "dev".to_string(), "login".to_string(), None).unwrap();
+        project.convert_note_to_task(&note_id).unwrap();
+        assert!(project.unlink_note_from_task(&note_id, "dev", "login").unwrap());
+        assert!(!project.unlink_note_from_task(&note_id, "dev", "login").unwrap());
+        project.link_note_to_task(&note_id, "dev", "login").unwrap();
+
+        assert_eq!(project.purge_converted_notes(None), 1);
+        assert!(project.get_task("dev", "login").unwrap().related_notes.is_empty());
+    }
+
+    #[test]
+    fn test_task_template_overrides() {
+        let template = TaskTemplate {
+            section: Some("qa".to_string()),
+            title: Some("Bug: {title}".to_string()),
+            status: Some(TaskStatus::Blocked),
+            tags: vec!["bug".to_string()],
+            ..TaskTemplate::default()
+        };
+        let task = template.with_overrides(TaskTemplate {
+            title: Some("Crash on save".to_string()),
+            status: Some(TaskStatus::Todo),
+            tags: vec!["urgent".to_string()],
+            ..TaskTemplate::default()
+        });
+        assert_eq!(task.title.as_deref(), Some("Bug: Crash on save"));
+        assert_eq!(task.section.as_deref(), Some("qa"));
+        assert_eq!(task.status, Some(TaskStatus::Todo));
+        assert_eq!(task.tags, vec!["bug", "urgent"]);
+        assert_eq!(template.with_overrides(TaskTemplate::default()).title, None);
+
+        let fixed = TaskTemplate { title: Some("Weekly sync".to_string()), ..TaskTemplate::default() };
+        assert_eq!(fixed.with_overrides(TaskTemplate::default()).title.as_deref(), Some("Weekly sync"));
+    }
+
+    #[test]
+    fn test_task_template_placeholders() {
+        let template = TaskTemplate {
+            title: Some("Bug in {component}: {summary}".to_string()),
+            description: Some("Reported by {reporter} for {component}. Keep {this text} and {}".to_string()),
+            ..TaskTemplate::default()
+        };
+        assert_eq!(template.placeholders(), vec!["component", "reporter", "summary"]);
+
+        let values: HashMap<String, String> = [("component", "parser"), ("summary", "crash on {eof}"), ("reporter", "qa")]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let filled = template.substitute(&values).unwrap();
+        assert_eq!(filled.title.as_deref(), Some("Bug in parser: crash on {eof}"));
+        assert_eq!(filled.description.as_deref(), Some("Reported by qa for parser. Keep {this text} and {}"));
+
+        let mut partial = values.clone();
+        partial.remove("summary");
+        partial.remove("reporter");
+        let error = template.substitute(&partial).unwrap_err();
+        assert_eq!(error.to_string(), "Missing values for template placeholders: reporter, summary");
+    }
+
+    #[test]
+    fn test_milestone_membership_follows_task_changes() {
+        let mut project = ProjectData::new(None);
+        for id in ["login", "logout", "cache"] {
+            project.add_task("dev", id, id.to_string(), None).unwrap();
+        }
+        project.create_milestone("v1", "First release", None).unwrap();
+        assert!(project.create_milestone("v1", "Again", None).is_err());
+        assert!(project.create_milestone(" ", "Blank", None).is_err());
+        assert!(project.add_task_to_milestone("v1", "dev", "login").unwrap());
+        assert!(!project.add_task_to_milestone("v1", "dev", "login").unwrap());
+        assert!(project.add_task_to_milestone("v1", "dev", "logout").unwrap());
+        assert!(project.add_task_to_milestone("v1", "dev", "cache").unwrap());
+        assert!(project.add_task_to_milestone("v1", "dev", "missing").is_err());
+        assert!(project.add_task_to_milestone("v2", "dev", "login").is_err());
+
+        project.move_task("dev", "login", "auth", "login").unwrap();
+        project.delete_task("dev", "logout").unwrap();
+        project.trash_task("dev", "cache").unwrap();
+        assert_eq!(project.milestones["v1"].task_refs, vec!["auth:login".to_string()]);
+        // Old IDs still reach the task through its alias
+        assert!(project.remove_task_from_milestone("v1", "dev", "login").unwrap());
+        assert!(!project.remove_task_from_milestone("v1", "dev", "login").unwrap());
+        // A restored task rejoins its milestones
+        project.restore_task("dev", "cache").unwrap();
+        assert_eq!(project.milestones["v1"].task_refs, vec!["dev:cache".to_string()]);
+
+        project.add_task_to_milestone("v1", "auth", "login").unwrap();
+        project.delete_milestone(" v1 ").unwrap();
+        assert!(project.milestones.is_empty());
+        assert!(project.get_task("auth", "login").is_some());
+        assert!(project.delete_milestone("v1").is_err());
+    }
+
+    #[test]
+    fn test_task_links_crud_and_validation() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        let url = "https://github.com/acme/app/issues/12";
+        let links = project.add_task_link("dev", "login", "", url).unwrap();
+        assert_eq!(links, vec![TaskLink { label: url.to_string(), url: url.to_string() }]);
+        let links = project.add_task_link("dev", "login", "Issue 12", url).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].label, "Issue 12");
+        project.add_task_link("dev", "login", "Design", "HTTP://docs.example.com/login").unwrap();
+
+        for invalid in ["ftp://example.com/file", "javascript:alert(1)", "https://", "https://exa mple.com", "docs/login.md"] {
+            assert!(project.add_task_link("dev", "login", "Bad", invalid).is_err(), "{}", invalid);
+        }
+        assert!(project.add_task_link("dev", "missing", "Issue", url).is_err());
+
+        let links = project.remove_task_link("dev", "login", url).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].label, "Design");
+        assert!(project.remove_task_link("dev", "login", url).is_err());
+    }
+
+    #[test]
+    fn test_task_comments_are_capped() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        let first = project.add_task_comment("dev", "login", "alice", "First").unwrap();
+        assert!(project.add_task_comment("dev", "login", "alice", "   ").is_err());
+        assert!(project.add_task_comment("dev", "login", "alice", &"x".repeat(MAX_COMMENT_LENGTH + 1)).is_err());
+        project.add_task_comment("dev", "login", "bob", &"ü".repeat(MAX_COMMENT_LENGTH)).unwrap();
+
+        for i in 0..MAX_COMMENTS_PER_TASK {
+            project.add_task_comment("dev", "login", "bob", &format!("Comment {}", i)).unwrap();
+        }
+        let comments = &project.get_task("dev", "login").unwrap().comments;
+        assert_eq!(comments.len(), MAX_COMMENTS_PER_TASK);
+        assert_eq!(comments[0].text, "Comment 0");
+        let last_id = comments.last().unwrap().id.clone();
+
+        assert!(project.delete_task_comment("dev", "login", &first.id).is_err());
+        project.delete_task_comment("dev", "login", &last_id).unwrap();
+        assert_eq!(project.get_task("dev", "login").unwrap().comments.len(), MAX_COMMENTS_PER_TASK - 1);
+    }
+
+    #[test]
+    fn test_task_tags_are_normalized() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        let tags = project.add_task_tags("dev", "login", &[" Backend ".to_string(), "backend".to_string(), "Ünïcode".to_string()]).unwrap();
+        assert_eq!(tags, vec!["backend".to_string(), "ünïcode".to_string()]);
+        assert!(project.add_task_tags("dev", "login", &["  ".to_string()]).is_err());
+
+        let tags = project.remove_task_tags("dev", "login", &["BACKEND".to_string()]).unwrap();
+        assert_eq!(tags, vec!["ünïcode".to_string()]);
+        assert_eq!(project.tag_counts().get("ünïcode"), Some(&1));
+        assert!(project.get_task("dev", "login").unwrap().has_any_tag(&["ÜNÏCODE".to_string()]));
+    }
+
+    #[test]
+    fn test_archive_section_keeps_tasks() {
+        let mut project = ProjectData::new(None);
+        project.add_task("v1", "ship", "Ship it".to_string(), None).unwrap();
+        project.add_task("v2", "plan", "Plan next".to_string(), None).unwrap();
+
+        project.archive_section("v1").unwrap();
+        assert!(project.archive_section("v1").is_err());
+        assert!(project.archive_section("missing").is_err());
+        let indexed = |project: &ProjectData, id: &str| {
+            project.index.tasks_by_status.values().any(|ids| ids.contains(id))
+        };
+        assert!(!indexed(&project, "v1.ship"));
+        assert!(indexed(&project, "v2.plan"));
+        project.rebuild_index();
+        assert!(!indexed(&project, "v1.ship"));
+        assert!(project.index_consistency_check().is_empty());
+
+        let mut visible = project.clone();
+        visible.hide_archived_sections();
+        assert!(!visible.sections.contains_key("v1"));
+        assert!(visible.sections.contains_key("v2"));
+
+        project.unarchive_section("v1").unwrap();
+        assert!(!project.is_section_archived("v1"));
+        assert_eq!(project.get_task("v1", "ship").unwrap().title, "Ship it");
+        assert!(indexed(&project, "v1.ship"));
+        assert!(project.index_consistency_check().is_empty());
+        assert!(project.unarchive_section("v1").is_err());
+    }
+
+    #[test]
+    fn test_status_parses_aliases() {
+        assert_eq!("WIP".parse::<TaskStatus>().unwrap(), TaskStatus::InProgress);
+        assert_eq!("resolved".parse::<TaskStatus>().unwrap(), TaskStatus::Done);
+        assert_eq!("on_hold".parse::<TaskStatus>().unwrap(), TaskStatus::Blocked);
+        assert!("shipped".parse::<TaskStatus>().is_err());
+    }
+
+    #[test]
+    fn test_purge_converted_notes() {
+        let mut project = ProjectData::new(None);
+        for (id, converted_days_ago) in [("old", Some(40)), ("recent", Some(2)), ("open", None)] {
+            let mut note = Note::new(id.to_string(), String::new(), "dev".to_string(), id.to_string(), None);
+            note.id = id.to_string();
+            if let Some(days) = converted_days_ago {
+                note.mark_as_converted();
+                note.converted_at = Some(Utc::now() - Duration::days(days));
+            }
+            project.notes.insert(note.id.clone(), note);
+        }
+        
+        assert_eq!(project.purge_converted_notes(Some(Duration::days(30))), 1);
+        assert!(!project.notes.contains_key("old"));
+        assert!(project.notes.contains_key("recent"));
+        assert_eq!(project.purge_converted_notes(Some(Duration::days(30))), 0);
+        assert_eq!(project.purge_converted_notes(None), 1);
+        assert_eq!(project.notes.keys().collect::<Vec<_>>(), vec!["open"]);
+    }
+
+    #[test]
+    fn test_link_match_confidence() {
+        let mut note = Note::new(
+            "Build the login form".to_string(),
+            "Email and password".to_string(),
+            "dev".to_string(),
+            "login_form".to_string(),
+            None,
+        );
+        assert_eq!(note.link_match_confidence("// dev:login_form:todo: Build the login form"), 0.0);
+        note.generate_task_link();
+
+        assert_eq!(note.link_match_confidence("fn a() {}\n  //   dev:login_form:todo:  Build the login form\n"), 1.0);
+        let edited = note.link_match_confidence("# dev:login_form:todo: Build login form (see design doc)");
+        assert!(edited > 0.75 && edited < 1.0);
+        assert!(note.link_match_confidence("// dev:login_form_v2:todo: Build the login form") == 0.0);
+        assert!(note.link_match_confidence("// dev:login_form: migrate the database schema") < 0.75);
+    }
+
+    #[test]
+    fn test_move_and_merge_tasks() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        project.add_task("ui", "login", "Login screen".to_string(), None).unwrap();
+        project.update_task_file("dev", "login", "a.rs".to_string(), 3, None).unwrap();
+        project.update_task_file("ui", "login", "b.rs".to_string(), 7, None).unwrap();
+        project.add_note("Remember".to_string(), "".to_string(), "dev".to_string(), "login".to_string(), None).unwrap();
+
+        assert!(project.move_task("dev", "login", "ui", "login").is_err());
+        project.move_task("dev", "login", "auth", "login").unwrap();
+        assert!(!project.sections.contains_key("dev"));
+        assert!(project.get_task("auth", "login").unwrap().files.contains_key("a.rs"));
+        assert_eq!(project.notes_for_task("auth", "login").len(), 1);
+
+        project.merge_task_into("auth", "login", "ui", "login").unwrap();
+        assert!(project.get_task("auth", "login").is_none());
+        let merged = project.get_task("ui", "login").unwrap();
+        assert!(merged.files.contains_key("a.rs") && merged.files.contains_key("b.rs"));
+        assert_eq!(project.notes_for_task("ui", "login").len(), 1);
+        assert!(project.merge_task_into("ui", "login", "ui", "login").is_err());
+    }
+
+    #[test]
+    fn test_task_history_follows_moves_and_is_capped() {
+        let mut project = ProjectData::new(None);
+        project.track_changes("dev", "login", ChangeSource::Manual, |data| data.add_task("dev", "login", "Login".to_string(), None)).unwrap();
+        project.track_changes("dev", "login", ChangeSource::Manual, |data| data.set_task_assignee("dev", "login", Some("ana".to_string()))).unwrap();
+        // An edit that changes nothing leaves no entry
+        project.track_changes("dev", "login", ChangeSource::Manual, |data| data.set_task_assignee("dev", "login", Some("ana".to_string()))).unwrap();
+        let fields: Vec<&str> = project.task_history("dev", "login", None).iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, vec!["assignee", "created"]);
+
+        project.move_task("dev", "login", "auth", "login").unwrap();
+        assert!(!project.history.contains_key("dev.login"));
+        assert_eq!(project.task_history("auth", "login", None).len(), 2);
+        assert_eq!(project.task_history("dev", "login", Some(1))[0].field, "assignee");
+
+        for _ in 0..MAX_TASK_HISTORY {
+            let change = TaskChange::diff(None, project.get_task("auth", "login").unwrap(), ChangeSource::Scan);
+            project.record_changes("auth", "login", change);
+        }
+        let history = project.task_history("auth", "login", None);
+        assert_eq!(history.len(), MAX_TASK_HISTORY);
+        assert!(history.iter().all(|change| change.source == ChangeSource::Scan));
+    }
+
+    #[test]
+    fn test_time_entries_accumulate_and_reject_bad_values() {
+        let mut project = ProjectData::new(None);
+        project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+        assert_eq!(project.add_time_entry("dev", "login", 30, Some(" pairing ".to_string())).unwrap(), 30);
+        assert_eq!(project.add_time_entry("dev", "login", 45, Some("  ".to_string())).unwrap(), 75);
+        let task = project.get_task("dev", "login").unwrap();
+        assert_eq!(task.time_spent_minutes, 75);
+        assert_eq!(task.time_entries.len(), 2);
+        assert_eq!(task.time_entries[0].note.as_deref(), Some("pairing"));
+        assert_eq!(task.time_entries[1].note, None);
+
+        assert!(project.add_time_entry("dev", "login", 0, None).is_err());
+        assert!(project.add_time_entry("dev", "login", MAX_TIME_ENTRY_MINUTES + 1, None).is_err());
+        assert!(project.add_time_entry("dev", "missing", 10, None).is_err());
+        assert_eq!(project.get_task("dev", "login").unwrap().time_spent_minutes, 75);
+
+        project.set_task_estimate("dev", "login", Some(60)).unwrap();
+        assert!(project.set_task_estimate("dev", "login", Some(0)).is_err());
+        assert!(project.set_task_estimate("dev", "login", Some(MAX_ESTIMATE_MINUTES + 1)).is_err());
+        assert_eq!(project.get_task("dev", "login").unwrap().estimate_minutes, Some(60));
+        project.set_task_estimate("dev", "login", None).unwrap();
+        assert_eq!(project.get_task("dev", "login").unwrap().estimate_minutes, None);
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+ * Timezone Module for Anchora Backend
+ *
+ * Storage always keeps UTC. When a client asks for a timezone, timestamps in
+ * the response are rewritten into that zone with an explicit offset.
+ */
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde_json::Value;
+
+/// Parse an IANA timezone name such as `America/New_York`
+pub fn parse_timezone(name: &str) -> anyhow::Result<Tz> {
+    name.parse::<Tz>()
+        .map_err(|_| anyhow::anyhow!("Invalid timezone: {}", name))
+}
+
+/// Fields that hold timestamps in responses; strings under any other key,
+/// such as titles or note content, are never rewritten
+pub const TIMESTAMP_FIELDS: &[&str] = &[
+    "converted_at",
+    "created",
+    "deleted_at",
+    "due",
+    "estimated_completion",
+    "generated_at",
+    "last_calculated",
+    "last_modified",
+    "last_updated",
+    "logged_at",
+    "timestamp",
+    "updated",
+];
+
+/// Rewrite the RFC 3339 timestamps under known timestamp fields into the given zone
+pub fn localize_timestamps(value: &mut Value, tz: Tz) {
+    match value {
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| localize_timestamps(item, tz)),
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                match field {
+                    Value::String(text) if TIMESTAMP_FIELDS.contains(&name.as_str()) => {
+                        if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+                            *text = timestamp
+                                .with_timezone(&Utc)
+                                .with_timezone(&tz)
+                                .to_rfc3339();
+                        }
+                    }
+                    _ => localize_timestamps(field, tz),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Localize a response when a timezone was requested, recording the zone used
+pub fn localize_response(mut value: Value, tz: Option<Tz>) -> Value {
+    if let Some(tz) = tz {
+        localize_timestamps(&mut value, tz);
+        if let Value::Object(fields) = &mut value {
+            fields.insert("timezone".to_string(), Value::String(tz.name().to_string()));
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_localize_timestamps() {
+        let tz = parse_timezone("Asia/Tokyo").unwrap();
+        let value = localize_response(
+            json!({
+                "updated": "2026-01-15T03:00:00+00:00",
+                "title": "2026-01-15T03:00:00Z",
+                "items": [{ "timestamp": "2026-01-15T03:00:00Z", "description": "not a date" }]
+            }),
+            Some(tz),
+        );
+        assert_eq!(value["updated"], "2026-01-15T12:00:00+09:00");
+        assert_eq!(value["items"][0]["timestamp"], "2026-01-15T12:00:00+09:00");
+        assert_eq!(value["items"][0]["description"], "not a date");
+        assert_eq!(value["title"], "2026-01-15T03:00:00Z");
+        assert_eq!(value["timezone"], "Asia/Tokyo");
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+}
@@ -8,12 +8,17 @@
  * - Context-aware validation rules
  */
 
-use std::collections::HashSet;
-use std::sync::RwLock;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 use serde::{Deserialize, Serialize};
-use regex::Regex;
-use crate::task_manager::ProjectData;
+use crate::identifier::{IdStyle, IdentifierMode};
+use crate::search_engine::{is_stop_word, normalize_text};
+use crate::similarity::{similarity, SimilarityIndex};
+use crate::storage::DataVersion;
+use crate::task_manager::{ProjectData, TaskKey, TaskStatus};
 use anyhow::Result;
+use regex::Regex;
 
 /// Parameters for task validation
 #[derive(Debug, Deserialize)]
@@ -26,6 +31,16 @@ pub struct ValidationParams {
     pub suggest_alternatives: Option<bool>,
 }
 
+/// Parameters for note validation, mirroring note creation
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoteValidationParams {
+    pub title: String,
+    pub content: String,
+    pub section: String,
+    pub suggested_task_id: String,
+    pub suggested_status: Option<String>,
+}
+
 /// Result of validation with errors, warnings, and suggestions
 #[derive(Debug, Serialize)]
 pub struct ValidationResult {
@@ -34,12 +49,112 @@ pub struct ValidationResult {
     pub warnings: Vec<ValidationWarning>,
     pub suggestions: Vec<String>,
     pub alternative_ids: Vec<String>,
+    /// Overall quality of the label from 0 (unusable) to 100 (no issues)
+    pub quality_score: f32,
+    /// False only when a hard error blocks creating the task
+    pub can_create: bool,
+}
+
+impl ValidationResult {
+    /// Recompute the derived fields after the issue lists changed
+    fn summarize(&mut self) {
+        let severities = self.errors.iter().map(|error| error.severity)
+            .chain(self.warnings.iter().map(|warning| warning.severity));
+        let penalty: f32 = severities.clone().map(Severity::penalty).sum();
+        
+        self.is_valid = self.errors.is_empty();
+        self.can_create = !severities.into_iter().any(|severity| severity == Severity::Error);
+        self.quality_score = (100.0 - penalty).max(0.0);
+    }
+    
+    /// Drop the duplicate id error for callers that intend to overwrite the task
+    pub fn allow_existing(&mut self) {
+        self.errors.retain(|error| error.error_type != "duplicate_task_id");
+        self.alternative_ids.clear();
+        self.summarize();
+    }
+}
+
+/// Error returned when a write is refused by validation, carrying the full result
+#[derive(Debug)]
+pub struct ValidationFailed(pub ValidationResult);
+
+impl std::fmt::Display for ValidationFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0.errors.iter().find(|error| error.severity == Severity::Error) {
+            Some(error) => {
+                write!(f, "{}", error.message)?;
+                if let Some(suggestion) = &error.suggestion {
+                    write!(f, ". {}", suggestion)?;
+                }
+                Ok(())
+            }
+            None => write!(f, "Validation failed"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationFailed {}
+
+/// Result of validating several labels at once
+#[derive(Debug, Serialize)]
+pub struct BatchValidationResult {
+    pub all_valid: bool,
+    pub results: Vec<ValidationResult>,
+}
+
+/// Stored task, or section when `task_id` is absent, that breaks a rule
+#[derive(Debug, Serialize)]
+pub struct RuleViolation {
+    pub section: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    pub message: String,
+}
+
+/// Violations of one rule across the project
+#[derive(Debug, Serialize)]
+pub struct RuleReport {
+    pub rule: String,
+    pub severity: Severity,
+    /// Total violations, including those left out of `violations` by the cap
+    pub count: usize,
+    pub violations: Vec<RuleViolation>,
+}
+
+/// Result of checking every stored task against the current rules
+#[derive(Debug, Serialize)]
+pub struct ProjectValidationReport {
+    pub tasks_checked: usize,
+    pub violation_count: usize,
+    pub rules: Vec<RuleReport>,
+}
+
+/// How serious a validation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    /// Points deducted from the quality score per issue
+    fn penalty(self) -> f32 {
+        match self {
+            Severity::Error => 50.0,
+            Severity::Warning => 15.0,
+            Severity::Info => 5.0,
+        }
+    }
 }
 
 /// Validation error with specific details
 #[derive(Debug, Serialize)]
 pub struct ValidationError {
     pub error_type: String,
+    pub severity: Severity,
     pub field: String,
     pub message: String,
     pub suggestion: Option<String>,
@@ -49,9 +164,22 @@ pub struct ValidationError {
 #[derive(Debug, Serialize)]
 pub struct ValidationWarning {
     pub warning_type: String,
+    pub severity: Severity,
     pub field: String,
     pub message: String,
     pub recommendation: Option<String>,
+    /// Existing task the warning refers to, so the UI can jump to it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub existing_task: Option<TaskLocation>,
+}
+
+/// Coordinates of an existing task
+#[derive(Debug, Serialize)]
+pub struct TaskLocation {
+    pub section: String,
+    pub task_id: String,
+    pub file_path: Option<String>,
+    pub line: Option<u32>,
 }
 
 /// Conflict detection result
@@ -70,17 +198,98 @@ pub struct Conflict {
     pub existing_task_id: String,
     pub description: String,
     pub severity: String,
+    /// Action that resolves the conflict, for `apply_conflict_resolution`
+    pub resolution: Option<ResolutionAction>,
+}
+
+/// Machine-actionable fix for a conflict, applied to the checked task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ResolutionAction {
+    RenameTo { new_id: String },
+    MoveToSection { section: String },
+    MergeInto { section: String, task_id: String },
 }
 
 /// Validation engine configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ValidationConfig {
     pub max_task_id_length: usize,
     pub min_task_id_length: usize,
+    pub max_section_length: usize,
+    pub min_section_length: usize,
     pub max_title_length: usize,
     pub max_description_length: usize,
     pub enable_smart_suggestions: bool,
     pub similarity_threshold: f32,
+    /// Extra names rejected as task IDs and sections
+    pub additional_reserved: Vec<String>,
+    /// Built-in reserved names the project is allowed to use
+    pub allowed_reserved: Vec<String>,
+    /// Grammar for section names and task IDs, shared with the file parser
+    pub identifier_mode: IdentifierMode,
+    /// Only suggest alternative IDs that are unused in every section
+    pub require_unique_ids_across_sections: bool,
+    /// Refuse tasks without a title
+    pub require_title: bool,
+    /// Per-section overrides of the rules above
+    pub sections: HashMap<String, SectionValidationProfile>,
+    /// Opt-in style checks on titles and descriptions, reported as warnings
+    pub conventions: ConventionRules,
+}
+
+/// Writing conventions for task text; every check is off by default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConventionRules {
+    /// Titles start with a capital letter
+    pub capitalized_title: bool,
+    /// Descriptions say more than the title
+    pub distinct_description: bool,
+    /// Upper bound on the number of words in a title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_title_words: Option<usize>,
+}
+
+/// Rules for one section; unset fields fall back to the global config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SectionValidationProfile {
+    /// Pattern every task ID in the section must match in full
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_task_id_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_title_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_title: Option<bool>,
+}
+
+/// Rules in effect for one section after merging its profile over the global config
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionRules {
+    pub id_pattern: Option<String>,
+    pub max_task_id_length: usize,
+    pub max_title_length: usize,
+    pub require_title: bool,
+}
+
+impl ValidationConfig {
+    /// Effective rules for a section
+    pub fn section_rules(&self, section: &str) -> SectionRules {
+        let profile = self.sections.get(section);
+        SectionRules {
+            id_pattern: profile.and_then(|profile| profile.id_pattern.clone()),
+            max_task_id_length: profile.and_then(|profile| profile.max_task_id_length)
+                .unwrap_or(self.max_task_id_length),
+            max_title_length: profile.and_then(|profile| profile.max_title_length)
+                .unwrap_or(self.max_title_length),
+            require_title: profile.and_then(|profile| profile.require_title)
+                .unwrap_or(self.require_title),
+        }
+    }
 }
 
 impl Default for ValidationConfig {
@@ -88,149 +297,574 @@ impl Default for ValidationConfig {
         Self {
             max_task_id_length: 50,
             min_task_id_length: 2,
+            max_section_length: 50,
+            min_section_length: 1,
             max_title_length: 200,
             max_description_length: 2000,
             enable_smart_suggestions: true,
             similarity_threshold: 0.8,
+            additional_reserved: Vec::new(),
+            allowed_reserved: Vec::new(),
+            identifier_mode: IdentifierMode::default(),
+            require_unique_ids_across_sections: false,
+            require_title: false,
+            sections: HashMap::new(),
+            conventions: ConventionRules::default(),
         }
     }
 }
 
 /// Validation engine with smart rules and suggestions
 pub struct ValidationEngine {
-    project_data: RwLock<Option<ProjectData>>,
-    reserved_names: HashSet<String>,
-    config: ValidationConfig,
-    task_id_pattern: Regex,
+    /// Swapped as a whole on refresh so readers never wait on a rebuild
+    context: RwLock<Option<Arc<ValidationContext>>>,
+    reserved_names: RwLock<HashSet<String>>,
+    config: RwLock<ValidationConfig>,
+    /// Compiled `id_pattern` of each section profile
+    section_patterns: RwLock<HashMap<String, Regex>>,
+    /// Number of context snapshots built so far
+    snapshot_builds: AtomicUsize,
+}
+
+/// Project snapshot the engine validates against
+struct ValidationContext {
+    project_data: ProjectData,
+    /// Naming conventions per section, computed once per snapshot
+    conventions: HashMap<String, SectionConventions>,
+    /// Task IDs per section, indexed for similarity lookups
+    id_indexes: HashMap<String, SimilarityIndex>,
+    /// Version of the tasks file the snapshot was taken from, if known
+    version: Option<DataVersion>,
+}
+
+/// Naming conventions observed among the task IDs of one section
+#[derive(Debug, Default)]
+struct SectionConventions {
+    /// Casing used by most IDs, if one clearly dominates
+    style: Option<IdStyle>,
+    /// Leading `word_` shared by most IDs
+    prefix: Option<String>,
+    /// Highest `_N` suffix when most IDs are numbered
+    numbered: Option<u32>,
+    /// Lowercased ID segments, used to spot IDs filed in the wrong section
+    words: HashSet<String>,
+}
+
+impl SectionConventions {
+    /// Share of IDs that must follow a pattern before it counts as a convention
+    const DOMINANCE: f32 = 0.6;
+    /// Minimum number of IDs before conventions are inferred at all
+    const MIN_IDS: usize = 3;
+
+    fn from_ids<'a>(ids: impl Iterator<Item = &'a String>) -> Self {
+        let ids: Vec<&String> = ids.collect();
+        let mut conventions = SectionConventions {
+            words: ids.iter().flat_map(|id| id_words(id)).collect(),
+            ..Default::default()
+        };
+        if ids.len() < Self::MIN_IDS {
+            return conventions;
+        }
+        let dominant = |count: usize, total: usize| total >= Self::MIN_IDS && count as f32 >= total as f32 * Self::DOMINANCE;
+        
+        let styles: Vec<IdStyle> = ids.iter().filter_map(|id| id_style(id)).collect();
+        for style in [IdStyle::SnakeCase, IdStyle::CamelCase, IdStyle::PascalCase] {
+            if dominant(styles.iter().filter(|s| **s == style).count(), styles.len()) {
+                conventions.style = Some(style);
+            }
+        }
+        
+        let mut prefixes: HashMap<&str, usize> = HashMap::new();
+        for id in &ids {
+            if let Some((prefix, rest)) = id.split_once('_') && !prefix.is_empty() && !rest.is_empty() {
+                *prefixes.entry(prefix).or_default() += 1;
+            }
+        }
+        if let Some((prefix, count)) = prefixes.into_iter().max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+            && dominant(count, ids.len())
+        {
+            conventions.prefix = Some(format!("{}_", prefix));
+        }
+        
+        let numbers: Vec<u32> = ids.iter().filter_map(|id| numeric_suffix(id)).collect();
+        if dominant(numbers.len(), ids.len()) {
+            conventions.numbered = numbers.into_iter().max();
+        }
+        conventions
+    }
+}
+
+/// Casing of an ID, or `None` when it reads the same in several styles
+fn id_style(id: &str) -> Option<IdStyle> {
+    let matching: Vec<IdStyle> = [IdStyle::SnakeCase, IdStyle::CamelCase, IdStyle::PascalCase]
+        .into_iter()
+        .filter(|style| style.apply(id) == id)
+        .collect();
+    match matching.as_slice() {
+        [style] => Some(*style),
+        _ => None,
+    }
+}
+
+fn id_words(id: &str) -> Vec<String> {
+    IdStyle::SnakeCase.apply(id)
+        .split('_')
+        .filter(|word| word.chars().count() > 1 && !word.chars().all(char::is_numeric))
+        .map(String::from)
+        .collect()
+}
+
+fn numeric_suffix(id: &str) -> Option<u32> {
+    let (_, suffix) = id.rsplit_once('_')?;
+    suffix.parse().ok()
+}
+
+fn style_name(style: IdStyle) -> &'static str {
+    match style {
+        IdStyle::SnakeCase => "snake_case",
+        IdStyle::CamelCase => "camelCase",
+        IdStyle::PascalCase => "PascalCase",
+    }
 }
 
 impl ValidationEngine {
     /// Create a new validation engine
     pub fn new(config: Option<ValidationConfig>) -> Self {
         let config = config.unwrap_or_default();
-        let reserved_names = Self::create_reserved_names();
-        let task_id_pattern = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_-]*$").unwrap();
+        let reserved_names = Self::build_reserved_names(&config);
+        let section_patterns = Self::build_section_patterns(&config).unwrap_or_else(|e| {
+            eprintln!("[WARN] Ignoring section id patterns: {}", e);
+            HashMap::new()
+        });
         
         Self {
-            project_data: RwLock::new(None),
-            reserved_names,
-            config,
-            task_id_pattern,
+            context: RwLock::new(None),
+            reserved_names: RwLock::new(reserved_names),
+            config: RwLock::new(config),
+            section_patterns: RwLock::new(section_patterns),
+            snapshot_builds: AtomicUsize::new(0),
         }
     }
 
     /// Update project data context for validation
     pub fn update_context(&self, project_data: ProjectData) -> Result<()> {
-        let mut data = self.project_data.write()
-            .map_err(|_| anyhow::anyhow!("Failed to acquire write lock on project data"))?;
-        *data = Some(project_data);
+        self.install_context(project_data, None)
+    }
+
+    /// Replace the context with project data read at the given version of the tasks file
+    pub fn update_context_at(&self, project_data: ProjectData, version: DataVersion) -> Result<()> {
+        self.install_context(project_data, Some(version))
+    }
+
+    /// Version of the tasks file behind the current context, if it was taken from storage
+    pub fn context_version(&self) -> Option<DataVersion> {
+        self.context().ok().flatten().and_then(|context| context.version)
+    }
+
+    /// Number of context snapshots built since the engine was created
+    pub fn snapshot_builds(&self) -> usize {
+        self.snapshot_builds.load(Ordering::Relaxed)
+    }
+
+    fn install_context(&self, project_data: ProjectData, version: Option<DataVersion>) -> Result<()> {
+        let conventions = project_data.sections.iter()
+            .map(|(name, tasks)| (name.clone(), SectionConventions::from_ids(tasks.keys())))
+            .collect();
+        let id_indexes = project_data.sections.iter()
+            .map(|(name, tasks)| (name.clone(), SimilarityIndex::new(tasks.keys())))
+            .collect();
+        let context = Arc::new(ValidationContext { project_data, conventions, id_indexes, version });
+        self.snapshot_builds.fetch_add(1, Ordering::Relaxed);
+        *self.context.write()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire write lock on validation context"))? = Some(context);
         Ok(())
     }
 
+    /// Current snapshot; the lock is only held long enough to clone the Arc
+    fn context(&self) -> Result<Option<Arc<ValidationContext>>> {
+        self.context.read()
+            .map(|context| context.clone())
+            .map_err(|_| anyhow::anyhow!("Failed to acquire read lock on validation context"))
+    }
+
+    /// Replace the configuration and rebuild the reserved names set
+    pub fn apply_config(&self, config: ValidationConfig) -> Result<()> {
+        if *self.config() == config {
+            return Ok(());
+        }
+        let section_patterns = Self::build_section_patterns(&config)?;
+        let reserved_names = Self::build_reserved_names(&config);
+        *self.section_patterns.write()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire write lock on section patterns"))? = section_patterns;
+        *self.reserved_names.write()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire write lock on reserved names"))? = reserved_names;
+        *self.config.write()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire write lock on validation config"))? = config;
+        Ok(())
+    }
+
+    /// Current configuration
+    pub fn get_config(&self) -> ValidationConfig {
+        self.config().clone()
+    }
+
+    /// Effective reserved names, sorted
+    pub fn get_reserved_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.reserved_names.read()
+            .map(|names| names.iter().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    fn config(&self) -> RwLockReadGuard<'_, ValidationConfig> {
+        self.config.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_reserved(&self, name: &str) -> bool {
+        self.reserved_names.read()
+            .map(|names| names.contains(&name.to_lowercase()))
+            .unwrap_or(false)
+    }
+
     /// Validate task creation parameters
     pub fn validate_task_creation(&self, params: &ValidationParams) -> Result<ValidationResult> {
         let start_time = std::time::Instant::now();
         
-        let project_data = self.project_data.read()
-            .map_err(|_| anyhow::anyhow!("Failed to acquire read lock on project data"))?;
+        let context = self.context()?;
+        let project_data = context.as_ref().map(|context| &context.project_data);
         
         let mut errors = Vec::new();
         let mut warnings = Vec::new();
         let mut suggestions = Vec::new();
         let mut alternative_ids = Vec::new();
         
-        if let Some(error) = self.validate_task_id_format(&params.task_id) {
+        if let Some(error) = self.validate_section_format(&params.section, project_data) {
+            errors.push(error);
+        } else if let Some(data) = project_data
+            && !data.sections.contains_key(&params.section)
+            && let Some(existing) = self.find_similar_section(&params.section, data)
+        {
+            warnings.push(ValidationWarning {
+                warning_type: "similar_section".to_string(),
+                severity: Severity::Warning,
+                field: "section".to_string(),
+                message: format!("Section '{}' does not exist yet but is very similar to '{}'", 
+                               params.section, existing),
+                recommendation: Some(format!("Did you mean '{}'?", existing)),
+                existing_task: None,
+            });
+        }
+        
+        if let Some(error) = self.validate_task_id_format(&params.section, &params.task_id) {
+            errors.push(error);
+        }
+        
+        if let Some(error) = self.validate_task_id_reserved(&params.task_id) {
             errors.push(error);
         }
         
-        if self.reserved_names.contains(&params.task_id.to_lowercase()) {
+        if let Some(data) = project_data
+            && let Some(section) = data.sections.get(&params.section)
+            && section.contains_key(&params.task_id)
+        {
             errors.push(ValidationError {
-                error_type: "reserved_name".to_string(),
+                error_type: "duplicate_task_id".to_string(),
+                severity: Severity::Error,
                 field: "task_id".to_string(),
-                message: format!("'{}' is a reserved name and cannot be used as task ID", params.task_id),
-                suggestion: Some(format!("Try '{}' or '{}_task' instead", 
-                                       params.task_id.clone() + "_1", params.task_id)),
+                message: format!("Task ID '{}' already exists in section '{}'", 
+                               params.task_id, params.section),
+                suggestion: Some("Please choose a different task ID".to_string()),
             });
+            
+            if params.suggest_alternatives.unwrap_or(true) {
+                alternative_ids = self.generate_alternative_ids(&params.task_id, &params.section,
+                                                                params.title.as_deref(), data);
+            }
         }
         
-        if let Some(data) = project_data.as_ref() {
-            if let Some(section) = data.sections.get(&params.section) {
-                if section.contains_key(&params.task_id) {
-                    errors.push(ValidationError {
-                        error_type: "duplicate_task_id".to_string(),
-                        field: "task_id".to_string(),
-                        message: format!("Task ID '{}' already exists in section '{}'", 
-                                       params.task_id, params.section),
-                        suggestion: Some("Please choose a different task ID".to_string()),
+        if let Some(data) = project_data {
+            let mut other_sections: Vec<&String> = data.sections.iter()
+                .filter(|(name, tasks)| **name != params.section && tasks.contains_key(&params.task_id))
+                .map(|(name, _)| name)
+                .collect();
+            other_sections.sort();
+            for other_section in other_sections {
+                warnings.push(ValidationWarning {
+                    warning_type: "duplicate_id_cross_section".to_string(),
+                    severity: Severity::Warning,
+                    field: "task_id".to_string(),
+                    message: format!("Task ID '{}' already exists in section '{}'", 
+                                   params.task_id, other_section),
+                    recommendation: Some(format!("Use a section-specific prefix like '{}_{}'", 
+                                               params.section, params.task_id)),
+                    existing_task: Self::task_location(data, other_section, &params.task_id),
+                });
+            }
+            
+            if let Some(title) = params.title.as_deref()
+                && !title.trim().is_empty()
+            {
+                for (section, task_id, existing_title) in self.find_similar_titles(title, params, data) {
+                    warnings.push(ValidationWarning {
+                        warning_type: "similar_title".to_string(),
+                        severity: Severity::Info,
+                        field: "title".to_string(),
+                        message: format!("Title is very similar to '{}' ({}:{})", 
+                                       existing_title, section, task_id),
+                        recommendation: Some(format!("Check whether {}:{} already covers this work", section, task_id)),
+                        existing_task: Self::task_location(data, section, task_id),
                     });
-                    
-                    if params.suggest_alternatives.unwrap_or(true) {
-                        alternative_ids = self.generate_alternative_ids(&params.task_id, data);
-                    }
                 }
             }
         }
         
-        if let Some(title) = &params.title {
+        let rules = self.config().section_rules(&params.section);
+        if rules.require_title && params.title.as_deref().is_none_or(|title| title.trim().is_empty()) {
+            errors.push(ValidationError {
+                error_type: "title_required".to_string(),
+                severity: Severity::Error,
+                field: "title".to_string(),
+                message: format!("Tasks in section '{}' must have a title", params.section),
+                suggestion: Some("Please provide a descriptive title".to_string()),
+            });
+        } else if let Some(title) = &params.title {
             if title.trim().is_empty() {
                 warnings.push(ValidationWarning {
                     warning_type: "empty_title".to_string(),
+                    severity: Severity::Warning,
                     field: "title".to_string(),
                     message: "Task title is empty".to_string(),
                     recommendation: Some("Consider providing a descriptive title".to_string()),
+                    existing_task: None,
                 });
-            } else if title.len() > self.config.max_title_length {
+            } else if title.len() > rules.max_title_length {
                 errors.push(ValidationError {
                     error_type: "title_too_long".to_string(),
+                    severity: Severity::Error,
                     field: "title".to_string(),
                     message: format!("Title exceeds maximum length of {} characters", 
-                                   self.config.max_title_length),
+                                   rules.max_title_length),
                     suggestion: Some("Please shorten the title".to_string()),
                 });
             }
         }
         
+        warnings.extend(Self::convention_warnings(&self.config().conventions, params));
+        
         if let Some(description) = &params.description {
-            if description.len() > self.config.max_description_length {
+            if description.len() > self.config().max_description_length {
                 errors.push(ValidationError {
                     error_type: "description_too_long".to_string(),
+                    severity: Severity::Error,
                     field: "description".to_string(),
                     message: format!("Description exceeds maximum length of {} characters", 
-                                   self.config.max_description_length),
+                                   self.config().max_description_length),
                     suggestion: Some("Please shorten the description".to_string()),
                 });
             }
         }
         
-        if self.config.enable_smart_suggestions && errors.is_empty() {
+        if self.config().enable_smart_suggestions && errors.is_empty() {
             suggestions = self.generate_smart_suggestions(params);
         }
         
-        let is_valid = errors.is_empty();
+        let mut result = ValidationResult {
+            is_valid: false,
+            errors,
+            warnings,
+            suggestions,
+            alternative_ids,
+            quality_score: 0.0,
+            can_create: false,
+        };
+        result.summarize();
         
         let duration = start_time.elapsed();
-        eprintln!("[DEBUG] Task validation completed in {:?} (valid: {})", duration, is_valid);
+        eprintln!("[DEBUG] Task validation completed in {:?} (valid: {})", duration, result.is_valid);
+        
+        Ok(result)
+    }
+
+    /// Validate note input so that converting the note into a task later cannot fail
+    pub fn validate_note_creation(&self, params: &NoteValidationParams) -> Result<ValidationResult> {
+        let context = self.context()?;
+        let project_data = context.as_ref().map(|context| &context.project_data);
+        
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut alternative_ids = Vec::new();
+        
+        if params.title.trim().is_empty() {
+            errors.push(ValidationError {
+                error_type: "empty_title".to_string(),
+                severity: Severity::Error,
+                field: "title".to_string(),
+                message: "Note title is empty".to_string(),
+                suggestion: Some("The title becomes the task title on conversion".to_string()),
+            });
+        } else if params.title.len() > self.config().section_rules(&params.section).max_title_length {
+            errors.push(ValidationError {
+                error_type: "title_too_long".to_string(),
+                severity: Severity::Error,
+                field: "title".to_string(),
+                message: format!("Title exceeds maximum length of {} characters", 
+                               self.config().section_rules(&params.section).max_title_length),
+                suggestion: Some("Please shorten the title".to_string()),
+            });
+        }
+        
+        if let Some(error) = self.validate_section_format(&params.section, project_data) {
+            errors.push(error);
+        }
+        for error in [
+            self.validate_task_id_format(&params.section, &params.suggested_task_id),
+            self.validate_task_id_reserved(&params.suggested_task_id),
+        ].into_iter().flatten() {
+            errors.push(ValidationError { field: "suggested_task_id".to_string(), ..error });
+        }
+        
+        if let Some(status) = &params.suggested_status
+            && let Err(e) = status.parse::<TaskStatus>()
+        {
+            errors.push(ValidationError {
+                error_type: "invalid_status".to_string(),
+                severity: Severity::Error,
+                field: "suggested_status".to_string(),
+                message: e.to_string(),
+                suggestion: Some("Use one of: todo, in_progress, done, blocked".to_string()),
+            });
+        }
+        
+        if let Some(data) = project_data {
+            if data.get_task(&params.section, &params.suggested_task_id).is_some() {
+                // The note can still be written down; converting it fails until the ID changes
+                warnings.push(ValidationWarning {
+                    warning_type: "duplicate_task_id".to_string(),
+                    severity: Severity::Warning,
+                    field: "suggested_task_id".to_string(),
+                    message: format!("Task ID '{}' already exists in section '{}', so the note could not be converted", 
+                                   params.suggested_task_id, params.section),
+                    recommendation: Some("Choose a different task ID before converting the note".to_string()),
+                    existing_task: Self::task_location(data, &params.section, &params.suggested_task_id),
+                });
+                alternative_ids = self.generate_alternative_ids(&params.suggested_task_id, &params.section,
+                                                                Some(&params.title), data);
+            }
+            
+            let mut pending: Vec<&str> = data.notes.values()
+                .filter(|note| !note.is_converted)
+                .filter(|note| note.section == params.section && note.suggested_task_id == params.suggested_task_id)
+                .map(|note| note.title.as_str())
+                .collect();
+            pending.sort();
+            for title in pending {
+                warnings.push(ValidationWarning {
+                    warning_type: "duplicate_note_suggestion".to_string(),
+                    severity: Severity::Warning,
+                    field: "suggested_task_id".to_string(),
+                    message: format!("Note '{}' already suggests '{}:{}'", 
+                                   title, params.section, params.suggested_task_id),
+                    recommendation: Some("Only the first of these notes can be converted".to_string()),
+                    existing_task: None,
+                });
+            }
+        }
+        
+        if params.content.len() > self.config().max_description_length {
+            warnings.push(ValidationWarning {
+                warning_type: "content_too_long".to_string(),
+                severity: Severity::Warning,
+                field: "content".to_string(),
+                message: format!("Content exceeds {} characters, the maximum length of a task description", 
+                               self.config().max_description_length),
+                recommendation: Some("Consider moving details into the code or a document".to_string()),
+                existing_task: None,
+            });
+        }
         
-        Ok(ValidationResult {
-            is_valid,
+        let mut result = ValidationResult {
+            is_valid: false,
             errors,
             warnings,
-            suggestions,
+            suggestions: Vec::new(),
             alternative_ids,
+            quality_score: 0.0,
+            can_create: false,
+        };
+        result.summarize();
+        Ok(result)
+    }
+
+    /// Validate many labels against the current context and against each other
+    pub fn validate_batch(&self, items: &[ValidationParams]) -> Result<BatchValidationResult> {
+        let mut results = items.iter()
+            .map(|item| self.validate_task_creation(item))
+            .collect::<Result<Vec<_>>>()?;
+        let context = self.context()?;
+        let project_data = context.as_ref().map(|context| &context.project_data);
+        let exists = |section: &str, task_id: &str| {
+            project_data
+                .and_then(|data| data.sections.get(section))
+                .is_some_and(|tasks| tasks.contains_key(task_id))
+        };
+        
+        let mut claimed: HashSet<(String, String)> = items.iter()
+            .map(|item| (item.section.clone(), item.task_id.clone()))
+            .collect();
+        let mut first_use: HashMap<(&str, &str), usize> = HashMap::new();
+        
+        for (index, (item, result)) in items.iter().zip(results.iter_mut()).enumerate() {
+            let key = (item.section.as_str(), item.task_id.as_str());
+            if let Some(first) = first_use.get(&key) {
+                result.errors.push(ValidationError {
+                    error_type: "duplicate_in_batch".to_string(),
+                    severity: Severity::Error,
+                    field: "task_id".to_string(),
+                    message: format!("Task ID '{}' is already used by item {} of this batch in section '{}'",
+                                   item.task_id, first, item.section),
+                    suggestion: Some("Please choose a different task ID".to_string()),
+                });
+                if item.suggest_alternatives.unwrap_or(true) && result.alternative_ids.is_empty() {
+                    result.alternative_ids = (1..)
+                        .map(|i| format!("{}_{}", item.task_id, i))
+                        .filter(|alternative| !exists(&item.section, alternative))
+                        .filter(|alternative| !claimed.contains(&(item.section.clone(), alternative.clone())))
+                        .take(3)
+                        .collect();
+                }
+            } else {
+                first_use.insert(key, index);
+            }
+            
+            result.alternative_ids.retain(|alternative| {
+                !claimed.contains(&(item.section.clone(), alternative.clone()))
+            });
+            for alternative in &result.alternative_ids {
+                claimed.insert((item.section.clone(), alternative.clone()));
+            }
+            result.summarize();
+        }
+        
+        Ok(BatchValidationResult {
+            all_valid: results.iter().all(|result| result.is_valid),
+            results,
         })
     }
 
     /// Check for conflicts with existing tasks
     pub fn check_task_conflicts(&self, section: &str, task_id: &str) -> Result<ConflictCheck> {
-        let project_data = self.project_data.read()
-            .map_err(|_| anyhow::anyhow!("Failed to acquire read lock on project data"))?;
+        let context = self.context()?;
+        let project_data = context.as_ref().map(|context| &context.project_data);
         
         let mut conflicts = Vec::new();
         let mut resolutions = Vec::new();
         
-        if let Some(data) = project_data.as_ref() {
+        if let Some(data) = project_data {
             for (other_section, section_data) in &data.sections {
                 if other_section != section && section_data.contains_key(task_id) {
+                    let prefixed_id = format!("{}_{}", section, task_id);
+                    let prefixed_taken = data.get_task(section, &prefixed_id).is_some();
                     conflicts.push(Conflict {
                         conflict_type: "duplicate_id_cross_section".to_string(),
                         existing_task_section: other_section.clone(),
@@ -238,6 +872,7 @@ impl ValidationEngine {
                         description: format!("Task ID '{}' already exists in section '{}'", 
                                            task_id, other_section),
                         severity: "medium".to_string(),
+                        resolution: (!prefixed_taken).then_some(ResolutionAction::RenameTo { new_id: prefixed_id }),
                     });
                     
                     resolutions.push(format!("Use a section-specific prefix like '{}_{}'", 
@@ -245,16 +880,21 @@ impl ValidationEngine {
                 }
             }
             
-            if let Some(current_section) = data.sections.get(section) {
-                for existing_id in current_section.keys() {
-                    if self.calculate_similarity(task_id, existing_id) > self.config.similarity_threshold {
+            if let Some(index) = context.as_ref().and_then(|context| context.id_indexes.get(section)) {
+                let threshold = self.config().similarity_threshold;
+                for (existing_id, _) in index.similar_to(task_id, threshold) {
+                    if existing_id != task_id {
                         conflicts.push(Conflict {
                             conflict_type: "similar_id".to_string(),
                             existing_task_section: section.to_string(),
-                            existing_task_id: existing_id.clone(),
+                            existing_task_id: existing_id.to_string(),
                             description: format!("Task ID '{}' is very similar to existing ID '{}'", 
                                                task_id, existing_id),
                             severity: "low".to_string(),
+                            resolution: Some(ResolutionAction::MergeInto {
+                                section: section.to_string(),
+                                task_id: existing_id.to_string(),
+                            }),
                         });
                         
                         resolutions.push(format!("Consider using a more distinctive name"));
@@ -270,63 +910,406 @@ impl ValidationEngine {
         })
     }
 
+    /// Check every task in the current context against the configured rules without
+    /// changing anything, listing at most `max_per_rule` violations for each rule
+    pub fn validate_project(&self, max_per_rule: usize) -> Result<ProjectValidationReport> {
+        let context = self.context()?;
+        let mut rules: BTreeMap<String, RuleReport> = BTreeMap::new();
+        let mut tasks_checked = 0;
+        let mut record = |rule: &str, severity: Severity, violation: RuleViolation| {
+            let report = rules.entry(rule.to_string()).or_insert_with(|| RuleReport {
+                rule: rule.to_string(),
+                severity,
+                count: 0,
+                violations: Vec::new(),
+            });
+            report.count += 1;
+            if report.violations.len() < max_per_rule {
+                report.violations.push(violation);
+            }
+        };
+        
+        if let Some(data) = context.as_ref().map(|context| &context.project_data) {
+            let mut sections: Vec<&String> = data.sections.keys().collect();
+            sections.sort();
+            let mut id_sections: HashMap<&str, Vec<&str>> = HashMap::new();
+            for section in sections {
+                if let Some(error) = self.validate_section_format(section, None) {
+                    record(&error.error_type, error.severity, RuleViolation {
+                        section: section.clone(),
+                        task_id: None,
+                        message: error.message,
+                    });
+                }
+                let section_rules = self.config().section_rules(section);
+                let mut task_ids: Vec<&String> = data.sections[section].keys().collect();
+                task_ids.sort();
+                for task_id in task_ids {
+                    tasks_checked += 1;
+                    id_sections.entry(task_id).or_default().push(section);
+                    let violation = |message: String| RuleViolation {
+                        section: section.clone(),
+                        task_id: Some(task_id.clone()),
+                        message,
+                    };
+                    let errors = [
+                        self.validate_task_id_format(section, task_id),
+                        self.validate_task_id_reserved(task_id),
+                    ];
+                    for error in errors.into_iter().flatten() {
+                        record(&error.error_type, error.severity, violation(error.message));
+                    }
+                    let title = &data.sections[section][task_id].title;
+                    if section_rules.require_title && title.trim().is_empty() {
+                        record("title_required", Severity::Error,
+                               violation(format!("Tasks in section '{}' must have a title", section)));
+                    } else if title.len() > section_rules.max_title_length {
+                        record("title_too_long", Severity::Error,
+                               violation(format!("Title exceeds maximum length of {} characters",
+                                                 section_rules.max_title_length)));
+                    }
+                }
+            }
+            
+            let mut shared_ids: Vec<(&str, Vec<&str>)> = id_sections.into_iter()
+                .filter(|(_, sections)| sections.len() > 1)
+                .collect();
+            shared_ids.sort();
+            for (task_id, sections) in shared_ids {
+                for section in &sections {
+                    let others: Vec<&str> = sections.iter().filter(|other| *other != section).copied().collect();
+                    record("duplicate_id_cross_section", Severity::Warning, RuleViolation {
+                        section: section.to_string(),
+                        task_id: Some(task_id.to_string()),
+                        message: format!("Task ID '{}' also exists in {}", task_id, others.join(", ")),
+                    });
+                }
+            }
+        }
+        
+        let rules: Vec<RuleReport> = rules.into_values().collect();
+        Ok(ProjectValidationReport {
+            tasks_checked,
+            violation_count: rules.iter().map(|report| report.count).sum(),
+            rules,
+        })
+    }
+
+    /// Check a label found in source code against the format and reserved-name rules.
+    /// Existence is not checked, since rescanning a label that is already tracked is normal.
+    pub fn validate_label(&self, section: &str, task_id: &str) -> Vec<ValidationError> {
+        [
+            self.validate_section_format(section, None),
+            self.validate_task_id_format(section, task_id),
+            self.validate_task_id_reserved(task_id),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Validate a section name against the parser's grammar and the reserved names
+    pub fn validate_section_name(&self, section: &str) -> Result<Option<ValidationError>> {
+        let context = self.context()?;
+        let project_data = context.as_ref().map(|context| &context.project_data);
+        Ok(self.validate_section_format(section, project_data))
+    }
+
+    /// Validate section format, suggesting the nearest existing section on failure
+    fn validate_section_format(&self, section: &str, project_data: Option<&ProjectData>) -> Option<ValidationError> {
+        let did_you_mean = project_data
+            .and_then(|data| self.find_similar_section(section, data))
+            .map(|existing| format!("Did you mean '{}'?", existing));
+        let length = section.chars().count();
+        
+        if length < self.config().min_section_length {
+            return Some(ValidationError {
+                error_type: "section_too_short".to_string(),
+                severity: Severity::Error,
+                field: "section".to_string(),
+                message: format!("Section must be at least {} characters long", 
+                               self.config().min_section_length),
+                suggestion: did_you_mean.or(Some("Please provide a section name".to_string())),
+            });
+        }
+        
+        if length > self.config().max_section_length {
+            return Some(ValidationError {
+                error_type: "section_too_long".to_string(),
+                severity: Severity::Error,
+                field: "section".to_string(),
+                message: format!("Section cannot exceed {} characters", 
+                               self.config().max_section_length),
+                suggestion: did_you_mean.or(Some("Please use a shorter section name".to_string())),
+            });
+        }
+        
+        let identifier_mode = self.config().identifier_mode;
+        if !identifier_mode.is_valid(section) {
+            return Some(ValidationError {
+                error_type: "invalid_section_format".to_string(),
+                severity: Severity::Error,
+                field: "section".to_string(),
+                message: format!("Section can only contain {}, and must start with a letter or underscore",
+                               identifier_mode.describe()),
+                suggestion: did_you_mean.or(Some(format!("Use only {}", identifier_mode.describe()))),
+            });
+        }
+        
+        if self.is_reserved(section) {
+            return Some(ValidationError {
+                error_type: "reserved_section_name".to_string(),
+                severity: Severity::Error,
+                field: "section".to_string(),
+                message: format!("'{}' is a reserved name and cannot be used as section", section),
+                suggestion: did_you_mean.or(Some(format!("Try '{}_tasks' instead", section))),
+            });
+        }
+        
+        None
+    }
+
+    /// Find the existing section closest to the given name within the similarity threshold
+    fn find_similar_section(&self, section: &str, project_data: &ProjectData) -> Option<String> {
+        let section_lower = section.to_lowercase();
+        project_data.sections.keys()
+            .filter(|existing| existing.as_str() != section)
+            .map(|existing| (existing, similarity(&section_lower, &existing.to_lowercase())))
+            .filter(|(_, similarity)| *similarity >= self.config().similarity_threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(existing, _)| existing.clone())
+    }
+
+    /// Closest existing task to a missing `section:task_id`: the most similar ID in that
+    /// section, or else the same ID in another section
+    pub fn nearest_task(&self, project_data: &ProjectData, section: &str, task_id: &str) -> Option<TaskKey> {
+        if let Some(tasks) = project_data.sections.get(section) {
+            let index = SimilarityIndex::new(tasks.keys());
+            let nearest = index.similar_to(task_id, self.config().similarity_threshold)
+                .into_iter()
+                .filter(|(existing, _)| *existing != task_id)
+                .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)));
+            if let Some((existing, _)) = nearest {
+                return Some(TaskKey::new(section, existing));
+            }
+        }
+        project_data.sections.iter()
+            .filter(|(name, tasks)| name.as_str() != section && tasks.contains_key(task_id))
+            .map(|(name, _)| name)
+            .min()
+            .map(|other| TaskKey::new(other, task_id))
+    }
+
+    /// Find existing tasks whose titles are within the similarity threshold, closest first
+    fn find_similar_titles<'a>(&self, title: &str, params: &ValidationParams, project_data: &'a ProjectData) -> Vec<(&'a str, &'a str, &'a str)> {
+        let title = Self::normalize_title(title);
+        let threshold = self.config().similarity_threshold;
+        let mut matches: Vec<(f32, &str, &str, &str)> = project_data.sections.iter()
+            .flat_map(|(section, tasks)| tasks.iter().map(move |(task_id, task)| (section, task_id, task)))
+            .filter(|(section, task_id, _)| **section != params.section || **task_id != params.task_id)
+            .map(|(section, task_id, task)| {
+                let score = similarity(&title, &Self::normalize_title(&task.title));
+                (score, section.as_str(), task_id.as_str(), task.title.as_str())
+            })
+            .filter(|(score, ..)| *score >= threshold)
+            .collect();
+        matches.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| (a.1, a.2).cmp(&(b.1, b.2))));
+        matches.into_iter()
+            .take(3)
+            .map(|(_, section, task_id, title)| (section, task_id, title))
+            .collect()
+    }
+
+    /// Title reduced to its search words, so filler words and punctuation do not count
+    fn normalize_title(title: &str) -> String {
+        let words = normalize_text(title);
+        if words.is_empty() {
+            title.trim().to_lowercase()
+        } else {
+            words.join(" ")
+        }
+    }
+
+    /// Warnings for the enabled writing conventions
+    fn convention_warnings(rules: &ConventionRules, params: &ValidationParams) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        let title = params.title.as_deref().map(str::trim).filter(|title| !title.is_empty());
+        
+        if let Some(title) = title {
+            let mut chars = title.chars();
+            if rules.capitalized_title && let Some(first) = chars.next() && first.is_lowercase() {
+                let capitalized: String = first.to_uppercase().chain(chars).collect();
+                warnings.push(ValidationWarning {
+                    warning_type: "title_not_capitalized".to_string(),
+                    severity: Severity::Warning,
+                    field: "title".to_string(),
+                    message: "Title does not start with a capital letter".to_string(),
+                    recommendation: Some(format!("Consider '{}'", capitalized)),
+                    existing_task: None,
+                });
+            }
+            
+            let words = title.split_whitespace().count();
+            if let Some(max_words) = rules.max_title_words && words > max_words {
+                warnings.push(ValidationWarning {
+                    warning_type: "title_too_wordy".to_string(),
+                    severity: Severity::Warning,
+                    field: "title".to_string(),
+                    message: format!("Title has {} words, more than the {} allowed", words, max_words),
+                    recommendation: Some("Move the details into the description".to_string()),
+                    existing_task: None,
+                });
+            }
+            
+            if rules.distinct_description
+                && let Some(description) = &params.description
+                && description.trim().to_lowercase() == title.to_lowercase() {
+                warnings.push(ValidationWarning {
+                    warning_type: "description_repeats_title".to_string(),
+                    severity: Severity::Warning,
+                    field: "description".to_string(),
+                    message: "Description only repeats the title".to_string(),
+                    recommendation: Some("Describe the expected outcome or leave the description empty".to_string()),
+                    existing_task: None,
+                });
+            }
+        }
+        
+        warnings
+    }
+    
+    /// Location of an existing task, pointing at its first recorded label if any
+    fn task_location(project_data: &ProjectData, section: &str, task_id: &str) -> Option<TaskLocation> {
+        let task = project_data.get_task(section, task_id)?;
+        let first_label = task.files.iter()
+            .filter_map(|(path, file)| file.lines.iter().min().map(|line| (path, *line)))
+            .min();
+        Some(TaskLocation {
+            section: section.to_string(),
+            task_id: task_id.to_string(),
+            file_path: first_label.map(|(path, _)| path.clone()),
+            line: first_label.map(|(_, line)| line),
+        })
+    }
+
+    /// Reject task IDs that collide with reserved names
+    fn validate_task_id_reserved(&self, task_id: &str) -> Option<ValidationError> {
+        if !self.is_reserved(task_id) {
+            return None;
+        }
+        Some(ValidationError {
+            error_type: "reserved_name".to_string(),
+            severity: Severity::Error,
+            field: "task_id".to_string(),
+            message: format!("'{}' is a reserved name and cannot be used as task ID", task_id),
+            suggestion: Some(format!("Try '{}_1' or '{}_task' instead", task_id, task_id)),
+        })
+    }
+
     /// Validate task ID format
-    fn validate_task_id_format(&self, task_id: &str) -> Option<ValidationError> {
-        if task_id.len() < self.config.min_task_id_length {
+    fn validate_task_id_format(&self, section: &str, task_id: &str) -> Option<ValidationError> {
+        let max_task_id_length = self.config().section_rules(section).max_task_id_length;
+        if task_id.len() < self.config().min_task_id_length {
             return Some(ValidationError {
                 error_type: "task_id_too_short".to_string(),
+                severity: Severity::Error,
                 field: "task_id".to_string(),
                 message: format!("Task ID must be at least {} characters long", 
-                               self.config.min_task_id_length),
+                               self.config().min_task_id_length),
                 suggestion: Some("Please use a longer, more descriptive ID".to_string()),
             });
         }
         
-        if task_id.len() > self.config.max_task_id_length {
+        if task_id.len() > max_task_id_length {
             return Some(ValidationError {
                 error_type: "task_id_too_long".to_string(),
+                severity: Severity::Error,
                 field: "task_id".to_string(),
-                message: format!("Task ID cannot exceed {} characters", 
-                               self.config.max_task_id_length),
+                message: format!("Task ID cannot exceed {} characters", max_task_id_length),
                 suggestion: Some("Please use a shorter ID".to_string()),
             });
         }
         
-        if !self.task_id_pattern.is_match(task_id) {
+        let identifier_mode = self.config().identifier_mode;
+        if !identifier_mode.is_valid(task_id) {
+            let suggestion = if task_id.contains('-') {
+                format!("Try '{}' - hyphens are not recognized by the scanner", task_id.replace('-', "_"))
+            } else {
+                format!("Use only {}", identifier_mode.describe())
+            };
             return Some(ValidationError {
                 error_type: "invalid_task_id_format".to_string(),
+                severity: Severity::Error,
                 field: "task_id".to_string(),
-                message: "Task ID can only contain letters, numbers, underscores, and hyphens, and must start with a letter or underscore".to_string(),
-                suggestion: Some("Use only alphanumeric characters, underscores, and hyphens".to_string()),
+                message: format!("Task ID can only contain {}, and must start with a letter or underscore",
+                               identifier_mode.describe()),
+                suggestion: Some(suggestion),
+            });
+        }
+        
+        let patterns = self.section_patterns.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(pattern) = patterns.get(section)
+            && !pattern.is_match(task_id)
+        {
+            let configured = self.config().section_rules(section).id_pattern.unwrap_or_default();
+            return Some(ValidationError {
+                error_type: "task_id_pattern_mismatch".to_string(),
+                severity: Severity::Error,
+                field: "task_id".to_string(),
+                message: format!("Task IDs in section '{}' must match '{}'", section, configured),
+                suggestion: Some(format!("Check the naming rules configured for '{}'", section)),
             });
         }
         
         None
     }
 
-    /// Generate alternative task IDs
-    fn generate_alternative_ids(&self, base_id: &str, project_data: &ProjectData) -> Vec<String> {
-        let mut alternatives = Vec::new();
-        let section_data = project_data.sections.values().next();
+    /// Generate up to five unused task IDs for the target section, preferring
+    /// suffixes taken from the title over numbered and generic ones
+    fn generate_alternative_ids(&self, base_id: &str, section: &str, title: Option<&str>, project_data: &ProjectData) -> Vec<String> {
+        let config = self.config().clone();
+        let base_lower = base_id.to_lowercase();
+        let base_words: HashSet<&str> = base_lower.split('_').collect();
         
-        if let Some(section) = section_data {
-            for i in 1..=5 {
-                let alternative = format!("{}_{}", base_id, i);
-                if !section.contains_key(&alternative) {
-                    alternatives.push(alternative);
-                }
-            }
-            
-            let suffixes = ["_new", "_v2", "_alt", "_task", "_item"];
-            for suffix in &suffixes {
-                let alternative = format!("{}{}", base_id, suffix);
-                if !section.contains_key(&alternative) && alternatives.len() < 5 {
-                    alternatives.push(alternative);
+        let mut candidates: Vec<String> = Vec::new();
+        if let Some(title) = title {
+            let title_lower = title.to_lowercase();
+            for word in title_lower.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+                if word.chars().count() > 1
+                    && !is_stop_word(word)
+                    && !base_words.contains(word)
+                {
+                    candidates.push(format!("{}_{}", base_id, word));
                 }
             }
         }
+        candidates.extend((2..=6).map(|i| format!("{}_{}", base_id, i)));
+        candidates.extend(["_new", "_v2", "_alt", "_task", "_item"].iter().map(|suffix| format!("{}{}", base_id, suffix)));
         
-        alternatives.truncate(3);
+        let is_taken = |candidate: &str| {
+            if config.require_unique_ids_across_sections {
+                project_data.sections.values().any(|tasks| tasks.contains_key(candidate))
+            } else {
+                project_data.sections.get(section).is_some_and(|tasks| tasks.contains_key(candidate))
+            }
+        };
+        
+        let mut alternatives: Vec<String> = Vec::new();
+        for candidate in candidates {
+            if alternatives.len() == 5 {
+                break;
+            }
+            if alternatives.contains(&candidate)
+                || candidate.chars().count() > config.max_task_id_length
+                || !config.identifier_mode.is_valid(&candidate)
+                || self.is_reserved(&candidate)
+                || is_taken(&candidate)
+            {
+                continue;
+            }
+            alternatives.push(candidate);
+        }
         alternatives
     }
 
@@ -346,56 +1329,79 @@ impl ValidationEngine {
             suggestions.push("Consider using a more descriptive task ID for better clarity".to_string());
         }
         
+        if let Ok(Some(context)) = self.context() {
+            suggestions.extend(Self::convention_suggestions(params, &context.conventions));
+        }
+        
         suggestions
     }
 
-    /// Calculate similarity between two strings (simple implementation)
-    fn calculate_similarity(&self, s1: &str, s2: &str) -> f32 {
-        if s1 == s2 {
-            return 1.0;
-        }
-        
-        let longer = if s1.len() > s2.len() { s1 } else { s2 };
-        let _shorter = if s1.len() <= s2.len() { s1 } else { s2 };
+    /// Compare the proposed ID against the precomputed section conventions
+    fn convention_suggestions(params: &ValidationParams, conventions: &HashMap<String, SectionConventions>) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        let task_id = params.task_id.as_str();
         
-        if longer.len() == 0 {
-            return 1.0;
+        if let Some(section) = conventions.get(&params.section) {
+            if let Some(style) = section.style
+                && id_style(task_id).is_some_and(|own| own != style)
+            {
+                suggestions.push(format!("Existing ids in '{}' use {} - consider '{}'",
+                                         params.section, style_name(style), style.apply(task_id)));
+            }
+            let conventional = section.style.map_or_else(|| task_id.to_string(), |style| style.apply(task_id));
+            if let Some(prefix) = &section.prefix
+                && !conventional.starts_with(prefix.as_str())
+            {
+                suggestions.push(format!("Existing ids in '{}' use the prefix '{}' - consider '{}{}'",
+                                         params.section, prefix, prefix, conventional));
+            }
+            if let Some(highest) = section.numbered
+                && numeric_suffix(task_id).is_none()
+            {
+                suggestions.push(format!("Existing ids in '{}' end with a number - consider '{}_{}'",
+                                         params.section, task_id, highest + 1));
+            }
         }
         
-        let edit_distance = self.levenshtein_distance(s1, s2);
-        (longer.len() - edit_distance) as f32 / longer.len() as f32
+        let words = id_words(task_id);
+        let overlap = |section: &SectionConventions| {
+            words.iter().filter(|word| section.words.contains(*word)).count()
+                + section.prefix.as_ref().map_or(0, |prefix| usize::from(task_id.starts_with(prefix.as_str())))
+        };
+        let current = conventions.get(&params.section).map_or(0, overlap);
+        if current == 0
+            && let Some((best, score)) = conventions.iter()
+                .filter(|(name, _)| **name != params.section)
+                .map(|(name, section)| (name, overlap(section)))
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+            && score > 0
+        {
+            suggestions.push(format!("Ids like '{}' usually live in section '{}' - is '{}' the right section?",
+                                     task_id, best, params.section));
+        }
+        suggestions
     }
 
-    /// Calculate Levenshtein distance between two strings
-    fn levenshtein_distance(&self, s1: &str, s2: &str) -> usize {
-        let chars1: Vec<char> = s1.chars().collect();
-        let chars2: Vec<char> = s2.chars().collect();
-        let len1 = chars1.len();
-        let len2 = chars2.len();
-        
-        let mut matrix = vec![vec![0; len2 + 1]; len1 + 1];
-        
-        for i in 0..=len1 {
-            matrix[i][0] = i;
-        }
-        for j in 0..=len2 {
-            matrix[0][j] = j;
-        }
-        
-        for i in 1..=len1 {
-            for j in 1..=len2 {
-                let cost = if chars1[i - 1] == chars2[j - 1] { 0 } else { 1 };
-                matrix[i][j] = std::cmp::min(
-                    std::cmp::min(
-                        matrix[i - 1][j] + 1,
-                        matrix[i][j - 1] + 1
-                    ),
-                    matrix[i - 1][j - 1] + cost
-                );
-            }
+    /// Compile the id pattern of every section profile, anchored to match whole IDs
+    fn build_section_patterns(config: &ValidationConfig) -> Result<HashMap<String, Regex>> {
+        config.sections.iter()
+            .filter_map(|(section, profile)| profile.id_pattern.as_ref().map(|pattern| (section, pattern)))
+            .map(|(section, pattern)| {
+                let regex = Regex::new(&format!("^(?:{})$", pattern))
+                    .map_err(|e| anyhow::anyhow!("Invalid id_pattern for section '{}': {}", section, e))?;
+                Ok((section.clone(), regex))
+            })
+            .collect()
+    }
+
+    /// Built-in reserved names merged with the project overrides
+    fn build_reserved_names(config: &ValidationConfig) -> HashSet<String> {
+        let mut reserved = Self::create_reserved_names();
+        reserved.extend(config.additional_reserved.iter().map(|name| name.to_lowercase()));
+        for name in &config.allowed_reserved {
+            reserved.remove(&name.to_lowercase());
         }
-        
-        matrix[len1][len2]
+        reserved
     }
 
     /// Create set of reserved names that cannot be used as task IDs
@@ -453,7 +1459,7 @@ mod tests {
     #[test]
     fn test_validation_engine_creation() {
         let engine = ValidationEngine::new(None);
-        assert!(!engine.reserved_names.is_empty());
+        assert!(!engine.get_reserved_names().is_empty());
     }
 
     #[test]
@@ -490,10 +1496,389 @@ mod tests {
     }
 
     #[test]
-    fn test_similarity_calculation() {
+    fn test_cross_section_and_similar_title_warnings() {
         let engine = ValidationEngine::new(None);
-        assert_eq!(engine.calculate_similarity("test", "test"), 1.0);
-        assert!(engine.calculate_similarity("test", "tast") > 0.5);
-        assert!(engine.calculate_similarity("hello", "world") < 0.5);
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("dev", "login", "Implement user login form".to_string(), None).unwrap();
+        project_data.update_task_file("dev", "login", "src/auth.rs".to_string(), 12, None).unwrap();
+        engine.update_context(project_data).unwrap();
+        
+        let params = |task_id: &str, title: &str| ValidationParams {
+            section: "qa".to_string(),
+            task_id: task_id.to_string(),
+            title: Some(title.to_string()),
+            description: None,
+            check_duplicates: Some(true),
+            suggest_alternatives: Some(true),
+        };
+        
+        let result = engine.validate_task_creation(&params("login", "Implement user login forms")).unwrap();
+        assert!(result.is_valid);
+        let cross = result.warnings.iter().find(|w| w.warning_type == "duplicate_id_cross_section").unwrap();
+        assert_eq!(cross.existing_task.as_ref().unwrap().section, "dev");
+        let similar = result.warnings.iter().find(|w| w.warning_type == "similar_title").unwrap();
+        let location = similar.existing_task.as_ref().unwrap();
+        assert_eq!((location.section.as_str(), location.task_id.as_str()), ("dev", "login"));
+        assert_eq!(location.file_path.as_deref(), Some("src/auth.rs"));
+        assert_eq!(location.line, Some(12));
+        
+        let result = engine.validate_task_creation(&params("logout", "Implement user logout page")).unwrap();
+        assert!(result.is_valid);
+        assert!(result.warnings.iter().all(|w| w.warning_type != "similar_title"
+            && w.warning_type != "duplicate_id_cross_section"));
+    }
+
+    #[test]
+    fn test_similar_title_ignores_filler_words() {
+        let engine = ValidationEngine::new(None);
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("dev", "login_bug", "Fix the login bug".to_string(), None).unwrap();
+        engine.update_context(project_data).unwrap();
+        
+        let result = engine.validate_task_creation(&ValidationParams {
+            section: "qa".to_string(),
+            task_id: "auth_fix".to_string(),
+            title: Some("fix login bug".to_string()),
+            description: None,
+            check_duplicates: Some(true),
+            suggest_alternatives: Some(true),
+        }).unwrap();
+        assert!(result.is_valid);
+        assert!(result.can_create);
+        let similar = result.warnings.iter().find(|w| w.warning_type == "similar_title").unwrap();
+        assert!(similar.message.contains("dev:login_bug"));
+        let location = similar.existing_task.as_ref().unwrap();
+        assert_eq!(location.task_id, "login_bug");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_alternative_ids_use_target_section() {
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("alpha", "unrelated", "Unrelated".to_string(), None).unwrap();
+        project_data.add_task("dev", "fix_login", "Fix login".to_string(), None).unwrap();
+        project_data.add_task("dev", "fix_login_ui", "Fix login UI".to_string(), None).unwrap();
+        project_data.add_task("dev", "fix_login_2", "Fix login again".to_string(), None).unwrap();
+        project_data.add_task("ops", "fix_login_3", "Fix login on servers".to_string(), None).unwrap();
+        
+        let engine = ValidationEngine::new(None);
+        let alternatives = engine.generate_alternative_ids("fix_login", "dev", Some("Fix login UI timeout"), &project_data);
+        assert_eq!(alternatives.len(), 5);
+        assert_eq!(alternatives[0], "fix_login_timeout");
+        assert!(!alternatives.contains(&"fix_login_ui".to_string()));
+        assert!(!alternatives.contains(&"fix_login_2".to_string()));
+        assert!(alternatives.contains(&"fix_login_3".to_string()));
+        for alternative in &alternatives {
+            assert!(!project_data.sections["dev"].contains_key(alternative));
+        }
+        
+        let engine = ValidationEngine::new(Some(ValidationConfig {
+            require_unique_ids_across_sections: true,
+            ..Default::default()
+        }));
+        let alternatives = engine.generate_alternative_ids("fix_login", "dev", None, &project_data);
+        assert_eq!(alternatives[0], "fix_login_4");
+        assert!(!alternatives.contains(&"fix_login_3".to_string()));
+    }
+
+    #[test]
+    fn test_naming_convention_suggestions() {
+        let mut project_data = ProjectData::new(None);
+        for id in ["api_users", "api_orders", "api_auth_tokens", "api_billing"] {
+            project_data.add_task("api", id, id.to_string(), None).unwrap();
+        }
+        for id in ["ui_button", "ui_modal", "ui_theme"] {
+            project_data.add_task("ui", id, id.to_string(), None).unwrap();
+        }
+        let params = |section: &str, task_id: &str| ValidationParams {
+            section: section.to_string(),
+            task_id: task_id.to_string(),
+            title: None,
+            description: None,
+            check_duplicates: None,
+            suggest_alternatives: None,
+        };
+        let convention_hints = |engine: &ValidationEngine, section: &str, task_id: &str| -> Vec<String> {
+            engine.validate_task_creation(&params(section, task_id)).unwrap()
+                .suggestions.into_iter()
+                .filter(|s| s.starts_with("Existing ids") || s.starts_with("Ids like"))
+                .collect()
+        };
+        
+        let engine = ValidationEngine::new(None);
+        engine.update_context(project_data.clone()).unwrap();
+        
+        let hints = convention_hints(&engine, "api", "rate_limit");
+        assert_eq!(hints, vec!["Existing ids in 'api' use the prefix 'api_' - consider 'api_rate_limit'".to_string()]);
+        
+        let hints = convention_hints(&engine, "api", "apiRateLimit");
+        assert_eq!(hints, vec!["Existing ids in 'api' use snake_case - consider 'api_rate_limit'".to_string()]);
+        
+        assert!(convention_hints(&engine, "api", "api_rate_limit").is_empty());
+        
+        let hints = convention_hints(&engine, "api", "ui_dropdown");
+        assert!(hints.iter().any(|h| h.contains("usually live in section 'ui'")));
+        
+        let engine = ValidationEngine::new(Some(ValidationConfig {
+            enable_smart_suggestions: false,
+            ..Default::default()
+        }));
+        engine.update_context(project_data).unwrap();
+        assert!(convention_hints(&engine, "api", "rate_limit").is_empty());
+    }
+
+    #[test]
+    fn test_opt_in_title_conventions() {
+        let params = |title: &str, description: &str| ValidationParams {
+            section: "dev".to_string(),
+            task_id: "login_form".to_string(),
+            title: Some(title.to_string()),
+            description: Some(description.to_string()),
+            check_duplicates: None,
+            suggest_alternatives: None,
+        };
+        let warning_types = |engine: &ValidationEngine, title: &str, description: &str| -> Vec<String> {
+            engine.validate_task_creation(&params(title, description)).unwrap()
+                .warnings.into_iter()
+                .map(|w| w.warning_type)
+                .collect()
+        };
+        
+        let engine = ValidationEngine::new(None);
+        assert!(warning_types(&engine, "fix login form", "fix login form").is_empty());
+        
+        let engine = ValidationEngine::new(Some(ValidationConfig {
+            conventions: ConventionRules {
+                capitalized_title: true,
+                distinct_description: true,
+                max_title_words: Some(4),
+            },
+            ..Default::default()
+        }));
+        
+        let result = engine.validate_task_creation(&params("fix login form", "Redirect back after submit")).unwrap();
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].warning_type, "title_not_capitalized");
+        assert_eq!(result.warnings[0].recommendation.as_deref(), Some("Consider 'Fix login form'"));
+        
+        assert_eq!(warning_types(&engine, "Fix login form", " fix LOGIN form "), vec!["description_repeats_title".to_string()]);
+        assert_eq!(warning_types(&engine, "Fix the login form on mobile", "Buttons overlap"), vec!["title_too_wordy".to_string()]);
+        assert!(warning_types(&engine, "Fix login form", "Redirect back after submit").is_empty());
+    }
+
+    #[test]
+    fn test_section_validation() {
+        let engine = ValidationEngine::new(None);
+        let mut project = ProjectData::new(None);
+        project.add_task("devops", "deploy_app", "Deploy".to_string(), None).unwrap();
+        engine.update_context(project).unwrap();
+
+        let params = ValidationParams {
+            section: "dev ops".to_string(),
+            task_id: "valid_task_id".to_string(),
+            title: Some("Test task".to_string()),
+            description: None,
+            check_duplicates: Some(true),
+            suggest_alternatives: Some(true),
+        };
+        let result = engine.validate_task_creation(&params).unwrap();
+        assert!(!result.is_valid);
+        let error = result.errors.iter().find(|e| e.field == "section").unwrap();
+        assert_eq!(error.error_type, "invalid_section_format");
+        assert_eq!(error.suggestion.as_deref(), Some("Did you mean 'devops'?"));
+
+        let params = ValidationParams { section: "devopss".to_string(), ..params };
+        let result = engine.validate_task_creation(&params).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(result.warnings[0].warning_type, "similar_section");
+        assert_eq!(result.warnings[0].recommendation.as_deref(), Some("Did you mean 'devops'?"));
+
+        assert!(engine.validate_section_name("").unwrap().is_some());
+        assert!(engine.validate_section_name("разработка").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_section_profile_overrides_global_rules() {
+        let config: ValidationConfig = serde_json::from_str(r#"{
+            "sections": {
+                "bug": { "id_pattern": "bug_\\d+", "require_title": true },
+                "docs": { "max_task_id_length": 80 }
+            }
+        }"#).unwrap();
+        let engine = ValidationEngine::new(Some(config));
+        let params = |section: &str, task_id: &str, title: Option<&str>| ValidationParams {
+            section: section.to_string(),
+            task_id: task_id.to_string(),
+            title: title.map(String::from),
+            description: None,
+            check_duplicates: None,
+            suggest_alternatives: None,
+        };
+        
+        let result = engine.validate_task_creation(&params("dev", "login_crash", None)).unwrap();
+        assert!(result.is_valid);
+        
+        let result = engine.validate_task_creation(&params("bug", "login_crash", Some("Crash on login"))).unwrap();
+        assert!(!result.is_valid);
+        assert_eq!(result.errors[0].error_type, "task_id_pattern_mismatch");
+        
+        let result = engine.validate_task_creation(&params("bug", "bug_42", Some("Crash on login"))).unwrap();
+        assert!(result.is_valid);
+        let result = engine.validate_task_creation(&params("bug", "bug_42x", Some("Crash on login"))).unwrap();
+        assert!(!result.is_valid);
+        let result = engine.validate_task_creation(&params("bug", "bug_42", None)).unwrap();
+        assert_eq!(result.errors[0].error_type, "title_required");
+        
+        let long_id = "a".repeat(60);
+        assert!(engine.validate_task_creation(&params("docs", &long_id, None)).unwrap().is_valid);
+        assert!(!engine.validate_task_creation(&params("dev", &long_id, None)).unwrap().is_valid);
+        
+        let rules = engine.get_config().section_rules("docs");
+        assert_eq!(rules.max_task_id_length, 80);
+        assert_eq!(rules.max_title_length, ValidationConfig::default().max_title_length);
+        
+        let mut invalid = engine.get_config();
+        invalid.sections.get_mut("bug").unwrap().id_pattern = Some("bug_(".to_string());
+        assert!(engine.apply_config(invalid).is_err());
+        assert!(!engine.validate_task_creation(&params("bug", "login_crash", Some("Crash"))).unwrap().is_valid);
+    }
+
+    #[test]
+    fn test_validate_project_groups_violations_by_rule() {
+        let mut project_data = ProjectData::new(None);
+        project_data.add_task("dev", "class", "Reserved".to_string(), None).unwrap();
+        project_data.add_task("dev", "login-form", "Hyphenated".to_string(), None).unwrap();
+        project_data.add_task("dev", "login", "x".repeat(250), None).unwrap();
+        project_data.add_task("ui", "login", "Login screen".to_string(), None).unwrap();
+        project_data.add_task("dev ops", "deploy", "Deploy".to_string(), None).unwrap();
+        for i in 0..5 {
+            project_data.add_task("qa", &format!("check-{}", i), "Check".to_string(), None).unwrap();
+        }
+        let engine = ValidationEngine::new(None);
+        engine.update_context(project_data).unwrap();
+        
+        let report = engine.validate_project(3).unwrap();
+        assert_eq!(report.tasks_checked, 10);
+        let rule = |name: &str| report.rules.iter().find(|report| report.rule == name).unwrap();
+        
+        let reserved = rule("reserved_name");
+        assert_eq!(reserved.count, 1);
+        assert_eq!(reserved.violations[0].task_id.as_deref(), Some("class"));
+        let format = rule("invalid_task_id_format");
+        assert_eq!(format.count, 6);
+        assert_eq!(format.violations.len(), 3);
+        assert_eq!(rule("title_too_long").violations[0].task_id.as_deref(), Some("login"));
+        let shared = rule("duplicate_id_cross_section");
+        assert_eq!(shared.count, 2);
+        assert_eq!(shared.severity, Severity::Warning);
+        let section = rule("invalid_section_format");
+        assert_eq!(section.violations[0].section, "dev ops");
+        assert!(section.violations[0].task_id.is_none());
+        assert_eq!(report.violation_count, report.rules.iter().map(|rule| rule.count).sum::<usize>());
+    }
+
+    #[test]
+    fn test_reserved_name_overrides() {
+        let engine = ValidationEngine::new(None);
+        let params = ValidationParams {
+            section: "test".to_string(),
+            task_id: "config".to_string(),
+            title: Some("Test task".to_string()),
+            description: None,
+            check_duplicates: Some(true),
+            suggest_alternatives: Some(true),
+        };
+        assert!(!engine.validate_task_creation(&params).unwrap().is_valid);
+
+        engine.apply_config(ValidationConfig {
+            allowed_reserved: vec!["Config".to_string()],
+            additional_reserved: vec!["legacy".to_string()],
+            ..Default::default()
+        }).unwrap();
+        assert!(engine.validate_task_creation(&params).unwrap().is_valid);
+        assert!(engine.get_reserved_names().contains(&"legacy".to_string()));
+        assert!(!engine.get_reserved_names().contains(&"config".to_string()));
+    }
+
+    #[test]
+    fn test_quality_score_and_severity() {
+        let engine = ValidationEngine::new(None);
+        let params = ValidationParams {
+            section: "dev".to_string(),
+            task_id: "login_form".to_string(),
+            title: Some("Login form".to_string()),
+            description: None,
+            check_duplicates: Some(true),
+            suggest_alternatives: Some(true),
+        };
+        let result = engine.validate_task_creation(&params).unwrap();
+        assert_eq!(result.quality_score, 100.0);
+        assert!(result.can_create);
+
+        let params = ValidationParams { title: Some("  ".to_string()), ..params };
+        let result = engine.validate_task_creation(&params).unwrap();
+        assert!(result.is_valid);
+        assert!(result.can_create);
+        assert_eq!(result.warnings[0].severity, Severity::Warning);
+        assert_eq!(result.quality_score, 85.0);
+
+        let params = ValidationParams { task_id: "config".to_string(), ..params };
+        let result = engine.validate_task_creation(&params).unwrap();
+        assert!(!result.is_valid);
+        assert!(!result.can_create);
+        assert_eq!(result.errors[0].severity, Severity::Error);
+        assert_eq!(result.quality_score, 35.0);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["errors"][0]["severity"], "error");
+        assert_eq!(json["warnings"][0]["severity"], "warning");
+        assert_eq!(json["can_create"], false);
+    }
+
+    #[test]
+    fn test_similar_id_conflicts_match_brute_force() {
+        let syllables = ["log", "in", "user", "form", "api", "auth", "fix", "ui", "data", "sync"];
+        let mut seed: u64 = 42;
+        let mut next = |bound: usize| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % bound
+        };
+        let mut project_data = ProjectData::new(None);
+        let mut ids = Vec::new();
+        while ids.len() < 5000 {
+            let parts: Vec<&str> = (0..1 + next(3)).map(|_| syllables[next(syllables.len())]).collect();
+            let id = format!("{}_{}", parts.join("_"), next(100));
+            if project_data.get_task("dev", &id).is_none() {
+                project_data.add_task("dev", &id, id.clone(), None).unwrap();
+                ids.push(id);
+            }
+        }
+        let engine = ValidationEngine::new(None);
+        engine.update_context(project_data).unwrap();
+        let threshold = engine.config().similarity_threshold;
+        
+        let queries: Vec<String> = ids.iter().step_by(250)
+            .flat_map(|id| [id.clone(), format!("{}x", id), id.replacen('_', "", 1), id[1..].to_string()])
+            .collect();
+        for query in &queries {
+            let mut found: Vec<String> = engine.check_task_conflicts("dev", query).unwrap()
+                .conflicts.into_iter()
+                .filter(|conflict| conflict.conflict_type == "similar_id")
+                .map(|conflict| conflict.existing_task_id)
+                .collect();
+            let mut expected: Vec<String> = ids.iter()
+                .filter(|id| *id != query && similarity(query, id) > threshold)
+                .cloned()
+                .collect();
+            found.sort();
+            expected.sort();
+            assert_eq!(found, expected, "conflicts for '{}'", query);
+        }
+        
+        let start = std::time::Instant::now();
+        for query in &queries {
+            engine.check_task_conflicts("dev", query).unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed < std::time::Duration::from_secs(5), "{} conflict checks took {:?}", queries.len(), elapsed);
+    }
+}
@@ -0,0 +1,225 @@
+/*!
+ * Workspace Watch Loop for Anchora Backend
+ *
+ * Keeps tasks.json in step with the files on disk: one full scan, then
+ * debounced watcher events applied as single-file rescans on the
+ * watcher's rescan scheduler. Shared by `--mode watch` and the server's
+ * `--watch` flag.
+ */
+
+use crate::communication::{ScanFilesResult, ScanProjectParams, ScanProjectResult};
+use crate::file_watcher::{EventDebouncer, FileEvent, FileWatcher, RescanScheduler, WatcherConfig};
+use crate::handler::TaskManagerHandler;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant, Interval, MissedTickBehavior};
+
+#[derive(Debug, Clone, Default)]
+pub struct WatchOptions {
+    pub watcher: WatcherConfig,
+    /// Rescan the whole workspace this often, catching anything the
+    /// watcher missed
+    pub full_rescan_interval: Option<Duration>,
+}
+
+/// Scan the workspace, then apply file changes as they settle until
+/// `shutdown` resolves. Changes still waiting in the debouncer or the
+/// rescan queue at that point are applied before returning.
+pub async fn run<S>(
+    handler: Arc<TaskManagerHandler>,
+    options: WatchOptions,
+    shutdown: S,
+) -> anyhow::Result<()>
+where
+    S: Future<Output = ()>,
+{
+    let workspace_path = handler.workspace_path().to_path_buf();
+    let result = full_rescan(&handler).await?;
+    eprintln!(
+        "[INFO] Initial scan: {} files, {} tasks",
+        result.files_scanned, result.tasks_found
+    );
+
+    let (watcher, events) = FileWatcher::new(&workspace_path, options.watcher.clone())?;
+    let (batch_tx, mut batches) = mpsc::unbounded_channel();
+    let debouncer = tokio::spawn(EventDebouncer::new(options.watcher.debounce_timeout).run(
+        events,
+        move |batch| {
+            let _ = batch_tx.send(batch);
+        },
+    ));
+    // Shares its queue with the watcher, so `FileWatcher::get_stats` sees it
+    let scheduler = watcher.rescan_scheduler().clone();
+    let mut watcher = Some(watcher);
+    let mut rescans: Vec<JoinHandle<()>> = Vec::new();
+    let mut rescan_timer = options.full_rescan_interval.map(|period| {
+        let mut timer = tokio::time::interval_at(Instant::now() + period, period);
+        timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        timer
+    });
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            batch = batches.recv() => match batch {
+                Some(batch) => {
+                    rescans.retain(|rescan| !rescan.is_finished());
+                    rescans.extend(apply_batch(&handler, &options.watcher, &scheduler, batch).await);
+                }
+                None => break,
+            },
+            _ = &mut shutdown, if watcher.is_some() => {
+                // Dropping the watcher closes its event channel; the debouncer
+                // then flushes what it holds and closes `batches`
+                watcher = None;
+            }
+            _ = next_tick(&mut rescan_timer), if watcher.is_some() => match full_rescan(&handler).await {
+                Ok(result) => eprintln!(
+                    "[INFO] Full rescan: {} files, {} tasks, {} removed",
+                    result.files_scanned, result.tasks_found, result.tasks_removed
+                ),
+                Err(e) => eprintln!("[WARN] Full rescan failed: {:#}", e),
+            },
+        }
+    }
+    debouncer.await?;
+    for rescan in rescans {
+        rescan.await?;
+    }
+    Ok(())
+}
+
+async fn full_rescan(handler: &TaskManagerHandler) -> anyhow::Result<ScanProjectResult> {
+    let _mutations = handler.lock_mutations().await;
+    handler
+        .scan_project(ScanProjectParams {
+            workspace_path: handler.workspace_path().to_string_lossy().to_string(),
+            file_patterns: None,
+            recognize_keyword_comments: None,
+            validate_labels: None,
+            reconcile_status: None,
+        })
+        .await
+}
+
+async fn next_tick(timer: &mut Option<Interval>) {
+    match timer {
+        Some(timer) => {
+            timer.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Move the references of renamed files, then queue a rescan of every touched
+/// file on the watcher's scheduler. Returns the queued rescans; a file that is
+/// already waiting for one is not queued again.
+async fn apply_batch(
+    handler: &Arc<TaskManagerHandler>,
+    config: &WatcherConfig,
+    scheduler: &RescanScheduler,
+    batch: Vec<FileEvent>,
+) -> Vec<JoinHandle<()>> {
+    let events: Vec<FileEvent> = batch
+        .into_iter()
+        .filter(|event| config.should_process_file(event.path()))
+        .collect();
+    if events.is_empty() {
+        return Vec::new();
+    }
+    {
+        let _mutations = handler.lock_mutations().await;
+        if let Err(e) = handler.move_renamed_references(&events).await {
+            eprintln!("[WARN] Could not move references of renamed files: {:#}", e);
+        }
+    }
+    let rescans: Vec<JoinHandle<()>> = events
+        .iter()
+        .filter_map(|event| {
+            let handler = handler.clone();
+            scheduler.schedule(event.path().to_path_buf(), move |path| async move {
+                let events = [FileEvent::Modified(path)];
+                let _mutations = handler.lock_mutations().await;
+                match handler.apply_file_events(&events).await {
+                    Ok(result) => eprintln!(
+                        "[INFO] {}",
+                        describe_update(handler.workspace_path(), &events, &result)
+                    ),
+                    Err(e) => eprintln!("[WARN] Could not apply file changes: {:#}", e),
+                }
+            })
+        })
+        .collect();
+    if scheduler.queue_depth() > 0 {
+        eprintln!(
+            "[INFO] {} rescans queued, {} running",
+            scheduler.queue_depth(),
+            scheduler.in_flight()
+        );
+    }
+    rescans
+}
+
+/// One log line for an applied batch, e.g. `Updated src/lib.rs: +1 ~0 -2`
+pub fn describe_update(
+    workspace_path: &Path,
+    events: &[FileEvent],
+    result: &ScanFilesResult,
+) -> String {
+    let target = match events {
+        [event] => {
+            let path = event.path();
+            path.strip_prefix(workspace_path)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        }
+        _ => format!("{} files", events.len()),
+    };
+    let mut line = format!(
+        "Updated {}: +{} ~{} -{}",
+        target,
+        result.tasks_added.len(),
+        result.tasks_updated.len(),
+        result.tasks_removed.len()
+    );
+    if !result.errors.is_empty() {
+        line.push_str(&format!(" ({} errors)", result.errors.len()));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_describe_update_names_single_files() {
+        let result = ScanFilesResult {
+            files_scanned: 1,
+            tasks_added: vec!["dev:login".to_string()],
+            tasks_updated: Vec::new(),
+            tasks_removed: vec!["dev:old".to_string(), "dev:older".to_string()],
+            errors: Vec::new(),
+            description_conflicts: Vec::new(),
+            converted_notes: Vec::new(),
+            skipped_lines: Vec::new(),
+            status_divergences: Vec::new(),
+        };
+        let workspace = PathBuf::from("/work");
+        let modified = FileEvent::Modified(workspace.join("src/lib.rs"));
+        assert_eq!(
+            describe_update(&workspace, std::slice::from_ref(&modified), &result),
+            "Updated src/lib.rs: +1 ~0 -2"
+        );
+        let deleted = FileEvent::Deleted(workspace.join("src/old.rs"));
+        assert_eq!(
+            describe_update(&workspace, &[modified, deleted], &result),
+            "Updated 2 files: +1 ~0 -2"
+        );
+    }
+}
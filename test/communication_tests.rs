@@ -153,6 +153,12 @@ fn test_scan_project_result_serialization() {
             "Error in file1.rs".to_string(),
             "Error in file2.rs".to_string(),
         ],
+        errors_omitted: 0,
+        diagnostics: Vec::new(),
+        description_conflicts: Vec::new(),
+        converted_notes: Vec::new(),
+        skipped_lines: Vec::new(),
+        status_divergences: Vec::new(),
     };
 
     let serialized = serde_json::to_string(&result).unwrap();
@@ -174,7 +180,11 @@ fn test_get_tasks_params_deserialization() {
     let params: GetTasksParams = serde_json::from_str(json_str).unwrap();
 
     assert_eq!(params.section, Some("dev".to_string()));
-    assert_eq!(params.status, Some("todo".to_string()));
+    assert_eq!(params.status, Some(StatusFilter::Single("todo".to_string())));
+
+    let params: GetTasksParams =
+        serde_json::from_str(r#"{ "status": ["todo", "in_progress"] }"#).unwrap();
+    assert_eq!(params.status.unwrap().values(), vec!["todo", "in_progress"]);
 }
 
 #[test]
@@ -198,6 +208,8 @@ fn test_delete_task_params() {
     let params = DeleteTaskParams {
         section: "dev".to_string(),
         task_id: "task_to_delete".to_string(),
+        cascade: None,
+        permanent: None,
     };
 
     let serialized = serde_json::to_string(&params).unwrap();
@@ -214,6 +226,10 @@ fn test_create_task_params() {
         task_id: "new_task".to_string(),
         title: "New Task Title".to_string(),
         description: Some("Detailed description".to_string()),
+        overwrite: None,
+        priority: None,
+        assignee: None,
+        parent: None,
     };
 
     let serialized = serde_json::to_string(&params).unwrap();
@@ -418,3 +434,94 @@ fn test_missing_required_fields() {
     let result: Result<JsonRpcRequest, _> = serde_json::from_str(incomplete_json);
     assert!(result.is_err());
 }
+
+struct EchoHandler;
+
+impl JsonRpcHandler for EchoHandler {
+    fn handle_request(
+        &self,
+        request: JsonRpcRequest,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = JsonRpcResponse> + Send + '_>> {
+        Box::pin(async move { JsonRpcServer::success_response(request.id, request.params.unwrap_or(Value::Null)) })
+    }
+}
+
+#[tokio::test]
+async fn test_server_reads_content_length_framed_messages() {
+    let server = JsonRpcServer::new(Box::new(EchoHandler));
+    let body = "{\n  \"jsonrpc\": \"2.0\",\n  \"method\": \"echo\",\n  \"params\": {\"text\": \"ünïcode\"},\n  \"id\": 7\n}";
+    let input = format!(
+        "Content-Length: {}\r\nContent-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n{}",
+        body.len(),
+        body
+    );
+
+    let mut output = Vec::new();
+    server.serve(input.as_bytes(), &mut output).await.unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let (header, response) = output.split_once("\r\n\r\n").unwrap();
+    assert_eq!(header, format!("Content-Length: {}", response.len()));
+    let response: JsonRpcResponse = serde_json::from_str(response).unwrap();
+    assert_eq!(response.id, Some(json!(7)));
+    assert_eq!(response.result, Some(json!({"text": "ünïcode"})));
+}
+
+#[tokio::test]
+async fn test_server_falls_back_to_line_framing() {
+    let server = JsonRpcServer::new(Box::new(EchoHandler));
+    let input = "{\"jsonrpc\":\"2.0\",\"method\":\"echo\",\"params\":1,\"id\":1}\n\nnot json\n";
+
+    let mut output = Vec::new();
+    server.serve(input.as_bytes(), &mut output).await.unwrap();
+    let responses: Vec<JsonRpcResponse> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].result, Some(json!(1)));
+    assert_eq!(responses[1].error.as_ref().unwrap().code, -32700);
+}
+
+/// Split `Content-Length` framed responses back into messages
+fn framed_responses(output: &[u8]) -> Vec<JsonRpcResponse> {
+    let mut output = std::str::from_utf8(output).unwrap();
+    let mut responses = Vec::new();
+    while let Some((header, rest)) = output.split_once("\r\n\r\n") {
+        let length: usize = header.strip_prefix("Content-Length: ").unwrap().parse().unwrap();
+        responses.push(serde_json::from_str(&rest[..length]).unwrap());
+        output = &rest[length..];
+    }
+    responses
+}
+
+#[tokio::test]
+async fn test_server_answers_bad_framing_and_keeps_reading() {
+    let server = JsonRpcServer::new(Box::new(EchoHandler));
+    let body = r#"{"jsonrpc":"2.0","method":"echo","params":2,"id":2}"#;
+    let input = format!("Content-Length: lots\r\n\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+
+    let mut output = Vec::new();
+    server.serve(input.as_bytes(), &mut output).await.unwrap();
+    let responses = framed_responses(&output);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].error.as_ref().unwrap().code, -32600);
+    assert_eq!(responses[1].result, Some(json!(2)));
+}
+
+#[tokio::test]
+async fn test_server_skips_oversized_bodies() {
+    let server = JsonRpcServer::new(Box::new(EchoHandler));
+    let oversized = 64 * 1024 * 1024 + 1;
+    let body = r#"{"jsonrpc":"2.0","method":"echo","params":3,"id":3}"#;
+    let mut input = format!("Content-Length: {}\r\n\r\n", oversized).into_bytes();
+    input.resize(input.len() + oversized, b' ');
+    input.extend_from_slice(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).as_bytes());
+
+    let mut output = Vec::new();
+    server.serve(input.as_slice(), &mut output).await.unwrap();
+    let responses = framed_responses(&output);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].error.as_ref().unwrap().code, -32600);
+    assert_eq!(responses[1].result, Some(json!(3)));
+}
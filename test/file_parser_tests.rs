@@ -354,4 +354,293 @@ fn test_unicode_support() {
         assert!(parsed.task_id.len() > 0);
         assert!(parsed.description.is_some());
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_keyword_comments_become_tasks() {
+    let parser = TaskParser::with_config(ParserConfig {
+        recognize_keyword_comments: true,
+        ..Default::default()
+    })
+    .unwrap();
+    let content = r#"
+fn main() {
+    // TODO: обработать ошибки чтения
+    let data = read();
+    // TODO: добавить кэширование
+    // dev:task_1: обычная задача
+}
+"#;
+
+    let labels = parser.scan_file("main.rs", content).unwrap();
+    assert_eq!(labels.len(), 3);
+
+    let mut project_data = ProjectData::new(Some("test".to_string()));
+    parser
+        .update_project_from_labels(&mut project_data, "main.rs", labels)
+        .unwrap();
+
+    let todo_section = project_data.sections.get("todo").unwrap();
+    assert_eq!(todo_section.len(), 2);
+    assert!(todo_section.values().any(|t| t.title == "обработать ошибки чтения"));
+    assert!(todo_section.values().any(|t| t.title == "добавить кэширование"));
+    assert!(project_data.get_task("dev", "task_1").is_some());
+}
+
+#[test]
+fn test_scan_file_with_bom() {
+    let parser = TaskParser::new().unwrap();
+    let content = "\u{feff}// dev:task_1: первая строка\nfn main() {}\n";
+
+    let results = parser.scan_file("bom.rs", content).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, 1);
+    assert_eq!(results[0].1.section, "dev");
+    assert_eq!(results[0].1.description, Some("первая строка".to_string()));
+}
+
+#[test]
+fn test_scan_file_with_stray_carriage_returns() {
+    let parser = TaskParser::new().unwrap();
+    let content = "// dev:task_1: описание\r\r\n// dev:task_1:заметка\r\nlet x = 1;\r// dev:task_2: вторая\r";
+
+    let results = parser.scan_file("crlf.rs", content).unwrap();
+    assert_eq!(results.len(), 3);
+    for (_, label) in &results {
+        assert!(!label.description.as_deref().unwrap_or("").contains('\r'));
+        assert!(!label.note.as_deref().unwrap_or("").contains('\r'));
+    }
+    assert_eq!(results[0].1.description, Some("описание".to_string()));
+    assert_eq!(results[1].1.note, Some("заметка".to_string()));
+    assert_eq!(results[2].1.task_id, "task_2");
+    assert_eq!(results[2].0, 5);
+}
+
+#[test]
+fn test_rescans_respect_manually_edited_text() {
+    let parser = TaskParser::new().unwrap();
+    let mut project_data = ProjectData::new(None);
+    let rescan = |project_data: &mut ProjectData, content: &str| {
+        let labels = parser.scan_file("main.rs", content).unwrap();
+        parser
+            .update_project_from_labels(project_data, "main.rs", labels)
+            .unwrap()
+    };
+
+    // Label-derived titles follow edits to the label
+    assert!(rescan(&mut project_data, "// dev:task_1: первый текст\n").is_empty());
+    assert!(rescan(&mut project_data, "// dev:task_1: второй текст\n").is_empty());
+    let task = project_data.get_task("dev", "task_1").unwrap();
+    assert_eq!(task.title, "второй текст");
+    assert_eq!(task.description_source, DescriptionSource::Label);
+
+    project_data
+        .edit_task_text("dev", "task_1", Some("Ручной заголовок".to_string()), Some("Подробности".to_string()))
+        .unwrap();
+
+    // An unchanged label leaves the manual text alone and reports nothing
+    assert!(rescan(&mut project_data, "// dev:task_1: второй текст\n").is_empty());
+    let task = project_data.get_task("dev", "task_1").unwrap();
+    assert_eq!(task.title, "Ручной заголовок");
+    assert_eq!(task.description.as_deref(), Some("Подробности"));
+
+    // A changed label is reported once instead of overwriting the manual text
+    let conflicts = rescan(&mut project_data, "\n// dev:task_1: третий текст\n");
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].line, 2);
+    assert_eq!(conflicts[0].label_text, "третий текст");
+    assert_eq!(conflicts[0].current_title, "Ручной заголовок");
+    let task = project_data.get_task("dev", "task_1").unwrap();
+    assert_eq!(task.title, "Ручной заголовок");
+    assert_eq!(task.description.as_deref(), Some("Подробности"));
+    assert!(rescan(&mut project_data, "\n// dev:task_1: третий текст\n").is_empty());
+}
+
+#[test]
+fn test_files_labelling_a_task_differently_keep_its_title() {
+    let parser = TaskParser::new().unwrap();
+    let mut project_data = ProjectData::new(None);
+    let rescan = |project_data: &mut ProjectData, file: &str, content: &str| {
+        let labels = parser.scan_file(file, content).unwrap();
+        parser
+            .update_project_from_labels(project_data, file, labels)
+            .unwrap()
+    };
+
+    rescan(&mut project_data, "a.rs", "// dev:task_1: Login form\n");
+    rescan(&mut project_data, "b.rs", "// dev:task_1: Login page\n");
+    for _ in 0..2 {
+        rescan(&mut project_data, "a.rs", "// dev:task_1: Login form\n");
+        rescan(&mut project_data, "b.rs", "// dev:task_1: Login page\n");
+        assert_eq!(project_data.get_task("dev", "task_1").unwrap().title, "Login form");
+    }
+
+    // Editing one of the labels still updates the title
+    rescan(&mut project_data, "b.rs", "// dev:task_1: Login screen\n");
+    assert_eq!(project_data.get_task("dev", "task_1").unwrap().title, "Login screen");
+
+    // Once edited by hand, disagreeing files are not reported as conflicts
+    project_data
+        .edit_task_text("dev", "task_1", Some("Sign-in".to_string()), None)
+        .unwrap();
+    for _ in 0..2 {
+        assert!(rescan(&mut project_data, "a.rs", "// dev:task_1: Login form\n").is_empty());
+        assert!(rescan(&mut project_data, "b.rs", "// dev:task_1: Login screen\n").is_empty());
+    }
+    assert_eq!(project_data.get_task("dev", "task_1").unwrap().title, "Sign-in");
+}
+
+#[test]
+fn test_trailing_punctuation_after_labels() {
+    let parser = TaskParser::new().unwrap();
+
+    let parsed = parser.parse_line("foo(); // dev:task_1.").unwrap();
+    assert_eq!(parsed.section, "dev");
+    assert_eq!(parsed.task_id, "task_1");
+    assert_eq!(parsed.status, None);
+
+    let parsed = parser.parse_line("// dev:task_1:done;").unwrap();
+    assert_eq!(parsed.task_id, "task_1");
+    assert_eq!(parsed.status, Some(TaskStatus::Done));
+    assert_eq!(parsed.note, None);
+
+    let parsed = parser.parse_line("bar(1, 2); // dev:task_2,  ").unwrap();
+    assert_eq!(parsed.task_id, "task_2");
+    assert!(parser.parse_line("// dev:task_1..").is_none());
+
+    let strict = TaskParser::with_config(ParserConfig {
+        trailing_punctuation: String::new(),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(strict.parse_line("// dev:task_1.").is_none());
+    assert!(strict.parse_line("// dev:task_1:done;").is_none());
+    assert_eq!(strict.parse_line("// dev:task_1").unwrap().task_id, "task_1");
+}
+
+#[test]
+fn test_near_miss_labels() {
+    let parser = TaskParser::new().unwrap();
+    let content = "\
+// dev:task_1: fine
+// dev-ops:task_1: hyphenated
+// dev: task_2: spaced
+// dev:task_3:Build it
+// dev:task_4:
+/* dev:task_5: in a block */
+// Note: see https://example.com:8080
+let url = \"http://host:8080\";
+// dev:task_1:done
+";
+    let misses: Vec<(u32, String)> = parser
+        .near_misses(content)
+        .into_iter()
+        .map(|miss| (miss.line, miss.rule))
+        .collect();
+    assert_eq!(
+        misses,
+        vec![
+            (2, "hyphenated_id".to_string()),
+            (3, "space_after_section".to_string()),
+            (4, "missing_space".to_string()),
+            (5, "missing_description".to_string()),
+            (6, "block_comment".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_reconcile_label_statuses() {
+    let parser = TaskParser::new().unwrap();
+    let mut project = ProjectData::new(None);
+    project.add_task("dev", "login", "Login".to_string(), None).unwrap();
+    project.update_task_status("dev", "login", TaskStatus::Blocked).unwrap();
+    project.add_task("dev", "logout", "Logout".to_string(), None).unwrap();
+    let content = "// dev:login:done: Login\r\n// dev:logout:todo\r\n// dev:signup:done: New\r\n";
+    let labels = parser.scan_file("auth.rs", content).unwrap();
+
+    let mut code_wins = labels.clone();
+    let divergences =
+        TaskParser::reconcile_label_statuses(&project, "auth.rs", &mut code_wins, StatusReconciliation::CodeWins);
+    assert_eq!(
+        divergences,
+        vec![StatusDivergence {
+            section: "dev".to_string(),
+            task_id: "login".to_string(),
+            file: "auth.rs".to_string(),
+            line: 1,
+            code_status: TaskStatus::Done,
+            stored_status: TaskStatus::Blocked,
+        }]
+    );
+    assert_eq!(code_wins, labels);
+
+    let mut report_only = labels.clone();
+    TaskParser::reconcile_label_statuses(&project, "auth.rs", &mut report_only, StatusReconciliation::ReportOnly);
+    assert_eq!(report_only[0].1.status, None);
+    assert_eq!(report_only[1..], labels[1..]);
+
+    let rewritten =
+        TaskParser::rewrite_label_status(content, &[CommentSyntax::Slashes], 1, "dev", "login", &TaskStatus::Blocked).unwrap();
+    assert_eq!(rewritten, content.replacen("login:done", "login:blocked", 1));
+    assert_eq!(
+        TaskParser::rewrite_label_status(content, &[CommentSyntax::Slashes], 2, "dev", "logout", &TaskStatus::Done).unwrap(),
+        content.replacen("logout:todo", "logout:done", 1)
+    );
+    assert!(TaskParser::rewrite_label_status(content, &[CommentSyntax::Slashes], 2, "dev", "login", &TaskStatus::Done).is_none());
+    assert!(TaskParser::rewrite_label_status(content, &[CommentSyntax::Slashes], 9, "dev", "login", &TaskStatus::Done).is_none());
+}
+
+#[test]
+fn test_comment_syntax_follows_file_extension() {
+    let content = "# Login\n\n<!-- dev:login:todo: Write the login guide -->\n\n```rust\n// dev:example: not a task\n```\n<!-- dev:login -->\n";
+
+    let parser = TaskParser::new().unwrap();
+    let labels = parser.scan_file("docs/README.md", content).unwrap();
+    let found: Vec<(u32, &str, Option<TaskStatus>)> = labels
+        .iter()
+        .map(|(line, label)| (*line, label.task_id.as_str(), label.status.clone()))
+        .collect();
+    assert_eq!(found, [(3, "login", Some(TaskStatus::Todo)), (8, "login", None)]);
+    assert_eq!(labels[0].1.description.as_deref(), Some("Write the login guide"));
+
+    // Source files keep `//` and ignore HTML comments
+    let labels = parser.scan_file("main.rs", content).unwrap();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].1.task_id, "example");
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("GUIDE.MD");
+    std::fs::write(&path, content).unwrap();
+    let scan = ScanBufferPool::new().scan_path(&parser, &path).unwrap();
+    assert_eq!(scan.labels, parser.scan_file("README.md", content).unwrap());
+
+    let configured = TaskParser::with_config(ParserConfig {
+        comment_syntax: [
+            (".md".to_string(), vec![CommentSyntax::Ignore]),
+            ("txt".to_string(), vec![CommentSyntax::Slashes, CommentSyntax::Html]),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(configured.scan_file("README.md", content).unwrap().is_empty());
+    assert!(ScanBufferPool::new().scan_path(&configured, &path).unwrap().labels.is_empty());
+    assert_eq!(configured.scan_file("notes.txt", content).unwrap().len(), 3);
+    assert_eq!(configured.comment_syntax("index.html"), [CommentSyntax::Html]);
+    assert_eq!(configured.comment_syntax("Makefile"), [CommentSyntax::Slashes]);
+
+    let html = "<p>Login</p>\n<!-- dev:login:done: Write the login guide -->\n// dev:login:done\n";
+    let syntax = parser.comment_syntax("index.html");
+    assert_eq!(
+        TaskParser::rewrite_label_status(html, syntax, 2, "dev", "login", &TaskStatus::Blocked).unwrap(),
+        html.replacen("login:done", "login:blocked", 1)
+    );
+    assert!(TaskParser::rewrite_label_status(html, syntax, 3, "dev", "login", &TaskStatus::Blocked).is_none());
+    assert!(TaskParser::rewrite_label_status(html, &[CommentSyntax::Ignore], 2, "dev", "login", &TaskStatus::Blocked).is_none());
+
+    let renames = [IdRename { section: "dev".to_string(), old_id: "login".to_string(), new_id: "sign_in".to_string() }];
+    let (rewritten, changed) = TaskParser::rewrite_task_ids(html, syntax, &renames).unwrap();
+    assert_eq!((rewritten, changed), (html.replacen("dev:login:", "dev:sign_in:", 1), 1));
+    assert_eq!(TaskParser::rewrite_task_ids(html, &[CommentSyntax::Ignore], &renames).unwrap().1, 0);
+}
@@ -1,5 +1,26 @@
+use anchora::{JsonRpcHandler, JsonRpcRequest, JsonRpcResponse, TaskManagerHandler};
 use tempfile::TempDir;
 
+/// A handler over a fresh workspace, which lives as long as the returned directory
+fn test_handler() -> (TempDir, TaskManagerHandler) {
+    let temp_dir = TempDir::new().unwrap();
+    let handler = TaskManagerHandler::new(temp_dir.path().to_path_buf()).unwrap();
+    (temp_dir, handler)
+}
+
+fn request(method: &str, params: serde_json::Value) -> JsonRpcRequest {
+    JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: Some(params),
+        id: Some(serde_json::json!(1)),
+    }
+}
+
+async fn rpc(handler: &TaskManagerHandler, method: &str, params: serde_json::Value) -> JsonRpcResponse {
+    handler.handle_request(request(method, params)).await
+}
+
 #[tokio::test]
 async fn test_full_workflow() {
     // Создать временную директорию для тестов
@@ -323,3 +344,3394 @@ async fn test_delete_task_integration() {
     
     println!("✅ Delete integration test passed!");
 }
+
+#[tokio::test]
+async fn test_export_statistics_json_and_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(Some("stats-project".to_string()));
+    project_data.add_task("dev", "task_1", "First".to_string(), None).unwrap();
+    project_data.add_task("dev", "task_2", "Second".to_string(), None).unwrap();
+    project_data.add_task("docs", "readme", "Readme".to_string(), None).unwrap();
+    project_data
+        .update_task_status("dev", "task_1", anchora::task_manager::TaskStatus::Done)
+        .unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let json_path = workspace_path.join("stats.json");
+    let csv_path = workspace_path.join("stats.csv");
+
+    for (path, format) in [(&json_path, "json"), (&csv_path, "csv")] {
+        let response = rpc(
+            &handler,
+            "export_statistics",
+            serde_json::json!({ "path": path.to_string_lossy(), "format": format, "fresh": true }),
+        )
+        .await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+    }
+
+    let exported: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(exported["project_name"], "stats-project");
+    assert_eq!(exported["overview"]["total_tasks"], 3);
+    assert_eq!(exported["sections"]["dev"]["done"], 1);
+    assert!(exported["generated_at"].is_string());
+
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("project,generated_at,section,total"));
+    assert!(lines[1].starts_with("stats-project,") && lines[1].contains(",dev,2,1,0,1,0,50.00"));
+    assert!(lines[2].contains(",docs,1,1,0,0,0,0.00"));
+    assert!(lines[3].contains(",TOTAL,3,2,0,1,0,33.33"));
+
+    let response = rpc(
+        &handler,
+        "export_statistics",
+        serde_json::json!({
+            "path": workspace_path.join("missing").join("stats.csv").to_string_lossy(),
+            "format": "csv"
+        }),
+    )
+    .await;
+    assert!(response.error.is_some());
+}
+
+#[tokio::test]
+async fn test_create_task_rejects_invalid_section() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(Some("sections".to_string()));
+    project_data.add_task("devops", "deploy", "Deploy".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev ops", "task_id": "rollback", "title": "Rollback" }),
+    )
+    .await;
+
+    let error = response.error.expect("create_task should fail");
+    assert!(error.message.contains("Did you mean 'devops'?"));
+    let loaded = storage.load_project_data().await.unwrap();
+    assert!(!loaded.sections.contains_key("dev ops"));
+}
+
+#[tokio::test]
+async fn test_project_config_overrides_reserved_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::create_dir_all(workspace_path.join(".anchora")).unwrap();
+    std::fs::write(
+        workspace_path.join(".anchora/config.json"),
+        r#"{ "validation": { "allowed_reserved": ["config"], "additional_reserved": ["legacy"] } }"#,
+    )
+    .unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+
+    let response = rpc(
+        &handler,
+        "validate_task_input",
+        serde_json::json!({ "section": "dev", "task_id": "config" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["is_valid"], true);
+
+    let response = rpc(
+        &handler,
+        "validate_task_input",
+        serde_json::json!({ "section": "dev", "task_id": "legacy" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["is_valid"], false);
+
+    let response = rpc(&handler, "set_config", serde_json::json!({ "validation": { "max_task_id_length": 10 } })).await;
+    assert!(response.error.is_none());
+
+    let response = rpc(&handler, "get_validation_config", serde_json::json!({})).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["config"]["max_task_id_length"], 10);
+    // The reserved name overrides were not part of the update, so they stay
+    let reserved = result["reserved_names"].as_array().unwrap();
+    assert!(!reserved.contains(&serde_json::json!("config")));
+    assert!(reserved.contains(&serde_json::json!("legacy")));
+}
+
+#[test]
+fn test_parser_and_validation_agree_on_identifiers() {
+    use anchora::{
+        IdentifierMode, ParserConfig, TaskParser, ValidationConfig, ValidationEngine,
+        ValidationParams,
+    };
+
+    let ids = [
+        "task_1", "_hidden", "my-task", "задача_1", "タスク", "1task", "task.v2", "fix bug", "ünïcode",
+    ];
+    for mode in [IdentifierMode::Extended, IdentifierMode::Strict] {
+        let parser = TaskParser::with_config(ParserConfig {
+            identifier_mode: mode,
+            ..Default::default()
+        })
+        .unwrap();
+        let engine = ValidationEngine::new(Some(ValidationConfig {
+            identifier_mode: mode,
+            ..Default::default()
+        }));
+        for id in ids {
+            let parsed = parser
+                .parse_line(&format!("// dev:{}: title", id))
+                .is_some_and(|label| label.task_id == id);
+            let validated = engine
+                .validate_task_creation(&ValidationParams {
+                    section: "dev".to_string(),
+                    task_id: id.to_string(),
+                    title: None,
+                    description: None,
+                    check_duplicates: None,
+                    suggest_alternatives: None,
+                })
+                .unwrap()
+                .is_valid;
+            assert_eq!(parsed, validated, "{:?} disagrees on '{}'", mode, id);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_get_tasks_with_multiple_statuses() {
+    use anchora::TaskStatus;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "planned", "Planned".to_string(), None).unwrap();
+    project_data.add_task("dev", "active", "Active".to_string(), None).unwrap();
+    project_data.add_task("dev", "finished", "Finished".to_string(), None).unwrap();
+    project_data.update_task_status("dev", "active", TaskStatus::InProgress).unwrap();
+    project_data.update_task_status("dev", "finished", TaskStatus::Done).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let get_tasks = |status: serde_json::Value| request("get_tasks", serde_json::json!({ "status": status }));
+
+    let response = handler.handle_request(get_tasks(serde_json::json!(["todo", "in_progress"]))).await;
+    let dev = response.result.unwrap()["sections"]["dev"].clone();
+    let mut ids: Vec<_> = dev.as_object().unwrap().keys().cloned().collect();
+    ids.sort();
+    assert_eq!(ids, vec!["active", "planned"]);
+
+    let response = handler.handle_request(get_tasks(serde_json::json!("done"))).await;
+    let dev = response.result.unwrap()["sections"]["dev"].clone();
+    assert_eq!(dev.as_object().unwrap().keys().collect::<Vec<_>>(), vec!["finished"]);
+}
+
+#[tokio::test]
+async fn test_validate_tasks_batch() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "login", "Login".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "validate_tasks_batch",
+        serde_json::json!({
+            "items": [
+                { "section": "dev", "task_id": "signup" },
+                { "section": "dev", "task_id": "login" },
+                { "section": "dev", "task_id": "signup" },
+                { "section": "dev", "task_id": "login_1" }
+            ]
+        }),
+    )
+    .await;
+
+    let result = response.result.unwrap();
+    assert_eq!(result["all_valid"], false);
+    let results = result["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["is_valid"], true);
+
+    assert_eq!(results[1]["errors"][0]["error_type"], "duplicate_task_id");
+    let alternatives = results[1]["alternative_ids"].as_array().unwrap();
+    assert!(!alternatives.is_empty());
+    assert!(!alternatives.contains(&serde_json::json!("login_1")));
+
+    assert_eq!(results[2]["errors"][0]["error_type"], "duplicate_in_batch");
+    let alternatives = results[2]["alternative_ids"].as_array().unwrap();
+    assert_eq!(alternatives[0], "signup_1");
+
+    assert_eq!(results[3]["is_valid"], true);
+}
+
+#[tokio::test]
+async fn test_normalize_ids_to_snake_case() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let source = "fn main() {\n    // dev:myTask: implement feature\n    // dev:myTask:done\n    // dev:myTaskList\n}\n";
+    std::fs::write(workspace_path.join("main.rs"), source).unwrap();
+    let guide = "# Guide\n<!-- dev:myTask: document it -->\nWrite `// dev:myTask` in code.\n";
+    std::fs::write(workspace_path.join("GUIDE.md"), guide).unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "scan_project",
+        serde_json::json!({ "workspace_path": workspace_path.to_string_lossy(), "file_patterns": ["**/*.rs", "**/*.md"] }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    let response = rpc(&handler, "normalize_ids", serde_json::json!({ "style": "snake_case", "dry_run": true })).await;
+    let plan = response.result.unwrap();
+    assert_eq!(plan["renames"][0]["old_id"], "myTask");
+    assert_eq!(plan["renames"][0]["new_id"], "my_task");
+    assert_eq!(std::fs::read_to_string(workspace_path.join("main.rs")).unwrap(), source);
+
+    let response = rpc(&handler, "normalize_ids", serde_json::json!({ "style": "snake_case" })).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["files_updated"], 2);
+    assert!(result["backup_path"].is_string());
+
+    let rewritten = std::fs::read_to_string(workspace_path.join("main.rs")).unwrap();
+    assert!(rewritten.contains("// dev:my_task: implement feature"));
+    assert!(rewritten.contains("// dev:my_task:done"));
+    assert!(rewritten.contains("// dev:myTaskList"));
+    // Markdown labels live in HTML comments; `//` there is just prose
+    let rewritten = std::fs::read_to_string(workspace_path.join("GUIDE.md")).unwrap();
+    assert_eq!(rewritten, guide.replacen("<!-- dev:myTask:", "<!-- dev:my_task:", 1));
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    let dev = &project_data.sections["dev"];
+    assert!(dev.contains_key("my_task"));
+    assert!(!dev.contains_key("myTask"));
+    assert!(dev["my_task"].files.contains_key("main.rs"));
+    let history = rpc(&handler, "get_task_history", serde_json::json!({ "section": "dev", "task_id": "my_task" }))
+        .await
+        .result
+        .unwrap();
+    let renamed = history["history"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|change| change["field"] == "task_id")
+        .unwrap();
+    assert_eq!((&renamed["old"], &renamed["new"]), (&serde_json::json!("myTask"), &serde_json::json!("my_task")));
+}
+
+#[tokio::test]
+async fn test_normalize_ids_changes_nothing_when_a_file_is_unreadable() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path();
+    let source = "// dev:myTask: implement feature\n";
+    std::fs::write(workspace_path.join("a.rs"), source).unwrap();
+    std::fs::write(workspace_path.join("b.rs"), "// dev:myTask\n").unwrap();
+    rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    std::fs::remove_file(workspace_path.join("b.rs")).unwrap();
+    let stored = std::fs::read_to_string(workspace_path.join(".anchora/tasks.json")).unwrap();
+
+    let response = rpc(&handler, "normalize_ids", serde_json::json!({ "style": "snake_case" })).await;
+    assert!(response.error.unwrap().message.contains("b.rs"));
+    assert_eq!(std::fs::read_to_string(workspace_path.join("a.rs")).unwrap(), source);
+    assert_eq!(std::fs::read_to_string(workspace_path.join(".anchora/tasks.json")).unwrap(), stored);
+}
+
+#[tokio::test]
+async fn test_get_notes_for_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "logout", "Logout".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "create_note",
+        serde_json::json!({
+            "title": "Remember session timeout",
+            "content": "Sessions should expire after 30 minutes",
+            "section": "dev",
+            "suggested_task_id": "login"
+        }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    // The suggested task shows up later, e.g. once someone labels it in code
+    let mut project_data = storage.load_project_data().await.unwrap();
+    project_data.add_task("dev", "login", "Login".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let response = rpc(&handler, "get_notes_for_task", serde_json::json!({ "section": "dev", "task_id": "login" })).await;
+    let notes = response.result.unwrap();
+    assert_eq!(notes.as_array().unwrap().len(), 1);
+    assert_eq!(notes[0]["title"], "Remember session timeout");
+
+    let response = rpc(&handler, "get_notes_for_task", serde_json::json!({ "section": "dev", "task_id": "logout" })).await;
+    assert!(response.result.unwrap().as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_task_overview_in_requested_timezone() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "Login" }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    let response = rpc(&handler, "get_task_overview", serde_json::json!({ "timezone": "America/New_York" })).await;
+    let overview = response.result.unwrap();
+    assert_eq!(overview["timezone"], "America/New_York");
+    let timestamp = overview["recent_activity"][0]["timestamp"].as_str().unwrap();
+    let parsed = chrono::DateTime::parse_from_rfc3339(timestamp).unwrap();
+    let expected_offset = chrono::TimeZone::offset_from_utc_datetime(
+        &chrono_tz::America::New_York,
+        &parsed.naive_utc(),
+    );
+    assert_eq!(
+        parsed.offset().local_minus_utc(),
+        chrono::Offset::fix(&expected_offset).local_minus_utc()
+    );
+    assert!(timestamp.ends_with("-04:00") || timestamp.ends_with("-05:00"));
+
+    let response = rpc(&handler, "get_task_overview", serde_json::json!({ "timezone": "Not/AZone" })).await;
+    assert!(response.error.unwrap().message.contains("Invalid timezone"));
+}
+
+#[tokio::test]
+async fn test_task_exists() {
+    use anchora::task_manager::TaskStatus;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "login", "Login".to_string(), None).unwrap();
+    project_data.update_task_status("dev", "login", TaskStatus::InProgress).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(&handler, "task_exists", serde_json::json!({ "section": "dev", "task_id": "login" })).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["exists"], true);
+    assert_eq!(result["status"], "in_progress");
+
+    let response = rpc(&handler, "task_exists", serde_json::json!({ "section": "ui", "task_id": "login" })).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["exists"], false);
+    assert!(result.get("status").is_none());
+}
+
+#[tokio::test]
+async fn test_scan_reports_label_diagnostics() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let source = "fn main() {\n    // dev:config: load settings\n    // dev:login_form: build form\n}\n";
+    std::fs::write(workspace_path.join("main.rs"), source).unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let scan = |validate_labels: bool| {
+        request(
+            "scan_project",
+            serde_json::json!({ "workspace_path": workspace_path.to_string_lossy(), "validate_labels": validate_labels }),
+        )
+    };
+
+    let result = handler.handle_request(scan(true)).await.result.unwrap();
+    let diagnostics = result["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["file"], "main.rs");
+    assert_eq!(diagnostics[0]["line"], 2);
+    assert_eq!(diagnostics[0]["rule"], "reserved_name");
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.get_task("dev", "config").is_some());
+    assert!(project_data.get_task("dev", "login_form").is_some());
+
+    let result = handler.handle_request(scan(false)).await.result.unwrap();
+    assert!(result["diagnostics"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_scan_files_updates_only_listed_files() {
+    use anchora::task_manager::TaskStatus;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::write(workspace_path.join("a.rs"), "// dev:login: build login\n").unwrap();
+    std::fs::write(workspace_path.join("b.rs"), "// dev:logout: build logout\n").unwrap();
+    std::fs::write(workspace_path.join("c.rs"), "// dev:signup: build signup\n").unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "scan_project",
+        serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    std::fs::write(workspace_path.join("a.rs"), "// dev:profile: build profile\n").unwrap();
+    std::fs::write(workspace_path.join("b.rs"), "// dev:logout:done\n").unwrap();
+    std::fs::remove_file(workspace_path.join("c.rs")).unwrap();
+
+    let response = rpc(&handler, "scan_files", serde_json::json!({ "files": ["a.rs", "./b.rs"] })).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["files_scanned"], 2);
+    assert_eq!(result["tasks_added"], serde_json::json!(["dev:profile"]));
+    assert_eq!(result["tasks_updated"], serde_json::json!(["dev:logout"]));
+    assert_eq!(result["tasks_removed"], serde_json::json!(["dev:login"]));
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.get_task("dev", "login").is_none());
+    assert_eq!(project_data.get_task("dev", "logout").unwrap().status, TaskStatus::Done);
+    assert!(project_data.get_task("dev", "signup").unwrap().files.contains_key("c.rs"));
+
+    let response = rpc(&handler, "scan_files", serde_json::json!({ "files": ["../outside.rs"] })).await;
+    assert!(response.error.unwrap().message.contains("outside the workspace"));
+}
+
+#[tokio::test]
+async fn test_validate_note_input() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "login", "Login".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let note = |task_id: &str, status: &str| {
+        serde_json::json!({
+            "title": "Session handling",
+            "content": "Sessions should expire after 30 minutes",
+            "section": "dev",
+            "suggested_task_id": task_id,
+            "suggested_status": status
+        })
+    };
+
+    // A taken ID only warns when the note is written down; converting it fails
+    let result = rpc(&handler, "validate_note_input", note("login", "todo")).await.result.unwrap();
+    assert_eq!(result["can_create"], true);
+    assert_eq!(result["warnings"][0]["warning_type"], "duplicate_task_id");
+    assert_eq!(result["warnings"][0]["field"], "suggested_task_id");
+    assert_eq!(result["warnings"][0]["existing_task"]["task_id"], "login");
+    assert!(!result["alternative_ids"].as_array().unwrap().is_empty());
+    let result = rpc(&handler, "create_note", note("login", "todo")).await.result.unwrap();
+    assert!(result["warnings"][0].as_str().unwrap().contains("already exists"));
+    let note_id = result["note_id"].clone();
+    let response = rpc(&handler, "convert_note_to_task", serde_json::json!({ "note_id": note_id })).await;
+    assert!(response.error.is_some());
+
+    let result = rpc(&handler, "validate_note_input", note("session", "someday")).await.result.unwrap();
+    assert_eq!(result["can_create"], false);
+    assert_eq!(result["errors"][0]["error_type"], "invalid_status");
+    let response = rpc(&handler, "create_note", note("session", "someday")).await;
+    assert!(response.error.is_some());
+
+    let result = rpc(&handler, "validate_note_input", note("session", "in_progress")).await.result.unwrap();
+    assert_eq!(result["can_create"], true);
+    let response = rpc(&handler, "create_note", note("session", "in_progress")).await;
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_reformatted_note_link_converts_note() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let mut config = anchora::ProjectConfig::default();
+    config.notes.auto_convert = true;
+    anchora::storage::StorageManager::new(workspace_path).save_config(&config).await.unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let result = rpc(
+        &handler,
+        "create_note",
+        serde_json::json!({
+            "title": "Build the login form",
+            "content": "Email and password fields",
+            "section": "dev",
+            "suggested_task_id": "login_form"
+        }),
+    )
+    .await
+    .result
+    .unwrap();
+    let note_id = result["note_id"].as_str().unwrap().to_string();
+    let result = rpc(&handler, "generate_task_link", serde_json::json!({ "note_id": note_id })).await.result.unwrap();
+    assert_eq!(result["link"], "// dev:login_form:todo: Build the login form");
+
+    let source = "fn main() {\n    //dev:login_form:todo:   Build the login form (remember me too)\n}\n";
+    std::fs::write(workspace_path.join("main.rs"), source).unwrap();
+    let response = rpc(
+        &handler,
+        "scan_project",
+        serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }),
+    )
+    .await;
+    assert!(response.error.is_none());
+    assert_eq!(
+        response.result.unwrap()["converted_notes"],
+        serde_json::json!([note_id])
+    );
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.notes[&note_id].is_converted);
+    let task = project_data.get_task("dev", "login_form").unwrap();
+    assert!(task.files.contains_key("main.rs"));
+    assert_eq!(task.description.as_deref(), Some("Email and password fields"));
+}
+
+#[tokio::test]
+async fn test_pasted_note_link_converts_note_with_default_config() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path();
+    let mut note_ids = Vec::new();
+    for (task_id, title) in [("login", "Build the login form"), ("logout", "Add a logout button")] {
+        let result = rpc(
+            &handler,
+            "create_note",
+            serde_json::json!({ "title": title, "content": "", "section": "dev", "suggested_task_id": task_id }),
+        )
+        .await
+        .result
+        .unwrap();
+        let note_id = result["note_id"].as_str().unwrap().to_string();
+        rpc(&handler, "generate_task_link", serde_json::json!({ "note_id": note_id })).await;
+        note_ids.push(note_id);
+    }
+
+    std::fs::write(workspace_path.join("login.rs"), "fn main() {\n    //  dev:login:todo:  Build the login form\n}\n").unwrap();
+    std::fs::write(workspace_path.join("logout.rs"), "// dev:logout:todo: Add logout button\n").unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert_eq!(response.result.unwrap()["converted_notes"], serde_json::json!([note_ids[0]]));
+
+    let project_data = anchora::storage::StorageManager::new(workspace_path).load_project_data().await.unwrap();
+    assert!(project_data.notes[&note_ids[0]].is_converted);
+    assert!(project_data.get_task("dev", "login").unwrap().files.contains_key("login.rs"));
+    // Edited links wait for notes.auto_convert
+    assert!(!project_data.notes[&note_ids[1]].is_converted);
+}
+
+#[tokio::test]
+async fn test_conversion_candidates_report_readiness() {
+    use anchora::ProjectConfig;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut config = ProjectConfig::default();
+    config.notes.auto_convert = false;
+    storage.save_config(&config).await.unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let mut note_ids = Vec::new();
+    for (task_id, title) in [("login", "Build the login form"), ("logout", "Add a logout button")] {
+        let result = rpc(
+            &handler,
+            "create_note",
+            serde_json::json!({ "title": title, "content": "", "section": "dev", "suggested_task_id": task_id }),
+        )
+        .await
+        .result
+        .unwrap();
+        let note_id = result["note_id"].as_str().unwrap().to_string();
+        let response = rpc(&handler, "generate_task_link", serde_json::json!({ "note_id": note_id })).await;
+        assert!(response.error.is_none());
+        note_ids.push(note_id);
+    }
+
+    std::fs::write(workspace_path.join("login.rs"), "// dev:login:todo: Build login form (see design doc)\n").unwrap();
+    std::fs::write(workspace_path.join("logout.rs"), "// dev:logout: tidy session cleanup\n").unwrap();
+    // Pasted links count before any scan has recorded the file for the task
+    let candidates = rpc(&handler, "get_conversion_candidates", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(candidates[0]["note_id"], note_ids[0].as_str());
+    assert_eq!((candidates[0]["ready"].as_bool(), candidates[0]["file"].as_str()), (Some(true), Some("login.rs")));
+    let response = rpc(
+        &handler,
+        "scan_project",
+        serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    let candidates = rpc(&handler, "get_conversion_candidates", serde_json::json!({})).await.result.unwrap();
+    let candidates = candidates.as_array().unwrap();
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0]["note_id"], note_ids[0].as_str());
+    assert_eq!(candidates[0]["ready"], true);
+    assert!(candidates[0]["confidence"].as_f64().unwrap() < 1.0);
+    assert_eq!(candidates[0]["file"], "login.rs");
+    assert_eq!(candidates[1]["note_id"], note_ids[1].as_str());
+    assert_eq!(candidates[1]["ready"], false);
+    assert!(candidates[1]["confidence"].as_f64().unwrap() < 0.75);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.notes.values().all(|note| !note.is_converted));
+}
+
+#[tokio::test]
+async fn test_convert_note_to_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let create_note = |content: &str| {
+        request(
+            "create_note",
+            serde_json::json!({ "title": "Auth ideas", "content": content, "section": "auth", "suggested_task_id": "ideas" }),
+        )
+    };
+    let response = rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "login_form", "title": "Existing" })).await;
+    assert!(response.error.is_none());
+
+    let note_id = handler
+        .handle_request(create_note("Brainstorm\n- Login form\n* Password reset\n- Remember me\n"))
+        .await
+        .result
+        .unwrap()["note_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let result = rpc(
+        &handler,
+        "convert_note_to_tasks",
+        serde_json::json!({ "note_id": note_id, "split": "bullets", "section": "dev" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(result["task_ids"], serde_json::json!(["login_form_2", "password_reset", "remember_me"]));
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    let note = &project_data.notes[&note_id];
+    assert!(note.is_converted);
+    assert_eq!(note.converted_tasks.len(), 3);
+    assert_eq!(note.related_tasks, note.converted_tasks);
+    let task = project_data.get_task("dev", "password_reset").unwrap();
+    assert_eq!(task.title, "Password reset");
+    assert_eq!(task.related_notes, vec![note_id.clone()]);
+    assert_eq!(project_data.get_task("dev", "login_form").unwrap().title, "Existing");
+
+    // One item slugs to an ID starting with a digit, so nothing is created
+    let note_id = handler
+        .handle_request(create_note("- Session timeout\n- 2FA support\n"))
+        .await
+        .result
+        .unwrap()["note_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let response = rpc(&handler, "convert_note_to_tasks", serde_json::json!({ "note_id": note_id, "split": "bullets" })).await;
+    assert!(response.error.is_some());
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(!project_data.notes[&note_id].is_converted);
+    assert!(project_data.get_task("auth", "session_timeout").is_none());
+    let result = rpc(
+        &handler,
+        "convert_note_to_tasks",
+        serde_json::json!({ "note_id": note_id, "split": "bullets", "id_prefix": "auth" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(result["task_ids"], serde_json::json!(["auth_session_timeout", "auth_2fa_support"]));
+}
+
+#[tokio::test]
+async fn test_preview_file_labels_leaves_storage_alone() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let content = "fn main() {\n    // dev:login: build login\n    // dev:login:done\n    // dev: logout: spaced\n}\n";
+    let result = rpc(&handler, "preview_file_labels", serde_json::json!({ "content": content })).await.result.unwrap();
+    assert_eq!(
+        result["labels"],
+        serde_json::json!([
+            { "line": 2, "section": "dev", "task_id": "login", "status": null, "description": "build login", "note": null },
+            { "line": 3, "section": "dev", "task_id": "login", "status": "done", "description": null, "note": null }
+        ])
+    );
+    assert_eq!(result["near_misses"][0]["line"], 4);
+    assert_eq!(result["near_misses"][0]["rule"], "space_after_section");
+
+    let markdown = "# Guide\n<!-- dev:guide:todo: Write the guide -->\n";
+    let result = rpc(&handler, "preview_file_labels", serde_json::json!({ "content": markdown, "path": "docs/GUIDE.md" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["labels"][0]["task_id"], "guide");
+    let result = rpc(&handler, "preview_file_labels", serde_json::json!({ "content": markdown })).await.result.unwrap();
+    assert_eq!(result["labels"], serde_json::json!([]));
+    assert!(!workspace_path.join(".anchora").join("tasks.json").exists());
+}
+
+#[tokio::test]
+async fn test_generate_task_link_reports_previous_link() {
+    let (_temp_dir, handler) = test_handler();
+    let result = rpc(
+        &handler,
+        "create_note",
+        serde_json::json!({ "title": "Login", "content": "", "section": "dev", "suggested_task_id": "login" }),
+    )
+    .await
+    .result
+    .unwrap();
+    let note_id = result["note_id"].as_str().unwrap().to_string();
+    let generate = || request("generate_task_link", serde_json::json!({ "note_id": note_id }));
+
+    let result = handler.handle_request(generate()).await.result.unwrap();
+    assert_eq!(result["link"], "// dev:login:todo: Login");
+    assert_eq!(result["previous_link"], serde_json::Value::Null);
+    assert_eq!(result["changed"], true);
+    let result = handler.handle_request(generate()).await.result.unwrap();
+    assert_eq!(result["previous_link"], "// dev:login:todo: Login");
+    assert_eq!(result["changed"], false);
+
+    let result = rpc(
+        &handler,
+        "update_note",
+        serde_json::json!({ "note_id": note_id, "suggested_task_id": "sign_in", "suggested_status": "blocked" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(result["generated_link"], "// dev:sign_in:blocked: Login");
+    let response = rpc(&handler, "update_note", serde_json::json!({ "note_id": note_id, "suggested_status": "stuck" })).await;
+    assert!(response.error.is_some());
+
+    // The merged note is validated like a new one and left unchanged when it fails
+    let response = rpc(&handler, "update_note", serde_json::json!({ "note_id": note_id, "title": "  " })).await;
+    assert!(response.error.unwrap().message.contains("Note title is empty"));
+    let response = rpc(&handler, "update_note", serde_json::json!({ "note_id": note_id, "suggested_task_id": "sign in" })).await;
+    assert!(response.error.is_some());
+    let result = rpc(&handler, "get_note", serde_json::json!({ "note_id": note_id })).await.result.unwrap();
+    assert_eq!(result["note"]["title"], "Login");
+    assert_eq!(result["note"]["suggested_task_id"], "sign_in");
+    // As on creation, a suggestion may name an existing task; only conversion refuses it
+    rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "sign_in", "title": "Sign in" })).await;
+    let response = rpc(&handler, "update_note", serde_json::json!({ "note_id": note_id, "title": "Sign-in form" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "logout", "title": "Logout" })).await;
+    let response = rpc(&handler, "update_note", serde_json::json!({ "note_id": note_id, "suggested_task_id": "logout" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+}
+
+#[tokio::test]
+async fn test_apply_conflict_resolution_rename() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::write(workspace_path.join("a.rs"), "// dev:login: build login\n// dev:login:done\n").unwrap();
+    std::fs::write(workspace_path.join("b.rs"), "// ui:login: login screen\n// ui:logout: logout button\n").unwrap();
+    std::fs::write(workspace_path.join("notes.md"), "<!-- dev:login: build login -->\nSee // dev:login in a.rs\n").unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let scan = request(
+        "scan_project",
+        serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }),
+    );
+    assert!(handler.handle_request(scan.clone()).await.error.is_none());
+
+    let check = rpc(&handler, "check_task_conflicts", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    let resolution = check["conflicts"][0]["resolution"].clone();
+    assert_eq!(resolution, serde_json::json!({ "action": "rename_to", "new_id": "dev_login" }));
+
+    let params = serde_json::json!({ "section": "dev", "task_id": "login", "resolution": resolution });
+    let result = rpc(&handler, "apply_conflict_resolution", params.clone()).await.result.unwrap();
+    assert_eq!(result["section"], "dev");
+    assert_eq!(result["task_id"], "dev_login");
+    assert_eq!(result["files_updated"], 2);
+    assert_eq!(
+        std::fs::read_to_string(workspace_path.join("a.rs")).unwrap(),
+        "// dev:dev_login: build login\n// dev:dev_login:done\n"
+    );
+    // Markdown labels live in HTML comments; `//` there is plain text
+    assert_eq!(
+        std::fs::read_to_string(workspace_path.join("notes.md")).unwrap(),
+        "<!-- dev:dev_login: build login -->\nSee // dev:login in a.rs\n"
+    );
+    assert_eq!(std::fs::read_dir(workspace_path).unwrap().count(), 4);
+
+    assert!(handler.handle_request(scan).await.error.is_none());
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.get_task("dev", "login").is_none());
+    assert!(project_data.get_task("dev", "dev_login").unwrap().files.contains_key("a.rs"));
+    assert!(project_data.get_task("ui", "login").is_some());
+
+    let response = rpc(
+        &handler,
+        "apply_conflict_resolution",
+        serde_json::json!({
+            "section": "ui",
+            "task_id": "login",
+            "resolution": { "action": "rename_to", "new_id": "logout" }
+        }),
+    )
+    .await;
+    assert!(response.error.unwrap().message.contains("collides"));
+    assert_eq!(
+        std::fs::read_to_string(workspace_path.join("b.rs")).unwrap(),
+        "// ui:login: login screen\n// ui:logout: logout button\n"
+    );
+}
+
+#[tokio::test]
+async fn test_done_task_reports_unblocked_dependents() {
+    use anchora::task_manager::TaskKey;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    for task_id in ["a", "b", "c", "d"] {
+        project_data.add_task("dev", task_id, task_id.to_uppercase(), None).unwrap();
+    }
+    assert!(project_data.add_dependency("dev", "a", TaskKey::new("dev", "a")).is_err());
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let depend = |method: &'static str, task_id: &str, depends_on: &str| {
+        request(
+            method,
+            serde_json::json!({
+                "section": "dev",
+                "task_id": task_id,
+                "depends_on": format!("dev.{}", depends_on)
+            }),
+        )
+    };
+    for (task_id, depends_on) in [("b", "a"), ("c", "a"), ("c", "d"), ("d", "b")] {
+        let response = handler.handle_request(depend("add_task_dependency", task_id, depends_on)).await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+    }
+    let response = handler.handle_request(depend("add_task_dependency", "a", "c")).await;
+    assert!(response.error.unwrap().message.contains("cycle"));
+    let result = handler
+        .handle_request(depend("remove_task_dependency", "d", "b"))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["dependencies"], serde_json::json!([]));
+    let response = handler.handle_request(depend("remove_task_dependency", "d", "b")).await;
+    assert!(response.error.is_some());
+
+    let dependents = rpc(&handler, "get_dependents", serde_json::json!({ "section": "dev", "task_id": "a" }))
+        .await
+        .result
+        .unwrap();
+    let ids: Vec<&str> = dependents.as_array().unwrap().iter().map(|d| d["task_id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["b", "c"]);
+
+    let result = rpc(
+        &handler,
+        "update_task_status",
+        serde_json::json!({ "section": "dev", "task_id": "a", "status": "done" }),
+    )
+    .await
+    .result
+    .unwrap();
+    let unblocked = result["unblocked"].as_array().unwrap();
+    assert_eq!(unblocked.len(), 1);
+    assert_eq!(unblocked[0]["task_id"], "b");
+    assert_eq!(unblocked[0]["title"], "B");
+
+    let result = rpc(
+        &handler,
+        "update_task_status",
+        serde_json::json!({ "section": "dev", "task_id": "d", "status": "in_progress" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert!(result.get("unblocked").is_none());
+}
+
+#[tokio::test]
+async fn test_validation_reuses_context_until_data_changes() {
+    let (_temp_dir, handler) = test_handler();
+    let validate = || request("validate_task_input", serde_json::json!({ "section": "dev", "task_id": "login" }));
+    let snapshot_builds = || async {
+        let stats = rpc(&handler, "get_performance_stats", serde_json::json!({})).await;
+        stats.result.unwrap()["validation_snapshot_builds"].as_u64().unwrap()
+    };
+
+    let result = handler.handle_request(validate()).await.result.unwrap();
+    assert_eq!(result["is_valid"], true);
+    let builds = snapshot_builds().await;
+    for _ in 0..10 {
+        handler.handle_request(validate()).await;
+    }
+    assert_eq!(snapshot_builds().await, builds);
+
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "Login" }),
+    )
+    .await;
+    assert!(response.error.is_none());
+    let result = handler.handle_request(validate()).await.result.unwrap();
+    assert_eq!(result["is_valid"], false);
+    assert_eq!(result["errors"][0]["error_type"], "duplicate_task_id");
+    assert_eq!(snapshot_builds().await, builds + 1);
+}
+
+#[tokio::test]
+async fn test_caches_follow_writes_from_other_processes() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let search = || request("search_tasks", serde_json::json!({ "query": "login" }));
+    let validate = || request("validate_task_input", serde_json::json!({ "section": "dev", "task_id": "login" }));
+    let result = handler.handle_request(search()).await.result.unwrap();
+    assert!(result["tasks"].as_array().unwrap().is_empty());
+    assert_eq!(handler.handle_request(validate()).await.result.unwrap()["is_valid"], true);
+
+    // Written by a second manager, as the CLI or another server would
+    let outside = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "login", "Login form".to_string(), None).unwrap();
+    outside.save_project_data(&project_data).await.unwrap();
+
+    let result = handler.handle_request(search()).await.result.unwrap();
+    assert_eq!(result["tasks"].as_array().unwrap().len(), 1);
+    assert_eq!(handler.handle_request(validate()).await.result.unwrap()["is_valid"], false);
+}
+
+#[tokio::test]
+async fn test_background_rebuild_picks_up_unannounced_changes() {
+    use anchora::ProjectConfig;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    // A second manager writes behind the handler's back, so its generation never moves
+    let outside = anchora::storage::StorageManager::new(workspace_path);
+    let mut config = ProjectConfig::default();
+    config.search.rebuild_interval_ms = Some(20);
+    outside.save_config(&config).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let rebuilder = handler.spawn_index_rebuilder().await.unwrap().unwrap();
+    let indexed_tasks = || async {
+        let result = rpc(&handler, "get_performance_stats", serde_json::json!({})).await.result.unwrap();
+        result["indexed_tasks"].as_u64().unwrap()
+    };
+    let wait_for = |expected: u64| async move {
+        for _ in 0..100 {
+            if indexed_tasks().await == expected {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("index never reached {} tasks", expected);
+    };
+
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "login", "Login form".to_string(), None).unwrap();
+    outside.save_project_data(&project_data).await.unwrap();
+    wait_for(1).await;
+
+    project_data.add_task("dev", "logout", "Logout button".to_string(), None).unwrap();
+    outside.save_project_data(&project_data).await.unwrap();
+    wait_for(2).await;
+    rebuilder.abort();
+}
+
+#[tokio::test]
+async fn test_overview_sections_follow_configured_order() {
+    use anchora::{ProjectConfig, SectionOrder};
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    for (section, count) in [("ops", 1), ("api", 2), ("web", 3), ("docs", 2)] {
+        for i in 0..count {
+            project_data.add_task(section, &format!("task_{}", i), format!("Task {}", i), None).unwrap();
+        }
+    }
+    project_data.get_task_mut("web", "task_0").unwrap().created = chrono::Utc::now() - chrono::Duration::days(3);
+    project_data.get_task_mut("docs", "task_1").unwrap().created = chrono::Utc::now() - chrono::Duration::days(2);
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let section_names = || async {
+        let result = rpc(&handler, "get_task_overview", serde_json::json!({})).await.result.unwrap();
+        result["sections"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|section| section["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(section_names().await, vec!["api", "docs", "ops", "web"]);
+    let mut config = ProjectConfig::default();
+    config.responses.section_order = SectionOrder::TaskCount;
+    storage.save_config(&config).await.unwrap();
+    let first = section_names().await;
+    assert_eq!(first, vec!["web", "api", "docs", "ops"]);
+    assert_eq!(section_names().await, first);
+
+    config.responses.section_order = SectionOrder::Creation;
+    storage.save_config(&config).await.unwrap();
+    assert_eq!(section_names().await[..2], ["web", "docs"]);
+
+    // Task listings and JSON exports name the sections in the same order
+    let creation_order = section_names().await;
+    let tasks = rpc(&handler, "get_tasks", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(tasks["section_order"], serde_json::json!(creation_order));
+    let filtered = rpc(&handler, "get_filtered_tasks", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(filtered["section_order"], serde_json::json!(creation_order));
+    let export_path = workspace_path.join("tasks.json");
+    let response = rpc(&handler, "export_tasks", serde_json::json!({ "path": export_path.to_string_lossy() })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let export: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&export_path).unwrap()).unwrap();
+    assert_eq!(export["section_order"], serde_json::json!(creation_order));
+}
+
+#[tokio::test]
+async fn test_validation_on_large_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    for section in 0..50 {
+        for task in 0..200 {
+            project_data
+                .add_task(&format!("section_{}", section), &format!("task_{}", task), format!("Task {}", task), None)
+                .unwrap();
+        }
+    }
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let started = std::time::Instant::now();
+    for i in 0..100 {
+        let response = rpc(
+            &handler,
+            "validate_task_input",
+            serde_json::json!({ "section": "section_7", "task_id": format!("new_task_{}", i) }),
+        )
+        .await;
+        assert_eq!(response.result.unwrap()["is_valid"], true);
+    }
+    let elapsed = started.elapsed();
+
+    let stats = rpc(&handler, "get_performance_stats", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(stats["validation_snapshot_builds"], 1);
+    assert!(elapsed < std::time::Duration::from_secs(30), "100 validations took {:?}", elapsed);
+}
+
+#[tokio::test]
+async fn test_scan_errors_are_capped() {
+    use anchora::ProjectConfig;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    for i in 0..150 {
+        let source = format!("// dev:ghost_{}\n", i);
+        std::fs::write(workspace_path.join(format!("file_{}.rs", i)), source).unwrap();
+    }
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut config = ProjectConfig::default();
+    config.scan.max_reported_errors = 10;
+    storage.save_config(&config).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let result = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["files_scanned"], 150);
+    let errors = result["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 11);
+    assert_eq!(errors[10], "(+140 more)");
+    assert_eq!(result["errors_omitted"], 140);
+}
+
+#[tokio::test]
+async fn test_duplicate_create_task_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:login: build login\n").unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "Login again" }),
+    )
+    .await;
+    let error = response.error.expect("duplicate create_task should fail");
+    let validation = &error.data.unwrap()["validation"];
+    assert_eq!(validation["can_create"], false);
+    assert_eq!(validation["errors"][0]["error_type"], "duplicate_task_id");
+    assert!(!validation["alternative_ids"].as_array().unwrap().is_empty());
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    let task = project_data.get_task("dev", "login").unwrap();
+    assert_eq!(task.title, "build login");
+    assert!(task.files.contains_key("main.rs"));
+
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev", "task_id": "default", "title": "Default" }),
+    )
+    .await;
+    let error = response.error.expect("reserved id should be refused");
+    assert_eq!(error.data.unwrap()["validation"]["errors"][0]["error_type"], "reserved_name");
+
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({
+            "section": "dev",
+            "task_id": "login",
+            "title": "Login again",
+            "overwrite": true
+        }),
+    )
+    .await;
+    assert!(response.error.is_none());
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().title, "Login again");
+}
+
+#[tokio::test]
+async fn test_methods_rejected_before_initialize() {
+    let temp_dir = TempDir::new().unwrap();
+    let handler = TaskManagerHandler::new(temp_dir.path().to_path_buf())
+        .unwrap()
+        .require_initialize(true);
+    let response = rpc(&handler, "get_tasks", serde_json::json!({})).await;
+    let error = response.error.expect("get_tasks should require initialize");
+    assert_eq!(error.code, -32002);
+    assert!(error.message.contains("not initialized"));
+
+    let response = rpc(
+        &handler,
+        "initialize",
+        serde_json::json!({ "client_version": "0.3.0", "capabilities": ["notes"] }),
+    )
+    .await;
+    let result = response.result.unwrap();
+    assert_eq!(result["server_version"], anchora::VERSION);
+    assert_eq!(result["schema_version"], anchora::PROTOCOL_SCHEMA_VERSION);
+    let methods = result["supported_methods"].as_array().unwrap();
+    assert!(methods.contains(&serde_json::json!("get_tasks")));
+
+    let response = rpc(&handler, "get_tasks", serde_json::json!({})).await;
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_section_validation_profile_from_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::create_dir_all(workspace_path.join(".anchora")).unwrap();
+    std::fs::write(
+        workspace_path.join(".anchora/config.json"),
+        r#"{ "validation": { "sections": { "bug": { "id_pattern": "bug_\\d+" } } } }"#,
+    )
+    .unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+
+    let response = rpc(
+        &handler,
+        "validate_task_input",
+        serde_json::json!({ "section": "dev", "task_id": "login_crash" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["is_valid"], true);
+
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "bug", "task_id": "login_crash", "title": "Crash" }),
+    )
+    .await;
+    let error = response.error.expect("id outside the bug pattern should be refused");
+    assert_eq!(
+        error.data.unwrap()["validation"]["errors"][0]["error_type"],
+        "task_id_pattern_mismatch"
+    );
+
+    let response = rpc(&handler, "get_validation_config", serde_json::json!({})).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["sections"]["bug"]["id_pattern"], "bug_\\d+");
+    assert_eq!(result["sections"]["bug"]["max_task_id_length"], 50);
+
+    let response = rpc(
+        &handler,
+        "set_config",
+        serde_json::json!({ "validation": { "sections": { "bug": { "id_pattern": "bug_(" } } } }),
+    )
+    .await;
+    assert!(response.error.is_some());
+
+    // A partial update leaves the sections it doesn't mention alone
+    let response = rpc(&handler, "set_config", serde_json::json!({ "responses": { "performance_metrics": false } })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let stored = anchora::storage::StorageManager::new(workspace_path).load_config().await.unwrap();
+    assert!(!stored.responses.performance_metrics);
+    assert_eq!(stored.validation.sections["bug"].id_pattern.as_deref(), Some("bug_\\d+"));
+}
+
+#[tokio::test]
+async fn test_purge_converted_notes() {
+    use anchora::Note;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    for (id, converted_days_ago) in [("old", Some(45)), ("recent", Some(1)), ("open", None)] {
+        let mut note = Note::new(
+            format!("Note {}", id),
+            "Content".to_string(),
+            "dev".to_string(),
+            id.to_string(),
+            None,
+        );
+        note.id = id.to_string();
+        if let Some(days) = converted_days_ago {
+            note.mark_as_converted();
+            note.converted_at = Some(chrono::Utc::now() - chrono::Duration::days(days));
+        }
+        project_data.notes.insert(note.id.clone(), note);
+    }
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(&handler, "purge_converted_notes", serde_json::json!({ "older_than_days": "thirty" })).await;
+    assert_eq!(response.error.unwrap().code, -32602);
+    assert_eq!(storage.load_project_data().await.unwrap().notes.len(), 3);
+
+    let response = rpc(&handler, "purge_converted_notes", serde_json::json!({ "older_than_days": 30 })).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["removed"], 1);
+    let backup_path = result["backup_path"].as_str().unwrap();
+    assert!(std::path::Path::new(backup_path).exists());
+
+    let loaded = storage.load_project_data().await.unwrap();
+    let mut remaining: Vec<&String> = loaded.notes.keys().collect();
+    remaining.sort();
+    assert_eq!(remaining, vec!["open", "recent"]);
+}
+
+#[tokio::test]
+async fn test_validate_project_reports_without_modifying() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "function", "Reserved".to_string(), None).unwrap();
+    project_data.add_task("dev", "fix-login", "Hyphenated".to_string(), None).unwrap();
+    project_data.add_task("dev", "logout", "Logout".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+    let saved = std::fs::read_to_string(workspace_path.join(".anchora/tasks.json")).unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(&handler, "validate_project", serde_json::json!({})).await;
+    let report = response.result.unwrap();
+    assert_eq!(report["tasks_checked"], 3);
+    assert_eq!(report["violation_count"], 2);
+    let rules: Vec<&str> = report["rules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rule| rule["rule"].as_str().unwrap())
+        .collect();
+    assert_eq!(rules, vec!["invalid_task_id_format", "reserved_name"]);
+    assert_eq!(
+        std::fs::read_to_string(workspace_path.join(".anchora/tasks.json")).unwrap(),
+        saved
+    );
+}
+
+#[tokio::test]
+async fn test_task_metadata_and_filtering() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let mut project_data = anchora::task_manager::ProjectData::new(None);
+    project_data.add_task("dev", "login", "Login".to_string(), None).unwrap();
+    project_data.add_task("dev", "logout", "Logout".to_string(), None).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+
+    let response = rpc(
+        &handler,
+        "set_task_metadata",
+        serde_json::json!({ "section": "dev", "task_id": "login", "key": "story_points", "value": 5 }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["success"], true);
+
+    let response = rpc(
+        &handler,
+        "set_task_metadata",
+        serde_json::json!({ "section": "dev", "task_id": "login", "key": " ", "value": 1 }),
+    )
+    .await;
+    assert!(response.error.is_some());
+
+    let response = rpc(&handler, "get_filtered_tasks", serde_json::json!({ "metadata": { "story_points": 5 } })).await;
+    let tasks = &response.result.unwrap()["sections"]["dev"];
+    assert_eq!(tasks.as_object().unwrap().len(), 1);
+    assert_eq!(tasks["login"]["metadata"]["story_points"], 5);
+
+    let response = rpc(
+        &handler,
+        "remove_task_metadata",
+        serde_json::json!({ "section": "dev", "task_id": "login", "key": "story_points" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["success"], true);
+    let response = rpc(&handler, "get_filtered_tasks", serde_json::json!({ "metadata": { "story_points": 5 } })).await;
+    assert!(response.result.unwrap()["sections"]["dev"].as_object().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_task_priority_filters_and_overview() {
+    let (_temp_dir, handler) = test_handler();
+    for (task_id, priority) in [("login", Some("critical")), ("logout", Some("low")), ("signup", None)] {
+        let response = rpc(
+            &handler,
+            "create_task",
+            serde_json::json!({
+                "section": "dev",
+                "task_id": task_id,
+                "title": format!("Build {}", task_id),
+                "priority": priority
+            }),
+        )
+        .await;
+        assert!(response.error.is_none());
+    }
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev", "task_id": "reset", "title": "Reset", "priority": "urgent" }),
+    )
+    .await;
+    assert!(response.error.unwrap().message.contains("Invalid priority"));
+
+    let response = rpc(
+        &handler,
+        "set_task_priority",
+        serde_json::json!({ "section": "dev", "task_id": "logout", "priority": "high" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["success"], true);
+
+    let response = rpc(&handler, "get_filtered_tasks", serde_json::json!({ "priorities": ["critical", "high"] })).await;
+    let tasks = response.result.unwrap()["sections"]["dev"].clone();
+    let mut ids: Vec<&String> = tasks.as_object().unwrap().keys().collect();
+    ids.sort();
+    assert_eq!(ids, vec!["login", "logout"]);
+    assert_eq!(tasks["login"]["priority"], "critical");
+
+    let response = rpc(
+        &handler,
+        "search_tasks",
+        serde_json::json!({ "query": "build", "filters": { "priorities": ["high"] } }),
+    )
+    .await;
+    let result = response.result.unwrap();
+    assert_eq!(result["tasks"].as_array().unwrap().len(), 1);
+    assert_eq!(result["tasks"][0]["task_id"], "logout");
+
+    let response = rpc(&handler, "get_task_overview", serde_json::json!({})).await;
+    let priorities: Vec<(String, u64)> = response.result.unwrap()["priorities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| (entry["name"].as_str().unwrap().to_string(), entry["total"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(
+        priorities,
+        vec![("critical".to_string(), 1), ("high".to_string(), 1), ("(none)".to_string(), 1)]
+    );
+}
+
+#[tokio::test]
+async fn test_benchmark_scan_report() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("src/main.rs"),
+        "// dev:task_1:todo: first\nfn main() {}\n// dev:task_2: second\n",
+    )
+    .unwrap();
+    std::fs::write(temp_dir.path().join("src/app.js"), "// ui:button:todo: add button\n").unwrap();
+    std::fs::write(temp_dir.path().join("notes.txt"), "// dev:task_3: not scanned\n").unwrap();
+
+    let handler = TaskManagerHandler::new(temp_dir.path().to_path_buf()).unwrap();
+    let response = rpc(&handler, "benchmark_scan", serde_json::json!({ "slowest_limit": 1 })).await;
+    let report = response.result.unwrap();
+    assert!(report["total_ms"].as_f64().unwrap() > 0.0);
+    assert_eq!(report["files_scanned"], 2);
+    assert_eq!(report["tasks_found"], 3);
+    assert_eq!(report["slowest_files"].as_array().unwrap().len(), 1);
+    let mut extensions: Vec<&str> = report["by_extension"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|timing| timing["extension"].as_str().unwrap())
+        .collect();
+    extensions.sort();
+    assert_eq!(extensions, vec!["js", "rs"]);
+
+    // A benchmark is a dry run and must not touch stored tasks
+    assert!(!temp_dir.path().join(".anchora/tasks.json").exists());
+}
+
+#[tokio::test]
+async fn test_archived_section_hidden_from_listings() {
+    let (_temp_dir, handler) = test_handler();
+    for (section, task_id) in [("v1", "ship"), ("v1", "announce"), ("v2", "plan")] {
+        let response = rpc(
+            &handler,
+            "create_task",
+            serde_json::json!({ "section": section, "task_id": task_id, "title": format!("Task {}", task_id) }),
+        )
+        .await;
+        assert!(response.error.is_none());
+    }
+
+    let response = rpc(&handler, "archive_section", serde_json::json!({ "section": "v1" })).await;
+    assert_eq!(response.result.unwrap()["success"], true);
+
+    let response = rpc(&handler, "get_tasks", serde_json::json!({})).await;
+    let sections = response.result.unwrap()["sections"].clone();
+    assert!(sections.get("v1").is_none());
+    assert!(sections.get("v2").is_some());
+
+    let response = rpc(&handler, "get_task_overview", serde_json::json!({})).await;
+    let overview = response.result.unwrap();
+    assert_eq!(overview["statistics"]["total_tasks"], 1);
+    assert_eq!(overview["sections"].as_array().unwrap().len(), 1);
+
+    let response = rpc(&handler, "get_tasks", serde_json::json!({ "include_archived": true })).await;
+    assert_eq!(response.result.unwrap()["sections"]["v1"].as_object().unwrap().len(), 2);
+
+    let response = rpc(&handler, "unarchive_section", serde_json::json!({ "section": "v1" })).await;
+    assert_eq!(response.result.unwrap()["success"], true);
+    let response = rpc(&handler, "get_tasks", serde_json::json!({})).await;
+    let v1 = response.result.unwrap()["sections"]["v1"].clone();
+    assert_eq!(v1["ship"]["title"], "Task ship");
+    assert_eq!(v1["announce"]["title"], "Task announce");
+}
+
+#[tokio::test]
+async fn test_task_tags_management() {
+    let (_temp_dir, handler) = test_handler();
+    for task_id in ["login", "logout"] {
+        let response = rpc(
+            &handler,
+            "create_task",
+            serde_json::json!({ "section": "dev", "task_id": task_id, "title": format!("Task {}", task_id) }),
+        )
+        .await;
+        assert!(response.error.is_none());
+    }
+
+    let response = rpc(
+        &handler,
+        "add_task_tags",
+        serde_json::json!({ "section": "dev", "task_id": "login", "tags": ["Backend", "backend ", "Безопасность"] }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["tags"], serde_json::json!(["backend", "безопасность"]));
+    let response = rpc(
+        &handler,
+        "add_task_tags",
+        serde_json::json!({ "section": "dev", "task_id": "logout", "tags": ["backend", "ui"] }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    let response = rpc(&handler, "get_tags", serde_json::json!({})).await;
+    assert_eq!(
+        response.result.unwrap(),
+        serde_json::json!([
+            { "tag": "backend", "count": 2, "kind": "task" },
+            { "tag": "ui", "count": 1, "kind": "task" },
+            { "tag": "безопасность", "count": 1, "kind": "task" }
+        ])
+    );
+    let response = rpc(&handler, "get_tags", serde_json::json!({ "prefix": "БЕЗ" })).await;
+    assert_eq!(response.result.unwrap().as_array().unwrap().len(), 1);
+
+    let response = rpc(&handler, "get_filtered_tasks", serde_json::json!({ "tags": ["UI"] })).await;
+    let ids: Vec<String> = response.result.unwrap()["sections"]["dev"].as_object().unwrap().keys().cloned().collect();
+    assert_eq!(ids, vec!["logout".to_string()]);
+
+    let response = rpc(
+        &handler,
+        "search_tasks",
+        serde_json::json!({ "query": "task", "filters": { "tags": ["безопасность"] } }),
+    )
+    .await;
+    let result = response.result.unwrap();
+    assert_eq!(result["tasks"].as_array().unwrap().len(), 1);
+    assert_eq!(result["tasks"][0]["task_id"], "login");
+
+    let response = rpc(
+        &handler,
+        "remove_task_tags",
+        serde_json::json!({ "section": "dev", "task_id": "login", "tags": ["BACKEND"] }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["tags"], serde_json::json!(["безопасность"]));
+
+    let response = rpc(&handler, "delete_task", serde_json::json!({ "section": "dev", "task_id": "logout" })).await;
+    assert!(response.error.is_none());
+    let response = rpc(&handler, "get_tags", serde_json::json!({})).await;
+    assert_eq!(response.result.unwrap(), serde_json::json!([{ "tag": "безопасность", "count": 1, "kind": "task" }]));
+}
+
+#[tokio::test]
+async fn test_note_tags_filtering() {
+    let (_temp_dir, handler) = test_handler();
+    let mut note_ids = Vec::new();
+    for (task_id, tags) in [("login", vec!["Idea", "ui"]), ("crash", vec!["bug-report"]), ("sync", vec![])] {
+        let response = rpc(
+            &handler,
+            "create_note",
+            serde_json::json!({
+                "title": format!("Note {}", task_id),
+                "content": "",
+                "section": "dev",
+                "suggested_task_id": task_id,
+                "tags": tags
+            }),
+        )
+        .await;
+        note_ids.push(response.result.unwrap()["note_id"].as_str().unwrap().to_string());
+    }
+    let response = rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "theme", "title": "Theme" })).await;
+    assert!(response.error.is_none());
+    rpc(&handler, "add_task_tags", serde_json::json!({ "section": "dev", "task_id": "theme", "tags": ["ui"] })).await;
+
+    let response = rpc(&handler, "get_notes", serde_json::json!({ "tags": ["IDEA"] })).await;
+    let notes = response.result.unwrap();
+    assert_eq!(notes.as_array().unwrap().len(), 1);
+    assert_eq!(notes[0]["tags"], serde_json::json!(["idea", "ui"]));
+    let response = rpc(&handler, "get_notes", serde_json::json!({})).await;
+    assert_eq!(response.result.unwrap().as_array().unwrap().len(), 3);
+
+    let response = rpc(&handler, "update_note", serde_json::json!({ "note_id": note_ids[2], "tags": ["meeting"] })).await;
+    assert_eq!(response.result.unwrap()["tags"], serde_json::json!(["meeting"]));
+    let response = rpc(&handler, "search_notes", serde_json::json!({ "query": "note", "tags": ["meeting", "bug-report"] })).await;
+    assert_eq!(response.result.unwrap()["total_count"], 2);
+
+    let response = rpc(&handler, "get_tags", serde_json::json!({ "prefix": "u" })).await;
+    assert_eq!(
+        response.result.unwrap(),
+        serde_json::json!([
+            { "tag": "ui", "count": 1, "kind": "task" },
+            { "tag": "ui", "count": 1, "kind": "note" }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_task_assignee_and_my_tasks() {
+    let (_temp_dir, handler) = test_handler();
+    for (task_id, assignee, priority) in [
+        ("login", Some("alice"), Some("low")),
+        ("logout", Some("alice"), Some("critical")),
+        ("signup", Some("bob"), None),
+        ("reset", None, None),
+    ] {
+        let response = rpc(
+            &handler,
+            "create_task",
+            serde_json::json!({
+                "section": "dev",
+                "task_id": task_id,
+                "title": format!("Task {}", task_id),
+                "assignee": assignee,
+                "priority": priority
+            }),
+        )
+        .await;
+        assert!(response.error.is_none());
+    }
+
+    let response = rpc(
+        &handler,
+        "set_task_assignee",
+        serde_json::json!({ "section": "dev", "task_id": "reset", "assignee": "alice" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["success"], true);
+    let response = rpc(
+        &handler,
+        "update_task_status",
+        serde_json::json!({ "section": "dev", "task_id": "login", "status": "done" }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    let response = rpc(&handler, "get_my_tasks", serde_json::json!({ "assignee": "alice" })).await;
+    let ids: Vec<String> = response
+        .result
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["task_id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, vec!["logout".to_string(), "reset".to_string()]);
+
+    let response = rpc(&handler, "get_filtered_tasks", serde_json::json!({ "assignees": ["bob"] })).await;
+    let ids: Vec<String> = response.result.unwrap()["sections"]["dev"].as_object().unwrap().keys().cloned().collect();
+    assert_eq!(ids, vec!["signup".to_string()]);
+
+    let response = rpc(
+        &handler,
+        "search_tasks",
+        serde_json::json!({ "query": "task", "filters": { "assignees": ["alice"] } }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["tasks"].as_array().unwrap().len(), 3);
+
+    let response = rpc(&handler, "get_statistics", serde_json::json!({})).await;
+    assert_eq!(response.result.unwrap()["by_assignee"]["alice"]["total"], 3);
+
+    let response = rpc(&handler, "get_statistics", serde_json::json!({ "assignee_filter": "alice" })).await;
+    assert_eq!(response.error.unwrap().code, -32602);
+
+    let response = rpc(&handler, "get_task_overview", serde_json::json!({})).await;
+    let activity = response.result.unwrap()["recent_activity"].clone();
+    assert!(
+        activity
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|entry| entry["description"] == "Task dev:reset modified")
+    );
+}
+
+#[tokio::test]
+async fn test_renamed_file_references_follow_the_file() {
+    use anchora::FileEvent;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::create_dir_all(workspace_path.join("src/auth")).unwrap();
+    std::fs::write(workspace_path.join("src/main.rs"), "// dev:login: add login\nfn main() {}\n").unwrap();
+    std::fs::write(workspace_path.join("src/auth/token.rs"), "\n// dev:refresh: refresh tokens\n").unwrap();
+
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let response = rpc(
+        &handler,
+        "scan_project",
+        serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    std::fs::rename(workspace_path.join("src/main.rs"), workspace_path.join("src/app.rs")).unwrap();
+    handler
+        .handle_file_event(&FileEvent::Renamed {
+            from: workspace_path.join("src/main.rs"),
+            to: workspace_path.join("src/app.rs"),
+        })
+        .await
+        .unwrap();
+
+    let response = rpc(
+        &handler,
+        "rename_file_references",
+        serde_json::json!({ "old_path": "src/auth", "new_path": "src/security" }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["updated_tasks"], 1);
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    let login = project_data.get_task("dev", "login").unwrap();
+    assert_eq!(login.files.keys().collect::<Vec<_>>(), vec!["src/app.rs"]);
+    assert_eq!(login.files["src/app.rs"].lines, vec![1]);
+    let refresh = project_data.get_task("dev", "refresh").unwrap();
+    assert_eq!(refresh.files.keys().collect::<Vec<_>>(), vec!["src/security/token.rs"]);
+    assert_eq!(refresh.files["src/security/token.rs"].lines, vec![2]);
+    assert_eq!(project_data.index.files["src/app.rs"], std::collections::HashSet::from(["dev.login".to_string()]));
+    assert!(!project_data.index.files.contains_key("src/main.rs"));
+}
+
+#[tokio::test]
+async fn test_subtask_tree_and_cascade_delete() {
+    let (temp_dir, handler) = test_handler();
+    let tasks = [
+        ("dev", "checkoutFlow", None),
+        ("dev", "cart", Some("checkoutFlow")),
+        ("dev", "payment", Some("checkoutFlow")),
+        ("qa", "payment_tests", Some("payment")),
+        ("dev", "unrelated", None),
+    ];
+    for (section, task_id, parent) in tasks {
+        let parent = parent.map(|parent| format!("dev.{}", parent));
+        let response = rpc(
+            &handler,
+            "create_task",
+            serde_json::json!({ "section": section, "task_id": task_id, "title": format!("Task {}", task_id), "parent": parent }),
+        )
+        .await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+    }
+    for (section, task_id) in [("dev", "cart"), ("qa", "payment_tests")] {
+        let response = rpc(
+            &handler,
+            "update_task_status",
+            serde_json::json!({ "section": section, "task_id": task_id, "status": "done" }),
+        )
+        .await;
+        assert!(response.error.is_none());
+    }
+
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "qa", "task_id": "payment_tests" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(details["task"]["parent"], "dev.payment");
+    let response = rpc(
+        &handler,
+        "set_task_parent",
+        serde_json::json!({ "section": "dev", "task_id": "unrelated", "parent": "dev" }),
+    )
+    .await;
+    assert_eq!(response.error.unwrap().code, -32602);
+
+    let response = rpc(&handler, "get_task_tree", serde_json::json!({ "section": "dev", "task_id": "checkoutFlow" })).await;
+    let tree = response.result.unwrap();
+    assert_eq!((tree["done"].as_u64(), tree["total"].as_u64()), (Some(2), Some(3)));
+    assert_eq!(tree["children"][1]["task_id"], "payment");
+    assert_eq!(tree["children"][1]["children"][0]["task_id"], "payment_tests");
+
+    // Leaves only: cart and unrelated in dev, payment_tests rolled into dev as well
+    let response = rpc(&handler, "get_statistics", serde_json::json!({ "roll_up_subtasks": true })).await;
+    let stats = response.result.unwrap();
+    assert_eq!(stats["overview"]["total_tasks"], 3);
+    assert_eq!(stats["overview"]["completed_tasks"], 2);
+
+    let response = rpc(&handler, "normalize_ids", serde_json::json!({ "style": "snake_case" })).await;
+    assert!(response.error.is_none());
+    let response = rpc(&handler, "get_task_tree", serde_json::json!({ "section": "dev", "task_id": "checkout_flow" })).await;
+    assert_eq!(response.result.unwrap()["total"], 3);
+
+    let response = rpc(
+        &handler,
+        "delete_task",
+        serde_json::json!({ "section": "dev", "task_id": "payment", "cascade": true }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["deleted"].as_array().unwrap().len(), 2);
+    let response = rpc(&handler, "delete_task", serde_json::json!({ "section": "dev", "task_id": "checkout_flow" })).await;
+    assert!(response.error.is_none());
+    let response = rpc(&handler, "get_task_tree", serde_json::json!({ "section": "dev", "task_id": "cart" })).await;
+    assert_eq!(response.result.unwrap()["total"], 0);
+    let project_data = anchora::storage::StorageManager::new(temp_dir.path())
+        .load_project_data()
+        .await
+        .unwrap();
+    assert!(project_data.get_task("dev", "cart").unwrap().parent.is_none());
+    assert!(project_data.get_task("qa", "payment_tests").is_none());
+}
+
+#[tokio::test]
+async fn test_task_comments_survive_scans_and_stay_out_of_listings() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:login:todo: Build the login form\n").unwrap();
+    let scan = serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() });
+    let response = rpc(&handler, "scan_project", scan.clone()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let mut comment_ids = Vec::new();
+    for text in ["Blocked on the design review", "Design approved"] {
+        let response = rpc(
+            &handler,
+            "add_task_comment",
+            serde_json::json!({ "section": "dev", "task_id": "login", "author": "alice", "text": text }),
+        )
+        .await;
+        let comment = response.result.unwrap();
+        assert_eq!(comment["author"], "alice");
+        comment_ids.push(comment["id"].as_str().unwrap().to_string());
+    }
+    let response = rpc(
+        &handler,
+        "add_task_comment",
+        serde_json::json!({ "section": "dev", "task_id": "login", "author": "alice", "text": "" }),
+    )
+    .await;
+    assert!(response.error.is_some());
+
+    let response = rpc(
+        &handler,
+        "delete_task_comment",
+        serde_json::json!({ "section": "dev", "task_id": "login", "comment_id": comment_ids[0] }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let response = rpc(&handler, "scan_project", scan).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    let comments = details["task"]["comments"].as_array().unwrap();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0]["text"], "Design approved");
+
+    let tasks = rpc(&handler, "get_tasks", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(tasks["sections"]["dev"]["login"]["comments"].as_array().unwrap().len(), 0);
+
+    let overview = rpc(&handler, "get_task_overview", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(overview["tasks_with_recent_comments"], 1);
+}
+
+#[tokio::test]
+async fn test_scan_reports_unreadable_directories() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    let missing = workspace_path.join("missing");
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": missing.to_string_lossy() })).await;
+    let errors = response.result.unwrap()["errors"].as_array().unwrap().clone();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].as_str().unwrap().starts_with("Cannot read directory"), "{:?}", errors);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let locked = workspace_path.join("locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::write(locked.join("hidden.rs"), "// dev:hidden:todo: Hidden task\n").unwrap();
+        std::fs::write(workspace_path.join("main.rs"), "// dev:visible:todo: Visible task\n").unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Privileged users can read the directory anyway, so there is nothing to report
+        let readable = std::fs::read_dir(&locked).is_ok();
+        let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let result = response.result.unwrap();
+        if !readable {
+            assert_eq!(result["errors"], serde_json::json!(["Cannot read directory locked: Permission denied (os error 13)"]));
+            assert_eq!(result["tasks_found"], 1);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_scan_reports_unreadable_files() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::create_dir(workspace_path.join("src")).unwrap();
+    std::fs::write(workspace_path.join("src/binary.rs"), b"// dev:binary:todo: \xff\xfe\n").unwrap();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:visible:todo: Visible task\n").unwrap();
+    let result = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }))
+        .await
+        .result
+        .unwrap();
+    let errors = result["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].as_str().unwrap().starts_with("Cannot read file src/binary.rs:"), "{:?}", errors);
+    assert_eq!(result["tasks_found"], 1);
+}
+
+#[tokio::test]
+async fn test_reorder_task_and_manual_sort() {
+    let (_temp_dir, handler) = test_handler();
+    for task_id in ["login", "signup", "logout"] {
+        let response = rpc(
+            &handler,
+            "create_task",
+            serde_json::json!({ "section": "ui", "task_id": task_id, "title": task_id }),
+        )
+        .await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+    }
+
+    let result = rpc(&handler, "reorder_task", serde_json::json!({ "section": "ui", "task_id": "logout", "before": "login" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["order"], serde_json::json!(["logout", "login", "signup"]));
+
+    let response = rpc(&handler, "reorder_task", serde_json::json!({ "section": "ui", "task_id": "logout", "after": "missing" })).await;
+    assert!(response.error.is_some());
+
+    let tasks = rpc(&handler, "get_tasks", serde_json::json!({ "sort_by": "manual" })).await.result.unwrap();
+    assert_eq!(tasks["order"]["ui"], serde_json::json!(["logout", "login", "signup"]));
+    let filtered = rpc(&handler, "get_filtered_tasks", serde_json::json!({ "sort_by": "manual" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(filtered["order"], tasks["order"]);
+    let response = rpc(&handler, "get_tasks", serde_json::json!({ "sort_by": "alphabetical" })).await;
+    assert!(response.error.is_some());
+    let response = rpc(&handler, "get_tasks", serde_json::json!({ "sort_by": 1 })).await;
+    assert_eq!(response.error.expect("malformed params should be rejected").code, -32602);
+}
+
+#[tokio::test]
+async fn test_performance_metrics_only_on_opted_in_methods() {
+    let (_temp_dir, handler) = test_handler();
+    let response = rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "login", "title": "Login" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let search = || request("search_tasks", serde_json::json!({ "query": "login" }));
+    let result = handler.handle_request(search()).await.result.unwrap();
+    assert_eq!(result["_performance"]["operation"], "Search tasks with indexing");
+    let result = rpc(&handler, "get_statistics", serde_json::json!({})).await.result.unwrap();
+    assert!(result.get("_performance").is_some());
+    for method in ["get_task_overview", "get_tasks", "get_performance_stats"] {
+        let result = rpc(&handler, method, serde_json::json!({})).await.result.unwrap();
+        assert!(result.get("_performance").is_none(), "{} has metrics", method);
+    }
+
+    let response = rpc(&handler, "set_config", serde_json::json!({ "responses": { "performance_metrics": false } })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = handler.handle_request(search()).await.result.unwrap();
+    assert!(result.get("_performance").is_none());
+    assert_eq!(result["tasks"][0]["task_id"], "login");
+}
+
+#[tokio::test]
+async fn test_link_notes_and_tasks() {
+    let (_temp_dir, handler) = test_handler();
+    let response = rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "login", "title": "Build login" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let note_id = rpc(
+        &handler,
+        "create_note",
+        serde_json::json!({
+            "title": "Session handling",
+            "content": "Sessions should expire after 30 minutes",
+            "section": "dev",
+            "suggested_task_id": "sessions",
+            "suggested_status": "todo"
+        }),
+    )
+    .await
+    .result
+    .unwrap()["note_id"]
+    .as_str()
+    .unwrap()
+    .to_string();
+    let link = serde_json::json!({ "note_id": note_id, "section": "dev", "task_id": "login" });
+
+    let response = rpc(&handler, "link_note_to_task", link.clone()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(details["related_notes"], serde_json::json!([{ "id": note_id, "title": "Session handling" }]));
+    let note = rpc(&handler, "get_note", serde_json::json!({ "note_id": note_id })).await.result.unwrap();
+    assert_eq!(
+        note["related_tasks"],
+        serde_json::json!([{ "section": "dev", "task_id": "login", "title": "Build login" }])
+    );
+
+    let notes = rpc(&handler, "get_notes", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(notes[0]["related_tasks"], serde_json::json!(["dev.login"]));
+
+    let response = rpc(&handler, "unlink_note_from_task", link.clone()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let note = rpc(&handler, "get_note", serde_json::json!({ "note_id": note_id })).await.result.unwrap();
+    assert_eq!(note["related_tasks"], serde_json::json!([]));
+
+    let response = rpc(&handler, "link_note_to_task", link).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let response = rpc(&handler, "delete_note", serde_json::json!({ "note_id": note_id })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(details["task"]["related_notes"], serde_json::json!([]));
+    let response = rpc(&handler, "link_note_to_task", serde_json::json!({ "note_id": note_id, "section": "dev", "task_id": "login" })).await;
+    assert!(response.error.is_some());
+}
+
+#[tokio::test]
+async fn test_create_task_from_template() {
+    let (_temp_dir, handler) = test_handler();
+    let response = rpc(
+        &handler,
+        "set_config",
+        serde_json::json!({
+            "templates": {
+                "bug": {
+                    "section": "qa",
+                    "title": "Bug: {title}",
+                    "status": "blocked",
+                    "tags": ["Bug"],
+                    "metadata": { "severity": "major" }
+                }
+            }
+        }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let response = rpc(
+        &handler,
+        "create_task_from_template",
+        serde_json::json!({
+            "template": "bug",
+            "task_id": "crash_on_save",
+            "overrides": { "title": "Crash on save", "tags": ["urgent"], "priority": "high" }
+        }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "qa", "task_id": "crash_on_save" }))
+        .await
+        .result
+        .unwrap();
+    let task = &details["task"];
+    assert_eq!(task["title"], "Bug: Crash on save");
+    assert_eq!(task["status"], "blocked");
+    assert_eq!(task["priority"], "high");
+    assert_eq!(task["tags"], serde_json::json!(["bug", "urgent"]));
+    assert_eq!(task["metadata"]["severity"], "major");
+
+    for params in [
+        serde_json::json!({ "template": "bug", "task_id": "crash_on_load" }),
+        serde_json::json!({ "template": "feature", "task_id": "dark_mode", "overrides": { "title": "Dark mode" } }),
+        serde_json::json!({ "template": "bug", "task_id": "crash_on_save", "overrides": { "title": "Again" } }),
+    ] {
+        let response = rpc(&handler, "create_task_from_template", params).await;
+        assert!(response.error.is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_task_links() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    let response = rpc(&handler, "set_config", serde_json::json!({ "scan": { "repository_url": "https://github.com/acme/app" } })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    std::fs::write(workspace_path.join("main.rs"), "// dev:crash:todo: Fix crash on save gh#42\n").unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let result = rpc(
+        &handler,
+        "add_task_link",
+        serde_json::json!({ "section": "dev", "task_id": "crash", "url": "https://docs.example.com/saving", "label": "Design doc" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(result["links"].as_array().unwrap().len(), 2);
+    let response = rpc(
+        &handler,
+        "add_task_link",
+        serde_json::json!({ "section": "dev", "task_id": "crash", "url": "file:///etc/passwd" }),
+    )
+    .await;
+    assert!(response.error.is_some());
+
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "crash" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(
+        details["task"]["links"],
+        serde_json::json!([
+            { "label": "gh#42", "url": "https://github.com/acme/app/issues/42" },
+            { "label": "Design doc", "url": "https://docs.example.com/saving" }
+        ])
+    );
+
+    let result = rpc(
+        &handler,
+        "remove_task_link",
+        serde_json::json!({ "section": "dev", "task_id": "crash", "url": "https://docs.example.com/saving" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(result["links"][0]["label"], "gh#42");
+    assert_eq!(result["links"].as_array().unwrap().len(), 1);
+
+    let response = rpc(&handler, "set_config", serde_json::json!({ "scan": { "repository_url": "github.com/acme/app" } })).await;
+    assert!(response.error.unwrap().message.contains("repository_url"));
+
+    // A bad URL already in the config is reported once and labels keep syncing
+    let storage = anchora::storage::StorageManager::new(&workspace_path);
+    let mut config = storage.load_config().await.unwrap();
+    config.scan.repository_url = Some("not a url".to_string());
+    storage.save_config(&config).await.unwrap();
+    std::fs::write(workspace_path.join("lib.rs"), "// dev:hang:todo: Fix hang gh#7\n").unwrap();
+    let result = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }))
+        .await
+        .result
+        .unwrap();
+    let errors = result["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].as_str().unwrap().contains("repository_url"));
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.get_task("dev", "hang").unwrap().links.is_empty());
+    assert_eq!(project_data.get_task("dev", "crash").unwrap().links.len(), 1);
+}
+
+#[tokio::test]
+async fn test_title_convention_warnings() {
+    let (_temp_dir, handler) = test_handler();
+    let warning_types = |result: serde_json::Value| -> Vec<String> {
+        result["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| w["warning_type"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let response = rpc(
+        &handler,
+        "set_config",
+        serde_json::json!({ "validation": { "conventions": { "capitalized_title": true, "distinct_description": true } } }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let result = rpc(
+        &handler,
+        "validate_task_input",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "fix login", "description": "Users get logged out" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(result["is_valid"], true);
+    assert_eq!(warning_types(result), vec!["title_not_capitalized".to_string()]);
+
+    let result = rpc(
+        &handler,
+        "validate_task_input",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "Fix login", "description": "Fix login" }),
+    )
+    .await
+    .result
+    .unwrap();
+    assert_eq!(warning_types(result), vec!["description_repeats_title".to_string()]);
+}
+
+#[tokio::test]
+async fn test_stored_task_templates() {
+    let (temp_dir, handler) = test_handler();
+    let response = rpc(
+        &handler,
+        "save_task_template",
+        serde_json::json!({
+            "template_id": "triage",
+            "template": {
+                "title": "{component}: {summary}",
+                "description": "Reported by {reporter}",
+                "status": "blocked",
+                "priority": "high",
+                "tags": ["triage"]
+            }
+        }),
+    )
+    .await;
+    let result = response.result.unwrap();
+    assert_eq!(result["placeholders"], serde_json::json!(["component", "reporter", "summary"]));
+    let response = rpc(&handler, "set_config", serde_json::json!({ "templates": { "weekly": { "section": "ops", "title": "Weekly sync" } } })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let result = rpc(&handler, "list_task_templates", serde_json::json!({})).await.result.unwrap();
+    let listed: Vec<&str> = result["templates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["template_id"].as_str().unwrap())
+        .collect();
+    // Both live in the config, so set_config merging kept the saved template
+    assert_eq!(listed, vec!["triage", "weekly"]);
+
+    let response = rpc(
+        &handler,
+        "create_task_from_template",
+        serde_json::json!({
+            "template_id": "triage",
+            "section": "bugs",
+            "task_id": "parser_crash",
+            "substitutions": { "component": "Parser", "summary": "crash on empty file" }
+        }),
+    )
+    .await;
+    let error = response.error.expect("missing placeholder values should be refused");
+    assert!(error.message.contains("reporter"), "{}", error.message);
+
+    let response = rpc(
+        &handler,
+        "create_task_from_template",
+        serde_json::json!({
+            "template_id": "triage",
+            "section": "bugs",
+            "task_id": "parser_crash",
+            "substitutions": { "component": "Parser", "summary": "crash on empty file", "reporter": "QA" }
+        }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "bugs", "task_id": "parser_crash" }))
+        .await
+        .result
+        .unwrap();
+    let task = &details["task"];
+    assert_eq!(task["title"], "Parser: crash on empty file");
+    assert_eq!(task["description"], "Reported by QA");
+    assert_eq!(task["status"], "blocked");
+    assert_eq!(task["priority"], "high");
+    assert_eq!(task["tags"], serde_json::json!(["triage"]));
+
+    let response = rpc(
+        &handler,
+        "create_task_from_template",
+        serde_json::json!({ "template_id": "weekly", "task_id": "sync_1" }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    assert!(std::fs::read_to_string(temp_dir.path().join(".anchora/config.json")).unwrap().contains("triage"));
+    assert!(!temp_dir.path().join(".anchora/templates.json").exists());
+}
+
+#[tokio::test]
+async fn test_get_all_file_decorations() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::write(
+        workspace_path.join("main.rs"),
+        "// dev:parser:todo: Parse input\n// dev:cli:blocked: Wire CLI\n// dev:parser:todo: Parse again\n",
+    )
+    .unwrap();
+    std::fs::write(workspace_path.join("lib.rs"), "// dev:parser:todo: Parse input\n").unwrap();
+    std::fs::write(
+        workspace_path.join("util.rs"),
+        "// dev:log:in_progress: Logging\n// api:auth:done: Auth\n// api:cache:todo: Cache\n",
+    )
+    .unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let result = rpc(&handler, "get_all_file_decorations", serde_json::json!({})).await.result.unwrap();
+    let decorations = result.as_array().unwrap();
+    let summary: Vec<(&str, u64, bool)> = decorations
+        .iter()
+        .map(|d| (d["file_path"].as_str().unwrap(), d["task_count"].as_u64().unwrap(), d["has_blocked"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(summary, vec![("lib.rs", 1, false), ("main.rs", 2, true), ("util.rs", 3, false)]);
+    assert_eq!(decorations[1]["statuses"], serde_json::json!({ "todo": 1, "blocked": 1 }));
+    assert_eq!(decorations[2]["statuses"], serde_json::json!({ "in_progress": 1, "done": 1, "todo": 1 }));
+
+    // Status edits keep the stored index current without a rebuild
+    let response = rpc(&handler, "update_task_status", serde_json::json!({ "section": "dev", "task_id": "cli", "status": "done" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = rpc(&handler, "get_all_file_decorations", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result[1]["statuses"], serde_json::json!({ "todo": 1, "done": 1 }));
+    assert_eq!(result[1]["has_blocked"], false);
+}
+
+#[tokio::test]
+async fn test_trash_restore_and_rescan() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::write(
+        workspace_path.join("main.rs"),
+        "// dev:parser:todo: Parse input\n// dev:cli:todo: Wire CLI\n",
+    )
+    .unwrap();
+    let scan = || request("scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() }));
+    let response = handler.handle_request(scan()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let task_ids = |result: serde_json::Value| -> Vec<String> {
+        let mut ids: Vec<String> = result["tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["task_id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    let result = rpc(&handler, "delete_task", serde_json::json!({ "section": "dev", "task_id": "parser" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["trashed"], true);
+    let result = rpc(&handler, "list_trash", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["trash"][0]["key"], "dev.parser");
+    assert_eq!(result["trash"][0]["task"]["files"]["main.rs"]["lines"], serde_json::json!([1]));
+
+    // The label is still in main.rs, but a rescan must not bring the task back
+    let response = handler.handle_request(scan()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let response = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "parser" })).await;
+    assert!(response.error.is_some());
+    let result = rpc(&handler, "search_tasks", serde_json::json!({ "query": "parse" })).await.result.unwrap();
+    assert!(task_ids(result).is_empty());
+    let result = rpc(&handler, "get_statistics", serde_json::json!({ "fresh": true })).await.result.unwrap();
+    assert_eq!(result["overview"]["total_tasks"], 1);
+
+    let response = rpc(&handler, "restore_task", serde_json::json!({ "section": "dev", "task_id": "parser" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    std::fs::write(
+        workspace_path.join("main.rs"),
+        "// dev:cli:todo: Wire CLI\n\n// dev:parser:in_progress: Parse input\n",
+    )
+    .unwrap();
+    let response = handler.handle_request(scan()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "parser" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(details["task"]["status"], "in_progress");
+    assert_eq!(details["task"]["files"]["main.rs"]["lines"], serde_json::json!([3]));
+    let result = rpc(&handler, "search_tasks", serde_json::json!({ "query": "parse" })).await.result.unwrap();
+    assert_eq!(task_ids(result), vec!["parser".to_string()]);
+    let result = rpc(&handler, "list_trash", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["trash"], serde_json::json!([]));
+
+    let result = rpc(&handler, "delete_task", serde_json::json!({ "section": "dev", "task_id": "cli", "permanent": true }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["trashed"], false);
+    let response = rpc(&handler, "restore_task", serde_json::json!({ "section": "dev", "task_id": "cli" })).await;
+    assert!(response.error.is_some());
+
+    rpc(&handler, "delete_task", serde_json::json!({ "section": "dev", "task_id": "parser" })).await;
+    let listing = rpc(&handler, "get_tasks", serde_json::json!({})).await.result.unwrap();
+    assert!(listing.get("trash").is_none());
+    let listing = rpc(&handler, "get_filtered_tasks", serde_json::json!({})).await.result.unwrap();
+    assert!(listing.get("trash").is_none());
+    // A malformed cutoff must not fall back to emptying everything
+    let response = rpc(&handler, "empty_trash", serde_json::json!({ "older_than_days": "a week" })).await;
+    assert_eq!(response.error.unwrap().code, -32602);
+    let result = rpc(&handler, "empty_trash", serde_json::json!({ "older_than_days": 7 })).await.result.unwrap();
+    assert_eq!(result["removed"], serde_json::json!([]));
+    let result = rpc(&handler, "empty_trash", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["removed"], serde_json::json!(["dev.parser"]));
+}
+
+#[tokio::test]
+async fn test_old_ids_resolve_after_rename() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    let response = rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "fixLogin", "title": "Fix login" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let response = rpc(&handler, "normalize_ids", serde_json::json!({ "style": "snake_case" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "fixLogin" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(details["task_id"], "fix_login");
+    assert_eq!(details["task"]["title"], "Fix login");
+    assert_eq!(details["renamed_from"]["task_id"], "fixLogin");
+    assert!(details["deprecation"].as_str().unwrap().contains("dev:fix_login"));
+
+    let result = rpc(&handler, "task_exists", serde_json::json!({ "section": "dev", "task_id": "fixLogin" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["exists"], true);
+    assert_eq!(result["renamed_to"], "dev.fix_login");
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "fix_login" }))
+        .await
+        .result
+        .unwrap();
+    assert!(details.get("deprecation").is_none());
+
+    let result = rpc(&handler, "list_aliases", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["aliases"], serde_json::json!([{ "from": "dev.fixLogin", "to": "dev.fix_login" }]));
+
+    // A label left behind with the old ID keeps referencing the renamed task
+    std::fs::write(workspace_path.join("main.rs"), "// dev:fixLogin:in_progress: Fix login\n").unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let response = rpc(&handler, "task_exists", serde_json::json!({ "section": "dev", "task_id": "fix_login" })).await;
+    assert_eq!(response.result.unwrap()["status"], "in_progress");
+    let result = rpc(&handler, "list_aliases", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["aliases"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_task_history_records_edits_and_summarizes_scans() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    let scan = serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() });
+    std::fs::write(workspace_path.join("a.rs"), "// dev:login: Login form\n").unwrap();
+    let response = rpc(&handler, "scan_project", scan.clone()).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let edits = [
+        ("update_task_status", serde_json::json!({ "section": "dev", "task_id": "login", "status": "in_progress" })),
+        ("set_task_priority", serde_json::json!({ "section": "dev", "task_id": "login", "priority": "high" })),
+        ("add_task_tags", serde_json::json!({ "section": "dev", "task_id": "login", "tags": ["auth"] })),
+    ];
+    for (method, params) in edits {
+        let response = rpc(&handler, method, params).await;
+        assert!(response.error.is_none(), "{}: {:?}", method, response.error);
+    }
+
+    // Two new references in one scan collapse into a single entry
+    std::fs::write(workspace_path.join("b.rs"), "// dev:login\n").unwrap();
+    std::fs::write(workspace_path.join("c.rs"), "fn c() {}\n// dev:login\n").unwrap();
+    rpc(&handler, "scan_project", scan.clone()).await;
+    // A scan that changes nothing records nothing
+    rpc(&handler, "scan_project", scan.clone()).await;
+
+    let result = rpc(&handler, "get_task_history", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    let history = result["history"].as_array().unwrap();
+    let fields: Vec<&str> = history.iter().map(|entry| entry["field"].as_str().unwrap()).collect();
+    assert_eq!(fields, vec!["references", "tags", "priority", "status", "created"]);
+    assert_eq!(history[0]["source"], "scan");
+    assert_eq!(history[0]["old"], serde_json::json!(["a.rs"]));
+    assert_eq!(history[0]["new"], serde_json::json!(["a.rs", "b.rs", "c.rs"]));
+    assert_eq!(history[1]["source"], "manual");
+    assert_eq!(history[2]["old"], serde_json::Value::Null);
+    assert_eq!(history[2]["new"], "high");
+    assert_eq!(history[3]["old"], "todo");
+    assert_eq!(history[3]["new"], "in_progress");
+    assert_eq!(history[4]["source"], "scan");
+    assert_eq!(history[4]["new"], "Login form");
+
+    let result = rpc(&handler, "get_task_history", serde_json::json!({ "section": "dev", "task_id": "login", "limit": 2 }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["history"].as_array().unwrap().len(), 2);
+    let response = rpc(&handler, "get_task_history", serde_json::json!({ "section": "dev", "task_id": "missing" })).await;
+    assert!(response.error.is_some());
+
+    // Trashing and restoring are recorded too, but history stays out of listings
+    let task = serde_json::json!({ "section": "dev", "task_id": "login" });
+    rpc(&handler, "delete_task", task.clone()).await;
+    rpc(&handler, "restore_task", task.clone()).await;
+    let result = rpc(&handler, "get_task_history", serde_json::json!({ "section": "dev", "task_id": "login", "limit": 2 }))
+        .await
+        .result
+        .unwrap();
+    let history = result["history"].as_array().unwrap();
+    assert_eq!(history[0]["field"], "trashed");
+    assert_eq!(history[0]["new"], false);
+    assert_eq!(history[1]["new"], true);
+    let result = rpc(&handler, "get_tasks", serde_json::json!({})).await.result.unwrap();
+    assert!(result["sections"]["dev"].get("login").is_some());
+    assert!(result.get("history").is_none());
+}
+
+#[tokio::test]
+async fn test_edit_task_text_survives_rescans() {
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    let scan = serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() });
+    std::fs::write(workspace_path.join("a.rs"), "// dev:login: Login form\n").unwrap();
+    rpc(&handler, "scan_project", scan.clone()).await;
+
+    let task = serde_json::json!({ "section": "dev", "task_id": "login" });
+    let response = rpc(&handler, "edit_task_text", task.clone()).await;
+    assert!(response.error.is_some());
+    let response = rpc(
+        &handler,
+        "edit_task_text",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "Sign-in page", "description": "OAuth only" }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    std::fs::write(workspace_path.join("a.rs"), "// dev:login: Login screen\n").unwrap();
+    rpc(&handler, "scan_project", scan).await;
+    let result = rpc(&handler, "get_task_details", task).await.result.unwrap();
+    assert_eq!(result["task"]["title"], "Sign-in page");
+    assert_eq!(result["task"]["description"], "OAuth only");
+    assert_eq!(result["task"]["description_source"], "manual");
+}
+
+#[tokio::test]
+async fn test_task_overview_uses_computed_statistics() {
+    let (_temp_dir, handler) = test_handler();
+    for (section, task_id) in [("dev", "login"), ("dev", "logout"), ("docs", "readme")] {
+        let response = rpc(&handler, "create_task", serde_json::json!({ "section": section, "task_id": task_id, "title": task_id })).await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+    }
+    let response = rpc(&handler, "update_task_status", serde_json::json!({ "section": "dev", "task_id": "login", "status": "done" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let overview = rpc(&handler, "get_task_overview", serde_json::json!({})).await.result.unwrap();
+    let statistics = &overview["statistics"];
+    assert_eq!(statistics["by_section"]["dev"]["total"], 2);
+    assert_eq!(statistics["by_section"]["dev"]["done"], 1);
+    assert_eq!(statistics["by_section"]["docs"]["total"], 1);
+    assert!(!statistics["recent_updates"].as_array().unwrap().is_empty());
+    assert_eq!(statistics["recent_updates"][0]["activity_type"], "task_completed");
+
+    // One completion against three creations this week
+    let trends = &statistics["trends"];
+    assert_eq!(trends["completion_trend_7d"], 1.0);
+    assert_eq!(trends["creation_trend_7d"], 3.0);
+    let score = trends["productivity_score"].as_f64().unwrap();
+    assert!((score - 25.0).abs() < 0.01, "{}", score);
+    assert_eq!(trends["busiest_sections"], serde_json::json!(["dev", "docs"]));
+    assert!(statistics["performance_metrics"]["performance"]["total_calculations"].as_u64().unwrap() >= 1);
+}
+
+#[tokio::test]
+async fn test_time_tracking_rpcs() {
+    let (_temp_dir, handler) = test_handler();
+    let response = rpc(&handler, "create_task", serde_json::json!({ "section": "dev", "task_id": "login", "title": "Login" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let response = rpc(&handler, "set_task_estimate", serde_json::json!({ "section": "dev", "task_id": "login", "estimate_minutes": 240 })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let log = |minutes: serde_json::Value| request("add_time_entry", serde_json::json!({ "section": "dev", "task_id": "login", "minutes": minutes, "note": "review" }));
+    let result = handler.handle_request(log(serde_json::json!(50))).await.result.unwrap();
+    assert_eq!(result["time_spent_minutes"], 50);
+    let result = handler.handle_request(log(serde_json::json!(70))).await.result.unwrap();
+    assert_eq!(result["time_spent_minutes"], 120);
+    assert_eq!(result["estimate_minutes"], 240);
+    for rejected in [serde_json::json!(-30), serde_json::json!(0), serde_json::json!(100_000)] {
+        let response = handler.handle_request(log(rejected.clone())).await;
+        assert!(response.error.is_some(), "{} minutes accepted", rejected);
+    }
+
+    let result = rpc(&handler, "get_task_history", serde_json::json!({ "section": "dev", "task_id": "login", "limit": 1 }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["history"][0]["field"], "time_spent_minutes");
+    assert_eq!(result["history"][0]["new"], 120);
+
+    let stats = rpc(&handler, "get_statistics", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(stats["sections"]["dev"]["estimate_minutes"], 240);
+    assert_eq!(stats["sections"]["dev"]["time_spent_minutes"], 120);
+    assert_eq!(stats["trends"]["time_logged_minutes_7d"], 120);
+    assert!(stats["recent_activity"].as_array().unwrap().iter().any(|activity| activity["description"] == "Task dev:login modified"));
+}
+
+#[tokio::test]
+async fn test_task_details_payload() {
+    use anchora::{StorageManager, TaskKey};
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:login: Login form\nfn main() {}\n// dev:login:validation\n").unwrap();
+    std::fs::write(workspace_path.join("auth.rs"), "// dev:session: Sessions\n// dev:logout: Logout\n// dev:login: Login form\n").unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.clone()).unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let storage = StorageManager::new(&workspace_path);
+    let mut project_data = storage.load_project_data().await.unwrap();
+    project_data.add_dependency("dev", "login", TaskKey::new("dev", "session")).unwrap();
+    project_data.add_dependency("dev", "logout", TaskKey::new("dev", "login")).unwrap();
+    project_data
+        .add_note("Rate limit".to_string(), "Lock after 5 attempts".to_string(), "dev".to_string(), "login".to_string(), None)
+        .unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let response = rpc(&handler, "update_task_status", serde_json::json!({ "section": "dev", "task_id": "login", "status": "in_progress" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(details["task"]["title"], "Login form");
+    assert_eq!(details["task"]["status"], "in_progress");
+    assert_eq!(
+        details["references"],
+        serde_json::json!([
+            { "file": "auth.rs", "lines": [{ "line": 3, "note": null }] },
+            { "file": "main.rs", "lines": [{ "line": 1, "note": null }, { "line": 3, "note": "validation" }] }
+        ])
+    );
+    assert_eq!(details["dependencies"][0]["task_id"], "session");
+    assert_eq!(details["dependencies"][0]["title"], "Sessions");
+    assert_eq!(details["dependents"][0]["task_id"], "logout");
+    assert_eq!(details["linked_notes"][0]["title"], "Rate limit");
+    assert_eq!(details["history"][0]["field"], "status");
+    assert_eq!(details["history"][0]["new"], "in_progress");
+    assert!(details["time_in_status"].is_object());
+
+    let response = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "loginn" })).await;
+    assert!(response.error.unwrap().message.contains("Did you mean 'dev:login'?"));
+    let response = rpc(&handler, "get_task_details", serde_json::json!({ "section": "ops", "task_id": "session" })).await;
+    assert!(response.error.unwrap().message.contains("Did you mean 'dev:session'?"));
+    let response = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "unrelated" })).await;
+    assert!(!response.error.unwrap().message.contains("Did you mean"));
+}
+
+#[tokio::test]
+async fn test_verify_references_detects_and_repairs_shifted_labels() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:login: Login form\nfn main() {}\n// dev:login:validation\n").unwrap();
+    std::fs::write(workspace_path.join("lib.rs"), "// dev:logout: Logout\n").unwrap();
+    std::fs::write(workspace_path.join("util.rs"), "// dev:cache: Cache\n").unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.clone()).unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = rpc(&handler, "verify_references", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["references_checked"], 4);
+    assert_eq!(result["stale"], serde_json::json!([]));
+
+    // Edits made without a rescan: two lines inserted above the labels, one label removed, one file deleted
+    std::fs::write(workspace_path.join("main.rs"), "use std::io;\n\n// dev:login: Login form\nfn main() {}\n// dev:login:validation\n").unwrap();
+    std::fs::write(workspace_path.join("lib.rs"), "fn logout() {}\n").unwrap();
+    std::fs::remove_file(workspace_path.join("util.rs")).unwrap();
+
+    let result = rpc(&handler, "verify_references", serde_json::json!({})).await.result.unwrap();
+    let stale: Vec<(String, u64, String)> = result["stale"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| (format!("{}:{}@{}", entry["section"].as_str().unwrap(), entry["task_id"].as_str().unwrap(), entry["file"].as_str().unwrap()), entry["line"].as_u64().unwrap(), entry["reason"].as_str().unwrap().to_string()))
+        .collect();
+    assert_eq!(
+        stale,
+        vec![
+            ("dev:cache@util.rs".to_string(), 1, "file_unreadable".to_string()),
+            ("dev:login@main.rs".to_string(), 1, "line_mismatch".to_string()),
+            // The label now on line 3 has no note, unlike the reference recorded there
+            ("dev:login@main.rs".to_string(), 3, "line_mismatch".to_string()),
+            ("dev:logout@lib.rs".to_string(), 1, "label_missing".to_string()),
+        ]
+    );
+
+    let result = rpc(&handler, "verify_references", serde_json::json!({ "repair": true })).await.result.unwrap();
+    assert_eq!(result["repaired"], 2);
+    let details = rpc(&handler, "get_task_details", serde_json::json!({ "section": "dev", "task_id": "login" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(
+        details["references"],
+        serde_json::json!([{ "file": "main.rs", "lines": [{ "line": 3, "note": null }, { "line": 5, "note": "validation" }] }])
+    );
+    let result = rpc(&handler, "verify_references", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["stale"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_milestones_over_rpc() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path().to_path_buf();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:login:done: Login form\n// api:auth: Auth endpoint\n").unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.clone()).unwrap();
+    rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    let response = rpc(&handler, "create_milestone", serde_json::json!({ "id": "v1", "title": "Release 1", "due": "2030-01-01T00:00:00Z" })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    for (section, task_id) in [("dev", "login"), ("api", "auth")] {
+        let response = rpc(&handler, "add_task_to_milestone", serde_json::json!({ "milestone_id": "v1", "section": section, "task_id": task_id })).await;
+        assert!(response.error.is_none(), "{:?}", response.error);
+    }
+
+    let result = rpc(&handler, "get_milestones", serde_json::json!({})).await.result.unwrap();
+    let milestone = &result["milestones"][0];
+    assert_eq!(milestone["task_refs"], serde_json::json!(["dev:login", "api:auth"]));
+    assert_eq!(milestone["progress"]["total"], 2);
+    assert_eq!(milestone["progress"]["completion_percentage"], 50.0);
+    let overview = rpc(&handler, "get_task_overview", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(overview["milestones"][0]["id"], "v1");
+
+    let export_path = workspace_path.join("milestones.csv");
+    let response = rpc(&handler, "export_statistics", serde_json::json!({ "path": export_path.to_string_lossy(), "format": "csv", "group_by_milestone": true })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let csv = std::fs::read_to_string(&export_path).unwrap();
+    assert!(csv.lines().nth(1).unwrap().contains(",v1,Release 1,2030-01-01T00:00:00+00:00,2,1,0,1,0,50.00"));
+
+    rpc(&handler, "delete_task", serde_json::json!({ "section": "api", "task_id": "auth" })).await;
+    let result = rpc(&handler, "get_milestones", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["milestones"][0]["task_refs"], serde_json::json!(["dev:login"]));
+    // Removing a task that is not a member is a no-op, like adding one twice
+    let result = rpc(&handler, "remove_task_from_milestone", serde_json::json!({ "milestone_id": "v1", "section": "api", "task_id": "auth" }))
+        .await
+        .result
+        .unwrap();
+    assert_eq!(result["success"], true);
+    rpc(&handler, "restore_task", serde_json::json!({ "section": "api", "task_id": "auth" })).await;
+    let result = rpc(&handler, "get_milestones", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["milestones"][0]["task_refs"], serde_json::json!(["dev:login", "api:auth"]));
+
+    let response = rpc(&handler, "delete_milestone", serde_json::json!({ "id": " v1 " })).await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let result = rpc(&handler, "get_milestones", serde_json::json!({})).await.result.unwrap();
+    assert_eq!(result["milestones"], serde_json::json!([]));
+    let response = rpc(&handler, "task_exists", serde_json::json!({ "section": "dev", "task_id": "login" })).await;
+    assert_eq!(response.result.unwrap()["exists"], true);
+}
+
+#[tokio::test]
+async fn test_describe_method_reports_parameters() {
+    let (_temp_dir, handler) = test_handler();
+
+    let result = rpc(&handler, "describe_method", serde_json::json!({ "name": "create_task" })).await.result.unwrap();
+    assert_eq!(result["description"], "Create new task");
+    assert_eq!(result["params"], "required");
+    let parameter = |name: &str| {
+        result["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|parameter| parameter["name"] == name)
+            .cloned()
+            .unwrap()
+    };
+    assert_eq!(parameter("title")["required"], true);
+    assert_eq!(parameter("title")["type"], "string");
+    assert_eq!(parameter("description")["required"], false);
+
+    let response = rpc(&handler, "describe_method", serde_json::json!({ "name": "no_such_method" })).await;
+    assert!(response.error.unwrap().message.contains("Unknown method: no_such_method"));
+
+    // Every listed method is dispatched; a bad params value never reaches "method not found"
+    let result = rpc(&handler, "list_methods", serde_json::json!({})).await.result.unwrap();
+    let methods = result["methods"].as_array().unwrap();
+    assert!(methods.iter().any(|method| method["name"] == "describe_method"));
+    for method in methods {
+        let name = method["name"].as_str().unwrap();
+        let response = rpc(&handler, name, serde_json::json!("probe")).await;
+        assert_ne!(response.error.map(|error| error.code), Some(-32601), "{} is not dispatched", name);
+    }
+}
+
+#[tokio::test]
+async fn test_method_docs_match_params_structs() {
+    use anchora::method_docs::{ParamsMode, METHODS};
+    use anchora::ProjectConfig;
+
+    let (temp_dir, handler) = test_handler();
+    let workspace_path = temp_dir.path().to_path_buf();
+    let sample = |name: &str, type_name: &str| match (name, type_name) {
+        ("depends_on", _) => serde_json::json!("docs.probe"),
+        ("split", _) => serde_json::json!("lines"),
+        ("style", _) => serde_json::json!("snake_case"),
+        ("resolution", _) => serde_json::json!({ "action": "rename_to", "new_id": "probe" }),
+        (_, "string" | "any") => serde_json::json!(workspace_path.join(format!("probe_{}", name)).to_string_lossy()),
+        (_, "integer") => serde_json::json!(1),
+        (_, "boolean") => serde_json::json!(false),
+        (_, "object") => serde_json::json!({}),
+        _ => serde_json::json!([]),
+    };
+    // Every value type but `any` rejects a number or a string
+    let wrong = |type_name: &str| match type_name {
+        "integer" | "boolean" => serde_json::json!("probe"),
+        _ => serde_json::json!(7),
+    };
+    let invalid_params = |response: anchora::JsonRpcResponse| response.error.is_some_and(|error| error.code == -32602);
+
+    for method in METHODS.iter().filter(|method| method.params != ParamsMode::None) {
+        let required: serde_json::Map<String, serde_json::Value> = method
+            .parameters
+            .iter()
+            .filter(|parameter| parameter.required)
+            .map(|parameter| (parameter.name.to_string(), sample(parameter.name, parameter.type_name)))
+            .collect();
+        // Documented optional parameters can be left out
+        let response = rpc(&handler, method.name, serde_json::Value::Object(required.clone())).await;
+        assert!(!invalid_params(response), "{} needs more than its documented required parameters", method.name);
+        for parameter in method.parameters {
+            // A documented parameter the struct does not know would be ignored rather than rejected.
+            // get_tasks has always fallen back to defaults on malformed params.
+            if parameter.type_name != "any" && method.name != "get_tasks" {
+                let mut params = required.clone();
+                params.insert(parameter.name.to_string(), wrong(parameter.type_name));
+                // set_config takes a partial ProjectConfig rather than a params struct
+                let rejected = if method.name == "set_config" {
+                    ProjectConfig::default().merged(serde_json::Value::Object(params)).is_err()
+                } else {
+                    invalid_params(rpc(&handler, method.name, serde_json::Value::Object(params)).await)
+                };
+                assert!(rejected, "{}.{} is not a {} parameter", method.name, parameter.name, parameter.type_name);
+            }
+            if parameter.required {
+                let mut params = required.clone();
+                params.remove(parameter.name);
+                let response = rpc(&handler, method.name, serde_json::Value::Object(params)).await;
+                assert!(invalid_params(response), "{}.{} is documented as required", method.name, parameter.name);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_pinned_notes_persist_and_list_first() {
+    let (temp_dir, handler) = test_handler();
+    let mut note_ids = Vec::new();
+    for task_id in ["first", "second", "third"] {
+        let response = rpc(
+            &handler,
+            "create_note",
+            serde_json::json!({ "title": task_id, "content": "", "section": "dev", "suggested_task_id": task_id }),
+        )
+        .await;
+        note_ids.push(response.result.unwrap()["note_id"].as_str().unwrap().to_string());
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    let titles = |notes: serde_json::Value| -> Vec<String> {
+        notes.as_array().unwrap().iter().map(|note| note["title"].as_str().unwrap().to_string()).collect()
+    };
+    let response = rpc(&handler, "get_notes", serde_json::json!({})).await;
+    assert_eq!(titles(response.result.unwrap()), vec!["third", "second", "first"]);
+
+    let response = rpc(&handler, "pin_note", serde_json::json!({ "note_id": note_ids[0] })).await;
+    assert!(response.error.is_none());
+    let storage = anchora::storage::StorageManager::new(temp_dir.path());
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.notes[&note_ids[0]].pinned);
+    assert!(!project_data.notes[&note_ids[1]].pinned);
+    let response = rpc(&handler, "get_notes", serde_json::json!({})).await;
+    assert_eq!(titles(response.result.unwrap()), vec!["first", "third", "second"]);
+
+    let response = rpc(&handler, "unpin_note", serde_json::json!({ "note_id": note_ids[0] })).await;
+    assert!(response.error.is_none());
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(!project_data.notes[&note_ids[0]].pinned);
+    let response = rpc(&handler, "pin_note", serde_json::json!({ "note_id": "missing" })).await;
+    assert!(response.error.is_some());
+}
+
+#[tokio::test]
+async fn test_note_attachments_are_normalized_and_carried_to_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let mut config = anchora::ProjectConfig::default();
+    config.notes.auto_convert = true;
+    anchora::storage::StorageManager::new(workspace_path).save_config(&config).await.unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let note = |attachments: serde_json::Value| {
+        serde_json::json!({
+            "title": "Retry the upload",
+            "content": "Fails on flaky networks",
+            "section": "net",
+            "suggested_task_id": "upload_retry",
+            "attachments": attachments
+        })
+    };
+    let outside = serde_json::json!([{ "file_path": "../elsewhere.rs", "line_start": 1, "line_end": 1 }]);
+    let response = rpc(&handler, "create_note", note(outside)).await;
+    assert!(response.error.is_some());
+
+    let absolute = workspace_path.join("src").join("upload.rs");
+    let attachments = serde_json::json!([
+        { "file_path": absolute.to_string_lossy(), "line_start": 10, "line_end": 14, "snippet": "client.put(url)" },
+        { "file_path": "./src/net.rs", "line_start": 3, "line_end": 3 }
+    ]);
+    let response = rpc(&handler, "create_note", note(attachments)).await;
+    let note_id = response.result.unwrap()["note_id"].as_str().unwrap().to_string();
+    let response = rpc(&handler, "get_note", serde_json::json!({ "note_id": note_id })).await;
+    let attachments = &response.result.unwrap()["note"]["attachments"];
+    assert_eq!(
+        *attachments,
+        serde_json::json!([
+            { "file_path": "src/upload.rs", "line_start": 10, "line_end": 14, "snippet": "client.put(url)" },
+            { "file_path": "src/net.rs", "line_start": 3, "line_end": 3 }
+        ])
+    );
+
+    rpc(&handler, "generate_task_link", serde_json::json!({ "note_id": note_id })).await;
+    std::fs::write(workspace_path.join("main.rs"), "// net:upload_retry:todo: Retry the upload\n").unwrap();
+    let response = rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+    assert_eq!(response.result.unwrap()["converted_notes"], serde_json::json!([note_id]));
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    let task = project_data.get_task("net", "upload_retry").unwrap();
+    assert_eq!(task.files["main.rs"].lines, vec![1]);
+    assert_eq!(task.files["src/upload.rs"].lines, vec![10]);
+    assert_eq!(task.files["src/net.rs"].lines, vec![3]);
+}
+
+#[tokio::test]
+async fn test_converting_note_never_overwrites_existing_task() {
+    let (temp_dir, handler) = test_handler();
+    let response = rpc(
+        &handler,
+        "create_note",
+        serde_json::json!({
+            "title": "Login form",
+            "content": "Email and password",
+            "section": "dev",
+            "suggested_task_id": "login"
+        }),
+    )
+    .await;
+    let note_id = response.result.unwrap()["note_id"].as_str().unwrap().to_string();
+    rpc(&handler, "generate_task_link", serde_json::json!({ "note_id": note_id })).await;
+    // The ID is claimed by someone else before the note is converted
+    let response = rpc(
+        &handler,
+        "create_task",
+        serde_json::json!({ "section": "dev", "task_id": "login", "title": "SSO login" }),
+    )
+    .await;
+    assert!(response.error.is_none());
+
+    let response = rpc(&handler, "convert_note_to_task", serde_json::json!({ "note_id": note_id })).await;
+    let error = response.error.unwrap();
+    let validation = &error.data.unwrap()["validation"];
+    assert_eq!(validation["errors"][0]["error_type"], "duplicate_task_id");
+    let alternative = validation["alternative_ids"][0].as_str().unwrap().to_string();
+    let storage = anchora::storage::StorageManager::new(temp_dir.path());
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().title, "SSO login");
+    assert!(!project_data.notes[&note_id].is_converted);
+
+    let response = rpc(
+        &handler,
+        "convert_note_to_task",
+        serde_json::json!({ "note_id": note_id, "auto_rename": true }),
+    )
+    .await;
+    let result = response.result.unwrap();
+    assert_eq!(result["renamed_from"], "login");
+    assert_eq!(result["task_id"], alternative.as_str());
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().title, "SSO login");
+    let task = project_data.get_task("dev", &alternative).unwrap();
+    assert_eq!(task.title, "Login form");
+    let note = &project_data.notes[&note_id];
+    assert!(note.is_converted);
+    assert_eq!(note.suggested_task_id, alternative);
+    assert_eq!(
+        note.generated_link.as_deref(),
+        Some(format!("// dev:{}:todo: Login form", alternative).as_str())
+    );
+
+    let response = rpc(&handler, "convert_note_to_task", serde_json::json!({ "note_id": note_id })).await;
+    assert!(response.error.is_some());
+}
+
+#[tokio::test]
+async fn test_scan_reconciles_label_and_stored_status() {
+    use anchora::TaskStatus;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let source = workspace_path.join("main.rs");
+    std::fs::write(&source, "// dev:login:done: Login page\nfn main() {}\n").unwrap();
+    let scan = |mode: &str| {
+        let params = serde_json::json!({ "workspace_path": workspace_path.to_string_lossy(), "reconcile_status": mode });
+        let handler = &handler;
+        async move { rpc(handler, "scan_project", params).await.result.unwrap() }
+    };
+    let result = scan("code_wins").await;
+    assert_eq!(result["status_divergences"], serde_json::json!([]));
+
+    // Someone edits tasks.json by hand
+    let mut project_data = storage.load_project_data().await.unwrap();
+    project_data.update_task_status("dev", "login", TaskStatus::Blocked).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+    let stored_status = || async {
+        storage.load_project_data().await.unwrap().get_task("dev", "login").unwrap().status.clone()
+    };
+    let divergence = serde_json::json!([{
+        "section": "dev",
+        "task_id": "login",
+        "file": "main.rs",
+        "line": 1,
+        "code_status": "done",
+        "stored_status": "blocked"
+    }]);
+
+    let result = scan("report_only").await;
+    assert_eq!(result["status_divergences"], divergence);
+    assert_eq!(stored_status().await, TaskStatus::Blocked);
+    assert!(std::fs::read_to_string(&source).unwrap().contains("dev:login:done:"));
+
+    let result = scan("json_wins").await;
+    assert_eq!(result["status_divergences"], divergence);
+    assert_eq!(stored_status().await, TaskStatus::Blocked);
+    assert_eq!(
+        std::fs::read_to_string(&source).unwrap(),
+        "// dev:login:blocked: Login page\nfn main() {}\n"
+    );
+    assert!(!workspace_path.join(".main.rs.anchora-tmp").exists());
+    let result = scan("code_wins").await;
+    assert_eq!(result["status_divergences"], serde_json::json!([]));
+
+    let mut project_data = storage.load_project_data().await.unwrap();
+    project_data.update_task_status("dev", "login", TaskStatus::InProgress).unwrap();
+    storage.save_project_data(&project_data).await.unwrap();
+    let result = scan("code_wins").await;
+    assert_eq!(result["status_divergences"][0]["stored_status"], "in_progress");
+    assert_eq!(stored_status().await, TaskStatus::Blocked);
+}
+
+#[tokio::test]
+async fn test_list_cli_reads_stored_tasks() {
+    use anchora::cli::{list_tasks, load_stored_project};
+    use anchora::{ListOptions, ListSort, ProjectData, TaskStatus};
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let error = load_stored_project(workspace_path).await.unwrap_err();
+    assert!(error.to_string().contains("No task data found"));
+
+    let mut project_data = ProjectData::new(Some("fixture".to_string()));
+    project_data.add_task("dev", "login", "Login page".to_string(), None).unwrap();
+    project_data.add_task("dev", "api", "REST API".to_string(), None).unwrap();
+    project_data.add_task("ui", "theme", "Dark theme".to_string(), None).unwrap();
+    project_data.update_task_status("dev", "api", TaskStatus::Done).unwrap();
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    storage.save_project_data(&project_data).await.unwrap();
+
+    let project_data = load_stored_project(workspace_path).await.unwrap();
+    let rows = list_tasks(&project_data, &ListOptions::default());
+    let listed: Vec<(&str, &str)> = rows.iter().map(|r| (r.section.as_str(), r.task_id.as_str())).collect();
+    assert_eq!(listed, [("dev", "api"), ("dev", "login"), ("ui", "theme")]);
+
+    let options = ListOptions {
+        section: Some("dev".to_string()),
+        status: Some(TaskStatus::Todo),
+        sort: ListSort::Title,
+    };
+    let json = serde_json::to_value(list_tasks(&project_data, &options)).unwrap();
+    let updated = project_data.get_task("dev", "login").unwrap().updated;
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "section": "dev",
+            "task_id": "login",
+            "status": "todo",
+            "title": "Login page",
+            "updated": updated
+        }])
+    );
+
+    let options = ListOptions { section: Some("missing".to_string()), ..Default::default() };
+    assert_eq!(serde_json::to_value(list_tasks(&project_data, &options)).unwrap(), serde_json::json!([]));
+
+    std::fs::write(workspace_path.join(".anchora/tasks.json"), "{ not json").unwrap();
+    let error = load_stored_project(workspace_path).await.unwrap_err();
+    assert!(format!("{:#}", error).contains("is not valid task data"));
+}
+
+#[test]
+fn test_stats_cli_renders_summary() {
+    use anchora::cli::{project_statistics, render_statistics, stale_task_count};
+    use anchora::{ProjectData, SectionOrder, TaskStatus};
+    use chrono::{Duration, Utc};
+
+    let mut project_data = ProjectData::new(Some("fixture".to_string()));
+    for (section, task_id, file) in [
+        ("dev", "login", "src/auth.rs"),
+        ("dev", "logout", "src/auth.rs"),
+        ("dev", "api", "src/api.rs"),
+        ("ui", "theme", "src/theme.rs"),
+    ] {
+        project_data.add_task(section, task_id, task_id.to_string(), None).unwrap();
+        project_data.update_task_file(section, task_id, file.to_string(), 1, None).unwrap();
+    }
+    project_data.update_task_status("dev", "api", TaskStatus::Done).unwrap();
+    project_data.update_task_status("ui", "theme", TaskStatus::Blocked).unwrap();
+    project_data.get_task_mut("dev", "logout").unwrap().updated = Utc::now() - Duration::days(45);
+    project_data
+        .create_milestone("beta", "Beta", Some(Utc::now() - Duration::days(1)))
+        .unwrap();
+    project_data.add_task_to_milestone("beta", "dev", "login").unwrap();
+
+    let stats = project_statistics(&project_data, None, SectionOrder::Alphabetical).unwrap();
+    let stale = stale_task_count(&project_data, None, Utc::now());
+    assert_eq!(stale, 1);
+    let report = render_statistics(&stats, stale);
+    let lines: Vec<&str> = report.lines().collect();
+    assert!(lines.contains(&"Project: fixture"));
+    assert!(lines.contains(&"Tasks: 4 total, 1 done, 0 in progress, 1 blocked, 2 todo (25.0% complete)"));
+    assert!(lines.contains(&"  dev  [#######-------------]  33.3%  1/3"));
+    assert!(lines.contains(&"  ui   [--------------------]   0.0%  0/1"));
+    assert!(lines.contains(&"  dev: src/auth.rs"));
+    assert!(lines.contains(&"  ui: src/theme.rs"));
+    assert!(lines.contains(&"  Overdue milestones: 1"));
+    assert!(lines.contains(&"  Stale tasks (no update in 30 days): 1"));
+
+    let stats = project_statistics(&project_data, Some("ui"), SectionOrder::Alphabetical).unwrap();
+    assert_eq!(stats.overview.total_tasks, 1);
+    assert!(!render_statistics(&stats, 0).contains("dev"));
+    assert_eq!(stale_task_count(&project_data, Some("ui"), Utc::now()), 0);
+}
+
+#[tokio::test]
+async fn test_export_cli_matches_rpc_and_refuses_to_overwrite() {
+    use anchora::cli::export;
+    use anchora::{ExportOptions, TaskStatus};
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    std::fs::write(
+        workspace_path.join("main.rs"),
+        "// dev:login:todo: Login page\n// dev:api:done: REST API\n// ui:theme:blocked: Dark theme\n",
+    )
+    .unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+
+    let rpc_path = workspace_path.join("rpc.md");
+    let response = rpc(
+        &handler,
+        "export_tasks",
+        serde_json::json!({
+            "path": rpc_path.to_string_lossy(),
+            "format": "markdown",
+            "sections": ["dev"],
+            "status": ["todo"],
+            "include_notes": true
+        }),
+    )
+    .await;
+    assert!(response.error.is_none(), "{:?}", response.error);
+
+    let out = workspace_path.join("TASKS.md");
+    let options = ExportOptions {
+        out: out.clone(),
+        format: "markdown".to_string(),
+        sections: Some(vec!["dev".to_string()]),
+        statuses: Some(vec![TaskStatus::Todo]),
+        include_notes: true,
+        force: false,
+    };
+    export(&handler, options.clone()).await.unwrap();
+    let exported = std::fs::read_to_string(&out).unwrap();
+    assert_eq!(exported, std::fs::read_to_string(&rpc_path).unwrap());
+    assert!(exported.contains("`login` Login page"));
+    assert!(!exported.contains("api") && !exported.contains("theme"));
+
+    // An existing file stays untouched without --force
+    std::fs::write(&out, "keep me").unwrap();
+    let error = export(&handler, options.clone()).await.unwrap_err();
+    assert!(error.to_string().contains("--force"));
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "keep me");
+    export(&handler, ExportOptions { force: true, ..options.clone() }).await.unwrap();
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), exported);
+
+    let error = export(&handler, ExportOptions { format: "pdf".to_string(), force: true, ..options.clone() })
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Invalid export format: pdf"));
+    let unwritable = ExportOptions { out: workspace_path.join("missing/TASKS.csv"), format: "csv".to_string(), ..options };
+    assert!(export(&handler, unwritable).await.is_err());
+}
+
+#[tokio::test]
+async fn test_bulk_apply_tags_only_matching_tasks() {
+    use anchora::BULK_APPLY_CONFIRM_THRESHOLD;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let mut source = String::from(
+        "// dev:login:todo: Auth login form\n// dev:tokens:todo: Refresh auth tokens\n// ui:theme:todo: Dark theme\n",
+    );
+    for i in 0..=BULK_APPLY_CONFIRM_THRESHOLD {
+        source.push_str(&format!("// perf:bench_{i}:todo: Benchmark case {i}\n"));
+    }
+    std::fs::write(workspace_path.join("main.rs"), source).unwrap();
+    let handler = TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap();
+    rpc(&handler, "scan_project", serde_json::json!({ "workspace_path": workspace_path.to_string_lossy() })).await;
+
+    let response = rpc(&handler, "bulk_apply", serde_json::json!({ "query": "auth", "add_tags": ["Security"] })).await;
+    let result = response.result.unwrap();
+    assert_eq!(result["matched"], 2);
+    assert_eq!(result["affected"], 2);
+    assert_eq!(
+        result["tasks"],
+        serde_json::json!(["dev.login", "dev.tokens"])
+    );
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().tags, ["security"]);
+    assert_eq!(project_data.get_task("dev", "tokens").unwrap().tags, ["security"]);
+    assert!(project_data.get_task("ui", "theme").unwrap().tags.is_empty());
+    assert!(project_data.get_task("perf", "bench_0").unwrap().tags.is_empty());
+
+    // Running it again matches the same tasks but changes nothing
+    let response = rpc(&handler, "bulk_apply", serde_json::json!({ "query": "auth", "add_tags": ["security"] })).await;
+    let result = response.result.unwrap();
+    assert_eq!((result["matched"].as_u64(), result["affected"].as_u64()), (Some(2), Some(0)));
+
+    let response = rpc(&handler, "bulk_apply", serde_json::json!({ "query": "benchmark", "set_status": "done" })).await;
+    assert!(response.error.unwrap().message.contains("confirm"));
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.sections["perf"].values().all(|task| task.status == anchora::TaskStatus::Todo));
+
+    let response = rpc(
+        &handler,
+        "bulk_apply",
+        serde_json::json!({ "query": "benchmark", "set_status": "done", "set_assignee": "qa", "confirm": true }),
+    )
+    .await;
+    assert_eq!(response.result.unwrap()["affected"], BULK_APPLY_CONFIRM_THRESHOLD + 1);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert!(project_data.sections["perf"].values().all(|task| {
+        task.status == anchora::TaskStatus::Done && task.assignee.as_deref() == Some("qa")
+    }));
+    assert!(rpc(&handler, "bulk_apply", serde_json::json!({ "query": "auth" })).await.error.is_some());
+
+    // A misspelt filter value fails instead of widening the edit to every match
+    let response = rpc(
+        &handler,
+        "bulk_apply",
+        serde_json::json!({ "query": "auth", "filters": { "statuses": ["finished"] }, "set_status": "done" }),
+    )
+    .await;
+    assert_eq!(response.error.unwrap().code, -32602);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().status, anchora::TaskStatus::Todo);
+    assert_eq!(project_data.get_task("dev", "tokens").unwrap().status, anchora::TaskStatus::Todo);
+}
+
+#[tokio::test]
+async fn test_watch_loop_applies_file_changes() {
+    use anchora::{WatchOptions, WatcherConfig};
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let workspace_path = temp_dir.path();
+    let source = workspace_path.join("main.rs");
+    std::fs::write(&source, "// dev:login:todo: Login form\n").unwrap();
+    let handler = std::sync::Arc::new(TaskManagerHandler::new(workspace_path.to_path_buf()).unwrap());
+    let options = WatchOptions {
+        watcher: WatcherConfig {
+            debounce_timeout: 50,
+            ..Default::default()
+        },
+        full_rescan_interval: None,
+    };
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let shutdown = async {
+        let _ = stopped.await;
+    };
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let tasks_file = workspace_path.join(".anchora/tasks.json");
+    let edit = async {
+        while !tasks_file.exists() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        // Keep rewriting until the change lands, since the watcher only
+        // starts once the initial scan has finished
+        for _ in 0..50 {
+            std::fs::write(&source, "// dev:login:done: Login form\n// dev:logout:todo: Logout button\n").unwrap();
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let project_data = storage.load_project_data().await.unwrap();
+            if project_data.get_task("dev", "logout").is_some() {
+                break;
+            }
+        }
+        stop.send(()).unwrap();
+    };
+    let (result, _) = tokio::time::timeout(
+        Duration::from_secs(20),
+        async { tokio::join!(anchora::watch::run(handler.clone(), options, shutdown), edit) },
+    )
+    .await
+    .expect("watch loop did not stop");
+    result.unwrap();
+
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().status, anchora::TaskStatus::Done);
+    assert!(project_data.get_task("dev", "logout").is_some());
+}
+
+#[tokio::test]
+async fn test_rpc_mutations_survive_concurrent_watch_rescans() {
+    use anchora::{WatchOptions, WatcherConfig};
+    use std::time::Duration;
+
+    let (temp_dir, handler) = test_handler();
+    let handler = std::sync::Arc::new(handler);
+    let workspace_path = temp_dir.path();
+    std::fs::write(workspace_path.join("main.rs"), "// dev:login:todo: Login form\n").unwrap();
+    let options = WatchOptions {
+        watcher: WatcherConfig::default(),
+        // Rescan back to back so every RPC call lands during a pass
+        full_rescan_interval: Some(Duration::from_millis(1)),
+    };
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let shutdown = async {
+        let _ = stopped.await;
+    };
+
+    let tasks_file = workspace_path.join(".anchora/tasks.json");
+    let log_time = async {
+        while !tasks_file.exists() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        for _ in 0..30 {
+            let response = rpc(
+                &handler,
+                "add_time_entry",
+                serde_json::json!({"section": "dev", "task_id": "login", "minutes": 1}),
+            )
+            .await;
+            assert!(response.error.is_none(), "{:?}", response.error);
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        stop.send(()).unwrap();
+    };
+    let (result, _) = tokio::time::timeout(
+        Duration::from_secs(20),
+        async { tokio::join!(anchora::watch::run(handler.clone(), options, shutdown), log_time) },
+    )
+    .await
+    .expect("watch loop did not stop");
+    result.unwrap();
+
+    let storage = anchora::storage::StorageManager::new(workspace_path);
+    let project_data = storage.load_project_data().await.unwrap();
+    assert_eq!(project_data.get_task("dev", "login").unwrap().time_spent_minutes, 30);
+}
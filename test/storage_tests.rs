@@ -305,6 +305,96 @@ async fn test_import_data() {
     assert_eq!(loaded_data.meta.project_name, Some("imported-project".to_string()));
 }
 
+#[tokio::test]
+async fn test_ndjson_export_import_round_trip() {
+    let source_dir = TempDir::new().unwrap();
+    let source = StorageManager::new(source_dir.path());
+
+    let mut project_data = ProjectData::new(Some("ndjson-test".to_string()));
+    project_data.add_task("dev", "task_1", "First".to_string(), Some("Details".to_string())).unwrap();
+    project_data.add_task("dev", "task_2", "Second".to_string(), None).unwrap();
+    project_data.add_task("v1", "ship", "Ship it".to_string(), None).unwrap();
+    project_data.update_task_file("dev", "task_1", "src/main.rs".to_string(), 3, None).unwrap();
+    project_data.set_task_metadata("dev", "task_2", "points", serde_json::json!(5)).unwrap();
+    project_data.archive_section("v1").unwrap();
+    project_data.sections.insert("empty".to_string(), TaskSection::new());
+    project_data.add_note("Idea".to_string(), "Later".to_string(), "dev".to_string(), "idea".to_string(), None).unwrap();
+    source.save_project_data(&project_data).await.unwrap();
+
+    let export_path = source_dir.path().join("tasks.ndjson");
+    assert_eq!(source.export_ndjson(&export_path).await.unwrap(), 3);
+    let content = tokio::fs::read_to_string(&export_path).await.unwrap();
+    // meta, three sections, three tasks and a note
+    assert_eq!(content.lines().count(), 8);
+    for line in content.lines() {
+        serde_json::from_str::<serde_json::Value>(line).unwrap();
+    }
+
+    let target_dir = TempDir::new().unwrap();
+    let target = StorageManager::new(target_dir.path());
+    assert_eq!(target.import_ndjson(&export_path).await.unwrap(), 3);
+    let imported = target.load_project_data().await.unwrap();
+    let original = source.load_project_data().await.unwrap();
+    // The index is rebuilt on import, everything else must survive unchanged
+    for (imported, original) in [
+        (serde_json::to_value(&imported.sections).unwrap(), serde_json::to_value(&original.sections).unwrap()),
+        (serde_json::to_value(&imported.notes).unwrap(), serde_json::to_value(&original.notes).unwrap()),
+        (serde_json::to_value(&imported.meta).unwrap(), serde_json::to_value(&original.meta).unwrap()),
+    ] {
+        assert_eq!(imported, original);
+    }
+    assert_eq!(imported.archived_sections, original.archived_sections);
+    assert_eq!(imported.index.files["src/main.rs"], std::collections::HashSet::from(["dev.task_1".to_string()]));
+
+    let invalid_path = source_dir.path().join("invalid.ndjson");
+    tokio::fs::write(&invalid_path, "{\"type\":\"section\",\"name\":\"dev\"}\nnot json\n").await.unwrap();
+    let error = target.import_ndjson(&invalid_path).await.unwrap_err();
+    assert!(error.to_string().contains("line 2"));
+}
+
+#[tokio::test]
+async fn test_templates_survive_export_and_import() {
+    let source_dir = TempDir::new().unwrap();
+    let source = StorageManager::new(source_dir.path());
+    assert!(source.load_templates().await.unwrap().is_empty());
+
+    let mut templates = std::collections::BTreeMap::new();
+    templates.insert("bug".to_string(), TaskTemplate {
+        title: Some("Bug: {summary}".to_string()),
+        status: Some(TaskStatus::Blocked),
+        priority: Some(TaskPriority::High),
+        tags: vec!["bug".to_string()],
+        ..TaskTemplate::default()
+    });
+    source.save_templates(&templates).await.unwrap();
+    assert_eq!(source.load_templates().await.unwrap(), templates);
+    let mut project_data = ProjectData::new(Some("templates-test".to_string()));
+    project_data.add_task("dev", "task_1", "First".to_string(), None).unwrap();
+    source.save_project_data(&project_data).await.unwrap();
+
+    let json_path = source_dir.path().join("export.json");
+    source.export_data(&json_path).await.unwrap();
+    let ndjson_path = source_dir.path().join("export.ndjson");
+    source.export_ndjson(&ndjson_path).await.unwrap();
+
+    for import_json in [true, false] {
+        let target_dir = TempDir::new().unwrap();
+        let target = StorageManager::new(target_dir.path());
+        let mut existing = std::collections::BTreeMap::new();
+        existing.insert("chore".to_string(), TaskTemplate::default());
+        target.save_templates(&existing).await.unwrap();
+        if import_json {
+            target.import_data(&json_path).await.unwrap();
+        } else {
+            target.import_ndjson(&ndjson_path).await.unwrap();
+        }
+        let imported = target.load_templates().await.unwrap();
+        assert_eq!(imported.keys().collect::<Vec<_>>(), vec!["bug", "chore"]);
+        assert_eq!(imported["bug"], templates["bug"]);
+        assert!(target.load_project_data().await.unwrap().get_task("dev", "task_1").is_some());
+    }
+}
+
 #[tokio::test]
 async fn test_import_invalid_data() {
     let temp_dir = TempDir::new().unwrap();
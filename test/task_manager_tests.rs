@@ -175,6 +175,74 @@ fn test_task_index_with_files() {
     assert!(main_tasks.contains(&"dev.task_1".to_string()));
 }
 
+#[test]
+fn test_task_index_deduplicates_and_removes() {
+    let mut index = TaskIndex::new();
+    let mut task = Task::new("Test task".to_string(), None);
+    task.add_file("src/main.rs".to_string(), 25, None);
+
+    index.update_task("dev", "task_1", &task);
+    index.update_task("dev", "task_1", &task);
+    assert_eq!(index.files["src/main.rs"].len(), 1);
+
+    task.update_status(TaskStatus::Done);
+    task.add_file("src/lib.rs".to_string(), 3, None);
+    index.update_task("dev", "task_1", &task);
+    assert!(!index.tasks_by_status.contains_key(&TaskStatus::Todo));
+    assert_eq!(index.tasks_by_status[&TaskStatus::Done].len(), 1);
+    assert_eq!(index.files.len(), 2);
+
+    let mut other = Task::new("Other".to_string(), None);
+    other.add_file("src/main.rs".to_string(), 40, None);
+    index.update_task("dev", "b_task", &other);
+    index.update_task("dev", "a_task", &other);
+    let serialized = serde_json::to_value(&index).unwrap();
+    assert_eq!(serialized["files"]["src/main.rs"], serde_json::json!(["dev.a_task", "dev.b_task", "dev.task_1"]));
+
+    index.remove_task("dev", "task_1");
+    assert!(!index.files.contains_key("src/lib.rs"));
+    assert_eq!(index.files["src/main.rs"].len(), 2);
+    assert!(!index.tasks_by_status.contains_key(&TaskStatus::Done));
+    let round_trip: TaskIndex = serde_json::from_value(serde_json::to_value(&index).unwrap()).unwrap();
+    assert_eq!(round_trip, index);
+}
+
+#[test]
+fn test_index_follows_deletes_and_moves() {
+    let mut project = ProjectData::new(None);
+    project.add_task("dev", "task_1", "Task 1".to_string(), None).unwrap();
+    project.add_task("dev", "task_2", "Task 2".to_string(), None).unwrap();
+    project.update_task_file("dev", "task_1", "file1.rs".to_string(), 10, None).unwrap();
+    project.update_task_file("dev", "task_2", "file1.rs".to_string(), 12, None).unwrap();
+    project.rebuild_index();
+
+    project.move_task("dev", "task_1", "ops", "task_1").unwrap();
+    project.delete_task("dev", "task_2").unwrap();
+    assert!(project.index_consistency_check().is_empty());
+    assert_eq!(project.index.files["file1.rs"].iter().collect::<Vec<_>>(), vec!["ops.task_1"]);
+}
+
+#[test]
+fn test_index_consistency_check_reports_discrepancies() {
+    let mut project = ProjectData::new(None);
+    project.add_task("dev", "task_1", "Task 1".to_string(), None).unwrap();
+    project.update_task_file("dev", "task_1", "file1.rs".to_string(), 10, None).unwrap();
+    project.rebuild_index();
+    assert!(project.index_consistency_check().is_empty());
+
+    project.index.files.get_mut("file1.rs").unwrap().insert("dev.ghost".to_string());
+    project.index.tasks_by_status.clear();
+    assert_eq!(
+        project.index_consistency_check(),
+        vec![
+            "File file1.rs: dev.ghost is indexed but does not belong there".to_string(),
+            "Status Todo: dev.task_1 is not indexed".to_string(),
+        ]
+    );
+    project.rebuild_index();
+    assert!(project.index_consistency_check().is_empty());
+}
+
 #[test]
 fn test_project_rebuild_index() {
     let mut project = ProjectData::new(None);
@@ -346,4 +414,75 @@ fn test_delete_task_updates_timestamp() {
     let result = project.delete_task("dev", "task_1");
     assert!(result.is_ok());
     assert!(project.meta.last_updated > initial_timestamp);
-}
\ No newline at end of file
+}
+#[test]
+fn test_section_files() {
+    let mut project = ProjectData::new(None);
+    project.add_task("api", "auth", "Auth".to_string(), None).unwrap();
+    project.add_task("api", "routes", "Routes".to_string(), None).unwrap();
+    project.add_task("ui", "login", "Login".to_string(), None).unwrap();
+    project.update_task_file("api", "auth", "src/auth.rs".to_string(), 5, None).unwrap();
+    project.update_task_file("api", "auth", "src/server.rs".to_string(), 12, None).unwrap();
+    project.update_task_file("api", "auth", "src/server.rs".to_string(), 40, None).unwrap();
+    project.update_task_file("api", "routes", "src/server.rs".to_string(), 20, None).unwrap();
+    project.update_task_file("ui", "login", "src/login.ts".to_string(), 1, None).unwrap();
+    project.rebuild_index();
+
+    let files = project.section_files("api").unwrap();
+    assert_eq!(
+        files,
+        vec![
+            ("src/auth.rs".to_string(), 1),
+            ("src/server.rs".to_string(), 2),
+        ]
+    );
+    project.archive_section("api").unwrap();
+    assert_eq!(project.section_files("api").unwrap(), files);
+    assert!(project.section_files("missing").is_err());
+}
+
+#[test]
+fn test_clone_section() {
+    let mut project = ProjectData::new(None);
+    project.add_task("template", "design", "Design".to_string(), Some("Write design doc".to_string())).unwrap();
+    project.add_task("template", "review", "Review".to_string(), None).unwrap();
+    project.update_task_status("template", "design", TaskStatus::Done).unwrap();
+    project.update_task_file("template", "design", "src/main.rs".to_string(), 10, None).unwrap();
+
+    let count = project.clone_section("template", "feature_x").unwrap();
+    assert_eq!(count, 2);
+
+    let design = project.get_task("feature_x", "design").unwrap();
+    assert_eq!(design.title, "Design");
+    assert_eq!(design.description, Some("Write design doc".to_string()));
+    assert_eq!(design.status, TaskStatus::Todo);
+    assert!(design.files.is_empty());
+    assert!(project.get_task("feature_x", "review").unwrap().files.is_empty());
+
+    // Source section is untouched
+    assert_eq!(project.get_task("template", "design").unwrap().status, TaskStatus::Done);
+
+    // Cloning into an existing section fails
+    assert!(project.clone_section("template", "feature_x").is_err());
+    assert!(project.clone_section("missing", "feature_y").is_err());
+}
+
+#[test]
+fn test_plan_id_normalization_reports_collisions() {
+    let mut project = ProjectData::new(None);
+    project.add_task("dev", "myTask", "A".to_string(), None).unwrap();
+    project.add_task("dev", "my_task", "B".to_string(), None).unwrap();
+    project.add_task("dev", "fooBar", "C".to_string(), None).unwrap();
+    project.add_task("dev", "foo-bar", "D".to_string(), None).unwrap();
+    project.add_task("dev", "otherTask", "E".to_string(), None).unwrap();
+
+    let (renames, collisions) = project.plan_id_normalization(anchora::IdStyle::SnakeCase);
+    assert_eq!(renames.len(), 1);
+    assert_eq!(renames[0].old_id, "otherTask");
+    assert_eq!(renames[0].new_id, "other_task");
+    assert_eq!(collisions.len(), 3);
+
+    project.apply_id_renames(&renames).unwrap();
+    assert!(project.get_task("dev", "other_task").is_some());
+    assert!(project.get_task("dev", "otherTask").is_none());
+}
@@ -293,7 +293,6 @@ export interface TaskOverview {
     readonly sections: ReadonlyArray<SectionSummary>;
     readonly statistics: TaskStatistics;
     readonly recent_activity: ReadonlyArray<TaskActivity>;
-    readonly recommendations: ReadonlyArray<string>;
 }
 
 export interface SectionSummary {